@@ -0,0 +1,39 @@
+use criterion::{black_box, criterion_group, criterion_main, Criterion, Throughput};
+use watto::codec;
+
+fn delta(c: &mut Criterion) {
+    let values: Vec<u64> = (0..100_000u64).map(|i| 0x1000 + i * 8 + i % 7).collect();
+    let mut encoded = vec![];
+    codec::encode_delta(&values, &mut encoded);
+
+    let mut group = c.benchmark_group("delta");
+    group.throughput(Throughput::Elements(values.len() as u64));
+
+    group.bench_function("encode", |b| {
+        let mut buffer = Vec::with_capacity(encoded.len());
+        b.iter(|| {
+            buffer.clear();
+            codec::encode_delta(black_box(&values), &mut buffer)
+        })
+    });
+    group.bench_function("decode_into", |b| {
+        let mut out = vec![0u64; values.len()];
+        b.iter(|| {
+            codec::decode_delta_into(black_box(&encoded), &mut out)
+                .unwrap()
+                .len()
+        })
+    });
+    group.bench_function("iter", |b| {
+        b.iter(|| {
+            codec::DeltaIter::<u64>::new(black_box(&encoded))
+                .map(Result::unwrap)
+                .fold(0u64, u64::wrapping_add)
+        })
+    });
+
+    group.finish();
+}
+
+criterion_group!(benches, delta);
+criterion_main!(benches);
@@ -0,0 +1,168 @@
+use core::ops::{Deref, DerefMut};
+use core::ptr::NonNull;
+use core::{fmt, slice};
+use std::alloc::{self, Layout};
+
+/// An error when allocating an [`AlignedVec`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AlignedVecError {
+    /// The requested length in bytes.
+    pub len: usize,
+    /// The requested alignment.
+    pub align: usize,
+}
+
+impl fmt::Display for AlignedVecError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "failed to allocate {} bytes aligned to {} bytes",
+            self.len, self.align
+        )
+    }
+}
+
+impl std::error::Error for AlignedVecError {}
+
+/// An owned, fixed-size byte buffer with a guaranteed alignment.
+///
+/// Unlike a `Vec<u8>`, which only guarantees an alignment of `1`, the bytes of
+/// an [`AlignedVec`] can be reparsed zero-copy using [`Pod`](crate::Pod)
+/// methods like [`slice_from_bytes`](crate::Pod::slice_from_bytes).
+///
+/// # Example
+/// ```
+/// use watto::{AlignedVec, Pod};
+///
+/// let bytes = AlignedVec::from_slice([1u64, 2, 3].as_bytes(), 8);
+/// assert_eq!(u64::slice_from_bytes(&bytes).unwrap(), &[1, 2, 3]);
+/// ```
+pub struct AlignedVec {
+    ptr: NonNull<u8>,
+    len: usize,
+    align: usize,
+}
+
+// SAFETY: `AlignedVec` uniquely owns its allocation, just like a `Vec<u8>`.
+unsafe impl Send for AlignedVec {}
+unsafe impl Sync for AlignedVec {}
+
+impl AlignedVec {
+    /// Creates a new zero-filled buffer of `len` bytes, aligned to `align` bytes.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `align` is not a power-of-two, or `len` overflows when rounded
+    /// up to `align`.
+    pub fn zeroed(len: usize, align: usize) -> Self {
+        let layout = Layout::from_size_align(len, align)
+            .expect("zeroed: align is not a power-of-two or len is too large");
+        Self::alloc_zeroed(layout).unwrap_or_else(|| alloc::handle_alloc_error(layout))
+    }
+
+    /// Creates a new zero-filled buffer of `len` bytes, aligned to `align` bytes.
+    ///
+    /// Unlike [`zeroed`](Self::zeroed), this returns an error instead of
+    /// panicking or aborting if the buffer can not be allocated, which makes
+    /// it suitable for lengths read from untrusted input.
+    pub fn try_zeroed(len: usize, align: usize) -> Result<Self, AlignedVecError> {
+        let error = AlignedVecError { len, align };
+        let layout = Layout::from_size_align(len, align).map_err(|_| error)?;
+        Self::alloc_zeroed(layout).ok_or(error)
+    }
+
+    /// Allocates a zero-filled buffer, returning `None` if the allocation fails.
+    fn alloc_zeroed(layout: Layout) -> Option<Self> {
+        let ptr = if layout.size() == 0 {
+            // A dangling, but properly aligned pointer.
+            NonNull::new(layout.align() as *mut u8).unwrap()
+        } else {
+            // SAFETY: the layout has a non-zero size.
+            NonNull::new(unsafe { alloc::alloc_zeroed(layout) })?
+        };
+        Some(Self {
+            ptr,
+            len: layout.size(),
+            align: layout.align(),
+        })
+    }
+
+    /// Creates a new buffer aligned to `align` bytes, containing a copy of `bytes`.
+    pub fn from_slice(bytes: &[u8], align: usize) -> Self {
+        let mut vec = Self::zeroed(bytes.len(), align);
+        vec.copy_from_slice(bytes);
+        vec
+    }
+
+    /// Returns the alignment of this buffer.
+    pub fn align(&self) -> usize {
+        self.align
+    }
+
+    /// Returns the buffer as a byte slice.
+    pub fn as_slice(&self) -> &[u8] {
+        // SAFETY: `ptr` is valid for `len` initialized bytes.
+        unsafe { slice::from_raw_parts(self.ptr.as_ptr(), self.len) }
+    }
+
+    /// Returns the buffer as a mutable byte slice.
+    pub fn as_mut_slice(&mut self) -> &mut [u8] {
+        // SAFETY: `ptr` is valid for `len` initialized bytes, and uniquely owned.
+        unsafe { slice::from_raw_parts_mut(self.ptr.as_ptr(), self.len) }
+    }
+}
+
+impl Drop for AlignedVec {
+    fn drop(&mut self) {
+        if self.len != 0 {
+            // SAFETY: the buffer was allocated with this exact layout.
+            unsafe {
+                let layout = Layout::from_size_align_unchecked(self.len, self.align);
+                alloc::dealloc(self.ptr.as_ptr(), layout);
+            }
+        }
+    }
+}
+
+impl Clone for AlignedVec {
+    fn clone(&self) -> Self {
+        Self::from_slice(self, self.align)
+    }
+}
+
+impl Deref for AlignedVec {
+    type Target = [u8];
+
+    fn deref(&self) -> &[u8] {
+        self.as_slice()
+    }
+}
+
+impl DerefMut for AlignedVec {
+    fn deref_mut(&mut self) -> &mut [u8] {
+        self.as_mut_slice()
+    }
+}
+
+impl AsRef<[u8]> for AlignedVec {
+    fn as_ref(&self) -> &[u8] {
+        self.as_slice()
+    }
+}
+
+impl PartialEq for AlignedVec {
+    fn eq(&self, other: &Self) -> bool {
+        self.as_slice() == other.as_slice()
+    }
+}
+
+impl Eq for AlignedVec {}
+
+impl fmt::Debug for AlignedVec {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("AlignedVec")
+            .field("align", &self.align)
+            .field("bytes", &self.as_slice())
+            .finish()
+    }
+}
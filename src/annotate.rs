@@ -0,0 +1,502 @@
+//! Schema-driven annotation of serialized buffers.
+//!
+//! A format describes its sections and types as a [`Schema`], which can then
+//! [`annotate`](Schema::annotate) a given buffer, producing a listing of the
+//! offsets, field names and values, as well as the padding regions and any
+//! trailing bytes of the buffer. This serves as an `objdump` for watto-based
+//! formats when debugging.
+//!
+//! Sections are aligned relative to the start of the buffer, and all
+//! values are read in native endianness.
+//!
+//! # Example
+//! ```
+//! use watto::annotate::{Count, Primitive, Schema, Type};
+//!
+//! let header = Type::Struct(vec![
+//!     ("version".into(), Type::Primitive(Primitive::U32)),
+//!     ("num_as".into(), Type::Primitive(Primitive::U16)),
+//! ]);
+//! let schema = Schema::new()
+//!     .value("header", header)
+//!     .array("as", Type::Primitive(Primitive::U8), Count::Field("header.num_as".into()))
+//!     .array("bs", Type::Primitive(Primitive::U32), Count::ToEnd);
+//!
+//! let mut buffer = vec![];
+//! buffer.extend_from_slice(&1u32.to_ne_bytes());
+//! buffer.extend_from_slice(&2u16.to_ne_bytes());
+//! buffer.extend_from_slice(&[0, 0]); // struct padding
+//! buffer.extend_from_slice(&[7, 8]);
+//! buffer.extend_from_slice(&[0, 0]); // section padding
+//! buffer.extend_from_slice(&3u32.to_ne_bytes());
+//!
+//! let annotated = schema.annotate(&buffer);
+//! let listing = annotated.to_string();
+//! assert!(listing.contains("header.num_as: u16 = 2"));
+//! assert!(listing.contains("as[1]: u8 = 8"));
+//! assert!(listing.contains("bs[0]: u32 = 3"));
+//! assert_eq!(annotated.errors().count(), 0);
+//! ```
+
+use std::collections::HashMap;
+use std::fmt;
+
+use crate::describe::{Describe, LayoutKind, TypeLayout};
+
+/// A primitive type, read in native endianness.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Primitive {
+    /// An `u8`.
+    U8,
+    /// An `u16`.
+    U16,
+    /// An `u32`.
+    U32,
+    /// An `u64`.
+    U64,
+    /// An `i8`.
+    I8,
+    /// An `i16`.
+    I16,
+    /// An `i32`.
+    I32,
+    /// An `i64`.
+    I64,
+    /// An `f32`.
+    F32,
+    /// An `f64`.
+    F64,
+}
+
+impl Primitive {
+    /// Returns the size, which is also the alignment, of this type.
+    pub fn size(self) -> usize {
+        match self {
+            Self::U8 | Self::I8 => 1,
+            Self::U16 | Self::I16 => 2,
+            Self::U32 | Self::I32 | Self::F32 => 4,
+            Self::U64 | Self::I64 | Self::F64 => 8,
+        }
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            Self::U8 => "u8",
+            Self::U16 => "u16",
+            Self::U32 => "u32",
+            Self::U64 => "u64",
+            Self::I8 => "i8",
+            Self::I16 => "i16",
+            Self::I32 => "i32",
+            Self::I64 => "i64",
+            Self::F32 => "f32",
+            Self::F64 => "f64",
+        }
+    }
+
+    /// Reads the value, returning its display representation, and its integer value if any.
+    fn read(self, bytes: &[u8]) -> (String, Option<u64>) {
+        macro_rules! read {
+            ($ty:ty) => {{
+                let value = <$ty>::from_ne_bytes(bytes.try_into().unwrap());
+                (value.to_string(), u64::try_from(value).ok())
+            }};
+        }
+        match self {
+            Self::U8 => read!(u8),
+            Self::U16 => read!(u16),
+            Self::U32 => read!(u32),
+            Self::U64 => read!(u64),
+            Self::I8 => read!(i8),
+            Self::I16 => read!(i16),
+            Self::I32 => read!(i32),
+            Self::I64 => read!(i64),
+            Self::F32 => (
+                f32::from_ne_bytes(bytes.try_into().unwrap()).to_string(),
+                None,
+            ),
+            Self::F64 => (
+                f64::from_ne_bytes(bytes.try_into().unwrap()).to_string(),
+                None,
+            ),
+        }
+    }
+}
+
+/// A type with `#[repr(C)]` layout.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Type {
+    /// A primitive type.
+    Primitive(Primitive),
+    /// A fixed-size array, like `[u8; 4]`.
+    Array(Box<Type>, usize),
+    /// A struct with the given named fields.
+    Struct(Vec<(String, Type)>),
+}
+
+impl Type {
+    /// Returns the [`Type`] of a type implementing [`Describe`].
+    ///
+    /// # Example
+    /// ```
+    /// use watto::annotate::{Primitive, Type};
+    ///
+    /// assert_eq!(
+    ///     Type::of::<[u16; 2]>(),
+    ///     Type::Array(Box::new(Type::Primitive(Primitive::U16)), 2)
+    /// );
+    /// ```
+    pub fn of<T: Describe>() -> Self {
+        Self::from(T::LAYOUT)
+    }
+
+    /// Returns the alignment of this type.
+    pub fn align(&self) -> usize {
+        match self {
+            Self::Primitive(primitive) => primitive.size(),
+            Self::Array(elem, _) => elem.align(),
+            Self::Struct(fields) => fields.iter().map(|(_, ty)| ty.align()).max().unwrap_or(1),
+        }
+    }
+
+    /// Returns the size of this type, including any padding.
+    pub fn size(&self) -> usize {
+        match self {
+            Self::Primitive(primitive) => primitive.size(),
+            Self::Array(elem, len) => elem.size() * len,
+            Self::Struct(fields) => {
+                let size = fields.iter().fold(0, |offset: usize, (_, ty)| {
+                    offset.next_multiple_of(ty.align()) + ty.size()
+                });
+                size.next_multiple_of(self.align())
+            }
+        }
+    }
+}
+
+impl From<&TypeLayout> for Type {
+    fn from(layout: &TypeLayout) -> Self {
+        match layout.kind {
+            LayoutKind::Primitive => {
+                let primitive = match layout.name {
+                    "u8" => Primitive::U8,
+                    "u16" => Primitive::U16,
+                    "u32" => Primitive::U32,
+                    "u64" => Primitive::U64,
+                    "i8" => Primitive::I8,
+                    "i16" => Primitive::I16,
+                    "i32" => Primitive::I32,
+                    "i64" => Primitive::I64,
+                    "f32" => Primitive::F32,
+                    "f64" => Primitive::F64,
+                    // Other primitives are shown as raw bytes.
+                    _ => return Self::Array(Box::new(Self::Primitive(Primitive::U8)), layout.size),
+                };
+                Self::Primitive(primitive)
+            }
+            LayoutKind::Array { element, len } => Self::Array(Box::new(element.into()), len),
+            LayoutKind::Struct(fields) => Self::Struct(
+                fields
+                    .iter()
+                    .map(|field| (field.name.to_owned(), field.layout.into()))
+                    .collect(),
+            ),
+        }
+    }
+}
+
+/// The number of elements of an array section.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Count {
+    /// A fixed number of elements.
+    Fixed(usize),
+    /// The number of elements is given by the integer value of a previous
+    /// field, referenced by its path like `header.num_entries`.
+    Field(String),
+    /// The array extends to the end of the buffer.
+    ToEnd,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+struct Section {
+    name: String,
+    ty: Type,
+    count: Option<Count>,
+}
+
+/// A description of a format as a sequence of aligned sections.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct Schema {
+    sections: Vec<Section>,
+}
+
+impl Schema {
+    /// Creates a new empty [`Schema`].
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Appends a section consisting of a single value.
+    pub fn value(mut self, name: impl Into<String>, ty: Type) -> Self {
+        self.sections.push(Section {
+            name: name.into(),
+            ty,
+            count: None,
+        });
+        self
+    }
+
+    /// Appends a section consisting of an array of values.
+    pub fn array(mut self, name: impl Into<String>, ty: Type, count: Count) -> Self {
+        self.sections.push(Section {
+            name: name.into(),
+            ty,
+            count: Some(count),
+        });
+        self
+    }
+
+    /// Annotates the given buffer according to this schema.
+    ///
+    /// Annotation stops at the first section which does not fit into the
+    /// buffer, recording an [`AnnotationKind::Error`].
+    pub fn annotate<'data>(&self, buffer: &'data [u8]) -> Annotated<'data> {
+        let mut annotator = Annotator {
+            buffer,
+            offset: 0,
+            annotations: Vec::new(),
+            values: HashMap::new(),
+        };
+        for section in &self.sections {
+            if let Err(error) = annotator.section(section) {
+                annotator.push(
+                    annotator.offset,
+                    0,
+                    &section.name,
+                    AnnotationKind::Error(error),
+                );
+                return annotator.finish();
+            }
+        }
+
+        let trailing = buffer.len() - annotator.offset;
+        if trailing > 0 {
+            annotator.push(annotator.offset, trailing, "", AnnotationKind::Trailing);
+        }
+        annotator.finish()
+    }
+}
+
+struct Annotator<'data> {
+    buffer: &'data [u8],
+    offset: usize,
+    annotations: Vec<Annotation>,
+    values: HashMap<String, u64>,
+}
+
+impl<'data> Annotator<'data> {
+    fn push(&mut self, offset: usize, len: usize, label: &str, kind: AnnotationKind) {
+        self.annotations.push(Annotation {
+            offset,
+            len,
+            label: label.into(),
+            kind,
+        });
+    }
+
+    fn finish(self) -> Annotated<'data> {
+        Annotated {
+            buffer: self.buffer,
+            annotations: self.annotations,
+        }
+    }
+
+    fn align(&mut self, align: usize, label: &str) -> Result<(), String> {
+        let aligned = self.offset.next_multiple_of(align);
+        if aligned > self.buffer.len() {
+            return Err(format!("buffer ends before {align}-byte alignment"));
+        }
+        if aligned > self.offset {
+            self.push(
+                self.offset,
+                aligned - self.offset,
+                label,
+                AnnotationKind::Padding,
+            );
+            self.offset = aligned;
+        }
+        Ok(())
+    }
+
+    fn section(&mut self, section: &Section) -> Result<(), String> {
+        let size = section.ty.size();
+        self.align(section.ty.align(), &section.name)?;
+
+        let Some(count) = &section.count else {
+            return self.value(&section.name, &section.ty);
+        };
+        let count = match count {
+            Count::Fixed(count) => *count,
+            Count::Field(path) => {
+                let count = *self
+                    .values
+                    .get(path)
+                    .ok_or_else(|| format!("unknown count field `{path}`"))?;
+                usize::try_from(count).map_err(|_| format!("invalid count {count}"))?
+            }
+            Count::ToEnd => {
+                let remaining = self.buffer.len() - self.offset;
+                if size == 0 || !remaining.is_multiple_of(size) {
+                    return Err(format!(
+                        "{remaining} remaining bytes are not a multiple of {size}"
+                    ));
+                }
+                remaining / size
+            }
+        };
+
+        let total = count.saturating_mul(size);
+        if total > self.buffer.len() - self.offset {
+            return Err(format!("{count} elements of {size} bytes do not fit"));
+        }
+        for index in 0..count {
+            self.value(&format!("{}[{index}]", section.name), &section.ty)?;
+        }
+        Ok(())
+    }
+
+    fn value(&mut self, path: &str, ty: &Type) -> Result<(), String> {
+        let start = self.offset;
+        match ty {
+            Type::Primitive(primitive) => {
+                let size = primitive.size();
+                let bytes = self
+                    .buffer
+                    .get(start..start + size)
+                    .ok_or_else(|| format!("{} does not fit", primitive.name()))?;
+                let (display, int) = primitive.read(bytes);
+                if let Some(int) = int {
+                    self.values.insert(path.into(), int);
+                }
+                self.push(
+                    start,
+                    size,
+                    path,
+                    AnnotationKind::Value(format!("{} = {display}", primitive.name())),
+                );
+                self.offset += size;
+            }
+            Type::Array(elem, len) => {
+                for index in 0..*len {
+                    self.value(&format!("{path}[{index}]"), elem)?;
+                }
+            }
+            Type::Struct(fields) => {
+                for (name, field) in fields {
+                    let field_path = format!("{path}.{name}");
+                    self.align(field.align(), &field_path)?;
+                    self.value(&field_path, field)?;
+                }
+                let end = start + ty.size();
+                if end > self.buffer.len() {
+                    return Err(format!("{path} does not fit"));
+                }
+                if end > self.offset {
+                    self.push(
+                        self.offset,
+                        end - self.offset,
+                        path,
+                        AnnotationKind::Padding,
+                    );
+                    self.offset = end;
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// The kind of an [`Annotation`].
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum AnnotationKind {
+    /// A value, described by its type and value.
+    Value(String),
+    /// Padding bytes.
+    Padding,
+    /// Bytes following the last section.
+    Trailing,
+    /// The section does not fit the buffer.
+    Error(String),
+}
+
+/// An annotated region of a buffer.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Annotation {
+    /// The offset of the region.
+    pub offset: usize,
+    /// The length of the region in bytes.
+    pub len: usize,
+    /// The path of the field, like `header.version` or `entries[3].name`.
+    pub label: String,
+    /// The kind of the region.
+    pub kind: AnnotationKind,
+}
+
+/// The annotations of a buffer, created by [`Schema::annotate`].
+///
+/// The [`Display`](fmt::Display) implementation prints an annotated listing.
+#[derive(Debug, Clone)]
+pub struct Annotated<'data> {
+    buffer: &'data [u8],
+    annotations: Vec<Annotation>,
+}
+
+impl Annotated<'_> {
+    /// Returns all the annotations, in buffer order.
+    pub fn annotations(&self) -> &[Annotation] {
+        &self.annotations
+    }
+
+    /// Returns all the error annotations.
+    pub fn errors(&self) -> impl Iterator<Item = &Annotation> {
+        self.annotations
+            .iter()
+            .filter(|annotation| matches!(annotation.kind, AnnotationKind::Error(_)))
+    }
+}
+
+impl fmt::Display for Annotated<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        const MAX_BYTES: usize = 8;
+
+        for annotation in &self.annotations {
+            let bytes = &self.buffer[annotation.offset..annotation.offset + annotation.len];
+            write!(f, "{:#010x} ", annotation.offset)?;
+            for byte in bytes.iter().take(MAX_BYTES) {
+                write!(f, " {byte:02x}")?;
+            }
+            let shown = bytes.len().min(MAX_BYTES);
+            let ellipsis = if bytes.len() > MAX_BYTES {
+                " …"
+            } else {
+                "  "
+            };
+            let padding = 3 * (MAX_BYTES - shown);
+            write!(f, "{:padding$}{ellipsis}  ", "")?;
+
+            match &annotation.kind {
+                AnnotationKind::Value(value) => writeln!(f, "{}: {value}", annotation.label)?,
+                AnnotationKind::Padding => writeln!(
+                    f,
+                    "<padding, {} bytes> {}",
+                    annotation.len, annotation.label
+                )?,
+                AnnotationKind::Trailing => writeln!(f, "<trailing, {} bytes>", annotation.len)?,
+                AnnotationKind::Error(error) => {
+                    writeln!(f, "<error> {}: {error}", annotation.label)?
+                }
+            }
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,117 @@
+use core::borrow::Borrow;
+use core::hash::{Hash, Hasher};
+use core::ops::{Deref, Range};
+use core::{fmt, str};
+use std::sync::Arc;
+
+use crate::{ReadStringError, StringTable};
+
+/// An owned handle to a string stored within a shared [`StringTable`] buffer.
+///
+/// An [`ArcStr`] holds a reference-counted pointer to the whole serialized
+/// buffer, plus the range of the string within it. It dereferences to `&str`,
+/// and can be freely cloned and stored in long-lived structures without copying
+/// the string data out of the table.
+///
+/// # Example
+/// ```
+/// use std::sync::Arc;
+/// use watto::StringTable;
+///
+/// let mut table = StringTable::new();
+/// let offset = table.insert("foo");
+///
+/// let buffer: Arc<[u8]> = table.into_bytes().into();
+/// let foo = StringTable::read_arc(&buffer, offset).unwrap();
+/// assert_eq!(&*foo, "foo");
+/// ```
+#[derive(Clone)]
+pub struct ArcStr {
+    buffer: Arc<[u8]>,
+    range: Range<usize>,
+}
+
+impl ArcStr {
+    /// Returns the string stored at the given offset in the shared buffer.
+    ///
+    /// The string is validated once, all further accesses are free.
+    pub fn new(buffer: &Arc<[u8]>, offset: usize) -> Result<Self, ReadStringError> {
+        let s = StringTable::read(buffer, offset)?;
+        let start = s.as_ptr() as usize - buffer.as_ptr() as usize;
+        Ok(Self {
+            buffer: Arc::clone(buffer),
+            range: start..start + s.len(),
+        })
+    }
+
+    /// Returns the string as a `&str`.
+    pub fn as_str(&self) -> &str {
+        // SAFETY:
+        // The range has been validated as UTF-8 when constructing `self`,
+        // and the underlying buffer is immutable.
+        unsafe { str::from_utf8_unchecked(&self.buffer[self.range.clone()]) }
+    }
+
+    /// Returns the shared buffer this string points into.
+    pub fn buffer(&self) -> &Arc<[u8]> {
+        &self.buffer
+    }
+}
+
+impl Deref for ArcStr {
+    type Target = str;
+
+    fn deref(&self) -> &str {
+        self.as_str()
+    }
+}
+
+impl AsRef<str> for ArcStr {
+    fn as_ref(&self) -> &str {
+        self.as_str()
+    }
+}
+
+impl Borrow<str> for ArcStr {
+    fn borrow(&self) -> &str {
+        self.as_str()
+    }
+}
+
+impl fmt::Debug for ArcStr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(self.as_str(), f)
+    }
+}
+
+impl fmt::Display for ArcStr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(self.as_str(), f)
+    }
+}
+
+impl PartialEq for ArcStr {
+    fn eq(&self, other: &Self) -> bool {
+        self.as_str() == other.as_str()
+    }
+}
+
+impl Eq for ArcStr {}
+
+impl PartialEq<str> for ArcStr {
+    fn eq(&self, other: &str) -> bool {
+        self.as_str() == other
+    }
+}
+
+impl PartialEq<&str> for ArcStr {
+    fn eq(&self, other: &&str) -> bool {
+        self.as_str() == *other
+    }
+}
+
+impl Hash for ArcStr {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.as_str().hash(state)
+    }
+}
@@ -0,0 +1,317 @@
+//! Inspects serialized watto tables.
+//!
+//! Run `watto-inspect --help` for usage.
+
+use std::ops::Range;
+use std::process::ExitCode;
+use std::{env, fmt, fs};
+
+use watto::{raw, varint, StringTable};
+
+const USAGE: &str = "\
+Usage: watto-inspect <COMMAND> <FILE> [OPTIONS]
+
+Commands:
+  list              List all the entries of the table
+  stats             Show statistics about the table
+  validate          Validate the table, listing all problems
+  hexdump <OFFSET>  Show a hexdump of the table starting at OFFSET
+
+Options:
+  --range <START>..<END>  Only inspect the given byte range of FILE
+  --kind <KIND>           The kind of table, `strings` (default) or `offset-set`
+  --elem-size <SIZE>      The element size of an `offset-set` in bytes (default 1)
+  --len <LEN>             The number of bytes to show in a hexdump (default 64)
+  -h, --help              Show this help
+
+Offsets and lengths can be given in decimal or in hex with a `0x` prefix.";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Kind {
+    Strings,
+    OffsetSet { elem_size: usize },
+}
+
+#[derive(Debug)]
+enum Command {
+    List,
+    Stats,
+    Validate,
+    Hexdump { offset: usize, len: usize },
+}
+
+#[derive(Debug)]
+struct Args {
+    command: Command,
+    file: String,
+    range: Option<Range<usize>>,
+    kind: Kind,
+}
+
+fn parse_number(s: &str) -> Result<usize, String> {
+    let result = match s.strip_prefix("0x") {
+        Some(hex) => usize::from_str_radix(hex, 16),
+        None => s.parse(),
+    };
+    result.map_err(|_| format!("invalid number `{s}`"))
+}
+
+fn parse_args(mut args: impl Iterator<Item = String>) -> Result<Option<Args>, String> {
+    let mut positional = Vec::new();
+    let mut range = None;
+    let mut kind = "strings".to_owned();
+    let mut elem_size = 1;
+    let mut len = 64;
+
+    while let Some(arg) = args.next() {
+        let mut value = |name: &str| args.next().ok_or(format!("missing value for `{name}`"));
+        match arg.as_str() {
+            "-h" | "--help" => return Ok(None),
+            "--range" => {
+                let value = value("--range")?;
+                let (start, end) = value
+                    .split_once("..")
+                    .ok_or(format!("invalid range `{value}`"))?;
+                range = Some(parse_number(start)?..parse_number(end)?);
+            }
+            "--kind" => kind = value("--kind")?,
+            "--elem-size" => elem_size = parse_number(&value("--elem-size")?)?,
+            "--len" => len = parse_number(&value("--len")?)?,
+            _ if arg.starts_with('-') => return Err(format!("unknown option `{arg}`")),
+            _ => positional.push(arg),
+        }
+    }
+
+    let kind = match kind.as_str() {
+        "strings" => Kind::Strings,
+        "offset-set" if elem_size > 0 => Kind::OffsetSet { elem_size },
+        "offset-set" => return Err("element size must not be 0".into()),
+        _ => return Err(format!("unknown table kind `{kind}`")),
+    };
+
+    let mut positional = positional.into_iter();
+    let command = positional.next().ok_or("missing command")?;
+    let command = match command.as_str() {
+        "list" => Command::List,
+        "stats" => Command::Stats,
+        "validate" => Command::Validate,
+        "hexdump" => {
+            let offset = positional.next().ok_or("missing hexdump offset")?;
+            Command::Hexdump {
+                offset: parse_number(&offset)?,
+                len,
+            }
+        }
+        _ => return Err(format!("unknown command `{command}`")),
+    };
+    let file = positional.next().ok_or("missing file")?;
+    if let Some(arg) = positional.next() {
+        return Err(format!("unexpected argument `{arg}`"));
+    }
+
+    Ok(Some(Args {
+        command,
+        file,
+        range,
+        kind,
+    }))
+}
+
+/// An entry of an offset set, or the reason no more entries can be read.
+enum Entry<'data> {
+    Ok { offset: usize, data: &'data [u8] },
+    Invalid { offset: usize, reason: &'static str },
+}
+
+/// Walks all the entries of a serialized offset set with the given element size.
+fn entries(buffer: &[u8], elem_size: usize) -> impl Iterator<Item = Entry<'_>> {
+    let mut offset = 0;
+    std::iter::from_fn(move || {
+        offset += raw::padding_len(buffer.get(offset..)?);
+        if offset >= buffer.len() {
+            return None;
+        }
+        let entry_offset = offset;
+        let invalid = |reason| {
+            Some(Entry::Invalid {
+                offset: entry_offset,
+                reason,
+            })
+        };
+        // Make sure the iteration stops after an invalid entry.
+        offset = buffer.len();
+
+        let Some((len, prefix_len)) = varint::read_u64(&buffer[entry_offset..]) else {
+            return invalid("invalid length prefix");
+        };
+        let start = entry_offset + prefix_len;
+        let Some(end) = usize::try_from(len)
+            .ok()
+            .and_then(|len| len.checked_mul(elem_size))
+            .and_then(|len| len.checked_add(start))
+            .filter(|end| *end <= buffer.len())
+        else {
+            return invalid("entry is out of bounds");
+        };
+
+        offset = end;
+        Some(Entry::Ok {
+            offset: entry_offset,
+            data: &buffer[start..end],
+        })
+    })
+}
+
+struct Hexdump<'data> {
+    buffer: &'data [u8],
+    range: Range<usize>,
+}
+
+impl fmt::Display for Hexdump<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let bytes = &self.buffer[self.range.clone()];
+        for (i, row) in bytes.chunks(16).enumerate() {
+            write!(f, "{:#010x} ", self.range.start + i * 16)?;
+            for byte in row {
+                write!(f, " {byte:02x}")?;
+            }
+            let padding = 3 * (16 - row.len());
+            write!(f, "{:padding$}  |", "")?;
+            for byte in row {
+                let c = if byte.is_ascii_graphic() || *byte == b' ' {
+                    *byte as char
+                } else {
+                    '.'
+                };
+                write!(f, "{c}")?;
+            }
+            writeln!(f, "|")?;
+        }
+        Ok(())
+    }
+}
+
+fn run(args: Args) -> Result<bool, String> {
+    let file =
+        fs::read(&args.file).map_err(|err| format!("failed to read `{}`: {err}", args.file))?;
+    let buffer = match args.range {
+        Some(range) => file.get(range.clone()).ok_or(format!(
+            "range {range:?} is out of bounds of {} bytes",
+            file.len()
+        ))?,
+        None => &file[..],
+    };
+
+    let elem_size = match args.kind {
+        Kind::Strings => 1,
+        Kind::OffsetSet { elem_size } => elem_size,
+    };
+
+    match args.command {
+        Command::List => match args.kind {
+            Kind::Strings => print!("{}", StringTable::dump(buffer)),
+            Kind::OffsetSet { .. } => {
+                for entry in entries(buffer, elem_size) {
+                    match entry {
+                        Entry::Ok { offset, data } => println!(
+                            "{offset:#010x}  len {:<6} {:02x?}",
+                            data.len() / elem_size,
+                            data
+                        ),
+                        Entry::Invalid { offset, reason } => println!("{offset:#010x}  <{reason}>"),
+                    }
+                }
+            }
+        },
+        Command::Stats => {
+            let mut count = 0;
+            let mut max_len = 0;
+            let mut total_len = 0;
+            let mut invalid = 0;
+            for entry in entries(buffer, elem_size) {
+                match entry {
+                    Entry::Ok { data, .. } => {
+                        let len = data.len() / elem_size;
+                        count += 1;
+                        max_len = max_len.max(len);
+                        total_len += len;
+                    }
+                    Entry::Invalid { .. } => invalid += 1,
+                }
+            }
+            println!("size:            {} bytes", buffer.len());
+            println!("entries:         {count}");
+            println!("max entry len:   {max_len}");
+            if count > 0 {
+                println!("avg entry len:   {:.2}", total_len as f64 / count as f64);
+            }
+            if invalid > 0 {
+                println!("invalid entries: {invalid}");
+            }
+        }
+        Command::Validate => {
+            let problems: Vec<String> = match args.kind {
+                Kind::Strings => StringTable::validate_report(buffer)
+                    .problems
+                    .iter()
+                    .map(ToString::to_string)
+                    .collect(),
+                Kind::OffsetSet { .. } => entries(buffer, elem_size)
+                    .filter_map(|entry| match entry {
+                        Entry::Ok { .. } => None,
+                        Entry::Invalid { offset, reason } => {
+                            Some(format!("{reason} at offset {offset}"))
+                        }
+                    })
+                    .collect(),
+            };
+            if problems.is_empty() {
+                println!("OK");
+            }
+            for problem in &problems {
+                println!("{problem}");
+            }
+            return Ok(problems.is_empty());
+        }
+        Command::Hexdump { offset, len } => {
+            if offset > buffer.len() {
+                return Err(format!(
+                    "offset {offset} is out of bounds of {} bytes",
+                    buffer.len()
+                ));
+            }
+            let end = offset.saturating_add(len).min(buffer.len());
+            print!(
+                "{}",
+                Hexdump {
+                    buffer,
+                    range: offset..end
+                }
+            );
+        }
+    }
+    Ok(true)
+}
+
+fn main() -> ExitCode {
+    let args = match parse_args(env::args().skip(1)) {
+        Ok(Some(args)) => args,
+        Ok(None) => {
+            println!("{USAGE}");
+            return ExitCode::SUCCESS;
+        }
+        Err(err) => {
+            eprintln!("error: {err}\n\n{USAGE}");
+            return ExitCode::from(2);
+        }
+    };
+
+    match run(args) {
+        Ok(true) => ExitCode::SUCCESS,
+        Ok(false) => ExitCode::FAILURE,
+        Err(err) => {
+            eprintln!("error: {err}");
+            ExitCode::FAILURE
+        }
+    }
+}
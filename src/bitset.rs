@@ -0,0 +1,177 @@
+/// A zero-copy view of a packed bitset.
+///
+/// Bits are packed least-significant bit first, so bit `i` is stored in byte
+/// `i / 8` at bit position `i % 8`. Bitsets are typically written with a
+/// [`BitSetBuilder`], and accompany optional columns as presence bitmaps.
+///
+/// # Example
+/// ```
+/// use watto::BitSlice;
+///
+/// let bits = BitSlice::from_bytes(&[0b0000_0101, 0b1], 9).unwrap();
+/// assert_eq!(bits.get(0), Some(true));
+/// assert_eq!(bits.get(1), Some(false));
+/// assert_eq!(bits.get(9), None);
+/// assert!(bits.iter_ones().eq([0, 2, 8]));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BitSlice<'data> {
+    bytes: &'data [u8],
+    bit_len: usize,
+}
+
+impl<'data> BitSlice<'data> {
+    /// Creates a view of the first `bit_len` bits of `bytes`.
+    ///
+    /// Returns [`None`] if `bytes` is too small to hold `bit_len` bits.
+    pub fn from_bytes(bytes: &'data [u8], bit_len: usize) -> Option<Self> {
+        let bytes = bytes.get(..bit_len.div_ceil(8))?;
+        Some(Self { bytes, bit_len })
+    }
+
+    /// Returns the underlying bytes, which includes the trailing bits of the last byte.
+    pub fn as_bytes(&self) -> &'data [u8] {
+        self.bytes
+    }
+
+    /// Returns the number of bits.
+    pub fn len(&self) -> usize {
+        self.bit_len
+    }
+
+    /// Returns `true` if there are no bits.
+    pub fn is_empty(&self) -> bool {
+        self.bit_len == 0
+    }
+
+    /// Returns whether the bit at `index` is set, or [`None`] if it is out of bounds.
+    pub fn get(&self, index: usize) -> Option<bool> {
+        if index >= self.bit_len {
+            return None;
+        }
+        Some(self.bytes[index / 8] & (1 << (index % 8)) != 0)
+    }
+
+    /// Returns the number of set bits.
+    pub fn count_ones(&self) -> usize {
+        self.iter_ones().count()
+    }
+
+    /// Iterates over the indices of all the set bits, in ascending order.
+    pub fn iter_ones(&self) -> IterOnes<'data> {
+        IterOnes {
+            bits: *self,
+            byte_index: 0,
+            current: self.bytes.first().copied().unwrap_or_default(),
+        }
+    }
+}
+
+/// An iterator over the indices of the set bits of a [`BitSlice`].
+#[derive(Debug, Clone)]
+pub struct IterOnes<'data> {
+    bits: BitSlice<'data>,
+    byte_index: usize,
+    current: u8,
+}
+
+impl Iterator for IterOnes<'_> {
+    type Item = usize;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if self.current != 0 {
+                let index = self.byte_index * 8 + self.current.trailing_zeros() as usize;
+                if index >= self.bits.bit_len {
+                    return None;
+                }
+                // Clear the lowest set bit.
+                self.current &= self.current - 1;
+                return Some(index);
+            }
+            self.byte_index += 1;
+            self.current = *self.bits.bytes.get(self.byte_index)?;
+        }
+    }
+}
+
+impl core::iter::FusedIterator for IterOnes<'_> {}
+
+/// A builder for a packed bitset, which can be read using a [`BitSlice`].
+#[cfg(feature = "std")]
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct BitSetBuilder {
+    bytes: Vec<u8>,
+    bit_len: usize,
+}
+
+#[cfg(feature = "std")]
+impl BitSetBuilder {
+    /// Creates a new empty [`BitSetBuilder`].
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Appends a bit.
+    pub fn push(&mut self, bit: bool) {
+        if self.bit_len.is_multiple_of(8) {
+            self.bytes.push(0);
+        }
+        if bit {
+            *self.bytes.last_mut().unwrap() |= 1 << (self.bit_len % 8);
+        }
+        self.bit_len += 1;
+    }
+
+    /// Returns the number of bits.
+    pub fn len(&self) -> usize {
+        self.bit_len
+    }
+
+    /// Returns `true` if there are no bits.
+    pub fn is_empty(&self) -> bool {
+        self.bit_len == 0
+    }
+
+    /// Returns a [`BitSlice`] view of the bits pushed so far.
+    pub fn as_bit_slice(&self) -> BitSlice<'_> {
+        BitSlice {
+            bytes: &self.bytes,
+            bit_len: self.bit_len,
+        }
+    }
+
+    /// Returns a byte slice containing the packed bits.
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.bytes
+    }
+
+    /// Writes the packed bits into the given [`Writer`](crate::Writer).
+    ///
+    /// The number of bits is not written, and has to be stored separately.
+    #[cfg(feature = "writer")]
+    pub fn write_to<W: std::io::Write>(
+        &self,
+        writer: &mut crate::Writer<W>,
+    ) -> std::io::Result<()> {
+        std::io::Write::write_all(writer, &self.bytes)
+    }
+}
+
+#[cfg(feature = "std")]
+impl Extend<bool> for BitSetBuilder {
+    fn extend<I: IntoIterator<Item = bool>>(&mut self, iter: I) {
+        for bit in iter {
+            self.push(bit);
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl FromIterator<bool> for BitSetBuilder {
+    fn from_iter<I: IntoIterator<Item = bool>>(iter: I) -> Self {
+        let mut builder = Self::new();
+        builder.extend(iter);
+        builder
+    }
+}
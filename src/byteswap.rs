@@ -0,0 +1,71 @@
+use crate::Pod;
+
+/// A [`Pod`] type whose byte order can be reversed.
+///
+/// This allows one `#[repr(C)]` definition to serve both as an in-memory
+/// type and as a disk format with a defined byte order. Reversing the byte
+/// order of a struct reverses the byte order of each of its fields, and of
+/// each element of an array.
+///
+/// With the `derive` feature, this can be derived for `#[repr(C)]` structs.
+/// A `#[watto(endian = "little")]` or `#[watto(endian = "big")]` attribute
+/// additionally generates `to_native` and `to_disk` methods, which convert
+/// between the disk and the native byte order, and are no-ops on hosts with
+/// a matching byte order.
+///
+/// # Example
+/// ```
+/// # #[cfg(feature = "derive")] {
+/// use watto::{ByteSwap, Pod};
+///
+/// #[derive(Debug, Clone, Copy, PartialEq, ByteSwap)]
+/// #[watto(endian = "big")]
+/// #[repr(C)]
+/// struct Header {
+///     magic: [u8; 4],
+///     len: u32,
+/// }
+/// unsafe impl Pod for Header {}
+///
+/// let header = Header { magic: *b"WATO", len: 1 };
+/// let disk = header.to_disk();
+/// assert_eq!(disk.as_bytes(), b"WATO\0\0\0\x01");
+/// assert_eq!(disk.to_native(), header);
+/// # }
+/// ```
+pub trait ByteSwap: Pod {
+    /// Returns this value with the byte order of all its fields reversed.
+    fn swap_bytes(self) -> Self;
+}
+
+macro_rules! swap_integers {
+    ($($ty:ty),*) => {
+        $(
+            impl ByteSwap for $ty {
+                fn swap_bytes(self) -> Self {
+                    <$ty>::swap_bytes(self)
+                }
+            }
+        )*
+    };
+}
+
+swap_integers!(u8, i8, u16, i16, u32, i32, u64, i64, u128, i128, usize, isize);
+
+impl ByteSwap for f32 {
+    fn swap_bytes(self) -> Self {
+        f32::from_bits(self.to_bits().swap_bytes())
+    }
+}
+
+impl ByteSwap for f64 {
+    fn swap_bytes(self) -> Self {
+        f64::from_bits(self.to_bits().swap_bytes())
+    }
+}
+
+impl<T: ByteSwap, const N: usize> ByteSwap for [T; N] {
+    fn swap_bytes(self) -> Self {
+        self.map(T::swap_bytes)
+    }
+}
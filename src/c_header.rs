@@ -0,0 +1,206 @@
+//! Generation of C headers from [`Describe`]d types.
+//!
+//! A [`CHeader`] emits C struct definitions matching the layout of
+//! `#[repr(C)]` types, together with static assertions of their sizes and
+//! field offsets. Generating the header from a build script or test keeps
+//! it in sync with the Rust definitions, so that non-Rust readers of the
+//! format do not have to maintain a copy by hand.
+//!
+//! # Example
+//! ```
+//! use watto::c_header::CHeader;
+//! # use watto::describe::{FieldLayout, LayoutKind, TypeLayout};
+//! # use watto::{Describe, Pod};
+//! #
+//! # #[derive(Clone, Copy)]
+//! # #[repr(C)]
+//! # struct Entry { addr: u64, len: u32 }
+//! # unsafe impl Pod for Entry {}
+//! # impl Describe for Entry {
+//! #     const LAYOUT: &'static TypeLayout = &TypeLayout {
+//! #         name: "Entry", size: 16, align: 8,
+//! #         kind: LayoutKind::Struct(&[
+//! #             FieldLayout { name: "addr", offset: 0, layout: u64::LAYOUT },
+//! #             FieldLayout { name: "len", offset: 8, layout: u32::LAYOUT },
+//! #         ]),
+//! #     };
+//! # }
+//!
+//! // `Entry` is a `#[derive(Describe)]`d struct with an `addr: u64` and `len: u32` field.
+//! let mut header = CHeader::new("WATTO_EXAMPLE_H");
+//! header.add::<Entry>();
+//!
+//! let header = header.to_string();
+//! assert!(header.contains("struct Entry {\n    uint64_t addr;\n    uint32_t len;\n};"));
+//! assert!(header.contains("_Static_assert(sizeof(struct Entry) == 16"));
+//! ```
+
+use std::fmt::{self, Write as _};
+use std::path::Path;
+use std::{fs, io};
+
+use crate::describe::{Describe, LayoutKind, TypeLayout};
+
+/// A C header with struct definitions of [`Describe`]d types.
+///
+/// The [`Display`](fmt::Display) implementation prints the header.
+#[derive(Debug, Clone)]
+pub struct CHeader {
+    guard: String,
+    structs: Vec<&'static TypeLayout>,
+}
+
+impl CHeader {
+    /// Creates a new empty header with the given include guard.
+    pub fn new(guard: impl Into<String>) -> Self {
+        Self {
+            guard: guard.into(),
+            structs: Vec::new(),
+        }
+    }
+
+    /// Adds the struct `T` to the header, along with all the structs it contains.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `T` is not a struct, or if a different struct with the same
+    /// name has been added before.
+    pub fn add<T: Describe>(&mut self) -> &mut Self {
+        let layout = T::LAYOUT;
+        assert!(
+            matches!(layout.kind, LayoutKind::Struct(_)),
+            "`{layout}` is not a struct"
+        );
+        self.add_layout(layout);
+        self
+    }
+
+    fn add_layout(&mut self, layout: &'static TypeLayout) {
+        match layout.kind {
+            LayoutKind::Primitive => {}
+            LayoutKind::Array { element, .. } => self.add_layout(element),
+            LayoutKind::Struct(fields) => {
+                if let Some(existing) = self.structs.iter().find(|s| s.name == layout.name) {
+                    assert!(
+                        *existing == layout,
+                        "conflicting definitions of struct `{}`",
+                        layout.name
+                    );
+                    return;
+                }
+                // Contained structs need to be defined first.
+                for field in fields {
+                    self.add_layout(field.layout);
+                }
+                self.structs.push(layout);
+            }
+        }
+    }
+
+    /// Writes the header to the file at `path`, unless it already has the same contents.
+    ///
+    /// This avoids needless rebuilds when used from a build script.
+    pub fn write_to_file(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        let path = path.as_ref();
+        let contents = self.to_string();
+        if fs::read_to_string(path).is_ok_and(|existing| existing == contents) {
+            return Ok(());
+        }
+        fs::write(path, contents)
+    }
+}
+
+/// Returns the C name of a primitive type.
+fn c_primitive(name: &str) -> &'static str {
+    match name {
+        "u8" => "uint8_t",
+        "u16" => "uint16_t",
+        "u32" => "uint32_t",
+        "u64" => "uint64_t",
+        "u128" => "unsigned __int128",
+        "usize" => "uintptr_t",
+        "i8" => "int8_t",
+        "i16" => "int16_t",
+        "i32" => "int32_t",
+        "i64" => "int64_t",
+        "i128" => "__int128",
+        "isize" => "intptr_t",
+        "f32" => "float",
+        "f64" => "double",
+        _ => panic!("unsupported primitive type `{name}`"),
+    }
+}
+
+/// Returns a valid C identifier for the field name, prefixing tuple struct indices.
+fn c_field_name(name: &str) -> String {
+    if name.starts_with(|c: char| c.is_ascii_digit()) {
+        format!("_{name}")
+    } else {
+        name.to_owned()
+    }
+}
+
+/// Writes a field declaration like `uint8_t name[4][2]`.
+fn write_field(f: &mut String, name: &str, mut layout: &TypeLayout) {
+    let mut dimensions = String::new();
+    while let LayoutKind::Array { element, len } = layout.kind {
+        write!(dimensions, "[{len}]").unwrap();
+        layout = element;
+    }
+    match layout.kind {
+        LayoutKind::Struct(_) => write!(f, "struct {}", layout.name).unwrap(),
+        _ => f.push_str(c_primitive(layout.name)),
+    }
+    write!(f, " {name}{dimensions}").unwrap();
+}
+
+impl fmt::Display for CHeader {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let guard = &self.guard;
+        writeln!(f, "/* This file is generated by watto, do not edit. */")?;
+        writeln!(f)?;
+        writeln!(f, "#ifndef {guard}")?;
+        writeln!(f, "#define {guard}")?;
+        writeln!(f)?;
+        writeln!(f, "#include <stddef.h>")?;
+        writeln!(f, "#include <stdint.h>")?;
+
+        for layout in &self.structs {
+            let LayoutKind::Struct(fields) = layout.kind else {
+                continue;
+            };
+            let name = layout.name;
+
+            writeln!(f)?;
+            writeln!(f, "struct {name} {{")?;
+            for field in fields {
+                let mut decl = String::new();
+                write_field(&mut decl, &c_field_name(field.name), field.layout);
+                writeln!(f, "    {decl};")?;
+            }
+            writeln!(f, "}};")?;
+            writeln!(f)?;
+            writeln!(
+                f,
+                "_Static_assert(sizeof(struct {name}) == {}, \"size of {name}\");",
+                layout.size
+            )?;
+            writeln!(
+                f,
+                "_Static_assert(_Alignof(struct {name}) == {}, \"alignment of {name}\");",
+                layout.align
+            )?;
+            for field in fields {
+                let field_name = c_field_name(field.name);
+                writeln!(
+                    f,
+                    "_Static_assert(offsetof(struct {name}, {field_name}) == {}, \"offset of {name}.{field_name}\");",
+                    field.offset
+                )?;
+            }
+        }
+
+        writeln!(f)?;
+        writeln!(f, "#endif /* {guard} */")
+    }
+}
@@ -0,0 +1,191 @@
+use core::mem;
+
+use crate::{Pod, PodCastError};
+
+/// Reinterprets a [`Pod`] value as another [`Pod`] type of the same size.
+///
+/// The sizes of `A` and `B` are checked at compile time.
+///
+/// # Example
+/// ```
+/// let bytes: [u8; 4] = watto::cast(1u32.to_be());
+/// assert_eq!(bytes, [0, 0, 0, 1]);
+/// ```
+pub fn cast<A: Pod, B: Pod>(a: A) -> B {
+    const {
+        assert!(
+            mem::size_of::<A>() == mem::size_of::<B>(),
+            "cast: the types need to have the same size"
+        )
+    };
+    let () = A::LAYOUT_CHECK;
+    let () = B::LAYOUT_CHECK;
+    // SAFETY:
+    // Both types have the same size, and `B` is a `Pod`, so any bit pattern is valid.
+    unsafe { mem::transmute_copy(&a) }
+}
+
+/// Reinterprets a reference to a [`Pod`] as a reference to another [`Pod`] type.
+///
+/// This checks that `A` and `B` have the same size and that `a` is properly
+/// aligned for `B`.
+pub fn try_cast_ref<A: Pod, B: Pod>(a: &A) -> Result<&B, PodCastError> {
+    B::try_ref_from_bytes(a.as_bytes())
+}
+
+/// Reinterprets a reference to a [`Pod`] as a reference to another [`Pod`] type.
+///
+/// # Panics
+///
+/// Panics if [`try_cast_ref`] fails.
+pub fn cast_ref<A: Pod, B: Pod>(a: &A) -> &B {
+    try_cast_ref(a).unwrap_or_else(|err| panic!("cast_ref: {err}"))
+}
+
+/// Reinterprets a mutable reference to a [`Pod`] as a mutable reference to
+/// another [`Pod`] type.
+///
+/// This checks that `A` and `B` have the same size and that `a` is properly
+/// aligned for `B`.
+pub fn try_cast_mut<A: Pod, B: Pod>(a: &mut A) -> Result<&mut B, PodCastError> {
+    B::try_mut_from_bytes(a.as_bytes_mut())
+}
+
+/// Reinterprets a mutable reference to a [`Pod`] as a mutable reference to
+/// another [`Pod`] type.
+///
+/// # Panics
+///
+/// Panics if [`try_cast_mut`] fails.
+pub fn cast_mut<A: Pod, B: Pod>(a: &mut A) -> &mut B {
+    try_cast_mut(a).unwrap_or_else(|err| panic!("cast_mut: {err}"))
+}
+
+/// Reinterprets a slice of [`Pod`]s as a slice of another [`Pod`] type.
+///
+/// This checks that the size of `a` in bytes is a multiple of the size of `B`,
+/// and that `a` is properly aligned for `B`. Empty slices can always be cast.
+///
+/// # Example
+/// ```
+/// use watto::PodCastError;
+///
+/// let values = [1u32, 2, 3, 4];
+/// let pairs: &[[u32; 2]] = watto::try_cast_slice(&values).unwrap();
+/// assert_eq!(pairs, [[1, 2], [3, 4]]);
+///
+/// let wide: Result<&[u64], _> = watto::try_cast_slice(&values[..3]);
+/// assert!(matches!(wide, Err(PodCastError::TrailingBytes { .. })));
+/// ```
+pub fn try_cast_slice<A: Pod, B: Pod>(a: &[A]) -> Result<&[B], PodCastError> {
+    if a.is_empty() {
+        return Ok(&[]);
+    }
+    B::try_slice_from_bytes(a.as_bytes())
+}
+
+/// Reinterprets a slice of [`Pod`]s as a slice of another [`Pod`] type.
+///
+/// # Panics
+///
+/// Panics if [`try_cast_slice`] fails.
+///
+/// # Example
+/// ```
+/// let values = [1u16.to_be(), 2u16.to_be()];
+/// let bytes: &[u8] = watto::cast_slice(&values);
+/// assert_eq!(bytes, [0, 1, 0, 2]);
+/// ```
+pub fn cast_slice<A: Pod, B: Pod>(a: &[A]) -> &[B] {
+    try_cast_slice(a).unwrap_or_else(|err| panic!("cast_slice: {err}"))
+}
+
+/// Reinterprets a mutable slice of [`Pod`]s as a mutable slice of another
+/// [`Pod`] type.
+///
+/// This checks the size and alignment like [`try_cast_slice`].
+pub fn try_cast_slice_mut<A: Pod, B: Pod>(a: &mut [A]) -> Result<&mut [B], PodCastError> {
+    if a.is_empty() {
+        return Ok(&mut []);
+    }
+    B::try_slice_from_bytes_mut(a.as_bytes_mut())
+}
+
+/// Reinterprets a mutable slice of [`Pod`]s as a mutable slice of another
+/// [`Pod`] type.
+///
+/// # Panics
+///
+/// Panics if [`try_cast_slice_mut`] fails.
+pub fn cast_slice_mut<A: Pod, B: Pod>(a: &mut [A]) -> &mut [B] {
+    try_cast_slice_mut(a).unwrap_or_else(|err| panic!("cast_slice_mut: {err}"))
+}
+
+/// Reinterprets a [`Vec`] of [`Pod`]s as a [`Vec`] of another [`Pod`] type,
+/// transferring ownership of the allocation without copying.
+///
+/// As the allocation has to be freed with the layout it was allocated with,
+/// this checks that `A` and `B` have the same alignment, and that both the
+/// length and capacity of `vec` in bytes are multiples of the size of `B`.
+/// On failure, the original `vec` is returned alongside the error.
+///
+/// A `Vec<u8>` can thus only be cast to types with an alignment of 1, like the
+/// [`endian`](crate::endian) types. Use [`cast_slice`] to borrow other types
+/// from it instead.
+///
+/// # Example
+/// ```
+/// use watto::endian::{LittleEndian, U32};
+/// use watto::PodCastError;
+///
+/// let bytes = vec![1u8, 0, 0, 0, 2, 0, 0, 0];
+/// let values: Vec<U32<LittleEndian>> = watto::try_cast_vec(bytes).unwrap();
+/// assert_eq!(values, [U32::new(1), U32::new(2)]);
+///
+/// let (err, bytes) = watto::try_cast_vec::<u8, u32>(vec![0; 8]).unwrap_err();
+/// assert_eq!(err, PodCastError::AlignmentMismatch { expected: 4, actual: 1 });
+/// assert_eq!(bytes.len(), 8);
+/// ```
+#[cfg(feature = "std")]
+pub fn try_cast_vec<A: Pod, B: Pod>(vec: Vec<A>) -> Result<Vec<B>, (PodCastError, Vec<A>)> {
+    assert_ne!(mem::size_of::<B>(), 0);
+    let () = A::LAYOUT_CHECK;
+    let () = B::LAYOUT_CHECK;
+
+    let elem_size = mem::size_of::<B>();
+    if mem::align_of::<A>() != mem::align_of::<B>() {
+        let err = PodCastError::AlignmentMismatch {
+            expected: mem::align_of::<B>(),
+            actual: mem::align_of::<A>(),
+        };
+        return Err((err, vec));
+    }
+    let len = vec.len() * mem::size_of::<A>();
+    if !len.is_multiple_of(elem_size) {
+        let err = PodCastError::TrailingBytes {
+            elem_size,
+            trailing: len % elem_size,
+        };
+        return Err((err, vec));
+    }
+    let capacity = vec.capacity() * mem::size_of::<A>();
+    if !capacity.is_multiple_of(elem_size) {
+        let err = PodCastError::CapacityMismatch {
+            elem_size,
+            capacity,
+        };
+        return Err((err, vec));
+    }
+
+    let mut vec = mem::ManuallyDrop::new(vec);
+    // SAFETY:
+    // The allocation keeps its alignment and its size in bytes, and `B` is a
+    // `Pod`, so the initialized bytes are valid elements.
+    Ok(unsafe {
+        Vec::from_raw_parts(
+            vec.as_mut_ptr() as *mut B,
+            len / elem_size,
+            capacity / elem_size,
+        )
+    })
+}
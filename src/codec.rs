@@ -0,0 +1,302 @@
+//! Compact encodings for integer columns.
+//!
+//! The delta codec encodes a sorted sequence of integers as the [`varint`]
+//! encoded differences between consecutive values. This works well for
+//! sequences like addresses or line offsets, which grow in small increments.
+//!
+//! The encoded sequence does not store its length, so it has to be stored
+//! separately, or the encoded bytes have to make up a whole section.
+//!
+//! The run-length codec encodes a column of [`Pod`] values as runs of equal
+//! values, which works well for columns that are overwhelmingly a single value.
+//! Runs are written into a [`Writer`](crate::Writer) with [`write_rle`], and
+//! read back zero-copy from a [`Reader`] with [`read_rle`].
+//!
+//! The decoding functions are available in `no_std` environments.
+//!
+//! # Example
+//! ```
+//! # #[cfg(feature = "std")] {
+//! use watto::codec;
+//!
+//! let addresses = [0x1000u64, 0x1004, 0x1010, 0x1011];
+//! let mut buffer = vec![];
+//! codec::encode_delta(&addresses, &mut buffer);
+//! assert_eq!(buffer, [0x80, 0x20, 0x04, 0x0c, 0x01]);
+//!
+//! let mut decoded = [0u64; 4];
+//! codec::decode_delta_into(&buffer, &mut decoded).unwrap();
+//! assert_eq!(decoded, addresses);
+//!
+//! let decoded: Result<Vec<u64>, _> = codec::DeltaIter::new(&buffer).collect();
+//! assert_eq!(decoded.unwrap(), addresses);
+//! # }
+//! ```
+
+use core::fmt;
+use core::marker::PhantomData;
+
+use crate::{varint, Pod, Reader};
+
+mod sealed {
+    pub trait Sealed {}
+    impl Sealed for u32 {}
+    impl Sealed for u64 {}
+}
+
+/// An integer type which can be delta encoded, either `u32` or `u64`.
+pub trait DeltaInt: Copy + Ord + sealed::Sealed {
+    #[doc(hidden)]
+    fn to_u64(self) -> u64;
+    #[doc(hidden)]
+    fn from_u64(value: u64) -> Option<Self>;
+}
+
+impl DeltaInt for u32 {
+    fn to_u64(self) -> u64 {
+        self as u64
+    }
+
+    fn from_u64(value: u64) -> Option<Self> {
+        value.try_into().ok()
+    }
+}
+
+impl DeltaInt for u64 {
+    fn to_u64(self) -> u64 {
+        self
+    }
+
+    fn from_u64(value: u64) -> Option<Self> {
+        Some(value)
+    }
+}
+
+/// An error when decoding an encoded sequence.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct DecodeError {
+    /// The byte offset of the invalid value within the encoded sequence.
+    pub offset: usize,
+}
+
+impl fmt::Display for DecodeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "invalid encoded value at offset {}", self.offset)
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for DecodeError {}
+
+/// Appends the delta encoding of the sorted `values` to `buffer`.
+///
+/// Returns the number of bytes written.
+///
+/// # Panics
+///
+/// Panics if `values` is not sorted in ascending order.
+#[cfg(feature = "std")]
+pub fn encode_delta<T: DeltaInt>(values: &[T], buffer: &mut Vec<u8>) -> usize {
+    let start = buffer.len();
+    let mut prev = 0;
+    for value in values {
+        let value = value.to_u64();
+        if value < prev {
+            panic!("encode_delta: values are not sorted");
+        }
+        varint::write_u64(buffer, value - prev);
+        prev = value;
+    }
+    buffer.len() - start
+}
+
+/// Decodes exactly `out.len()` delta encoded values from the start of `bytes` into `out`.
+///
+/// Returns the trailing bytes following the decoded values.
+pub fn decode_delta_into<'data, T: DeltaInt>(
+    bytes: &'data [u8],
+    out: &mut [T],
+) -> Result<&'data [u8], DecodeError> {
+    let mut iter = DeltaIter::new(bytes);
+    for slot in out {
+        *slot = match iter.next() {
+            Some(value) => value?,
+            None => {
+                return Err(DecodeError {
+                    offset: iter.offset,
+                })
+            }
+        };
+    }
+    Ok(iter.remaining())
+}
+
+/// An iterator decoding a sequence of delta encoded values.
+///
+/// The iterator yields values until `bytes` is exhausted. After the first
+/// error, it does not yield any more values.
+#[derive(Debug, Clone)]
+pub struct DeltaIter<'data, T> {
+    bytes: &'data [u8],
+    offset: usize,
+    prev: u64,
+    _t: PhantomData<T>,
+}
+
+impl<'data, T: DeltaInt> DeltaIter<'data, T> {
+    /// Creates an iterator decoding the delta encoded values in `bytes`.
+    pub fn new(bytes: &'data [u8]) -> Self {
+        Self {
+            bytes,
+            offset: 0,
+            prev: 0,
+            _t: PhantomData,
+        }
+    }
+
+    /// Returns the bytes which have not been decoded yet.
+    pub fn remaining(&self) -> &'data [u8] {
+        &self.bytes[self.offset..]
+    }
+}
+
+impl<T: DeltaInt> Iterator for DeltaIter<'_, T> {
+    type Item = Result<T, DecodeError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let bytes = self.remaining();
+        if bytes.is_empty() {
+            return None;
+        }
+
+        let value = varint::read_u64(bytes).and_then(|(delta, len)| {
+            let value = self.prev.checked_add(delta)?;
+            Some((T::from_u64(value)?, value, len))
+        });
+        match value {
+            Some((result, value, len)) => {
+                self.prev = value;
+                self.offset += len;
+                Some(Ok(result))
+            }
+            None => {
+                let err = DecodeError {
+                    offset: self.offset,
+                };
+                self.offset = self.bytes.len();
+                Some(Err(err))
+            }
+        }
+    }
+}
+
+impl<T: DeltaInt> core::iter::FusedIterator for DeltaIter<'_, T> {}
+
+/// Writes `values` run-length encoded into the given [`Writer`](crate::Writer).
+///
+/// The encoding consists of the number of runs as a `u32`, followed by the
+/// aligned slice of run values, and the aligned slice of `u32` run lengths.
+/// Runs longer than `u32::MAX` are split.
+///
+/// Returns the number of runs written.
+///
+/// # Example
+/// ```
+/// use watto::{codec, Reader, Writer};
+///
+/// let flags = [0u8, 0, 0, 0, 1, 0, 0];
+/// let mut writer = Writer::new(vec![]);
+/// codec::write_rle(&mut writer, &flags).unwrap();
+/// let buffer = writer.into_inner();
+///
+/// let runs = codec::read_rle::<u8>(&mut Reader::new(&buffer)).unwrap();
+/// assert_eq!(runs.values(), &[0, 1, 0]);
+/// assert_eq!(runs.counts(), &[4, 1, 2]);
+/// assert_eq!(runs.len(), 7);
+/// assert!(runs.iter().eq(flags.iter()));
+/// ```
+#[cfg(feature = "writer")]
+pub fn write_rle<T, W>(writer: &mut crate::Writer<W>, values: &[T]) -> std::io::Result<usize>
+where
+    T: Pod + PartialEq,
+    W: std::io::Write,
+{
+    use std::io::Write;
+
+    let mut runs: Vec<&T> = Vec::new();
+    let mut counts: Vec<u32> = Vec::new();
+    for value in values {
+        match (runs.last(), counts.last_mut()) {
+            (Some(last), Some(count)) if *last == value && *count < u32::MAX => *count += 1,
+            _ => {
+                runs.push(value);
+                counts.push(1);
+            }
+        }
+    }
+
+    let num_runs = u32::try_from(runs.len())
+        .map_err(|_| std::io::Error::new(std::io::ErrorKind::InvalidInput, "too many runs"))?;
+    writer.align_to_type::<u32>()?;
+    writer.write_all(num_runs.as_bytes())?;
+    writer.align_to_type::<T>()?;
+    for value in &runs {
+        writer.write_all(value.as_bytes())?;
+    }
+    writer.align_to_type::<u32>()?;
+    writer.write_all(counts.as_bytes())?;
+
+    Ok(runs.len())
+}
+
+/// Reads run-length encoded values written by [`write_rle`] from the given [`Reader`].
+///
+/// On failure, the position of the reader is unspecified.
+pub fn read_rle<'data, T: Pod>(reader: &mut Reader<'data>) -> Option<Runs<'data, T>> {
+    reader.align_to_type::<u32>()?;
+    let num_runs = *reader.read_ref::<u32>()? as usize;
+    reader.align_to_type::<T>()?;
+    let values = reader.read_slice::<T>(num_runs)?;
+    reader.align_to_type::<u32>()?;
+    let counts = reader.read_slice::<u32>(num_runs)?;
+
+    Some(Runs { values, counts })
+}
+
+/// A zero-copy view of run-length encoded values, read by [`read_rle`].
+#[derive(Debug, Clone, Copy)]
+pub struct Runs<'data, T> {
+    values: &'data [T],
+    counts: &'data [u32],
+}
+
+impl<'data, T> Runs<'data, T> {
+    /// Returns the value of each run.
+    pub fn values(&self) -> &'data [T] {
+        self.values
+    }
+
+    /// Returns the length of each run.
+    pub fn counts(&self) -> &'data [u32] {
+        self.counts
+    }
+
+    /// Returns the total number of decoded values.
+    pub fn len(&self) -> usize {
+        self.counts.iter().map(|count| *count as usize).sum()
+    }
+
+    /// Returns `true` if there are no values.
+    pub fn is_empty(&self) -> bool {
+        self.counts.iter().all(|count| *count == 0)
+    }
+
+    /// Iterates over all the decoded values.
+    pub fn iter(&self) -> impl Iterator<Item = &'data T> + 'data {
+        self.values
+            .iter()
+            .zip(self.counts)
+            .flat_map(|(value, count)| core::iter::repeat_n(value, *count as usize))
+    }
+}
@@ -0,0 +1,226 @@
+use std::io;
+
+use thiserror::Error;
+
+use crate::{AlignedVec, AlignedVecError, Pod};
+
+/// The codec used to compress a section written with [`write_compressed_section`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum SectionCodec {
+    /// The section is stored uncompressed.
+    None,
+    /// The section is compressed with zstd at the given level, `0` being the zstd default.
+    Zstd(i32),
+}
+
+impl SectionCodec {
+    #[cfg(feature = "writer")]
+    fn id(self) -> u8 {
+        match self {
+            Self::None => 0,
+            Self::Zstd(_) => 1,
+        }
+    }
+}
+
+/// The header preceding the data of a compressed section.
+#[derive(Debug, Clone, Copy)]
+#[repr(C)]
+struct CompressedSectionHeader {
+    codec: u8,
+    reserved: [u8; 3],
+    align: u32,
+    uncompressed_len: u64,
+    compressed_len: u64,
+}
+unsafe impl Pod for CompressedSectionHeader {}
+
+/// An error when trying to read a compressed section.
+#[derive(Debug, Error)]
+pub enum CompressedSectionError {
+    /// The section header or data is truncated or misaligned.
+    #[error("compressed section is truncated")]
+    Truncated,
+    /// The section uses an unknown codec.
+    #[error("unknown section codec {0}")]
+    UnknownCodec(u8),
+    /// The recorded alignment is not a power-of-two.
+    #[error("invalid section alignment {0}")]
+    InvalidAlignment(u32),
+    /// The section data could not be decompressed.
+    #[error("error decompressing section data")]
+    Decompress(#[source] io::Error),
+    /// The decompressed data does not have the recorded length.
+    #[error("decompressed section length does not match")]
+    LengthMismatch,
+    /// The recorded uncompressed length exceeds the given maximum.
+    #[error("section of {len} bytes exceeds the maximum of {max} bytes")]
+    TooLarge {
+        /// The recorded uncompressed length.
+        len: u64,
+        /// The maximum length.
+        max: usize,
+    },
+    /// The buffer for the uncompressed section could not be allocated.
+    #[error(transparent)]
+    Alloc(#[from] AlignedVecError),
+}
+
+/// Writes `data` as a compressed section into the given [`Writer`](crate::Writer).
+///
+/// The section header records the codec, the uncompressed length, and the
+/// required alignment `align` of the uncompressed data, which is restored by
+/// [`read_compressed_section`].
+///
+/// # Example
+/// ```
+/// use watto::{read_compressed_section, write_compressed_section, Pod, SectionCodec, Writer};
+///
+/// let numbers: Vec<u64> = (0..1000).collect();
+///
+/// let mut writer = Writer::new(vec![]);
+/// write_compressed_section(&mut writer, numbers.as_bytes(), 8, SectionCodec::Zstd(0)).unwrap();
+/// let buffer = writer.into_inner();
+/// assert!(buffer.len() < numbers.as_bytes().len());
+///
+/// let (section, _rest) = read_compressed_section(&buffer).unwrap();
+/// assert_eq!(u64::slice_from_bytes(&section).unwrap(), numbers);
+/// ```
+#[cfg(feature = "writer")]
+pub fn write_compressed_section<W: io::Write>(
+    writer: &mut crate::Writer<W>,
+    data: &[u8],
+    align: usize,
+    codec: SectionCodec,
+) -> io::Result<()> {
+    use io::Write;
+
+    crate::trace::timed_span!("write_compressed_section", size = data.len());
+
+    let align_u32 = u32::try_from(align)
+        .ok()
+        .filter(|align| align.is_power_of_two())
+        .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "invalid alignment"))?;
+
+    let compressed;
+    let payload = match codec {
+        SectionCodec::None => data,
+        SectionCodec::Zstd(level) => {
+            compressed = zstd::bulk::compress(data, level)?;
+            &compressed[..]
+        }
+    };
+
+    let header = CompressedSectionHeader {
+        codec: codec.id(),
+        reserved: [0; 3],
+        align: align_u32,
+        uncompressed_len: data.len() as u64,
+        compressed_len: payload.len() as u64,
+    };
+    crate::trace::event!(compressed_size = payload.len(), "compressed section");
+    writer.align_to_type::<CompressedSectionHeader>()?;
+    writer.write_all(header.as_bytes())?;
+    writer.write_all(payload)
+}
+
+/// Reads a section written by [`write_compressed_section`] from the start of `buffer`,
+/// skipping any padding preceding it.
+///
+/// The section is decompressed into an [`AlignedVec`] with the recorded
+/// alignment. Returns the section and the trailing bytes.
+///
+/// The uncompressed length recorded in the header is checked against the
+/// payload before allocating, but a zstd payload can still claim a large
+/// length. Use [`read_compressed_section_with_limit`] for untrusted input.
+pub fn read_compressed_section(
+    buffer: &[u8],
+) -> Result<(AlignedVec, &[u8]), CompressedSectionError> {
+    read_compressed_section_with_limit(buffer, usize::MAX)
+}
+
+/// Reads a section like [`read_compressed_section`], failing if its
+/// uncompressed length exceeds `max_len` bytes.
+///
+/// # Example
+/// ```
+/// # #[cfg(feature = "writer")] {
+/// use watto::{
+///     read_compressed_section_with_limit, write_compressed_section, CompressedSectionError,
+///     SectionCodec, Writer,
+/// };
+///
+/// let mut writer = Writer::new(vec![]);
+/// write_compressed_section(&mut writer, &[0; 4096], 1, SectionCodec::Zstd(0)).unwrap();
+/// let buffer = writer.into_inner();
+///
+/// assert!(read_compressed_section_with_limit(&buffer, 4096).is_ok());
+/// assert!(matches!(
+///     read_compressed_section_with_limit(&buffer, 1024),
+///     Err(CompressedSectionError::TooLarge { len: 4096, max: 1024 })
+/// ));
+/// # }
+/// ```
+pub fn read_compressed_section_with_limit(
+    buffer: &[u8],
+    max_len: usize,
+) -> Result<(AlignedVec, &[u8]), CompressedSectionError> {
+    crate::trace::timed_span!("read_compressed_section");
+
+    let (_, buffer) = crate::align_to_type::<CompressedSectionHeader>(buffer)
+        .ok_or(CompressedSectionError::Truncated)?;
+    let (header, rest) = CompressedSectionHeader::ref_from_prefix(buffer)
+        .ok_or(CompressedSectionError::Truncated)?;
+    if !header.align.is_power_of_two() {
+        return Err(CompressedSectionError::InvalidAlignment(header.align));
+    }
+    let compressed_len =
+        usize::try_from(header.compressed_len).map_err(|_| CompressedSectionError::Truncated)?;
+    if compressed_len > rest.len() {
+        return Err(CompressedSectionError::Truncated);
+    }
+    let (payload, rest) = rest.split_at(compressed_len);
+
+    let uncompressed_len = usize::try_from(header.uncompressed_len)
+        .ok()
+        .filter(|&len| len <= max_len)
+        .ok_or(CompressedSectionError::TooLarge {
+            len: header.uncompressed_len,
+            max: max_len,
+        })?;
+
+    // Check the recorded length against the payload before allocating it.
+    match header.codec {
+        0 => {
+            if payload.len() != uncompressed_len {
+                return Err(CompressedSectionError::LengthMismatch);
+            }
+        }
+        1 => {
+            let content_size = zstd::zstd_safe::get_frame_content_size(payload).map_err(|_| {
+                CompressedSectionError::Decompress(io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    "invalid zstd frame header",
+                ))
+            })?;
+            if content_size.is_some_and(|size| size != header.uncompressed_len) {
+                return Err(CompressedSectionError::LengthMismatch);
+            }
+        }
+        codec => return Err(CompressedSectionError::UnknownCodec(codec)),
+    }
+
+    let mut section = AlignedVec::try_zeroed(uncompressed_len, header.align as usize)?;
+    if header.codec == 0 {
+        section.copy_from_slice(payload);
+    } else {
+        let len = zstd::bulk::decompress_to_buffer(payload, &mut section[..])
+            .map_err(CompressedSectionError::Decompress)?;
+        if len != uncompressed_len {
+            return Err(CompressedSectionError::LengthMismatch);
+        }
+    }
+
+    Ok((section, rest))
+}
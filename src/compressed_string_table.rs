@@ -0,0 +1,227 @@
+use std::collections::TryReserveError;
+use std::io;
+
+use thiserror::Error;
+
+use crate::{varint, OffsetSet, ReadOffsetSetError, ReadStringError};
+
+const RAW: u8 = 0;
+const ZSTD: u8 = 1;
+
+/// Checks the flag byte and the uncompressed length prefix of a
+/// [`CompressedStringTable`] entry, without decompressing it.
+pub(crate) fn is_valid_entry_encoding(entry: &[u8]) -> bool {
+    match entry.split_first() {
+        Some((&RAW, data)) => std::str::from_utf8(data).is_ok(),
+        Some((&ZSTD, data)) => varint::read_u64(data).is_some(),
+        _ => false,
+    }
+}
+
+/// An error when trying to read a string from a serialized [`CompressedStringTable`].
+#[derive(Debug, Error)]
+pub enum ReadCompressedStringError {
+    /// The string could not be read from the table.
+    #[error("error reading string")]
+    Read(#[from] ReadStringError),
+    /// The string entry has an unknown encoding flag or is missing it.
+    #[error("invalid string encoding")]
+    InvalidEncoding,
+    /// The compressed string data could not be decompressed.
+    #[error("error decompressing string data")]
+    Decompress(#[source] io::Error),
+    /// The recorded uncompressed length exceeds the given maximum.
+    #[error("string of {len} bytes exceeds the maximum of {max} bytes")]
+    TooLarge {
+        /// The recorded uncompressed length.
+        len: u64,
+        /// The maximum length.
+        max: usize,
+    },
+    /// The buffer for the uncompressed string could not be allocated.
+    #[error("failed to allocate the uncompressed string")]
+    Alloc(#[source] TryReserveError),
+}
+
+impl From<ReadOffsetSetError> for ReadCompressedStringError {
+    fn from(value: ReadOffsetSetError) -> Self {
+        Self::Read(value.into())
+    }
+}
+
+/// A [`StringTable`](crate::StringTable) which stores long strings compressed with zstd.
+///
+/// Strings with a length of at least the configured threshold are stored
+/// compressed, and are transparently decompressed into a caller provided
+/// buffer when [`read`](Self::read) back.
+///
+/// Each entry is prefixed with a flag byte signalling whether it is compressed.
+/// The serialized representation is thus *not* compatible with [`StringTable`](crate::StringTable).
+///
+/// # Example
+/// ```
+/// use watto::CompressedStringTable;
+///
+/// let long = "watto ".repeat(100);
+///
+/// let mut table = CompressedStringTable::new(64);
+/// let short_offset = table.insert("foo");
+/// let long_offset = table.insert(&long);
+/// assert!(table.as_bytes().len() < long.len());
+///
+/// let mut scratch = Vec::new();
+/// let buffer = table.as_bytes();
+/// assert_eq!(CompressedStringTable::read(buffer, short_offset, &mut scratch).unwrap(), "foo");
+/// assert_eq!(CompressedStringTable::read(buffer, long_offset, &mut scratch).unwrap(), long);
+/// ```
+#[derive(Debug, Clone)]
+pub struct CompressedStringTable {
+    inner: OffsetSet<u8>,
+    threshold: usize,
+    level: i32,
+    scratch: Vec<u8>,
+}
+
+impl CompressedStringTable {
+    /// Initializes an empty [`CompressedStringTable`], which compresses all
+    /// strings with a length of at least `threshold` bytes.
+    pub fn new(threshold: usize) -> Self {
+        Self {
+            inner: Default::default(),
+            threshold,
+            level: 0,
+            scratch: Default::default(),
+        }
+    }
+
+    /// Sets the zstd compression level, `0` being the zstd default.
+    pub fn set_level(&mut self, level: i32) {
+        self.level = level;
+    }
+
+    /// Insert a string into this [`CompressedStringTable`].
+    ///
+    /// Returns an offset that can be used to retrieve the inserted string
+    /// with [`read`](Self::read) after serializing this table with [`as_bytes`](Self::as_bytes).
+    pub fn insert(&mut self, s: &str) -> usize {
+        self.scratch.clear();
+        if s.len() >= self.threshold {
+            self.scratch.push(ZSTD);
+            varint::write_u64(&mut self.scratch, s.len() as u64);
+
+            let start = self.scratch.len();
+            self.scratch
+                .resize(start + zstd::zstd_safe::compress_bound(s.len()), 0);
+            let compressed_len = zstd::bulk::compress_to_buffer(
+                s.as_bytes(),
+                &mut self.scratch[start..],
+                self.level,
+            )
+            .expect("compression into a sufficiently sized buffer can not fail");
+            self.scratch.truncate(start + compressed_len);
+        } else {
+            self.scratch.push(RAW);
+            self.scratch.extend_from_slice(s.as_bytes());
+        }
+
+        self.inner.insert(&self.scratch)
+    }
+
+    /// Returns a byte slice containing the serialized representation of this table.
+    pub fn as_bytes(&self) -> &[u8] {
+        self.inner.as_bytes()
+    }
+
+    /// Returns a byte vector containing the serialized representation of this table.
+    ///
+    /// This consumes the [`CompressedStringTable`].
+    pub fn into_bytes(self) -> Vec<u8> {
+        self.inner.into_bytes()
+    }
+
+    /// Returns the string stored at the given offset in the byte slice.
+    ///
+    /// Uncompressed strings are returned directly from `buffer`, whereas
+    /// compressed strings are decompressed into `scratch`.
+    ///
+    /// The uncompressed length recorded in `buffer` is trusted up to the size
+    /// of the zstd frame, use [`read_with_limit`](Self::read_with_limit) to
+    /// read untrusted input.
+    pub fn read<'data>(
+        buffer: &'data [u8],
+        offset: usize,
+        scratch: &'data mut Vec<u8>,
+    ) -> Result<&'data str, ReadCompressedStringError> {
+        Self::read_with_limit(buffer, offset, scratch, usize::MAX)
+    }
+
+    /// Returns the string stored at the given offset in the byte slice,
+    /// refusing to decompress strings longer than `max_len` bytes.
+    ///
+    /// The recorded uncompressed length is checked against the limit and the
+    /// zstd frame header before allocating, so a malformed table can not
+    /// trigger huge allocations.
+    ///
+    /// # Example
+    /// ```
+    /// use watto::{CompressedStringTable, ReadCompressedStringError};
+    ///
+    /// let mut table = CompressedStringTable::new(16);
+    /// let offset = table.insert(&"watto".repeat(10));
+    /// let buffer = table.into_bytes();
+    ///
+    /// let mut scratch = Vec::new();
+    /// assert!(matches!(
+    ///     CompressedStringTable::read_with_limit(&buffer, offset, &mut scratch, 32),
+    ///     Err(ReadCompressedStringError::TooLarge { len: 50, max: 32 })
+    /// ));
+    /// ```
+    pub fn read_with_limit<'data>(
+        buffer: &'data [u8],
+        offset: usize,
+        scratch: &'data mut Vec<u8>,
+        max_len: usize,
+    ) -> Result<&'data str, ReadCompressedStringError> {
+        let entry = OffsetSet::<u8>::read(buffer, offset)?;
+        let (flag, data) = entry
+            .split_first()
+            .ok_or(ReadCompressedStringError::InvalidEncoding)?;
+
+        let bytes = match *flag {
+            RAW => data,
+            ZSTD => {
+                let (recorded_len, len_len) =
+                    varint::try_read_u64(data).map_err(ReadOffsetSetError::from)?;
+                let len = usize::try_from(recorded_len)
+                    .ok()
+                    .filter(|&len| len <= max_len)
+                    .ok_or(ReadCompressedStringError::TooLarge {
+                        len: recorded_len,
+                        max: max_len,
+                    })?;
+                let data = &data[len_len..];
+
+                let content_size = zstd::zstd_safe::get_frame_content_size(data)
+                    .map_err(|_| ReadCompressedStringError::InvalidEncoding)?;
+                if content_size.is_some_and(|size| size != recorded_len) {
+                    return Err(ReadCompressedStringError::InvalidEncoding);
+                }
+
+                scratch.clear();
+                scratch
+                    .try_reserve_exact(len)
+                    .map_err(ReadCompressedStringError::Alloc)?;
+                scratch.resize(len, 0);
+                let decompressed_len = zstd::bulk::decompress_to_buffer(data, scratch)
+                    .map_err(ReadCompressedStringError::Decompress)?;
+                if decompressed_len != len {
+                    return Err(ReadCompressedStringError::InvalidEncoding);
+                }
+                &scratch[..]
+            }
+            _ => return Err(ReadCompressedStringError::InvalidEncoding),
+        };
+
+        Ok(std::str::from_utf8(bytes).map_err(ReadStringError::from)?)
+    }
+}
@@ -0,0 +1,132 @@
+use core::hash::BuildHasher;
+use std::sync::Mutex;
+
+use hashbrown::DefaultHashBuilder;
+
+use crate::StringTable;
+
+/// The offset of a string within one shard of a [`ConcurrentStringTable`].
+///
+/// This can be resolved into an offset within the merged table using
+/// [`ShardOffsets::resolve`] once the table has been serialized.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ShardedOffset {
+    shard: u32,
+    offset: u32,
+}
+
+/// The start offsets of each shard of a merged [`ConcurrentStringTable`].
+///
+/// Use [`resolve`](Self::resolve) to translate a [`ShardedOffset`] into an
+/// offset that can be used with [`StringTable::read`].
+#[derive(Debug, Clone)]
+pub struct ShardOffsets {
+    starts: Vec<usize>,
+}
+
+impl ShardOffsets {
+    /// Translates a [`ShardedOffset`] into an offset within the merged buffer.
+    pub fn resolve(&self, offset: ShardedOffset) -> usize {
+        self.starts[offset.shard as usize] + offset.offset as usize
+    }
+}
+
+/// A [`StringTable`] which supports concurrent insertion from many threads.
+///
+/// Strings are distributed across a number of independently locked shards
+/// based on their hash, so each unique string is only ever stored in a single
+/// shard. This means that the concatenation of all the shards forms a valid
+/// [`StringTable`] buffer without duplicates.
+///
+/// # Example
+/// ```
+/// use watto::{ConcurrentStringTable, StringTable};
+///
+/// let table = ConcurrentStringTable::new();
+/// let (foo, bar) = std::thread::scope(|s| {
+///     let foo = s.spawn(|| table.insert("foo"));
+///     let bar = s.spawn(|| table.insert("bar"));
+///     (foo.join().unwrap(), bar.join().unwrap())
+/// });
+///
+/// let (buffer, shards) = table.into_bytes();
+/// assert_eq!(StringTable::read(&buffer, shards.resolve(foo)).unwrap(), "foo");
+/// assert_eq!(StringTable::read(&buffer, shards.resolve(bar)).unwrap(), "bar");
+/// ```
+#[derive(Debug)]
+pub struct ConcurrentStringTable {
+    hasher: DefaultHashBuilder,
+    shards: Box<[Mutex<StringTable>]>,
+}
+
+impl Default for ConcurrentStringTable {
+    fn default() -> Self {
+        let shards = std::thread::available_parallelism().map_or(1, |n| n.get());
+        Self::with_shards(shards * 4)
+    }
+}
+
+impl ConcurrentStringTable {
+    /// Initializes an empty [`ConcurrentStringTable`], with a number of shards
+    /// appropriate for the available parallelism.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Initializes an empty [`ConcurrentStringTable`] with the given number of shards.
+    pub fn with_shards(shards: usize) -> Self {
+        assert_ne!(shards, 0);
+        Self {
+            hasher: Default::default(),
+            shards: (0..shards).map(|_| Default::default()).collect(),
+        }
+    }
+
+    /// Insert a string into this [`ConcurrentStringTable`].
+    ///
+    /// Returns a [`ShardedOffset`] that can be resolved into an offset within
+    /// the merged buffer returned from [`into_bytes`](Self::into_bytes).
+    ///
+    /// # Panics
+    ///
+    /// Panics if a single shard grows beyond 4 GiB.
+    pub fn insert(&self, s: &str) -> ShardedOffset {
+        let shard = (self.hasher.hash_one(s) % self.shards.len() as u64) as usize;
+        let offset = self.shards[shard]
+            .lock()
+            .unwrap_or_else(|err| err.into_inner())
+            .insert(s);
+        ShardedOffset {
+            shard: shard as u32,
+            offset: offset.try_into().expect("shard exceeds 4 GiB"),
+        }
+    }
+
+    /// Merges all the shards into a single serialized [`StringTable`] buffer.
+    ///
+    /// Also returns the [`ShardOffsets`] needed to resolve the [`ShardedOffset`]s
+    /// returned from [`insert`](Self::insert).
+    pub fn into_bytes(self) -> (Vec<u8>, ShardOffsets) {
+        crate::trace::timed_span!(
+            "ConcurrentStringTable::into_bytes",
+            shards = self.shards.len()
+        );
+
+        let shards: Vec<_> = self
+            .shards
+            .into_vec()
+            .into_iter()
+            .map(|shard| shard.into_inner().unwrap_or_else(|err| err.into_inner()))
+            .collect();
+
+        let len = shards.iter().map(|shard| shard.as_bytes().len()).sum();
+        let mut buffer = Vec::with_capacity(len);
+        let mut starts = Vec::with_capacity(shards.len());
+        for shard in shards {
+            starts.push(buffer.len());
+            buffer.extend_from_slice(shard.as_bytes());
+        }
+
+        (buffer, ShardOffsets { starts })
+    }
+}
@@ -0,0 +1,224 @@
+use std::io::{self, Write};
+
+use thiserror::Error;
+
+use crate::{check_header, Header, HeaderError, Pod, ReadStringError, StringTable, Writer};
+
+/// The magic bytes of a serialized [`Container`].
+pub const CONTAINER_MAGIC: [u8; 4] = *b"WCNT";
+/// The major version of the [`Container`] format.
+pub const CONTAINER_VERSION: u16 = 1;
+
+/// The fixed-size part of the [`Container`] format, following the [`Header`].
+#[derive(Debug, Clone, Copy)]
+#[repr(C)]
+struct ContainerHeader {
+    num_sections: u32,
+    names_len: u32,
+}
+unsafe impl Pod for ContainerHeader {}
+
+/// An entry in the table of contents of a [`Container`].
+#[derive(Debug, Clone, Copy)]
+#[repr(C)]
+struct TocEntry {
+    /// The offset of the section name in the names [`StringTable`].
+    name_offset: u32,
+    /// The alignment of the section.
+    align: u32,
+    /// The offset of the section, relative to the start of the container.
+    offset: u64,
+    /// The length of the section in bytes.
+    len: u64,
+}
+unsafe impl Pod for TocEntry {}
+
+/// An error when trying to parse a [`Container`].
+#[derive(Debug, Error)]
+pub enum ContainerError {
+    /// The container header is invalid.
+    #[error("invalid container header")]
+    Header(#[from] HeaderError),
+    /// The table of contents is truncated.
+    #[error("container table of contents is truncated")]
+    InvalidToc,
+    /// A section name could not be read.
+    #[error("invalid section name")]
+    InvalidName(#[from] ReadStringError),
+    /// A section is out of bounds or not properly aligned.
+    #[error("section `{0}` is out of bounds or misaligned")]
+    InvalidSection(String),
+}
+
+/// A zero-copy reader for a container of named, aligned sections.
+///
+/// Containers are written with a [`ContainerWriter`]. The serialized format
+/// starts with a [`Header`], followed by a table of contents, a
+/// [`StringTable`] of section names, and finally the section data.
+///
+/// Section offsets and alignment are relative to the start of the container,
+/// so the buffer needs to be aligned to the largest section alignment in order
+/// for the sections to be properly aligned.
+///
+/// # Example
+/// ```
+/// use watto::{Container, ContainerWriter, Pod, Writer};
+///
+/// let mut container = ContainerWriter::new();
+/// container.add_section("numbers", 8, [1u64, 2, 3].as_bytes());
+/// container.add_section("name", 1, b"watto");
+///
+/// let mut writer = Writer::new(vec![]);
+/// container.write_to(&mut writer).unwrap();
+/// let buffer = writer.into_inner();
+///
+/// let container = Container::parse(&buffer).unwrap();
+/// let numbers = u64::slice_from_bytes(container.section("numbers").unwrap()).unwrap();
+/// assert_eq!(numbers, &[1, 2, 3]);
+/// assert_eq!(container.section("name"), Some(&b"watto"[..]));
+/// assert_eq!(container.section("missing"), None);
+/// ```
+#[derive(Debug, Clone)]
+pub struct Container<'data> {
+    buffer: &'data [u8],
+    toc: &'data [TocEntry],
+    names: &'data [u8],
+}
+
+impl<'data> Container<'data> {
+    /// Parses a serialized [`Container`], validating its table of contents.
+    pub fn parse(buffer: &'data [u8]) -> Result<Self, ContainerError> {
+        let (_header, rest) = check_header(buffer, CONTAINER_MAGIC, CONTAINER_VERSION)?;
+        let (container_header, rest) =
+            ContainerHeader::ref_from_prefix(rest).ok_or(ContainerError::InvalidToc)?;
+        let (toc, rest) = TocEntry::slice_from_prefix(rest, container_header.num_sections as usize)
+            .ok_or(ContainerError::InvalidToc)?;
+        let names = rest
+            .get(..container_header.names_len as usize)
+            .ok_or(ContainerError::InvalidToc)?;
+
+        let container = Self { buffer, toc, names };
+        for entry in toc {
+            let name = StringTable::read(names, entry.name_offset as usize)?;
+            container
+                .section_data(entry)
+                .ok_or_else(|| ContainerError::InvalidSection(name.into()))?;
+        }
+
+        Ok(container)
+    }
+
+    fn section_data(&self, entry: &TocEntry) -> Option<&'data [u8]> {
+        let start = usize::try_from(entry.offset).ok()?;
+        let len = usize::try_from(entry.len).ok()?;
+        let align = entry.align as usize;
+        if !align.is_power_of_two() || start % align != 0 {
+            return None;
+        }
+        self.buffer.get(start..start.checked_add(len)?)
+    }
+
+    /// Returns the data of the section with the given name, if any.
+    pub fn section(&self, name: &str) -> Option<&'data [u8]> {
+        self.sections()
+            .find(|(section_name, _)| *section_name == name)
+            .map(|(_, data)| data)
+    }
+
+    /// Iterates over all the sections in this container, yielding `(name, data)` pairs.
+    pub fn sections(&self) -> impl Iterator<Item = (&'data str, &'data [u8])> + '_ {
+        self.toc.iter().map(|entry| {
+            // The names and bounds have been validated in `parse`.
+            let name = StringTable::read(self.names, entry.name_offset as usize).unwrap();
+            (name, self.section_data(entry).unwrap())
+        })
+    }
+}
+
+/// A writer for a [`Container`] of named, aligned sections.
+#[derive(Debug, Clone, Default)]
+pub struct ContainerWriter {
+    names: StringTable,
+    sections: Vec<(u32, u32, Vec<u8>)>,
+}
+
+impl ContainerWriter {
+    /// Creates a new empty [`ContainerWriter`].
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a section with the given name, alignment and data.
+    ///
+    /// Adding a section with the name of an existing section replaces it.
+    pub fn add_section(&mut self, name: &str, align: usize, data: impl Into<Vec<u8>>) {
+        if !align.is_power_of_two() {
+            panic!("add_section: align is not a power-of-two");
+        }
+        let name_offset = self.names.insert(name) as u32;
+        let align = align as u32;
+        let data = data.into();
+
+        match self.sections.iter_mut().find(|s| s.0 == name_offset) {
+            Some(section) => *section = (name_offset, align, data),
+            None => self.sections.push((name_offset, align, data)),
+        }
+    }
+
+    /// Writes the container into the given [`Writer`].
+    ///
+    /// The writer is first aligned to the largest section alignment, so that
+    /// all the sections are properly aligned in the output.
+    pub fn write_to<W: Write>(&self, writer: &mut Writer<W>) -> io::Result<()> {
+        crate::trace::timed_span!("ContainerWriter::write_to", sections = self.sections.len());
+
+        let max_align = self
+            .sections
+            .iter()
+            .map(|section| section.1 as usize)
+            .chain([core::mem::align_of::<TocEntry>()])
+            .max()
+            .unwrap_or(1);
+        writer.align_to(max_align)?;
+
+        let names = self.names.as_bytes();
+        let mut offset = core::mem::size_of::<Header>()
+            + core::mem::size_of::<ContainerHeader>()
+            + self.sections.len() * core::mem::size_of::<TocEntry>()
+            + names.len();
+
+        let mut toc = Vec::with_capacity(self.sections.len());
+        for (name_offset, align, data) in &self.sections {
+            offset = offset.next_multiple_of(*align as usize);
+            toc.push(TocEntry {
+                name_offset: *name_offset,
+                align: *align,
+                offset: offset as u64,
+                len: data.len() as u64,
+            });
+            offset += data.len();
+        }
+
+        let header = Header::new(CONTAINER_MAGIC, CONTAINER_VERSION, 0);
+        writer.write_all(header.as_bytes())?;
+        let container_header = ContainerHeader {
+            num_sections: self.sections.len() as u32,
+            names_len: names.len() as u32,
+        };
+        writer.write_all(container_header.as_bytes())?;
+        writer.write_all(toc.as_bytes())?;
+        writer.write_all(names)?;
+
+        // As the container start is aligned to the largest section alignment,
+        // aligning the writer yields the same padding as computed above.
+        for (name_offset, align, data) in &self.sections {
+            if let Ok(name) = StringTable::read(names, *name_offset as usize) {
+                writer.begin_section(name);
+            }
+            writer.align_to(*align as usize)?;
+            writer.write_all(data)?;
+        }
+
+        Ok(())
+    }
+}
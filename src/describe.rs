@@ -0,0 +1,201 @@
+//! Static layout descriptions of [`Pod`] types.
+//!
+//! Types implementing [`Describe`] expose a [`TypeLayout`] with the names,
+//! offsets, sizes and types of their fields. This allows generic tools like
+//! debug dumpers, schema documentation, or the [`annotate`](crate::annotate)
+//! module to work on user types without registering them manually.
+//!
+//! With the `derive` feature, [`Describe`] can be derived for `#[repr(C)]` structs.
+//!
+//! # Example
+//! ```
+//! # #[cfg(feature = "derive")] {
+//! use watto::describe::{describe, LayoutKind};
+//! use watto::{Describe, Pod};
+//!
+//! #[derive(Clone, Copy, Describe)]
+//! #[repr(C)]
+//! struct Entry {
+//!     addr: u64,
+//!     len: u32,
+//!     flags: [u8; 2],
+//! }
+//! unsafe impl Pod for Entry {}
+//!
+//! let layout = describe::<Entry>();
+//! assert_eq!(layout.name, "Entry");
+//! assert_eq!(layout.size, 16);
+//!
+//! let LayoutKind::Struct(fields) = layout.kind else { unreachable!() };
+//! assert_eq!(fields[1].name, "len");
+//! assert_eq!(fields[1].offset, 8);
+//! assert_eq!(fields[2].layout.to_string(), "[u8; 2]");
+//! # }
+//! ```
+
+use core::{fmt, mem};
+
+use crate::Pod;
+
+/// The layout of a [`Describe`]d type.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TypeLayout {
+    /// The name of the type.
+    ///
+    /// Arrays are named `array`, the [`Display`](fmt::Display) implementation
+    /// prints their full name like `[u8; 2]`.
+    pub name: &'static str,
+    /// The size of the type in bytes.
+    pub size: usize,
+    /// The alignment of the type in bytes.
+    pub align: usize,
+    /// The kind of the type.
+    pub kind: LayoutKind,
+}
+
+impl fmt::Display for TypeLayout {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.kind {
+            LayoutKind::Array { element, len } => write!(f, "[{element}; {len}]"),
+            _ => f.write_str(self.name),
+        }
+    }
+}
+
+/// The kind of a [`TypeLayout`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LayoutKind {
+    /// A primitive integer or floating point type.
+    Primitive,
+    /// A fixed-size array.
+    Array {
+        /// The layout of the array elements.
+        element: &'static TypeLayout,
+        /// The number of elements.
+        len: usize,
+    },
+    /// A struct with the given fields, in declaration order.
+    Struct(&'static [FieldLayout]),
+}
+
+/// The layout of a field of a struct.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FieldLayout {
+    /// The name of the field, or its index for tuple structs.
+    pub name: &'static str,
+    /// The offset of the field within the struct in bytes.
+    pub offset: usize,
+    /// The layout of the field's type.
+    pub layout: &'static TypeLayout,
+}
+
+impl FieldLayout {
+    /// Returns the size of the field in bytes.
+    pub fn size(&self) -> usize {
+        self.layout.size
+    }
+}
+
+/// A [`Pod`] type with a static description of its layout.
+///
+/// This can be derived for `#[repr(C)]` structs with the `derive` feature.
+pub trait Describe: Pod {
+    /// The layout of this type.
+    const LAYOUT: &'static TypeLayout;
+
+    /// A stable fingerprint of the layout of this type.
+    ///
+    /// See [`fingerprint`] for details.
+    const LAYOUT_FINGERPRINT: u64 = fingerprint(Self::LAYOUT);
+
+    /// Returns the layout of this type.
+    fn layout() -> &'static TypeLayout {
+        Self::LAYOUT
+    }
+}
+
+/// Returns the layout of the type `T`.
+pub fn describe<T: Describe>() -> &'static TypeLayout {
+    T::LAYOUT
+}
+
+const FNV_OFFSET: u64 = 0xcbf2_9ce4_8422_2325;
+const FNV_PRIME: u64 = 0x0000_0100_0000_01b3;
+
+const fn hash_bytes(mut hash: u64, bytes: &[u8]) -> u64 {
+    let mut i = 0;
+    while i < bytes.len() {
+        hash = (hash ^ bytes[i] as u64).wrapping_mul(FNV_PRIME);
+        i += 1;
+    }
+    hash
+}
+
+const fn hash_usize(hash: u64, value: usize) -> u64 {
+    hash_bytes(hash, &(value as u64).to_le_bytes())
+}
+
+const fn hash_layout(mut hash: u64, layout: &TypeLayout) -> u64 {
+    hash = hash_usize(hash, layout.size);
+    hash = hash_usize(hash, layout.align);
+    match layout.kind {
+        LayoutKind::Primitive => hash_bytes(hash_bytes(hash, b"P"), layout.name.as_bytes()),
+        LayoutKind::Array { element, len } => {
+            hash = hash_usize(hash_bytes(hash, b"A"), len);
+            hash_layout(hash, element)
+        }
+        LayoutKind::Struct(fields) => {
+            hash = hash_usize(hash_bytes(hash, b"S"), fields.len());
+            let mut i = 0;
+            while i < fields.len() {
+                hash = hash_usize(hash, fields[i].offset);
+                hash = hash_layout(hash, fields[i].layout);
+                i += 1;
+            }
+            hash
+        }
+    }
+}
+
+/// Computes a stable fingerprint of a [`TypeLayout`].
+///
+/// The fingerprint covers the sizes, alignments and offsets of all fields,
+/// as well as the primitive types they consist of, recursively. It does not
+/// cover the names of types and fields, so renaming them does not change the
+/// fingerprint, whereas any change to the layout does.
+///
+/// Store the fingerprint alongside serialized data, for example with
+/// [`Header::with_layout_fingerprint`](crate::Header::with_layout_fingerprint),
+/// to detect accidental changes of a struct at load time.
+pub const fn fingerprint(layout: &TypeLayout) -> u64 {
+    hash_layout(FNV_OFFSET, layout)
+}
+
+macro_rules! describe_primitives {
+    ($($ty:ty),*) => {
+        $(
+            impl Describe for $ty {
+                const LAYOUT: &'static TypeLayout = &TypeLayout {
+                    name: stringify!($ty),
+                    size: mem::size_of::<$ty>(),
+                    align: mem::align_of::<$ty>(),
+                    kind: LayoutKind::Primitive,
+                };
+            }
+        )*
+    };
+}
+
+describe_primitives!(u8, i8, u16, i16, u32, i32, u64, i64, u128, i128, usize, isize, f32, f64);
+
+impl<T: Describe, const N: usize> Describe for [T; N] {
+    const LAYOUT: &'static TypeLayout = &TypeLayout {
+        name: "array",
+        size: mem::size_of::<[T; N]>(),
+        align: mem::align_of::<[T; N]>(),
+        kind: LayoutKind::Array {
+            element: T::LAYOUT,
+            len: N,
+        },
+    };
+}
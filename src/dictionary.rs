@@ -0,0 +1,204 @@
+use core::fmt;
+use core::hash::Hash;
+
+use hashbrown::HashMap;
+
+use crate::{OffsetSet, Pod, ReadOffsetSetError};
+
+/// A dictionary of entries shared by multiple [`DictionarySection`]s.
+///
+/// File formats with multiple sections often repeat the same strings or
+/// slices in each section. Instead of giving each section its own
+/// [`OffsetSet`] or [`StringTable`](crate::StringTable), all sections can
+/// intern their entries into one [`SharedDictionary`], which stores each
+/// entry only once. The sections themselves only hold references into the
+/// dictionary.
+///
+/// The serialized dictionary is a plain [`OffsetSet<T>`], and each serialized
+/// section is a slice of `u32` dictionary offsets, which needs to be aligned to 4.
+///
+/// # Example
+/// ```
+/// # #[cfg(feature = "strings")] {
+/// use watto::{DictionarySection, SharedDictionary};
+///
+/// let mut dictionary = SharedDictionary::new();
+/// let mut functions = DictionarySection::new();
+/// let mut files = DictionarySection::new();
+///
+/// let main = functions.insert_str(&mut dictionary, "main");
+/// let file = files.insert_str(&mut dictionary, "main.rs");
+/// assert_eq!(files.insert_str(&mut dictionary, "main"), 1);
+/// assert_eq!(dictionary.len(), 2);
+///
+/// let dictionary = dictionary.into_bytes();
+/// let functions = functions.into_bytes();
+/// assert_eq!(DictionarySection::read_str(&dictionary, &functions, main).unwrap(), "main");
+/// # let files = files.into_bytes();
+/// # assert_eq!(DictionarySection::read_str(&dictionary, &files, file).unwrap(), "main.rs");
+/// # }
+/// ```
+#[derive(Clone)]
+pub struct SharedDictionary<T = u8> {
+    entries: OffsetSet<T>,
+}
+
+impl<T: fmt::Debug + Pod> fmt::Debug for SharedDictionary<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.entries.fmt(f)
+    }
+}
+
+impl<T> Default for SharedDictionary<T> {
+    fn default() -> Self {
+        Self {
+            entries: Default::default(),
+        }
+    }
+}
+
+impl<T: Pod> SharedDictionary<T> {
+    /// Creates a new empty [`SharedDictionary`].
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the number of unique entries in this dictionary.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Returns `true` if this dictionary is empty.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Returns a byte slice containing the serialized representation of this dictionary.
+    pub fn as_bytes(&self) -> &[u8] {
+        self.entries.as_bytes()
+    }
+
+    /// Returns a byte vector containing the serialized representation of this dictionary.
+    ///
+    /// This consumes the [`SharedDictionary`].
+    pub fn into_bytes(self) -> Vec<u8> {
+        self.entries.into_bytes()
+    }
+}
+
+impl<T: Pod + PartialEq + Hash> SharedDictionary<T> {
+    /// Initializes a [`SharedDictionary`] from a previously serialized representation.
+    ///
+    /// This allows adding more sections to an existing dictionary.
+    pub fn from_bytes(buffer: &[u8]) -> Result<Self, ReadOffsetSetError> {
+        Ok(Self {
+            entries: OffsetSet::from_bytes(buffer)?,
+        })
+    }
+}
+
+/// A section of entries stored in a [`SharedDictionary`].
+///
+/// Entries are identified by their `u32` index within the section, and are
+/// deduplicated within the section as well.
+///
+/// See [`SharedDictionary`] for an example.
+#[derive(Debug, Clone, Default)]
+pub struct DictionarySection {
+    offsets: Vec<u32>,
+    indices: HashMap<u32, u32>,
+}
+
+impl DictionarySection {
+    /// Creates a new empty [`DictionarySection`].
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Inserts `items` into the `dictionary`, and returns their index within this section.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the dictionary grows beyond 4 GiB.
+    pub fn insert<T: Pod + PartialEq + Hash>(
+        &mut self,
+        dictionary: &mut SharedDictionary<T>,
+        items: &[T],
+    ) -> u32 {
+        let offset = dictionary.entries.insert(items);
+        let offset = u32::try_from(offset).expect("dictionary exceeds 4 GiB");
+        *self.indices.entry(offset).or_insert_with(|| {
+            self.offsets.push(offset);
+            (self.offsets.len() - 1) as u32
+        })
+    }
+
+    /// Inserts a string into the `dictionary`, and returns its index within this section.
+    pub fn insert_str(&mut self, dictionary: &mut SharedDictionary<u8>, s: &str) -> u32 {
+        self.insert(dictionary, s.as_bytes())
+    }
+
+    /// Returns the number of entries in this section.
+    pub fn len(&self) -> usize {
+        self.offsets.len()
+    }
+
+    /// Returns `true` if this section is empty.
+    pub fn is_empty(&self) -> bool {
+        self.offsets.is_empty()
+    }
+
+    /// Returns the dictionary offsets of all the entries in this section.
+    pub fn offsets(&self) -> &[u32] {
+        &self.offsets
+    }
+
+    /// Returns a byte slice containing the serialized representation of this section.
+    pub fn as_bytes(&self) -> &[u8] {
+        self.offsets.as_bytes()
+    }
+
+    /// Returns a byte vector containing the serialized representation of this section.
+    pub fn into_bytes(self) -> Vec<u8> {
+        self.as_bytes().to_vec()
+    }
+
+    /// Writes the serialized representation of this section into the given [`Writer`](crate::Writer).
+    ///
+    /// This first aligns the writer to the alignment of `u32`.
+    #[cfg(feature = "writer")]
+    pub fn write_to<W: std::io::Write>(
+        &self,
+        writer: &mut crate::Writer<W>,
+    ) -> std::io::Result<()> {
+        use std::io::Write;
+
+        writer.align_to_type::<u32>()?;
+        writer.write_all(self.as_bytes())
+    }
+
+    /// Returns the entry with the given index in a serialized section,
+    /// reading it from the serialized `dictionary`.
+    pub fn read<'data, T: Pod>(
+        dictionary: &'data [u8],
+        section: &[u8],
+        index: u32,
+    ) -> Result<&'data [T], ReadOffsetSetError> {
+        let offset = u32::slice_from_bytes(section)
+            .and_then(|offsets| offsets.get(index as usize))
+            .ok_or(ReadOffsetSetError::OutOfBounds)?;
+        OffsetSet::read(dictionary, *offset as usize)
+    }
+
+    /// Returns the string with the given index in a serialized section,
+    /// reading it from the serialized `dictionary`.
+    #[cfg(feature = "strings")]
+    pub fn read_str<'data>(
+        dictionary: &'data [u8],
+        section: &[u8],
+        index: u32,
+    ) -> Result<&'data str, crate::ReadStringError> {
+        let bytes = Self::read::<u8>(dictionary, section, index)?;
+        Ok(core::str::from_utf8(bytes)?)
+    }
+}
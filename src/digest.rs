@@ -0,0 +1,283 @@
+use core::fmt;
+use std::io::{self, Write};
+
+/// A streaming hash function used for integrity checks.
+///
+/// Implementations for the supported [`DigestAlgorithm`]s are available
+/// through [`DigestAlgorithm::hasher`].
+pub trait Digest {
+    /// Feeds `data` into the hash function.
+    fn update(&mut self, data: &[u8]);
+
+    /// Returns the digest of all the data fed so far.
+    fn finish(&self) -> DigestOutput;
+}
+
+/// The output of a [`Digest`], which is at most 32 bytes long.
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+pub struct DigestOutput {
+    bytes: [u8; 32],
+    len: u8,
+}
+
+impl DigestOutput {
+    /// Creates a new [`DigestOutput`] from the given bytes.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `bytes` is longer than 32 bytes.
+    pub fn new(bytes: &[u8]) -> Self {
+        let mut output = Self {
+            bytes: [0; 32],
+            len: bytes.len().try_into().unwrap(),
+        };
+        output.bytes[..bytes.len()].copy_from_slice(bytes);
+        output
+    }
+
+    /// Returns the raw bytes of the digest.
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.bytes[..self.len as usize]
+    }
+}
+
+impl AsRef<[u8]> for DigestOutput {
+    fn as_ref(&self) -> &[u8] {
+        self.as_bytes()
+    }
+}
+
+impl fmt::Debug for DigestOutput {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "DigestOutput({self})")
+    }
+}
+
+impl fmt::Display for DigestOutput {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for byte in self.as_bytes() {
+            write!(f, "{byte:02x}")?;
+        }
+        Ok(())
+    }
+}
+
+/// A hash algorithm that can be selected at runtime.
+///
+/// The available algorithms depend on the enabled features:
+///
+/// - `crc32`: [`Crc32`](Self::Crc32), a 4-byte CRC32 checksum.
+/// - `xxhash`: [`Xxh3`](Self::Xxh3), an 8-byte XXH3 hash.
+/// - `sha256`: [`Sha256`](Self::Sha256), a 32-byte SHA-256 hash.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum DigestAlgorithm {
+    /// CRC32 (IEEE), stored as little-endian `u32`.
+    #[cfg(feature = "crc32")]
+    Crc32,
+    /// XXH3 64-bit, stored as little-endian `u64`.
+    #[cfg(feature = "xxhash")]
+    Xxh3,
+    /// SHA-256.
+    #[cfg(feature = "sha256")]
+    Sha256,
+}
+
+impl DigestAlgorithm {
+    /// Returns a stable numeric identifier of this algorithm, to be stored in file formats.
+    pub fn id(self) -> u8 {
+        match self {
+            #[cfg(feature = "crc32")]
+            Self::Crc32 => 1,
+            #[cfg(feature = "xxhash")]
+            Self::Xxh3 => 2,
+            #[cfg(feature = "sha256")]
+            Self::Sha256 => 3,
+        }
+    }
+
+    /// Returns the algorithm with the given [`id`](Self::id), if it is supported.
+    pub fn from_id(id: u8) -> Option<Self> {
+        match id {
+            #[cfg(feature = "crc32")]
+            1 => Some(Self::Crc32),
+            #[cfg(feature = "xxhash")]
+            2 => Some(Self::Xxh3),
+            #[cfg(feature = "sha256")]
+            3 => Some(Self::Sha256),
+            _ => None,
+        }
+    }
+
+    /// Returns the length of the digest in bytes.
+    pub fn output_len(self) -> usize {
+        match self {
+            #[cfg(feature = "crc32")]
+            Self::Crc32 => 4,
+            #[cfg(feature = "xxhash")]
+            Self::Xxh3 => 8,
+            #[cfg(feature = "sha256")]
+            Self::Sha256 => 32,
+        }
+    }
+
+    /// Creates a new streaming [`Digest`] for this algorithm.
+    pub fn hasher(self) -> Box<dyn Digest + Send> {
+        match self {
+            #[cfg(feature = "crc32")]
+            Self::Crc32 => Box::new(crc32fast::Hasher::new()),
+            #[cfg(feature = "xxhash")]
+            Self::Xxh3 => Box::new(xxhash_rust::xxh3::Xxh3::new()),
+            #[cfg(feature = "sha256")]
+            Self::Sha256 => Box::new(<sha2::Sha256 as sha2::Digest>::new()),
+        }
+    }
+
+    /// Computes the digest of `data`.
+    pub fn digest(self, data: &[u8]) -> DigestOutput {
+        let mut hasher = self.hasher();
+        hasher.update(data);
+        hasher.finish()
+    }
+}
+
+#[cfg(feature = "crc32")]
+impl Digest for crc32fast::Hasher {
+    fn update(&mut self, data: &[u8]) {
+        crc32fast::Hasher::update(self, data)
+    }
+
+    fn finish(&self) -> DigestOutput {
+        DigestOutput::new(&self.clone().finalize().to_le_bytes())
+    }
+}
+
+#[cfg(feature = "xxhash")]
+impl Digest for xxhash_rust::xxh3::Xxh3 {
+    fn update(&mut self, data: &[u8]) {
+        xxhash_rust::xxh3::Xxh3::update(self, data)
+    }
+
+    fn finish(&self) -> DigestOutput {
+        DigestOutput::new(&self.digest().to_le_bytes())
+    }
+}
+
+#[cfg(feature = "sha256")]
+impl Digest for sha2::Sha256 {
+    fn update(&mut self, data: &[u8]) {
+        sha2::Digest::update(self, data)
+    }
+
+    fn finish(&self) -> DigestOutput {
+        DigestOutput::new(&sha2::Digest::finalize(self.clone()))
+    }
+}
+
+/// An error when a section does not match its expected digest.
+///
+/// Returned from [`verify_section`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DigestMismatch {
+    /// The algorithm used to verify the section.
+    pub algorithm: DigestAlgorithm,
+    /// The actual digest of the section.
+    pub actual: DigestOutput,
+}
+
+impl fmt::Display for DigestMismatch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{:?} digest mismatch, section has digest {}",
+            self.algorithm, self.actual
+        )
+    }
+}
+
+impl std::error::Error for DigestMismatch {}
+
+/// Verifies that `bytes` hash to the `expected` digest using the given algorithm.
+pub fn verify_section(
+    bytes: &[u8],
+    algorithm: DigestAlgorithm,
+    expected: &[u8],
+) -> Result<(), DigestMismatch> {
+    let actual = algorithm.digest(bytes);
+    if actual.as_bytes() != expected {
+        return Err(DigestMismatch { algorithm, actual });
+    }
+    Ok(())
+}
+
+/// A wrapper around [`Write`] which hashes all the written data.
+///
+/// This can be wrapped in a [`Writer`](crate::Writer) in order to hash all
+/// the written data including padding, or wrap a [`Writer`](crate::Writer)
+/// to only hash a single section.
+///
+/// # Example
+/// ```
+/// # #[cfg(feature = "crc32")] {
+/// use std::io::Write;
+/// use watto::{verify_section, DigestAlgorithm, HashingWriter};
+///
+/// let mut writer = HashingWriter::new(vec![], DigestAlgorithm::Crc32.hasher());
+/// writer.write_all(b"watto").unwrap();
+/// let (buffer, digest) = writer.finish();
+///
+/// assert!(verify_section(&buffer, DigestAlgorithm::Crc32, digest.as_bytes()).is_ok());
+/// # }
+/// ```
+pub struct HashingWriter<W, D = Box<dyn Digest + Send>> {
+    inner: W,
+    digest: D,
+}
+
+impl<W: Write, D: Digest> HashingWriter<W, D> {
+    /// Creates a new [`HashingWriter`] feeding all the data written to `inner` into `digest`.
+    pub fn new(inner: W, digest: D) -> Self {
+        Self { inner, digest }
+    }
+
+    /// Returns the digest of all the data written so far.
+    pub fn digest(&self) -> DigestOutput {
+        self.digest.finish()
+    }
+
+    /// Unwraps this [`HashingWriter`], returning the inner writer and the final digest.
+    pub fn finish(self) -> (W, DigestOutput) {
+        let digest = self.digest.finish();
+        (self.inner, digest)
+    }
+}
+
+impl<D: Digest + ?Sized> Digest for Box<D> {
+    fn update(&mut self, data: &[u8]) {
+        (**self).update(data)
+    }
+
+    fn finish(&self) -> DigestOutput {
+        (**self).finish()
+    }
+}
+
+impl<W: Write, D: Digest> Write for HashingWriter<W, D> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let written = self.inner.write(buf)?;
+        self.digest.update(&buf[..written]);
+        Ok(written)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+impl<W: fmt::Debug, D> fmt::Debug for HashingWriter<W, D> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("HashingWriter")
+            .field("inner", &self.inner)
+            .finish_non_exhaustive()
+    }
+}
@@ -0,0 +1,140 @@
+use core::{fmt, mem};
+
+use crate::Pod;
+
+/// An error when patching a buffer with an [`Editor`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum EditError {
+    /// The patched range is outside the bounds of the buffer.
+    OutOfBounds {
+        /// The offset of the patched range.
+        offset: usize,
+        /// The length of the patched range in bytes.
+        len: usize,
+        /// The length of the buffer.
+        buffer_len: usize,
+    },
+    /// The offset is not a multiple of the alignment of the patched type.
+    Misaligned {
+        /// The offset of the patched range.
+        offset: usize,
+        /// The required alignment.
+        align: usize,
+    },
+}
+
+impl fmt::Display for EditError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::OutOfBounds {
+                offset,
+                len,
+                buffer_len,
+            } => write!(
+                f,
+                "patching {len} bytes at offset {offset} exceeds the buffer of {buffer_len} bytes"
+            ),
+            Self::Misaligned { offset, align } => {
+                write!(f, "offset {offset} is not aligned to {align} bytes")
+            }
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for EditError {}
+
+/// An editor patching values of an existing serialized buffer in place.
+///
+/// This allows rewriting specific header fields or table entries without
+/// parsing the whole buffer and writing it anew. All patches are checked to be
+/// within the bounds of the buffer, and at an offset which is a multiple of
+/// the alignment of the patched type, so that the patched values stay readable
+/// in place.
+///
+/// The editor works on any mutable byte slice, including an
+/// [`AlignedVec`](crate::AlignedVec) or a writable memory map.
+///
+/// # Example
+/// ```
+/// use watto::{EditError, Editor, Pod};
+///
+/// let mut buffer = [0u32, 1, 2, 3].as_bytes().to_vec();
+/// let mut editor = Editor::new(&mut buffer);
+///
+/// editor.patch_pod(4, &10u32).unwrap();
+/// editor.patch_slice(8, &[20u32, 30]).unwrap();
+/// assert_eq!(
+///     editor.patch_pod(2, &0u32),
+///     Err(EditError::Misaligned { offset: 2, align: 4 })
+/// );
+/// assert!(editor.patch_slice(12, &[0u32; 2]).is_err());
+///
+/// let values = u32::slice_from_bytes(editor.as_bytes()).unwrap();
+/// assert_eq!(values, [0, 10, 20, 30]);
+/// ```
+#[derive(Debug)]
+pub struct Editor<'data> {
+    buffer: &'data mut [u8],
+}
+
+impl<'data> Editor<'data> {
+    /// Creates a new [`Editor`] patching `buffer`.
+    pub fn new(buffer: &'data mut [u8]) -> Self {
+        Self { buffer }
+    }
+
+    /// Returns the current contents of the buffer.
+    pub fn as_bytes(&self) -> &[u8] {
+        self.buffer
+    }
+
+    /// Unwraps the [`Editor`] into the patched buffer.
+    pub fn into_inner(self) -> &'data mut [u8] {
+        self.buffer
+    }
+
+    /// Returns a copy of the `T` at `offset`.
+    ///
+    /// This is useful to read the current value of a field before patching it.
+    pub fn read_pod<T: Pod>(&self, offset: usize) -> Result<T, EditError> {
+        let range = self.checked_range::<T>(offset, mem::size_of::<T>())?;
+        Ok(T::read_from_bytes(&self.buffer[range]).expect("range has the size of T"))
+    }
+
+    /// Overwrites the `T` at `offset` with `value`.
+    pub fn patch_pod<T: Pod>(&mut self, offset: usize, value: &T) -> Result<(), EditError> {
+        self.patch_slice(offset, core::slice::from_ref(value))
+    }
+
+    /// Overwrites the `values.len()` `T`s starting at `offset` with `values`.
+    pub fn patch_slice<T: Pod>(&mut self, offset: usize, values: &[T]) -> Result<(), EditError> {
+        let bytes = values.as_bytes();
+        let range = self.checked_range::<T>(offset, bytes.len())?;
+        self.buffer[range].copy_from_slice(bytes);
+        Ok(())
+    }
+
+    /// Returns the range of `len` bytes at `offset`, if it is in bounds and
+    /// aligned for `T`.
+    fn checked_range<T>(
+        &self,
+        offset: usize,
+        len: usize,
+    ) -> Result<core::ops::Range<usize>, EditError> {
+        let align = mem::align_of::<T>();
+        if !offset.is_multiple_of(align) {
+            return Err(EditError::Misaligned { offset, align });
+        }
+        offset
+            .checked_add(len)
+            .filter(|end| *end <= self.buffer.len())
+            .map(|end| offset..end)
+            .ok_or(EditError::OutOfBounds {
+                offset,
+                len,
+                buffer_len: self.buffer.len(),
+            })
+    }
+}
@@ -0,0 +1,131 @@
+use core::fmt;
+
+use crate::OffsetSet;
+
+/// The identifier of version 1 of the serialized [`OffsetSet`] encoding.
+pub const OFFSET_SET_ENCODING_V1: u16 = 1;
+/// The identifier of version 1 of the serialized [`StringTable`](crate::StringTable) encoding.
+pub const STRING_TABLE_ENCODING_V1: u16 = 2;
+/// The identifier of version 1 of the serialized `CompressedStringTable` encoding.
+pub const COMPRESSED_STRING_TABLE_ENCODING_V1: u16 = 3;
+/// The identifier of version 1 of the serialized `Container` encoding.
+pub const CONTAINER_ENCODING_V1: u16 = 4;
+
+/// An encoding produced by watto.
+///
+/// Use [`probe`] to detect which encoding produced a buffer. Each encoding
+/// has a stable [`id`](Self::id), which can be stored alongside a buffer
+/// so that readers can branch on it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum Encoding {
+    /// A serialized [`OffsetSet`] of bytes.
+    OffsetSetV1,
+    /// A serialized [`StringTable`](crate::StringTable).
+    StringTableV1,
+    /// A serialized `CompressedStringTable`.
+    CompressedStringTableV1,
+    /// A serialized `Container`.
+    ContainerV1,
+}
+
+impl Encoding {
+    /// Returns the stable identifier of this encoding.
+    pub fn id(self) -> u16 {
+        match self {
+            Self::OffsetSetV1 => OFFSET_SET_ENCODING_V1,
+            Self::StringTableV1 => STRING_TABLE_ENCODING_V1,
+            Self::CompressedStringTableV1 => COMPRESSED_STRING_TABLE_ENCODING_V1,
+            Self::ContainerV1 => CONTAINER_ENCODING_V1,
+        }
+    }
+
+    /// Returns the encoding with the given identifier, if it is known.
+    pub fn from_id(id: u16) -> Option<Self> {
+        Some(match id {
+            OFFSET_SET_ENCODING_V1 => Self::OffsetSetV1,
+            STRING_TABLE_ENCODING_V1 => Self::StringTableV1,
+            COMPRESSED_STRING_TABLE_ENCODING_V1 => Self::CompressedStringTableV1,
+            CONTAINER_ENCODING_V1 => Self::ContainerV1,
+            _ => return None,
+        })
+    }
+
+    /// Returns `true` if `bytes` is a valid buffer of this encoding.
+    ///
+    /// This always returns `false` for encodings whose feature is not enabled.
+    /// As raw tables do not carry any magic, a buffer can be valid in
+    /// more than one encoding. For example, every valid [`StringTable`](crate::StringTable)
+    /// is also a valid [`OffsetSet`].
+    pub fn matches(self, bytes: &[u8]) -> bool {
+        match self {
+            Self::OffsetSetV1 => entries(bytes).all(|entry| entry.is_some()),
+            Self::StringTableV1 => {
+                entries(bytes).all(|entry| entry.is_some_and(|s| core::str::from_utf8(s).is_ok()))
+            }
+            #[cfg(feature = "zstd")]
+            Self::CompressedStringTableV1 => entries(bytes).all(|entry| {
+                entry.is_some_and(crate::compressed_string_table::is_valid_entry_encoding)
+            }),
+            #[cfg(feature = "container")]
+            Self::ContainerV1 => crate::Container::parse(bytes).is_ok(),
+            #[allow(unreachable_patterns)]
+            _ => false,
+        }
+    }
+}
+
+impl fmt::Display for Encoding {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Self::OffsetSetV1 => "OffsetSet v1",
+            Self::StringTableV1 => "StringTable v1",
+            Self::CompressedStringTableV1 => "CompressedStringTable v1",
+            Self::ContainerV1 => "Container v1",
+        };
+        f.write_str(name)
+    }
+}
+
+/// Iterates over the entries of a serialized [`OffsetSet`] of bytes,
+/// yielding [`None`] for an invalid entry.
+fn entries(bytes: &[u8]) -> impl Iterator<Item = Option<&[u8]>> {
+    OffsetSet::<u8>::iter_buffer(bytes).map(|entry| entry.ok().map(|(_, entry)| entry))
+}
+
+/// Detects which watto encoding produced the given buffer.
+///
+/// Returns the most specific matching [`Encoding`], trying a `Container`
+/// first, then a [`StringTable`](crate::StringTable), a `CompressedStringTable`,
+/// and finally a plain [`OffsetSet`]. Returns [`None`] if the buffer is not
+/// valid in any of the encodings enabled by the crate features.
+///
+/// Raw tables do not carry any magic, so the result is a best guess for
+/// them. In particular, an empty buffer is reported as an empty [`StringTable`](crate::StringTable).
+///
+/// # Example
+/// ```
+/// # #[cfg(feature = "strings")] {
+/// use watto::{probe, Encoding, OffsetSet, StringTable};
+///
+/// let mut strings = StringTable::new();
+/// strings.insert("watto");
+/// assert_eq!(probe(strings.as_bytes()), Some(Encoding::StringTableV1));
+///
+/// let mut set = OffsetSet::<u8>::new();
+/// set.insert(&[0xff, 0xfe]);
+/// assert_eq!(probe(set.as_bytes()), Some(Encoding::OffsetSetV1));
+///
+/// assert_eq!(probe(&[0x80]), None);
+/// # }
+/// ```
+pub fn probe(bytes: &[u8]) -> Option<Encoding> {
+    [
+        Encoding::ContainerV1,
+        Encoding::StringTableV1,
+        Encoding::CompressedStringTableV1,
+        Encoding::OffsetSetV1,
+    ]
+    .into_iter()
+    .find(|encoding| encoding.matches(bytes))
+}
@@ -0,0 +1,222 @@
+//! Integers and floats with an explicit byte order.
+//!
+//! The types of this module store their value as a byte array in the byte
+//! order given by their [`ByteOrder`] parameter, and convert it from and to
+//! native order with `get` and `set`. This makes formats using them readable
+//! on both little- and big-endian targets.
+//!
+//! All of them have an alignment of 1, so they can be placed anywhere in a
+//! record without padding, and stored in an [`OffsetSet`](crate::OffsetSet),
+//! which is limited to types with an alignment of 1.
+//!
+//! # Example
+//! ```
+//! use watto::endian::{BigEndian, LittleEndian, U32, U16};
+//! use watto::Pod;
+//!
+//! #[repr(C)]
+//! struct Header {
+//!     magic: [u8; 2],
+//!     version: U16<BigEndian>,
+//!     len: U32<LittleEndian>,
+//! }
+//! unsafe impl Pod for Header {}
+//!
+//! let header = Header::ref_from_bytes(b"WT\x00\x02\x10\x00\x00\x00").unwrap();
+//! assert_eq!(header.version.get(), 2);
+//! assert_eq!(header.len.get(), 16);
+//!
+//! let mut len = U32::<BigEndian>::new(16);
+//! assert_eq!(len.as_bytes(), [0, 0, 0, 16]);
+//! len.set(len.get() + 1);
+//! assert_eq!(len.as_bytes(), [0, 0, 0, 17]);
+//! ```
+
+use core::marker::PhantomData;
+use core::{cmp, fmt, hash};
+
+use crate::describe::{Describe, LayoutKind, TypeLayout};
+use crate::{ByteSwap, Pod};
+
+mod sealed {
+    pub trait Sealed {}
+}
+
+/// A byte order, either [`LittleEndian`] or [`BigEndian`].
+pub trait ByteOrder: sealed::Sealed + Copy + Default + fmt::Debug + 'static {
+    /// `true` for little-endian byte order.
+    const IS_LITTLE: bool;
+}
+
+/// The little-endian byte order, with the least significant byte first.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct LittleEndian;
+
+/// The big-endian byte order, with the most significant byte first.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct BigEndian;
+
+impl sealed::Sealed for LittleEndian {}
+impl sealed::Sealed for BigEndian {}
+
+impl ByteOrder for LittleEndian {
+    const IS_LITTLE: bool = true;
+}
+
+impl ByteOrder for BigEndian {
+    const IS_LITTLE: bool = false;
+}
+
+/// A shorthand for [`LittleEndian`].
+pub type LE = LittleEndian;
+/// A shorthand for [`BigEndian`].
+pub type BE = BigEndian;
+
+/// The byte order of the target.
+#[cfg(target_endian = "little")]
+pub type NativeEndian = LittleEndian;
+/// The byte order of the target.
+#[cfg(target_endian = "big")]
+pub type NativeEndian = BigEndian;
+
+macro_rules! endian_types {
+    ($($name:ident($native:ty, $len:literal) $desc:literal;)*) => {
+        $(
+            #[doc = concat!("A ", $desc, " stored in the byte order `E`, with an alignment of 1.")]
+            #[repr(transparent)]
+            pub struct $name<E: ByteOrder>([u8; $len], PhantomData<E>);
+
+            unsafe impl<E: ByteOrder> Pod for $name<E> {}
+
+            impl<E: ByteOrder> $name<E> {
+                /// Creates a new value from a native one.
+                pub fn new(value: $native) -> Self {
+                    let bytes = if E::IS_LITTLE {
+                        value.to_le_bytes()
+                    } else {
+                        value.to_be_bytes()
+                    };
+                    Self(bytes, PhantomData)
+                }
+
+                /// Returns the value in native byte order.
+                pub fn get(self) -> $native {
+                    if E::IS_LITTLE {
+                        <$native>::from_le_bytes(self.0)
+                    } else {
+                        <$native>::from_be_bytes(self.0)
+                    }
+                }
+
+                /// Sets the value from a native one.
+                pub fn set(&mut self, value: $native) {
+                    *self = Self::new(value);
+                }
+            }
+
+            impl<E: ByteOrder> Clone for $name<E> {
+                fn clone(&self) -> Self {
+                    *self
+                }
+            }
+
+            impl<E: ByteOrder> Copy for $name<E> {}
+
+            impl<E: ByteOrder> Default for $name<E> {
+                fn default() -> Self {
+                    Self([0; $len], PhantomData)
+                }
+            }
+
+            impl<E: ByteOrder> PartialEq for $name<E> {
+                fn eq(&self, other: &Self) -> bool {
+                    self.get() == other.get()
+                }
+            }
+
+            // This is shared with the floats, which are not `Ord`.
+            #[allow(clippy::non_canonical_partial_ord_impl)]
+            impl<E: ByteOrder> PartialOrd for $name<E> {
+                fn partial_cmp(&self, other: &Self) -> Option<cmp::Ordering> {
+                    self.get().partial_cmp(&other.get())
+                }
+            }
+
+            impl<E: ByteOrder> From<$native> for $name<E> {
+                fn from(value: $native) -> Self {
+                    Self::new(value)
+                }
+            }
+
+            impl<E: ByteOrder> From<$name<E>> for $native {
+                fn from(value: $name<E>) -> Self {
+                    value.get()
+                }
+            }
+
+            impl<E: ByteOrder> fmt::Debug for $name<E> {
+                fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                    fmt::Debug::fmt(&self.get(), f)
+                }
+            }
+
+            impl<E: ByteOrder> fmt::Display for $name<E> {
+                fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                    fmt::Display::fmt(&self.get(), f)
+                }
+            }
+
+            impl<E: ByteOrder> ByteSwap for $name<E> {
+                fn swap_bytes(mut self) -> Self {
+                    self.0.reverse();
+                    self
+                }
+            }
+
+            impl<E: ByteOrder> Describe for $name<E> {
+                const LAYOUT: &'static TypeLayout = &TypeLayout {
+                    name: stringify!($name),
+                    size: $len,
+                    align: 1,
+                    kind: LayoutKind::Primitive,
+                };
+            }
+        )*
+    };
+}
+
+/// Implements the traits only available for integers.
+macro_rules! endian_integers {
+    ($($name:ident),*) => {
+        $(
+            impl<E: ByteOrder> Eq for $name<E> {}
+
+            impl<E: ByteOrder> Ord for $name<E> {
+                fn cmp(&self, other: &Self) -> cmp::Ordering {
+                    self.get().cmp(&other.get())
+                }
+            }
+
+            impl<E: ByteOrder> hash::Hash for $name<E> {
+                fn hash<H: hash::Hasher>(&self, state: &mut H) {
+                    self.0.hash(state);
+                }
+            }
+        )*
+    };
+}
+
+endian_types! {
+    U16(u16, 2) "`u16`";
+    U32(u32, 4) "`u32`";
+    U64(u64, 8) "`u64`";
+    U128(u128, 16) "`u128`";
+    I16(i16, 2) "`i16`";
+    I32(i32, 4) "`i32`";
+    I64(i64, 8) "`i64`";
+    I128(i128, 16) "`i128`";
+    F32(f32, 4) "`f32`";
+    F64(f64, 8) "`f64`";
+}
+
+endian_integers!(U16, U32, U64, U128, I16, I32, I64, I128);
@@ -0,0 +1,329 @@
+use core::fmt;
+use std::error::Error as StdError;
+
+/// The kind of an [`Error`], allowing to handle errors without matching on
+/// the error types of individual modules.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum ErrorKind {
+    /// The data is truncated, or an offset or length is out of its bounds.
+    OutOfBounds,
+    /// The data is malformed, for example invalid UTF-8 or an unknown encoding.
+    InvalidData,
+    /// The data was written with an unsupported version of its format.
+    UnsupportedVersion,
+    /// The data does not match its expected size, layout or digest.
+    Mismatch,
+    /// A size limit or capacity would be exceeded.
+    LimitExceeded,
+    /// An I/O error occurred.
+    Io,
+}
+
+impl fmt::Display for ErrorKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::OutOfBounds => "out of bounds",
+            Self::InvalidData => "invalid data",
+            Self::UnsupportedVersion => "unsupported version",
+            Self::Mismatch => "mismatch",
+            Self::LimitExceeded => "limit exceeded",
+            Self::Io => "I/O error",
+        })
+    }
+}
+
+/// An error of any of watto's modules.
+///
+/// All the module-specific error types convert into this, so that downstream
+/// crates need only a single conversion into their own error types. The
+/// original error is available as the [`source`](StdError::source), and can be
+/// retrieved with [`downcast_ref`](Self::downcast_ref).
+///
+/// # Example
+/// ```
+/// # #[cfg(feature = "strings")] {
+/// use watto::{ErrorKind, StringTable};
+///
+/// fn read(buffer: &[u8]) -> Result<&str, watto::Error> {
+///     Ok(StringTable::read(buffer, 0)?)
+/// }
+///
+/// let err = read(&[3, b'a']).unwrap_err();
+/// assert_eq!(err.kind(), ErrorKind::OutOfBounds);
+/// assert_eq!(err.context(), "string table");
+/// # }
+/// ```
+pub struct Error {
+    kind: ErrorKind,
+    context: &'static str,
+    source: Box<dyn StdError + Send + Sync + 'static>,
+}
+
+impl Error {
+    /// Creates a new [`Error`] of the given kind, which was caused by `source`
+    /// while working with `context`.
+    pub fn new<E>(kind: ErrorKind, context: &'static str, source: E) -> Self
+    where
+        E: Into<Box<dyn StdError + Send + Sync + 'static>>,
+    {
+        Self {
+            kind,
+            context,
+            source: source.into(),
+        }
+    }
+
+    /// Returns the kind of this error.
+    pub fn kind(&self) -> ErrorKind {
+        self.kind
+    }
+
+    /// Returns a short description of the format or table this error occurred in,
+    /// like `"string table"` or `"header"`.
+    pub fn context(&self) -> &'static str {
+        self.context
+    }
+
+    /// Returns a reference to the original error, if it is of type `E`.
+    pub fn downcast_ref<E: StdError + 'static>(&self) -> Option<&E> {
+        self.source.downcast_ref()
+    }
+}
+
+impl fmt::Debug for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Error")
+            .field("kind", &self.kind)
+            .field("context", &self.context)
+            .field("source", &self.source)
+            .finish()
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.context, self.source)
+    }
+}
+
+impl StdError for Error {
+    fn source(&self) -> Option<&(dyn StdError + 'static)> {
+        Some(&*self.source)
+    }
+}
+
+/// An error of a watto module, which can be converted into an [`Error`].
+pub(crate) trait ModuleError: StdError + Send + Sync + 'static {
+    /// The context of all errors of this type.
+    const CONTEXT: &'static str;
+
+    /// Returns the kind of this error.
+    fn kind(&self) -> ErrorKind;
+}
+
+impl<E: ModuleError> From<E> for Error {
+    fn from(error: E) -> Self {
+        Self::new(error.kind(), E::CONTEXT, error)
+    }
+}
+
+impl ModuleError for crate::varint::VarintError {
+    const CONTEXT: &'static str = "varint";
+
+    fn kind(&self) -> ErrorKind {
+        match self {
+            Self::Truncated => ErrorKind::OutOfBounds,
+            Self::Overflow => ErrorKind::InvalidData,
+        }
+    }
+}
+
+impl ModuleError for crate::raw::RawReadError {
+    const CONTEXT: &'static str = "raw table";
+
+    fn kind(&self) -> ErrorKind {
+        match self {
+            Self::OutOfBounds => ErrorKind::OutOfBounds,
+            Self::InvalidLength | Self::Utf8(_) => ErrorKind::InvalidData,
+        }
+    }
+}
+
+impl ModuleError for crate::codec::DecodeError {
+    const CONTEXT: &'static str = "codec";
+
+    fn kind(&self) -> ErrorKind {
+        ErrorKind::InvalidData
+    }
+}
+
+impl ModuleError for crate::PodCastError {
+    const CONTEXT: &'static str = "pod";
+
+    fn kind(&self) -> ErrorKind {
+        match self {
+            Self::TooShort { .. } | Self::Overflow { .. } => ErrorKind::OutOfBounds,
+            Self::SizeMismatch { .. }
+            | Self::TrailingBytes { .. }
+            | Self::Misaligned { .. }
+            | Self::AlignmentMismatch { .. }
+            | Self::CapacityMismatch { .. } => ErrorKind::Mismatch,
+        }
+    }
+}
+
+impl ModuleError for crate::EditError {
+    const CONTEXT: &'static str = "editor";
+
+    fn kind(&self) -> ErrorKind {
+        match self {
+            Self::OutOfBounds { .. } => ErrorKind::OutOfBounds,
+            Self::Misaligned { .. } => ErrorKind::Mismatch,
+        }
+    }
+}
+
+impl ModuleError for crate::AlignedVecError {
+    const CONTEXT: &'static str = "aligned buffer";
+
+    fn kind(&self) -> ErrorKind {
+        ErrorKind::LimitExceeded
+    }
+}
+
+impl ModuleError for crate::CapacityError {
+    const CONTEXT: &'static str = "fixed-capacity table";
+
+    fn kind(&self) -> ErrorKind {
+        ErrorKind::LimitExceeded
+    }
+}
+
+impl ModuleError for crate::HeaderError {
+    const CONTEXT: &'static str = "header";
+
+    fn kind(&self) -> ErrorKind {
+        match self {
+            Self::Invalid => ErrorKind::OutOfBounds,
+            Self::WrongMagic { .. } => ErrorKind::InvalidData,
+            Self::UnsupportedVersion { .. } => ErrorKind::UnsupportedVersion,
+            Self::LayoutMismatch { .. } => ErrorKind::Mismatch,
+        }
+    }
+}
+
+impl ModuleError for crate::VersionedError {
+    const CONTEXT: &'static str = "versioned format";
+
+    fn kind(&self) -> ErrorKind {
+        match self {
+            Self::Header(error) => error.kind(),
+            Self::InvalidData { .. } => ErrorKind::InvalidData,
+        }
+    }
+}
+
+#[cfg(feature = "offset_set")]
+impl ModuleError for crate::ReadOffsetSetError {
+    const CONTEXT: &'static str = "offset set";
+
+    fn kind(&self) -> ErrorKind {
+        match self {
+            Self::Leb128(error) => error.kind(),
+            Self::OutOfBounds => ErrorKind::OutOfBounds,
+            Self::TooLarge => ErrorKind::LimitExceeded,
+        }
+    }
+}
+
+#[cfg(feature = "offset_set")]
+impl ModuleError for crate::InsertError {
+    const CONTEXT: &'static str = "offset set";
+
+    fn kind(&self) -> ErrorKind {
+        match self {
+            Self::EntryTooLong { .. }
+            | Self::TableTooLarge { .. }
+            | Self::OffsetOverflow { .. } => ErrorKind::LimitExceeded,
+            Self::Storage(_) => ErrorKind::Io,
+        }
+    }
+}
+
+#[cfg(feature = "offset_set")]
+impl ModuleError for crate::patch::PatchError {
+    const CONTEXT: &'static str = "patch";
+
+    fn kind(&self) -> ErrorKind {
+        match self {
+            Self::SizeMismatch { .. } => ErrorKind::Mismatch,
+            Self::OutOfBounds => ErrorKind::OutOfBounds,
+        }
+    }
+}
+
+#[cfg(feature = "strings")]
+impl ModuleError for crate::ReadStringError {
+    const CONTEXT: &'static str = "string table";
+
+    fn kind(&self) -> ErrorKind {
+        match self {
+            Self::Leb128(error) => error.kind(),
+            Self::Utf8(_) => ErrorKind::InvalidData,
+            Self::OutOfBounds => ErrorKind::OutOfBounds,
+            Self::TooLarge => ErrorKind::LimitExceeded,
+        }
+    }
+}
+
+#[cfg(feature = "zstd")]
+impl ModuleError for crate::ReadCompressedStringError {
+    const CONTEXT: &'static str = "compressed string table";
+
+    fn kind(&self) -> ErrorKind {
+        match self {
+            Self::Read(error) => error.kind(),
+            Self::InvalidEncoding | Self::Decompress(_) => ErrorKind::InvalidData,
+            Self::TooLarge { .. } | Self::Alloc(_) => ErrorKind::LimitExceeded,
+        }
+    }
+}
+
+#[cfg(feature = "zstd")]
+impl ModuleError for crate::CompressedSectionError {
+    const CONTEXT: &'static str = "compressed section";
+
+    fn kind(&self) -> ErrorKind {
+        match self {
+            Self::Truncated => ErrorKind::OutOfBounds,
+            Self::UnknownCodec(_)
+            | Self::InvalidAlignment(_)
+            | Self::Decompress(_)
+            | Self::LengthMismatch => ErrorKind::InvalidData,
+            Self::TooLarge { .. } | Self::Alloc(_) => ErrorKind::LimitExceeded,
+        }
+    }
+}
+
+#[cfg(feature = "container")]
+impl ModuleError for crate::ContainerError {
+    const CONTEXT: &'static str = "container";
+
+    fn kind(&self) -> ErrorKind {
+        match self {
+            Self::Header(error) => error.kind(),
+            Self::InvalidName(error) => error.kind(),
+            Self::InvalidToc | Self::InvalidSection(_) => ErrorKind::OutOfBounds,
+        }
+    }
+}
+
+#[cfg(feature = "digest")]
+impl ModuleError for crate::DigestMismatch {
+    const CONTEXT: &'static str = "digest";
+
+    fn kind(&self) -> ErrorKind {
+        ErrorKind::Mismatch
+    }
+}
@@ -0,0 +1,233 @@
+use core::marker::PhantomData;
+use core::{fmt, mem};
+
+use crate::{raw, varint, Pod};
+
+/// An error when inserting into a full [`FixedCapOffsetSet`] or [`FixedCapStringTable`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct CapacityError {
+    /// The size the table would have after inserting the entry, in bytes.
+    pub required: usize,
+    /// The capacity of the table, in bytes.
+    pub capacity: usize,
+}
+
+impl fmt::Display for CapacityError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "table size {} would exceed its capacity of {} bytes",
+            self.required, self.capacity
+        )
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for CapacityError {}
+
+/// A fixed-capacity variant of `OffsetSet` which does not allocate.
+///
+/// The serialized representation is kept in an inline buffer of `N` bytes,
+/// and is identical to that of an `OffsetSet` with the same entries inserted
+/// in the same order. It can be read back with [`raw::read`], or `OffsetSet::read`.
+///
+/// Duplicates are found by scanning all entries, so this is intended for small
+/// tables, for example emitted by embedded producers without an allocator.
+///
+/// # Example
+/// ```
+/// use watto::{raw, FixedCapOffsetSet};
+///
+/// let mut set = FixedCapOffsetSet::<u8, 8>::new();
+/// let offset = set.insert(&[1, 2, 3]).unwrap();
+/// assert_eq!(set.insert(&[1, 2, 3]), Ok(offset));
+/// assert!(set.insert(&[4, 5, 6, 7]).is_err());
+///
+/// assert_eq!(raw::read::<u8>(set.as_bytes(), offset), Ok(&[1, 2, 3][..]));
+/// ```
+#[derive(Clone)]
+pub struct FixedCapOffsetSet<T, const N: usize> {
+    buffer: [u8; N],
+    size: usize,
+    len: usize,
+    _t: PhantomData<T>,
+}
+
+impl<T, const N: usize> Default for FixedCapOffsetSet<T, N> {
+    fn default() -> Self {
+        Self {
+            buffer: [0; N],
+            size: 0,
+            len: 0,
+            _t: PhantomData,
+        }
+    }
+}
+
+impl<T: fmt::Debug + Pod, const N: usize> fmt::Debug for FixedCapOffsetSet<T, N> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_map().entries(self.entries()).finish()
+    }
+}
+
+impl<T: Pod, const N: usize> FixedCapOffsetSet<T, N> {
+    #[doc(hidden)]
+    const _ALIGN_OF_T: () = assert!(
+        mem::align_of::<T>() == 1,
+        "T is currently limited to alignment `1`"
+    );
+
+    /// Initializes an empty [`FixedCapOffsetSet`].
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the number of unique entries in this [`FixedCapOffsetSet`].
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns `true` if this [`FixedCapOffsetSet`] is empty.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Returns the number of bytes which can still be inserted.
+    pub fn remaining(&self) -> usize {
+        N - self.size
+    }
+
+    /// Iterates over all the entries is this [`FixedCapOffsetSet`] in the
+    /// order in which they were inserted.
+    ///
+    /// This yields `(offset, slice)` pairs.
+    pub fn entries(&self) -> impl Iterator<Item = (usize, &[T])> + '_ {
+        raw::entries(self.as_bytes()).map(|entry| entry.unwrap())
+    }
+
+    /// Returns a byte slice containing the serialized representation of this [`FixedCapOffsetSet`].
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.buffer[..self.size]
+    }
+}
+
+impl<T: Pod + PartialEq, const N: usize> FixedCapOffsetSet<T, N> {
+    /// Returns the offset of the given slice if it is contained in this [`FixedCapOffsetSet`].
+    pub fn get(&self, items: &[T]) -> Option<usize> {
+        self.entries()
+            .find(|(_offset, entry)| *entry == items)
+            .map(|(offset, _entry)| offset)
+    }
+
+    /// Insert a slice into this [`FixedCapOffsetSet`].
+    ///
+    /// Returns an offset that can be used to retrieve the inserted input
+    /// with [`raw::read`], or an error if the table does not have enough
+    /// capacity left. Inserting a duplicate never grows the table.
+    pub fn insert(&mut self, input: &[T]) -> Result<usize, CapacityError> {
+        if let Some(offset) = self.get(input) {
+            return Ok(offset);
+        }
+
+        let mut len_bytes = [0; varint::MAX_LEN];
+        let len_len = varint::encode_u64(&mut len_bytes, input.len() as u64);
+        let input = input.as_bytes();
+
+        let offset = self.size;
+        let required = offset + len_len + input.len();
+        let Some(buffer) = self.buffer.get_mut(offset..required) else {
+            return Err(CapacityError {
+                required,
+                capacity: N,
+            });
+        };
+
+        let (prefix, data) = buffer.split_at_mut(len_len);
+        prefix.copy_from_slice(&len_bytes[..len_len]);
+        data.copy_from_slice(input);
+
+        self.size = required;
+        self.len += 1;
+        Ok(offset)
+    }
+}
+
+/// A fixed-capacity variant of `StringTable` which does not allocate.
+///
+/// See [`FixedCapOffsetSet`] for details. The serialized strings can be read
+/// back with [`raw::read_str`], or `StringTable::read`.
+///
+/// # Example
+/// ```
+/// use watto::{raw, FixedCapStringTable};
+///
+/// let mut table = FixedCapStringTable::<16>::new();
+/// let foo = table.insert("foo").unwrap();
+/// let bar = table.insert("bar").unwrap();
+/// assert_eq!(table.insert("foo"), Ok(foo));
+///
+/// assert_eq!(raw::read_str(table.as_bytes(), bar), Ok("bar"));
+/// assert_eq!(table.remaining(), 8);
+/// ```
+#[derive(Clone, Default)]
+pub struct FixedCapStringTable<const N: usize> {
+    inner: FixedCapOffsetSet<u8, N>,
+}
+
+impl<const N: usize> fmt::Debug for FixedCapStringTable<N> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_map().entries(self.entries()).finish()
+    }
+}
+
+impl<const N: usize> FixedCapStringTable<N> {
+    /// Initializes an empty [`FixedCapStringTable`].
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the number of unique strings in this [`FixedCapStringTable`].
+    pub fn len(&self) -> usize {
+        self.inner.len()
+    }
+
+    /// Returns `true` if this [`FixedCapStringTable`] is empty.
+    pub fn is_empty(&self) -> bool {
+        self.inner.is_empty()
+    }
+
+    /// Returns the number of bytes which can still be inserted.
+    pub fn remaining(&self) -> usize {
+        self.inner.remaining()
+    }
+
+    /// Iterates over all the strings in this [`FixedCapStringTable`] in the
+    /// order in which they were inserted.
+    ///
+    /// This yields `(offset, string)` pairs.
+    pub fn entries(&self) -> impl Iterator<Item = (usize, &str)> + '_ {
+        self.inner.entries().map(|(offset, bytes)| {
+            // SAFETY: Only strings are inserted into this table.
+            (offset, unsafe { core::str::from_utf8_unchecked(bytes) })
+        })
+    }
+
+    /// Returns the offset of the given string if it is contained in this [`FixedCapStringTable`].
+    pub fn get(&self, s: &str) -> Option<usize> {
+        self.inner.get(s.as_bytes())
+    }
+
+    /// Insert a string into this [`FixedCapStringTable`].
+    ///
+    /// See [`FixedCapOffsetSet::insert`] for details.
+    pub fn insert(&mut self, s: &str) -> Result<usize, CapacityError> {
+        self.inner.insert(s.as_bytes())
+    }
+
+    /// Returns a byte slice containing the concatenation of the strings that have been
+    /// added to this [`FixedCapStringTable`].
+    pub fn as_bytes(&self) -> &[u8] {
+        self.inner.as_bytes()
+    }
+}
@@ -0,0 +1,197 @@
+use core::fmt;
+
+use crate::describe::Describe;
+use crate::Pod;
+
+/// A generic file header, identifying a format by its magic and version.
+///
+/// The header is 16 bytes in size and has an alignment of 4. All fields are
+/// stored in native endianness.
+///
+/// The `major` version should be incremented for incompatible format changes,
+/// whereas the `minor` version signals backwards-compatible additions.
+/// [`check_header`] only verifies the `major` version.
+///
+/// # Example
+/// ```
+/// use watto::{check_header, Header, HeaderError};
+///
+/// let header = Header::new(*b"WATO", 1, 2);
+/// let buffer = watto::Pod::as_bytes(&header).to_vec();
+///
+/// let (header, _rest) = check_header(&buffer, *b"WATO", 1).unwrap();
+/// assert_eq!(header.minor, 2);
+///
+/// assert!(matches!(
+///     check_header(&buffer, *b"WATO", 2),
+///     Err(HeaderError::UnsupportedVersion { major: 1, minor: 2 })
+/// ));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[repr(C)]
+pub struct Header {
+    /// The magic bytes identifying the format.
+    pub magic: [u8; 4],
+    /// The major version of the format.
+    pub major: u16,
+    /// The minor version of the format.
+    pub minor: u16,
+    /// Format specific flags.
+    pub flags: u32,
+    /// Reserved for future use, should be all zeroes.
+    ///
+    /// Alternatively, this can hold a layout fingerprint written by
+    /// [`with_layout_fingerprint`](Self::with_layout_fingerprint).
+    pub reserved: [u8; 4],
+}
+
+unsafe impl Pod for Header {}
+
+impl Header {
+    /// Creates a new [`Header`] with the given magic and version, and no flags.
+    pub const fn new(magic: [u8; 4], major: u16, minor: u16) -> Self {
+        Self {
+            magic,
+            major,
+            minor,
+            flags: 0,
+            reserved: [0; 4],
+        }
+    }
+
+    /// Returns a copy of this [`Header`] with the given flags.
+    pub const fn with_flags(self, flags: u32) -> Self {
+        Self { flags, ..self }
+    }
+
+    /// Returns a copy of this [`Header`] with the layout fingerprint of `T`
+    /// stored in its `reserved` bytes.
+    ///
+    /// The stored fingerprint can be checked with [`check_layout_fingerprint`]
+    /// when loading the data, in order to detect changes to the layout of `T`.
+    ///
+    /// # Example
+    /// ```
+    /// use watto::{check_layout_fingerprint, Header, HeaderError};
+    ///
+    /// let header = Header::new(*b"WATO", 1, 0).with_layout_fingerprint::<[u32; 4]>();
+    ///
+    /// assert!(check_layout_fingerprint::<[u32; 4]>(&header).is_ok());
+    /// assert!(matches!(
+    ///     check_layout_fingerprint::<[u64; 2]>(&header),
+    ///     Err(HeaderError::LayoutMismatch { .. })
+    /// ));
+    /// ```
+    pub const fn with_layout_fingerprint<T: Describe>(self) -> Self {
+        Self {
+            reserved: short_fingerprint::<T>().to_ne_bytes(),
+            ..self
+        }
+    }
+
+    /// Returns the layout fingerprint stored in the `reserved` bytes.
+    pub const fn layout_fingerprint(&self) -> u32 {
+        u32::from_ne_bytes(self.reserved)
+    }
+}
+
+/// Folds the 64-bit layout fingerprint of `T` into the 32 bits available in a [`Header`].
+const fn short_fingerprint<T: Describe>() -> u32 {
+    let fingerprint = T::LAYOUT_FINGERPRINT;
+    (fingerprint ^ (fingerprint >> 32)) as u32
+}
+
+/// An error when checking a [`Header`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum HeaderError {
+    /// The buffer is too small or not properly aligned to hold a [`Header`].
+    Invalid,
+    /// The magic bytes do not match the expected magic.
+    WrongMagic {
+        /// The magic bytes found in the header.
+        magic: [u8; 4],
+    },
+    /// The major version is not supported.
+    UnsupportedVersion {
+        /// The major version found in the header.
+        major: u16,
+        /// The minor version found in the header.
+        minor: u16,
+    },
+    /// The layout fingerprint does not match the expected type.
+    LayoutMismatch {
+        /// The layout fingerprint of the expected type.
+        expected: u32,
+        /// The layout fingerprint found in the header.
+        found: u32,
+    },
+}
+
+impl fmt::Display for HeaderError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Invalid => f.write_str("header is truncated or misaligned"),
+            Self::WrongMagic { magic } => write!(f, "wrong magic {magic:02x?}"),
+            Self::UnsupportedVersion { major, minor } => {
+                write!(f, "unsupported version {major}.{minor}")
+            }
+            Self::LayoutMismatch { expected, found } => write!(
+                f,
+                "layout fingerprint {found:#010x} does not match expected {expected:#010x}"
+            ),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for HeaderError {}
+
+/// Reads a [`Header`] from the start of `bytes`, checking its magic and major version.
+///
+/// Returns the header and the trailing bytes.
+pub fn check_header(
+    bytes: &[u8],
+    magic: [u8; 4],
+    major: u16,
+) -> Result<(&Header, &[u8]), HeaderError> {
+    let (header, rest) = Header::ref_from_prefix(bytes).ok_or(HeaderError::Invalid)?;
+    if header.magic != magic {
+        return Err(HeaderError::WrongMagic {
+            magic: header.magic,
+        });
+    }
+    if header.major != major {
+        return Err(HeaderError::UnsupportedVersion {
+            major: header.major,
+            minor: header.minor,
+        });
+    }
+    Ok((header, rest))
+}
+
+/// Checks that the layout fingerprint stored in the `header` matches the layout of `T`.
+///
+/// See [`Header::with_layout_fingerprint`].
+pub fn check_layout_fingerprint<T: Describe>(header: &Header) -> Result<(), HeaderError> {
+    let expected = short_fingerprint::<T>();
+    let found = header.layout_fingerprint();
+    if found != expected {
+        return Err(HeaderError::LayoutMismatch { expected, found });
+    }
+    Ok(())
+}
+
+/// Writes a [`Header`] into the given [`Writer`](crate::Writer).
+///
+/// This first aligns the writer to the alignment of [`Header`].
+#[cfg(feature = "writer")]
+pub fn write_header<W: std::io::Write>(
+    writer: &mut crate::Writer<W>,
+    header: &Header,
+) -> std::io::Result<()> {
+    use std::io::Write;
+
+    writer.align_to_type::<Header>()?;
+    writer.write_all(header.as_bytes())
+}
@@ -0,0 +1,207 @@
+/// Declares a whole-file format as a header followed by aligned sections.
+///
+/// This generates a struct holding zero-copy references to each part of the
+/// format, together with a `parse` function reading it from a buffer, and a
+/// `write_to` function writing it into a [`Writer`](crate::Writer).
+///
+/// Each field can be one of:
+///
+/// - `name: T`, a single [`Pod`](crate::Pod) value, exposed as `&'data T`.
+/// - `name: [T; count]`, a slice of `count` [`Pod`](crate::Pod) values, exposed
+///   as `&'data [T]`. The `count` expression can refer to the previous fields.
+/// - `name: [T]`, a slice of [`Pod`](crate::Pod) values extending to the end of
+///   the buffer, exposed as `&'data [T]`. This can only be the last field.
+///
+/// Every field is aligned to the alignment of its type, both when parsing and
+/// writing. Trailing bytes are ignored when parsing, unless the last field
+/// extends to the end of the buffer. When writing, it is the caller's
+/// responsibility that any counts stored in the format match the actual
+/// lengths of the slices.
+///
+/// # Example
+/// ```
+/// use watto::Pod;
+///
+/// #[repr(C)]
+/// struct Header {
+///     version: u32,
+///     num_as: u32,
+/// }
+/// unsafe impl Pod for Header {}
+///
+/// #[repr(C)]
+/// #[derive(Debug, PartialEq)]
+/// struct A(u16);
+/// unsafe impl Pod for A {}
+///
+/// watto::layout! {
+///     /// Our format.
+///     pub struct Format<'data> {
+///         header: Header,
+///         r#as: [A; header.num_as as usize],
+///         bs: [u64],
+///     }
+/// }
+///
+/// let format = Format {
+///     header: &Header { version: 1, num_as: 2 },
+///     r#as: &[A(1), A(2)],
+///     bs: &[3, 4, 5],
+/// };
+/// let mut writer = watto::Writer::new(vec![]);
+/// format.write_to(&mut writer).unwrap();
+/// let buffer = writer.into_inner();
+///
+/// let format = Format::parse(&buffer).unwrap();
+/// assert_eq!(format.header.version, 1);
+/// assert_eq!(format.r#as, &[A(1), A(2)]);
+/// assert_eq!(format.bs, &[3, 4, 5]);
+/// ```
+#[macro_export]
+macro_rules! layout {
+    (
+        $(#[$meta:meta])*
+        $vis:vis struct $name:ident<$lt:lifetime> {
+            $($body:tt)*
+        }
+    ) => {
+        $crate::__layout! {
+            @munch
+            [$(#[$meta])* $vis struct $name]
+            $lt [__buffer __writer self]
+            fields: []
+            parse: []
+            write: []
+            names: []
+            rest: [$($body)*]
+        }
+    };
+}
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __layout {
+    // A slice with an explicit count.
+    (
+        @munch $decl:tt $lt:lifetime [$buf:ident $writer:ident $this:ident]
+        fields: [$($fields:tt)*]
+        parse: [$($parse:tt)*]
+        write: [$($write:tt)*]
+        names: [$($names:ident)*]
+        rest: [
+            $(#[$fmeta:meta])* $field:ident : [$elem:ty; $count:expr]
+            $(, $($tail:tt)*)?
+        ]
+    ) => {
+        $crate::__layout! {
+            @munch $decl $lt [$buf $writer $this]
+            fields: [$($fields)* $(#[$fmeta])* pub $field: &$lt [$elem],]
+            parse: [
+                $($parse)*
+                let (_, $buf) = $crate::align_to_type::<$elem>($buf)?;
+                let ($field, $buf) = <$elem as $crate::Pod>::slice_from_prefix($buf, $count)?;
+            ]
+            write: [
+                $($write)*
+                $writer.align_to_type::<$elem>()?;
+                ::std::io::Write::write_all($writer, $crate::Pod::as_bytes($this.$field))?;
+            ]
+            names: [$($names)* $field]
+            rest: [$($($tail)*)?]
+        }
+    };
+    // A slice extending to the end of the buffer.
+    (
+        @munch $decl:tt $lt:lifetime [$buf:ident $writer:ident $this:ident]
+        fields: [$($fields:tt)*]
+        parse: [$($parse:tt)*]
+        write: [$($write:tt)*]
+        names: [$($names:ident)*]
+        rest: [
+            $(#[$fmeta:meta])* $field:ident : [$elem:ty] $(,)?
+        ]
+    ) => {
+        $crate::__layout! {
+            @munch $decl $lt [$buf $writer $this]
+            fields: [$($fields)* $(#[$fmeta])* pub $field: &$lt [$elem],]
+            parse: [
+                $($parse)*
+                let (_, $buf) = $crate::align_to_type::<$elem>($buf)?;
+                let $field = <$elem as $crate::Pod>::slice_from_bytes($buf)?;
+            ]
+            write: [
+                $($write)*
+                $writer.align_to_type::<$elem>()?;
+                ::std::io::Write::write_all($writer, $crate::Pod::as_bytes($this.$field))?;
+            ]
+            names: [$($names)* $field]
+            rest: []
+        }
+    };
+    // A single value.
+    (
+        @munch $decl:tt $lt:lifetime [$buf:ident $writer:ident $this:ident]
+        fields: [$($fields:tt)*]
+        parse: [$($parse:tt)*]
+        write: [$($write:tt)*]
+        names: [$($names:ident)*]
+        rest: [
+            $(#[$fmeta:meta])* $field:ident : $ty:ty
+            $(, $($tail:tt)*)?
+        ]
+    ) => {
+        $crate::__layout! {
+            @munch $decl $lt [$buf $writer $this]
+            fields: [$($fields)* $(#[$fmeta])* pub $field: &$lt $ty,]
+            parse: [
+                $($parse)*
+                let (_, $buf) = $crate::align_to_type::<$ty>($buf)?;
+                let ($field, $buf) = <$ty as $crate::Pod>::ref_from_prefix($buf)?;
+            ]
+            write: [
+                $($write)*
+                $writer.align_to_type::<$ty>()?;
+                ::std::io::Write::write_all($writer, $crate::Pod::as_bytes($this.$field))?;
+            ]
+            names: [$($names)* $field]
+            rest: [$($($tail)*)?]
+        }
+    };
+    // All fields have been processed.
+    (
+        @munch [$(#[$meta:meta])* $vis:vis struct $name:ident]
+        $lt:lifetime [$buf:ident $writer:ident $this:ident]
+        fields: [$($fields:tt)*]
+        parse: [$($parse:tt)*]
+        write: [$($write:tt)*]
+        names: [$($names:ident)*]
+        rest: []
+    ) => {
+        $(#[$meta])*
+        $vis struct $name<$lt> {
+            $($fields)*
+        }
+
+        impl<$lt> $name<$lt> {
+            /// Parses the format from the given buffer.
+            ///
+            /// Returns [`None`] if the buffer is too small, or any of the parts
+            /// is not properly aligned.
+            pub fn parse($buf: &$lt [u8]) -> ::core::option::Option<Self> {
+                $($parse)*
+                let _ = $buf;
+                ::core::option::Option::Some(Self { $($names,)* })
+            }
+
+            /// Writes the format into the given [`Writer`](watto::Writer), aligning
+            /// each part as necessary.
+            pub fn write_to<W: ::std::io::Write>(
+                &$this,
+                $writer: &mut $crate::Writer<W>,
+            ) -> ::std::io::Result<()> {
+                $($write)*
+                ::core::result::Result::Ok(())
+            }
+        }
+    };
+}
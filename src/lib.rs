@@ -5,20 +5,158 @@
 #![cfg_attr(docsrs, feature(doc_cfg_hide))]
 #![cfg_attr(docsrs, doc(cfg_hide(doc)))]
 
+#[cfg(feature = "std")]
+mod aligned_vec;
+#[cfg(feature = "std")]
+pub mod annotate;
+#[cfg(feature = "strings")]
+mod arc_str;
+mod bitset;
+mod byteswap;
+#[cfg(feature = "c_header")]
+pub mod c_header;
+mod cast;
+pub mod codec;
+#[cfg(feature = "zstd")]
+mod compressed_section;
+#[cfg(feature = "zstd")]
+mod compressed_string_table;
+#[cfg(feature = "strings")]
+mod concurrent_string_table;
+#[cfg(feature = "container")]
+mod container;
+pub mod describe;
+#[cfg(feature = "offset_set")]
+mod dictionary;
+#[cfg(feature = "digest")]
+mod digest;
+mod editor;
+#[cfg(feature = "offset_set")]
+mod encoding;
+pub mod endian;
+#[cfg(feature = "std")]
+mod error;
+mod fixed_cap;
+mod header;
+#[cfg(feature = "writer")]
+mod layout;
+mod map_table;
+#[cfg(any(feature = "glam", feature = "mint"))]
+mod math;
+#[cfg(feature = "offset_set")]
+mod meta_offset_set;
+#[cfg(feature = "offset_set")]
+mod multi_map;
+#[cfg(feature = "offset_set")]
+mod observer;
 #[cfg(feature = "offset_set")]
 mod offset_set;
+mod packed_int;
+#[cfg(feature = "writer")]
+mod paged_writer;
+#[cfg(feature = "offset_set")]
+pub mod patch;
+#[cfg(feature = "strings")]
+mod path_table;
 mod pod;
+mod range_table;
+pub mod raw;
+mod reader;
+#[cfg(feature = "offset_set")]
+mod record_table;
+#[cfg(feature = "offset_set")]
+mod storage;
+#[cfg(feature = "strings")]
+mod string_list_table;
+#[cfg(feature = "strings")]
+mod string_record_table;
 #[cfg(feature = "strings")]
 mod string_table;
+#[cfg(feature = "strings")]
+pub mod substring;
+#[cfg(feature = "strings")]
+mod symbol_table;
+#[cfg(feature = "strings")]
+mod tagged_offset;
+#[cfg(feature = "testing")]
+pub mod testing;
+#[cfg(feature = "offset_set")]
+mod trace;
+mod unalign;
 mod utils;
+pub mod varint;
+#[cfg(feature = "std")]
+mod versioned;
 #[cfg(feature = "writer")]
 mod writer;
+mod zeroable;
 
+#[cfg(feature = "std")]
+pub use aligned_vec::*;
+#[cfg(feature = "strings")]
+pub use arc_str::*;
+pub use bitset::*;
+pub use byteswap::*;
+pub use cast::*;
+#[cfg(feature = "zstd")]
+pub use compressed_section::*;
+#[cfg(feature = "zstd")]
+pub use compressed_string_table::*;
+#[cfg(feature = "strings")]
+pub use concurrent_string_table::*;
+#[cfg(feature = "container")]
+pub use container::*;
+pub use describe::Describe;
+#[cfg(feature = "offset_set")]
+pub use dictionary::*;
+#[cfg(feature = "digest")]
+pub use digest::*;
+pub use editor::*;
+#[cfg(feature = "offset_set")]
+pub use encoding::*;
+#[cfg(feature = "std")]
+pub use error::{Error, ErrorKind};
+pub use fixed_cap::*;
+pub use header::*;
+pub use map_table::*;
+#[cfg(feature = "offset_set")]
+pub use meta_offset_set::*;
+#[cfg(feature = "offset_set")]
+pub use multi_map::*;
+#[cfg(feature = "offset_set")]
+pub use observer::*;
 #[cfg(feature = "offset_set")]
 pub use offset_set::*;
+pub use packed_int::*;
+#[cfg(feature = "writer")]
+pub use paged_writer::*;
+#[cfg(feature = "strings")]
+pub use path_table::*;
 pub use pod::*;
+pub use range_table::*;
+pub use reader::*;
+#[cfg(feature = "offset_set")]
+pub use record_table::*;
+#[cfg(feature = "offset_set")]
+pub use storage::*;
+#[cfg(feature = "strings")]
+pub use string_list_table::*;
+#[cfg(feature = "strings")]
+pub use string_record_table::*;
 #[cfg(feature = "strings")]
 pub use string_table::*;
+#[cfg(feature = "strings")]
+pub use symbol_table::*;
+#[cfg(feature = "strings")]
+pub use tagged_offset::*;
+pub use unalign::*;
 pub use utils::{align_to, align_to_type};
+#[cfg(feature = "std")]
+pub use versioned::*;
+#[cfg(all(feature = "derive", feature = "writer"))]
+pub use watto_derive::WriteTo;
+#[cfg(feature = "derive")]
+pub use watto_derive::{ByteSwap, Describe, Pod, Zeroable};
 #[cfg(feature = "writer")]
 pub use writer::*;
+pub use zeroable::*;
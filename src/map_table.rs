@@ -0,0 +1,196 @@
+use core::cmp::Ordering;
+
+use crate::{Pod, Reader};
+
+/// A builder for a sorted map of [`Pod`] keys to [`Pod`] values.
+///
+/// The map is serialized as the number of entries as a `u32`, followed by the
+/// aligned slice of sorted keys and the aligned slice of the corresponding
+/// values. It can be read zero-copy with a [`MapView`], which looks up keys
+/// using binary search directly over the serialized data.
+///
+/// # Example
+/// ```
+/// use watto::{MapTable, MapView, Writer};
+///
+/// let mut map = MapTable::new();
+/// map.insert(0x2000u64, 2u32);
+/// map.insert(0x1000, 1);
+/// map.insert(0x3000, 3);
+///
+/// let mut writer = Writer::new(vec![]);
+/// map.write_to(&mut writer).unwrap();
+/// let buffer = writer.into_inner();
+///
+/// assert_eq!(MapTable::lookup(&buffer, &0x1000u64), Some(&1u32));
+/// assert_eq!(MapTable::<u64, u32>::lookup(&buffer, &0x1001), None);
+///
+/// let view = MapView::<u64, u32>::parse(&buffer).unwrap();
+/// assert_eq!(view.keys(), &[0x1000, 0x2000, 0x3000]);
+/// ```
+#[cfg(feature = "writer")]
+#[derive(Debug, Clone)]
+pub struct MapTable<K, V> {
+    entries: Vec<(K, V)>,
+}
+
+#[cfg(feature = "writer")]
+impl<K, V> Default for MapTable<K, V> {
+    fn default() -> Self {
+        Self {
+            entries: Vec::new(),
+        }
+    }
+}
+
+#[cfg(feature = "writer")]
+impl<K: Pod + Ord, V: Pod> MapTable<K, V> {
+    /// Creates a new empty [`MapTable`].
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Inserts a key/value pair into this [`MapTable`].
+    ///
+    /// If the same key is inserted more than once, the last value wins.
+    pub fn insert(&mut self, key: K, value: V) {
+        self.entries.push((key, value));
+    }
+
+    /// Returns the number of inserted pairs, including those with duplicate keys.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Returns `true` if no pairs have been inserted.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Writes the sorted map into the given [`Writer`](crate::Writer).
+    pub fn write_to<W: std::io::Write>(
+        &self,
+        writer: &mut crate::Writer<W>,
+    ) -> std::io::Result<()> {
+        use std::io::Write;
+
+        let mut sorted: Vec<&(K, V)> = self.entries.iter().collect();
+        // The sort is stable, so the last inserted value comes last among equal keys.
+        sorted.sort_by(|a, b| a.0.cmp(&b.0));
+        let mut entries: Vec<&(K, V)> = Vec::with_capacity(sorted.len());
+        for entry in sorted {
+            match entries.last_mut() {
+                Some(last) if last.0 == entry.0 => *last = entry,
+                _ => entries.push(entry),
+            }
+        }
+
+        let len = u32::try_from(entries.len()).map_err(|_| {
+            std::io::Error::new(std::io::ErrorKind::InvalidInput, "too many map entries")
+        })?;
+        writer.align_to_type::<u32>()?;
+        writer.write_all(len.as_bytes())?;
+        writer.align_to_type::<K>()?;
+        for (key, _) in &entries {
+            writer.write_all(key.as_bytes())?;
+        }
+        writer.align_to_type::<V>()?;
+        for (_, value) in &entries {
+            writer.write_all(value.as_bytes())?;
+        }
+        Ok(())
+    }
+
+    /// Looks up the value for `key` in a serialized [`MapTable`].
+    ///
+    /// This is a shortcut for [`MapView::parse`] followed by [`MapView::lookup`].
+    pub fn lookup<'data>(buffer: &'data [u8], key: &K) -> Option<&'data V>
+    where
+        K: 'data,
+        V: 'data,
+    {
+        MapView::parse(buffer)?.lookup(key)
+    }
+}
+
+/// A zero-copy view of a serialized [`MapTable`](crate::MapTable).
+#[derive(Debug, Clone, Copy)]
+pub struct MapView<'data, K, V> {
+    keys: &'data [K],
+    values: &'data [V],
+}
+
+impl<'data, K: Pod + Ord, V: Pod> MapView<'data, K, V> {
+    /// Parses a serialized map from the start of `buffer`.
+    ///
+    /// Returns [`None`] if the buffer is too small or not properly aligned.
+    /// The keys are *not* verified to be sorted.
+    pub fn parse(buffer: &'data [u8]) -> Option<Self> {
+        Self::read(&mut Reader::new(buffer))
+    }
+
+    /// Reads a serialized map from the given [`Reader`].
+    pub fn read(reader: &mut Reader<'data>) -> Option<Self> {
+        reader.align_to_type::<u32>()?;
+        let len = *reader.read_ref::<u32>()? as usize;
+        reader.align_to_type::<K>()?;
+        let keys = reader.read_slice::<K>(len)?;
+        reader.align_to_type::<V>()?;
+        let values = reader.read_slice::<V>(len)?;
+        Some(Self { keys, values })
+    }
+
+    /// Returns the number of entries.
+    pub fn len(&self) -> usize {
+        self.keys.len()
+    }
+
+    /// Returns `true` if the map is empty.
+    pub fn is_empty(&self) -> bool {
+        self.keys.is_empty()
+    }
+
+    /// Returns the sorted keys.
+    pub fn keys(&self) -> &'data [K] {
+        self.keys
+    }
+
+    /// Returns the values, in the order of their keys.
+    pub fn values(&self) -> &'data [V] {
+        self.values
+    }
+
+    /// Looks up the value for `key` using binary search.
+    pub fn lookup(&self, key: &K) -> Option<&'data V> {
+        self.lookup_by(|probe| probe.cmp(key))
+    }
+
+    /// Looks up a value using binary search with the given comparator function.
+    ///
+    /// See [`slice::binary_search_by`] for the requirements of `f`.
+    pub fn lookup_by<F>(&self, f: F) -> Option<&'data V>
+    where
+        F: FnMut(&'data K) -> Ordering,
+    {
+        let index = self.keys.binary_search_by(f).ok()?;
+        self.values.get(index)
+    }
+
+    /// Iterates over all the entries in key order.
+    pub fn iter(&self) -> impl Iterator<Item = (&'data K, &'data V)> + 'data {
+        self.keys.iter().zip(self.values)
+    }
+}
+
+#[cfg(feature = "writer")]
+impl<K: Pod + Ord, V: Pod> crate::WriteTo for MapTable<K, V> {
+    fn write_to<W: std::io::Write>(&self, writer: &mut crate::Writer<W>) -> std::io::Result<()> {
+        self.write_to(writer)
+    }
+}
+
+impl<'data, K: Pod + Ord, V: Pod> crate::ReadFrom<'data> for MapView<'data, K, V> {
+    fn read_from(reader: &mut Reader<'data>) -> Option<Self> {
+        Self::read(reader)
+    }
+}
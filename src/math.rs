@@ -0,0 +1,103 @@
+use crate::Pod;
+
+/// Implements [`Pod`] for types consisting of exactly `$len` `$scalar`s.
+///
+/// The size is asserted at compile time, as some types contain padding
+/// depending on the target and features of the math crate.
+#[cfg(feature = "glam")]
+macro_rules! impl_pod_scalars {
+    ($($type:ty: [$scalar:ty; $len:expr]),* $(,)?) => {
+        $(
+            const _: () = assert!(
+                core::mem::size_of::<$type>() == core::mem::size_of::<$scalar>() * $len
+            );
+            unsafe impl Pod for $type {}
+        )*
+    };
+}
+
+// `Vec3A`, `Mat3A`, `Affine2` and `Affine3A` are left out, as they contain
+// padding bytes when SIMD is used.
+#[cfg(feature = "glam")]
+impl_pod_scalars!(
+    glam::Vec2: [f32; 2],
+    glam::Vec3: [f32; 3],
+    glam::Vec4: [f32; 4],
+    glam::Quat: [f32; 4],
+    glam::Mat2: [f32; 4],
+    glam::Mat3: [f32; 9],
+    glam::Mat4: [f32; 16],
+    glam::DVec2: [f64; 2],
+    glam::DVec3: [f64; 3],
+    glam::DVec4: [f64; 4],
+    glam::DQuat: [f64; 4],
+    glam::DMat2: [f64; 4],
+    glam::DMat3: [f64; 9],
+    glam::DMat4: [f64; 16],
+    glam::DAffine2: [f64; 6],
+    glam::DAffine3: [f64; 12],
+    glam::I8Vec2: [i8; 2],
+    glam::I8Vec3: [i8; 3],
+    glam::I8Vec4: [i8; 4],
+    glam::U8Vec2: [u8; 2],
+    glam::U8Vec3: [u8; 3],
+    glam::U8Vec4: [u8; 4],
+    glam::I16Vec2: [i16; 2],
+    glam::I16Vec3: [i16; 3],
+    glam::I16Vec4: [i16; 4],
+    glam::U16Vec2: [u16; 2],
+    glam::U16Vec3: [u16; 3],
+    glam::U16Vec4: [u16; 4],
+    glam::IVec2: [i32; 2],
+    glam::IVec3: [i32; 3],
+    glam::IVec4: [i32; 4],
+    glam::UVec2: [u32; 2],
+    glam::UVec3: [u32; 3],
+    glam::UVec4: [u32; 4],
+    glam::I64Vec2: [i64; 2],
+    glam::I64Vec3: [i64; 3],
+    glam::I64Vec4: [i64; 4],
+    glam::U64Vec2: [u64; 2],
+    glam::U64Vec3: [u64; 3],
+    glam::U64Vec4: [u64; 4],
+);
+
+/// Implements [`Pod`] for `#[repr(C)]` types generic over their scalar type.
+///
+/// These consist only of fields of that scalar type, so they have no padding.
+#[cfg(feature = "mint")]
+macro_rules! impl_pod_generic {
+    ($($type:ident),* $(,)?) => {
+        $(
+            unsafe impl<T: Pod> Pod for mint::$type<T> {}
+        )*
+    };
+}
+
+#[cfg(feature = "mint")]
+impl_pod_generic!(
+    Vector2,
+    Vector3,
+    Vector4,
+    Point2,
+    Point3,
+    Quaternion,
+    RowMatrix2,
+    RowMatrix2x3,
+    RowMatrix2x4,
+    RowMatrix3x2,
+    RowMatrix3,
+    RowMatrix3x4,
+    RowMatrix4x2,
+    RowMatrix4x3,
+    RowMatrix4,
+    ColumnMatrix2,
+    ColumnMatrix2x3,
+    ColumnMatrix2x4,
+    ColumnMatrix3x2,
+    ColumnMatrix3,
+    ColumnMatrix3x4,
+    ColumnMatrix4x2,
+    ColumnMatrix4x3,
+    ColumnMatrix4,
+);
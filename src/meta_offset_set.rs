@@ -0,0 +1,161 @@
+use core::hash::{BuildHasher, Hash};
+use core::marker::PhantomData;
+use core::{fmt, mem};
+
+use hashbrown::DefaultHashBuilder;
+
+use crate::{OffsetSet, Pod, ReadOffsetSetError};
+
+/// An [`OffsetSet`] whose entries start with a fixed-size metadata header.
+///
+/// Every entry consists of an `M` value stored immediately before a slice of
+/// `T`, which are read back together with [`read`](Self::read). This allows
+/// annotating entries with flags, timestamps or type tags without maintaining
+/// a parallel table keyed by offset. Entries are only deduplicated if both
+/// their metadata and their slices are equal.
+///
+/// The length prefix of each entry includes the metadata, so the serialized
+/// table must not be read as a plain [`OffsetSet`].
+///
+/// # Example
+/// ```
+/// use watto::{MetaOffsetSet, U48};
+///
+/// let mut set = MetaOffsetSet::<U48, u8>::new();
+/// let a = set.insert(&U48::new(1700000000), b"foo");
+/// let b = set.insert(&U48::new(1700000042), b"foo");
+/// assert_ne!(a, b);
+/// assert_eq!(set.get(&U48::new(1700000042), b"foo"), Some(b));
+/// let buffer = set.into_bytes();
+///
+/// let (timestamp, items) = MetaOffsetSet::<U48, u8>::read(&buffer, b).unwrap();
+/// assert_eq!(timestamp.get(), 1700000042);
+/// assert_eq!(items, b"foo");
+/// ```
+#[derive(Clone)]
+pub struct MetaOffsetSet<M, T, H = DefaultHashBuilder> {
+    inner: OffsetSet<T, Vec<u8>, H>,
+    _m: PhantomData<M>,
+}
+
+impl<M: fmt::Debug + Pod, T: fmt::Debug + Pod, H> fmt::Debug for MetaOffsetSet<M, T, H> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let iter = self
+            .entries()
+            .map(|(offset, meta, items)| (offset, (meta, items)));
+        f.debug_map().entries(iter).finish()
+    }
+}
+
+impl<M: Pod, T: Pod, H: Default> Default for MetaOffsetSet<M, T, H> {
+    fn default() -> Self {
+        Self::with_hasher(H::default())
+    }
+}
+
+impl<M: Pod, T: Pod> MetaOffsetSet<M, T> {
+    /// Initializes an empty [`MetaOffsetSet`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if `M` is not aligned to 1 byte, or its size is not a multiple
+    /// of the size of `T`.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the metadata header and the slice stored at the given offset in
+    /// the byte slice.
+    ///
+    /// Fails if the entry is too short to hold an `M`.
+    pub fn read(buffer: &[u8], offset: usize) -> Result<(&M, &[T]), ReadOffsetSetError> {
+        let entry = OffsetSet::<T>::read(buffer, offset)?;
+        split_entry(entry).ok_or(ReadOffsetSetError::OutOfBounds)
+    }
+}
+
+impl<M: Pod, T: Pod, H> MetaOffsetSet<M, T, H> {
+    /// Initializes an empty [`MetaOffsetSet`] which hashes its entries with
+    /// the given [`BuildHasher`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if `M` is not aligned to 1 byte, or its size is not a multiple
+    /// of the size of `T`.
+    pub fn with_hasher(hasher: H) -> Self {
+        assert!(mem::align_of::<M>() == 1, "M is limited to alignment `1`");
+        assert!(
+            mem::size_of::<M>().is_multiple_of(mem::size_of::<T>()),
+            "the size of M must be a multiple of the size of T"
+        );
+        let meta_len = mem::size_of::<M>() / mem::size_of::<T>();
+        Self {
+            inner: OffsetSet::with_meta_len(hasher, meta_len),
+            _m: PhantomData,
+        }
+    }
+
+    /// Returns the number of entries in this [`MetaOffsetSet`].
+    pub fn len(&self) -> usize {
+        self.inner.len()
+    }
+
+    /// Returns `true` if this [`MetaOffsetSet`] is empty.
+    pub fn is_empty(&self) -> bool {
+        self.inner.is_empty()
+    }
+
+    /// Iterates over all the entries in this [`MetaOffsetSet`].
+    ///
+    /// This yields `(offset, meta, slice)` tuples.
+    pub fn entries(&self) -> impl Iterator<Item = (usize, &M, &[T])> + '_ {
+        self.inner.entries().map(|(offset, entry)| {
+            let (meta, items) = split_entry(entry).unwrap();
+            (offset, meta, items)
+        })
+    }
+
+    /// Returns the serialized representation of this [`MetaOffsetSet`].
+    pub fn as_bytes(&self) -> &[u8] {
+        self.inner.as_bytes()
+    }
+
+    /// Returns a byte vector containing the serialized representation of this
+    /// [`MetaOffsetSet`].
+    ///
+    /// This consumes the [`MetaOffsetSet`].
+    pub fn into_bytes(self) -> Vec<u8> {
+        self.inner.into_bytes()
+    }
+}
+
+impl<M: Pod, T: Pod + PartialEq + Hash, H: BuildHasher> MetaOffsetSet<M, T, H> {
+    /// Inserts a slice together with its metadata into this [`MetaOffsetSet`].
+    ///
+    /// Returns an offset that can be used to retrieve both with
+    /// [`read`](MetaOffsetSet::read) after serializing this table.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the table grows beyond 4 GiB.
+    pub fn insert(&mut self, meta: &M, input: &[T]) -> usize {
+        self.inner.insert_with_meta(meta_items(meta), input)
+    }
+
+    /// Returns the offset of the given metadata and slice if they are
+    /// contained in this [`MetaOffsetSet`].
+    pub fn get(&self, meta: &M, items: &[T]) -> Option<usize> {
+        self.inner.get_with_meta(meta_items(meta), items)
+    }
+}
+
+/// Reinterprets the metadata of an entry as elements of the entry.
+fn meta_items<M: Pod, T: Pod>(meta: &M) -> &[T] {
+    T::slice_from_bytes(meta.as_bytes()).expect("M is aligned and sized for T")
+}
+
+/// Splits an entry into its metadata and its slice.
+fn split_entry<M: Pod, T: Pod>(entry: &[T]) -> Option<(&M, &[T])> {
+    let (meta, items) = M::ref_from_prefix(entry.as_bytes())?;
+    Some((meta, T::slice_from_bytes(items)?))
+}
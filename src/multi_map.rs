@@ -0,0 +1,198 @@
+use crate::{OffsetSet, Pod, Reader};
+
+/// A builder for a one-to-many mapping of [`Pod`] keys to slices of [`Pod`] values.
+///
+/// The value slices are stored in an internal [`OffsetSet`], which deduplicates
+/// identical slices. Just like for the [`OffsetSet`], `V` is currently limited
+/// to an alignment of `1`.
+///
+/// The map is serialized as the number of keys as a `u32`, the aligned slice
+/// of sorted keys, the corresponding `u32` offsets into the [`OffsetSet`],
+/// the size of the [`OffsetSet`] as a `u32`, and finally the [`OffsetSet`] itself.
+/// It can be read zero-copy with a [`MultiMapView`].
+///
+/// # Example
+/// ```
+/// use watto::{MultiMap, MultiMapView, Writer};
+///
+/// let mut map = MultiMap::new();
+/// map.insert(2u32, b'b');
+/// map.insert(1, b'a');
+/// map.insert(2, b'c');
+///
+/// let mut writer = Writer::new(vec![]);
+/// map.write_to(&mut writer).unwrap();
+/// let buffer = writer.into_inner();
+///
+/// let view = MultiMapView::<u32, u8>::parse(&buffer).unwrap();
+/// assert_eq!(view.get_all(&1), b"a");
+/// assert_eq!(view.get_all(&2), b"bc");
+/// assert_eq!(view.get_all(&3), b"");
+/// ```
+#[cfg(feature = "writer")]
+#[derive(Debug, Clone)]
+pub struct MultiMap<K, V> {
+    entries: std::collections::BTreeMap<K, Vec<V>>,
+}
+
+#[cfg(feature = "writer")]
+impl<K, V> Default for MultiMap<K, V> {
+    fn default() -> Self {
+        Self {
+            entries: Default::default(),
+        }
+    }
+}
+
+#[cfg(feature = "writer")]
+impl<K: Pod + Ord, V: Pod + PartialEq + core::hash::Hash> MultiMap<K, V> {
+    /// Creates a new empty [`MultiMap`].
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Appends a value to the values of `key`.
+    pub fn insert(&mut self, key: K, value: V) {
+        self.entries.entry(key).or_default().push(value);
+    }
+
+    /// Appends all the given values to the values of `key`.
+    pub fn insert_all<I: IntoIterator<Item = V>>(&mut self, key: K, values: I) {
+        self.entries.entry(key).or_default().extend(values);
+    }
+
+    /// Returns the number of keys.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Returns `true` if there are no keys.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Writes the map into the given [`Writer`](crate::Writer).
+    pub fn write_to<W: std::io::Write>(
+        &self,
+        writer: &mut crate::Writer<W>,
+    ) -> std::io::Result<()> {
+        use std::io::{Error, ErrorKind, Write};
+
+        let too_large = || Error::new(ErrorKind::InvalidInput, "multi map is too large");
+
+        let mut values = OffsetSet::<V>::new();
+        let mut offsets = Vec::with_capacity(self.entries.len());
+        for list in self.entries.values() {
+            let offset = u32::try_from(values.insert(list)).map_err(|_| too_large())?;
+            offsets.push(offset);
+        }
+        let values = values.into_bytes();
+
+        let len = u32::try_from(self.entries.len()).map_err(|_| too_large())?;
+        let values_len = u32::try_from(values.len()).map_err(|_| too_large())?;
+
+        writer.align_to_type::<u32>()?;
+        writer.write_all(len.as_bytes())?;
+        writer.align_to_type::<K>()?;
+        for key in self.entries.keys() {
+            writer.write_all(key.as_bytes())?;
+        }
+        writer.align_to_type::<u32>()?;
+        writer.write_all(offsets.as_bytes())?;
+        writer.write_all(values_len.as_bytes())?;
+        writer.write_all(&values)
+    }
+}
+
+/// A zero-copy view of a serialized [`MultiMap`](crate::MultiMap).
+#[derive(Debug, Clone, Copy)]
+pub struct MultiMapView<'data, K, V> {
+    keys: &'data [K],
+    offsets: &'data [u32],
+    values: &'data [u8],
+    _v: core::marker::PhantomData<&'data [V]>,
+}
+
+impl<'data, K: Pod + Ord, V: Pod> MultiMapView<'data, K, V> {
+    /// Parses a serialized multi map from the start of `buffer`.
+    ///
+    /// Returns [`None`] if the buffer is too small or not properly aligned,
+    /// or any of the value slices is invalid.
+    /// The keys are *not* verified to be sorted.
+    pub fn parse(buffer: &'data [u8]) -> Option<Self> {
+        Self::read(&mut Reader::new(buffer))
+    }
+
+    /// Reads a serialized multi map from the given [`Reader`].
+    pub fn read(reader: &mut Reader<'data>) -> Option<Self> {
+        reader.align_to_type::<u32>()?;
+        let len = *reader.read_ref::<u32>()? as usize;
+        reader.align_to_type::<K>()?;
+        let keys = reader.read_slice::<K>(len)?;
+        reader.align_to_type::<u32>()?;
+        let offsets = reader.read_slice::<u32>(len)?;
+        let values_len = *reader.read_ref::<u32>()? as usize;
+        let values = reader.read_bytes(values_len)?;
+
+        // Validate all the value slices upfront, so that lookups are infallible.
+        for offset in offsets {
+            OffsetSet::<V>::read(values, *offset as usize).ok()?;
+        }
+
+        Some(Self {
+            keys,
+            offsets,
+            values,
+            _v: Default::default(),
+        })
+    }
+
+    /// Returns the number of keys.
+    pub fn len(&self) -> usize {
+        self.keys.len()
+    }
+
+    /// Returns `true` if there are no keys.
+    pub fn is_empty(&self) -> bool {
+        self.keys.is_empty()
+    }
+
+    /// Returns the sorted keys.
+    pub fn keys(&self) -> &'data [K] {
+        self.keys
+    }
+
+    /// Returns all the values of `key`, or an empty slice if the key does not exist.
+    pub fn get_all(&self, key: &K) -> &'data [V] {
+        match self.keys.binary_search(key) {
+            Ok(index) => self.values_at(index),
+            Err(_) => &[],
+        }
+    }
+
+    fn values_at(&self, index: usize) -> &'data [V] {
+        // The value slices have been validated in `read`.
+        OffsetSet::<V>::read(self.values, self.offsets[index] as usize).unwrap()
+    }
+
+    /// Iterates over all the keys and their values in key order.
+    pub fn iter(&self) -> impl Iterator<Item = (&'data K, &'data [V])> + '_ {
+        self.keys
+            .iter()
+            .enumerate()
+            .map(|(index, key)| (key, self.values_at(index)))
+    }
+}
+
+#[cfg(feature = "writer")]
+impl<K: Pod + Ord, V: Pod + PartialEq + core::hash::Hash> crate::WriteTo for MultiMap<K, V> {
+    fn write_to<W: std::io::Write>(&self, writer: &mut crate::Writer<W>) -> std::io::Result<()> {
+        self.write_to(writer)
+    }
+}
+
+impl<'data, K: Pod + Ord, V: Pod> crate::ReadFrom<'data> for MultiMapView<'data, K, V> {
+    fn read_from(reader: &mut Reader<'data>) -> Option<Self> {
+        Self::read(reader)
+    }
+}
@@ -0,0 +1,55 @@
+/// An observer which is notified about the growth of an [`OffsetSet`](crate::OffsetSet)
+/// or [`StringTable`](crate::StringTable).
+///
+/// Register an observer with [`OffsetSet::set_observer`](crate::OffsetSet::set_observer)
+/// to export insertions, deduplication rates and table sizes as metrics.
+/// All methods have an empty default implementation.
+///
+/// # Example
+/// ```
+/// # #[cfg(feature = "strings")] {
+/// use std::sync::atomic::{AtomicUsize, Ordering};
+/// use std::sync::Arc;
+///
+/// use watto::{StringTable, TableObserver};
+///
+/// #[derive(Default)]
+/// struct DedupCounter(AtomicUsize);
+///
+/// impl TableObserver for DedupCounter {
+///     fn on_dedup_hit(&self, _saved_bytes: usize) {
+///         self.0.fetch_add(1, Ordering::Relaxed);
+///     }
+/// }
+///
+/// let counter = Arc::new(DedupCounter::default());
+/// let mut table = StringTable::new();
+/// table.set_observer(counter.clone());
+///
+/// table.insert("foo");
+/// table.insert("foo");
+/// assert_eq!(counter.0.load(Ordering::Relaxed), 1);
+/// # }
+/// ```
+pub trait TableObserver: Send + Sync {
+    /// Called when a new entry is inserted, with its serialized size in bytes.
+    fn on_insert(&self, size: usize) {
+        let _ = size;
+    }
+
+    /// Called when an inserted entry was deduplicated, with the number of bytes saved.
+    fn on_dedup_hit(&self, saved_bytes: usize) {
+        let _ = saved_bytes;
+    }
+
+    /// Called when the buffer holding the serialized table was reallocated,
+    /// with its capacity in bytes before and after.
+    fn on_buffer_growth(&self, old_capacity: usize, new_capacity: usize) {
+        let _ = (old_capacity, new_capacity);
+    }
+
+    /// Called when the table is serialized with `into_bytes`, with its size in bytes.
+    fn on_serialize(&self, size: usize) {
+        let _ = size;
+    }
+}
@@ -1,23 +1,133 @@
 use core::hash::{BuildHasher, Hash};
 use core::marker::PhantomData;
 use core::{fmt, mem};
-use std::io::Cursor;
+use std::io;
+use std::path::Path;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
 
-use hashbrown::hash_table::Entry;
-use hashbrown::{DefaultHashBuilder, HashTable};
+use hashbrown::{DefaultHashBuilder, HashSet, HashTable};
 use thiserror::Error;
 
-use crate::Pod;
+use crate::trace::{event, timed_span};
+use crate::{raw, varint, FileStorage, Pod, SharedStorage, Storage, TableObserver};
 
 /// An error when trying to read a slice from a serialized [`OffsetSet`].
 #[derive(Debug, Error)]
 pub enum ReadOffsetSetError {
     /// The entry's length prefix is not valid LEB128.
     #[error("error reading LEB128 encoded number")]
-    Leb128(#[from] leb128::read::Error),
+    Leb128(#[from] varint::VarintError),
     /// The entry's offset or length is outside the bounds of the data blob.
     #[error("element offset or length is out of bounds")]
     OutOfBounds,
+    /// The serialized table has entries beyond the maximum offset of 4 GiB.
+    #[error("table exceeds the maximum offset of 4 GiB")]
+    TooLarge,
+}
+
+impl From<raw::EntryError> for ReadOffsetSetError {
+    fn from(err: raw::EntryError) -> Self {
+        match err {
+            raw::EntryError::Length(err) => Self::Leb128(err),
+            raw::EntryError::OutOfBounds => Self::OutOfBounds,
+        }
+    }
+}
+
+/// The maximum offset of an entry, as offsets are stored as `u32` in the index.
+const MAX_OFFSET: usize = u32::MAX as usize;
+
+/// The number of bytes after which the progress of loading a table is reported.
+///
+/// See [`OffsetSet::from_bytes_with_progress`].
+pub const PROGRESS_INTERVAL: usize = 1 << 20;
+
+/// The number of entries up to which they are found by a linear scan,
+/// instead of being hashed.
+const LINEAR_SCAN_LEN: usize = 16;
+
+/// The position of an entry passed to the validation function of
+/// [`OffsetSet::from_bytes_with_progress`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct EntryContext {
+    /// The index of the entry, counting all entries in the order they are stored.
+    pub index: usize,
+    /// The offset of the entry.
+    pub offset: usize,
+}
+
+/// The progress of loading a serialized [`OffsetSet`], reported by
+/// [`OffsetSet::from_bytes_with_progress`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LoadProgress {
+    /// The number of entries loaded so far.
+    pub entries: usize,
+    /// The number of bytes loaded so far.
+    pub bytes: usize,
+    /// The total size of the serialized table.
+    pub total_bytes: usize,
+}
+
+/// An error when trying to insert a slice that exceeds the configured [`Limits`].
+#[derive(Debug, Error)]
+pub enum InsertError {
+    /// The entry is longer than the configured maximum.
+    #[error("entry length {len} exceeds the maximum of {max}")]
+    EntryTooLong {
+        /// The length of the entry, in elements.
+        len: usize,
+        /// The configured maximum entry length.
+        max: usize,
+    },
+    /// Inserting the entry would grow the table beyond the configured maximum size.
+    #[error("table size {size} would exceed the maximum of {max} bytes")]
+    TableTooLarge {
+        /// The size of the table including the new entry, in bytes.
+        size: usize,
+        /// The configured maximum table size.
+        max: usize,
+    },
+    /// The table's [`Storage`] could not be grown to hold the entry.
+    #[error("failed to grow the table storage")]
+    Storage(#[source] io::Error),
+    /// The entry would be stored beyond the maximum offset of 4 GiB.
+    #[error("entry offset {offset} exceeds the maximum offset of 4 GiB")]
+    OffsetOverflow {
+        /// The offset the entry would be stored at.
+        offset: usize,
+    },
+}
+
+/// Size limits enforced by [`OffsetSet::try_insert`] and [`StringTable::try_insert`](crate::StringTable::try_insert).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Limits {
+    /// The maximum length of a single entry, in elements.
+    pub max_entry_len: Option<usize>,
+    /// The maximum size of the serialized table, in bytes.
+    pub max_size: Option<usize>,
+}
+
+/// Statistics about the contents and memory usage of an [`OffsetSet`] or [`StringTable`](crate::StringTable).
+///
+/// Returned from [`OffsetSet::stats`].
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct TableStats {
+    /// The number of unique entries.
+    pub entries: usize,
+    /// The size of the serialized representation in bytes.
+    pub serialized_size: usize,
+    /// The number of insertions that were deduplicated.
+    pub duplicates: usize,
+    /// The number of bytes that deduplicated insertions would have added to
+    /// the serialized representation.
+    pub dedup_saved_bytes: usize,
+    /// The heap memory used by the deduplication index in bytes.
+    pub index_heap_bytes: usize,
+    /// The length of the longest entry, in elements.
+    pub max_entry_len: usize,
+    /// The average length of all entries, in elements.
+    pub avg_entry_len: f64,
 }
 
 /// A struct for storing arbitrary slices without duplicates.
@@ -31,26 +141,228 @@ pub enum ReadOffsetSetError {
 ///   within the buffer.
 /// - It is intended to be serialized as an opaque buffer, and data to be loaded
 ///   from it with minimal overhead.
+///
+/// The buffer is kept in a [`Vec<u8>`] by default, but can be kept in any other
+/// [`Storage`], see [`from_storage`](Self::from_storage).
+/// The entries are hashed with a [`DefaultHashBuilder`] by default, but any other
+/// [`BuildHasher`] can be used, see [`with_hasher`](Self::with_hasher).
+///
+/// Cloning a table copies its buffer and deduplication index, unless the
+/// buffer is kept in a [`SharedStorage`], see [`into_shared`](Self::into_shared).
+/// Clones of such a table share both until one of them is modified, which
+/// makes taking snapshots of large tables cheap.
+///
+/// The deduplication index stores offsets as `u32`, so entries can only be
+/// inserted at offsets up to 4 GiB.
+/// Small tables find duplicates by comparing all entries, and switch to a hash
+/// table once they grow beyond a few entries. This avoids hashing the entries
+/// of the many tiny tables that are commonly built.
 #[derive(Clone)]
-pub struct OffsetSet<T> {
-    hasher: DefaultHashBuilder,
-    offsets: HashTable<usize>,
-    buffer: Vec<u8>,
+pub struct OffsetSet<T, S = Vec<u8>, H = DefaultHashBuilder> {
+    hasher: H,
+    offsets: Arc<Index>,
+    buffer: S,
+    duplicates: usize,
+    dedup_saved_bytes: usize,
+    limits: Limits,
+    entry_align: usize,
+    /// The number of leading elements of each entry which hold the metadata
+    /// of a [`MetaOffsetSet`](crate::MetaOffsetSet).
+    meta_len: usize,
+    observer: Option<Arc<dyn TableObserver>>,
     _t: PhantomData<T>,
 }
 
-impl<T: fmt::Debug + Pod> fmt::Debug for OffsetSet<T> {
+/// The deduplication index of an [`OffsetSet`].
+#[derive(Clone)]
+enum Index {
+    /// The offsets of all entries, which are compared one by one.
+    Linear(Vec<u32>),
+    /// A hash table of the offsets of all entries.
+    Hashed(HashTable<u32>),
+    /// A hash table of the offsets of recently used entries.
+    Bounded(BoundedIndex),
+}
+
+impl Default for Index {
+    fn default() -> Self {
+        Self::Linear(Vec::new())
+    }
+}
+
+impl Index {
+    /// Creates an index with room for `capacity` entries.
+    fn with_capacity(capacity: usize) -> Self {
+        if capacity > LINEAR_SCAN_LEN {
+            Self::Hashed(HashTable::with_capacity(capacity))
+        } else {
+            Self::Linear(Vec::with_capacity(capacity))
+        }
+    }
+
+    fn len(&self) -> usize {
+        match self {
+            Self::Linear(offsets) => offsets.len(),
+            Self::Hashed(offsets) => offsets.len(),
+            Self::Bounded(index) => index.entries,
+        }
+    }
+
+    fn capacity(&self) -> usize {
+        match self {
+            Self::Linear(offsets) => offsets.capacity(),
+            Self::Hashed(offsets) => offsets.capacity(),
+            Self::Bounded(index) => index.slots.capacity(),
+        }
+    }
+
+    fn allocation_size(&self) -> usize {
+        match self {
+            Self::Linear(offsets) => offsets.capacity() * mem::size_of::<u32>(),
+            Self::Hashed(offsets) => offsets.allocation_size(),
+            Self::Bounded(index) => {
+                index.table.allocation_size() + index.slots.capacity() * mem::size_of::<LruSlot>()
+            }
+        }
+    }
+
+    /// Iterates over the offsets of all indexed entries.
+    fn iter(&self) -> impl Iterator<Item = usize> + '_ {
+        let (linear, hashed, bounded) = match self {
+            Self::Linear(offsets) => (&offsets[..], None, None),
+            Self::Hashed(offsets) => (&[][..], Some(offsets.iter()), None),
+            Self::Bounded(index) => (&[][..], None, Some(index.slots.iter())),
+        };
+        linear
+            .iter()
+            .chain(hashed.into_iter().flatten())
+            .chain(bounded.into_iter().flatten().map(|slot| &slot.offset))
+            .map(|&offset| offset as usize)
+    }
+}
+
+/// Marks the end of the list of a [`BoundedIndex`].
+const NIL: u32 = u32::MAX;
+
+/// An entry of a [`BoundedIndex`], linked in the order of recent use.
+#[derive(Clone, Copy)]
+struct LruSlot {
+    offset: u32,
+    prev: u32,
+    next: u32,
+}
+
+/// A deduplication index which holds at most `max_entries` offsets, and
+/// evicts the least recently used one when it is full.
+#[derive(Clone)]
+struct BoundedIndex {
+    /// Indices into `slots`, hashed by the entry at their offset.
+    table: HashTable<u32>,
+    /// The indexed offsets, linked from the most to the least recently used.
+    slots: Vec<LruSlot>,
+    head: u32,
+    tail: u32,
+    max_entries: usize,
+    /// The number of entries of the table, including evicted ones.
+    entries: usize,
+}
+
+impl BoundedIndex {
+    fn new(max_entries: usize) -> Self {
+        Self {
+            table: HashTable::new(),
+            slots: vec![],
+            head: NIL,
+            tail: NIL,
+            max_entries,
+            entries: 0,
+        }
+    }
+
+    /// Returns the slot of the entry with the given `hash` for which `eq` returns `true`.
+    fn find(&self, hash: u64, eq: impl Fn(u32) -> bool) -> Option<u32> {
+        self.table
+            .find(hash, |&slot| eq(self.slots[slot as usize].offset))
+            .copied()
+    }
+
+    /// Marks the entry in `slot` as the most recently used one.
+    fn touch(&mut self, slot: u32) {
+        if self.head != slot {
+            self.unlink(slot);
+            self.push_front(slot);
+        }
+    }
+
+    fn unlink(&mut self, slot: u32) {
+        let LruSlot { prev, next, .. } = self.slots[slot as usize];
+        match prev {
+            NIL => self.head = next,
+            prev => self.slots[prev as usize].next = next,
+        }
+        match next {
+            NIL => self.tail = prev,
+            next => self.slots[next as usize].prev = prev,
+        }
+    }
+
+    fn push_front(&mut self, slot: u32) {
+        self.slots[slot as usize].prev = NIL;
+        self.slots[slot as usize].next = self.head;
+        match self.head {
+            NIL => self.tail = slot,
+            head => self.slots[head as usize].prev = slot,
+        }
+        self.head = slot;
+    }
+
+    /// Adds the offset of a new entry, evicting the least recently used one if
+    /// the index is full.
+    fn insert(&mut self, hash: u64, offset: u32, hash_at: impl Fn(u32) -> u64) {
+        self.entries += 1;
+        let slot = if self.slots.len() < self.max_entries {
+            self.slots.push(LruSlot {
+                offset,
+                prev: NIL,
+                next: NIL,
+            });
+            (self.slots.len() - 1) as u32
+        } else {
+            let slot = self.tail;
+            let evicted = self.slots[slot as usize].offset;
+            if let Ok(entry) = self.table.find_entry(hash_at(evicted), |&s| s == slot) {
+                entry.remove();
+            }
+            self.unlink(slot);
+            self.slots[slot as usize].offset = offset;
+            slot
+        };
+        self.push_front(slot);
+
+        let slots = &self.slots;
+        self.table
+            .insert_unique(hash, slot, |&slot| hash_at(slots[slot as usize].offset));
+    }
+}
+
+impl<T: fmt::Debug + Pod, S: Storage, H> fmt::Debug for OffsetSet<T, S, H> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         f.debug_map().entries(self.entries()).finish()
     }
 }
 
-impl<T> Default for OffsetSet<T> {
+impl<T, S: Default, H: Default> Default for OffsetSet<T, S, H> {
     fn default() -> Self {
         Self {
             hasher: Default::default(),
             offsets: Default::default(),
             buffer: Default::default(),
+            duplicates: 0,
+            dedup_saved_bytes: 0,
+            limits: Default::default(),
+            entry_align: 1,
+            meta_len: 0,
+            observer: None,
             _t: Default::default(),
         }
     }
@@ -60,7 +372,7 @@ impl<T: Pod> OffsetSet<T> {
     #[doc(hidden)]
     const _ALIGN_OF_T: () = {
         // TODO: this is not a hard requirement for now, and we can lift this in the future.
-        // - using `leb128` encoding might not make sense at all for types with larger alignment
+        // - using LEB128 encoding might not make sense at all for types with larger alignment
         // - otherwise this might be missing a couple of places that need explicit alignment
         // - and as we have found out, miri is particularly picky about alignment as well :-)
         assert!(
@@ -74,6 +386,15 @@ impl<T: Pod> OffsetSet<T> {
         Self::default()
     }
 
+    /// Initializes an empty [`OffsetSet`] which enforces the given [`Limits`]
+    /// when inserting with [`try_insert`](Self::try_insert).
+    pub fn with_limits(limits: Limits) -> Self {
+        Self {
+            limits,
+            ..Default::default()
+        }
+    }
+
     /// Returns the slice stored at the given offset in the byte slice, if any.
     ///
     /// Use this to retrieve a slice that was previously [inserted](OffsetSet::insert) into an [`OffsetSet`].
@@ -81,63 +402,389 @@ impl<T: Pod> OffsetSet<T> {
         Ok(OffsetSet::read_internal(buffer, offset)?.0)
     }
 
+    /// Returns the `count` entries stored consecutively starting at `offset`.
+    ///
+    /// Entries are stored in the order they were inserted, so this reads a batch
+    /// of entries inserted one after the other with a single bounds check per
+    /// entry, without having to know all of their offsets. Duplicates are only
+    /// stored once though, so an entry which was deduplicated on insertion is
+    /// not part of the batch. The padding between entries written with an
+    /// [entry alignment](Self::set_entry_alignment) is skipped.
+    ///
+    /// Fails if there are less than `count` entries following `offset`, or any
+    /// of them is invalid.
+    ///
+    /// # Example
+    /// ```
+    /// use watto::OffsetSet;
+    ///
+    /// let mut set = OffsetSet::<u8>::new();
+    /// set.insert(b"foo");
+    /// let start = set.insert(b"bar");
+    /// set.insert(b"baz");
+    /// let buffer = set.into_bytes();
+    ///
+    /// let entries = OffsetSet::<u8>::read_range(&buffer, start, 2).unwrap();
+    /// assert_eq!(entries, [b"bar", b"baz"]);
+    /// assert!(OffsetSet::<u8>::read_range(&buffer, start, 3).is_err());
+    /// ```
+    pub fn read_range(
+        buffer: &[u8],
+        offset: usize,
+        count: usize,
+    ) -> Result<Vec<&[T]>, ReadOffsetSetError> {
+        if offset > buffer.len() {
+            return Err(ReadOffsetSetError::OutOfBounds);
+        }
+        let entries = BufferEntries {
+            buffer,
+            offset,
+            _t: PhantomData,
+        };
+        let mut slices = Vec::with_capacity(count.min(buffer.len() - offset));
+        for entry in entries.take(count) {
+            let (_, slice) = entry.map_err(|(_, err)| err)?;
+            slices.push(slice);
+        }
+        if slices.len() < count {
+            return Err(ReadOffsetSetError::OutOfBounds);
+        }
+        Ok(slices)
+    }
+
+    #[inline]
     fn read_internal(buffer: &[u8], offset: usize) -> Result<(&[T], usize), ReadOffsetSetError> {
-        let mut cursor = Cursor::new(
-            buffer
-                .get(offset..)
-                .ok_or(ReadOffsetSetError::OutOfBounds)?,
-        );
-        let len = leb128::read::unsigned(&mut cursor)? as usize;
-        // it would be nice if `leb128` would directly return this as well,
-        // so one wouldn't have to use a `Cursor`.
-        let leb_len = cursor.position() as usize;
+        Ok(raw::read_internal(buffer, offset)?)
+    }
+
+    /// Scans the entry boundaries of a serialized [`OffsetSet`], deferring the
+    /// validation of each entry to its first access.
+    ///
+    /// This is an alternative to validating all entries up front with
+    /// [`from_bytes_validated`](Self::from_bytes_validated), trading a little
+    /// cost on the read path for much faster loading of huge tables.
+    pub fn validate_lazy<V, E>(
+        buffer: &[u8],
+        validate: V,
+    ) -> Result<LazyValidated<'_, T, V>, ReadOffsetSetError>
+    where
+        V: Fn(&[T]) -> Result<(), E>,
+    {
+        let offsets: Vec<usize> = OffsetSet::<T>::iter_buffer(buffer)
+            .map(|entry| entry.map(|(offset, _)| offset).map_err(|(_, err)| err))
+            .collect::<Result<_, _>>()?;
+        let validated = (0..offsets.len().div_ceil(64))
+            .map(|_| AtomicU64::new(0))
+            .collect();
+        Ok(LazyValidated {
+            buffer,
+            offsets,
+            validated,
+            validate,
+            _t: PhantomData,
+        })
+    }
 
-        let start = offset + leb_len;
-        let end = start + len * mem::size_of::<T>();
+    /// Discards a partially written entry at the end of `storage`.
+    pub(crate) fn recover(mut storage: FileStorage) -> io::Result<FileStorage> {
+        let buffer = storage.as_bytes();
+        let mut len = buffer.len();
+        for entry in OffsetSet::<T>::iter_buffer(buffer) {
+            match entry {
+                Ok(_) => {}
+                // An entry extending beyond the end of the file was not fully written.
+                Err((offset, ReadOffsetSetError::Leb128(varint::VarintError::Truncated))) => {
+                    len = offset
+                }
+                Err((offset, ReadOffsetSetError::OutOfBounds)) if Self::is_torn(buffer, offset) => {
+                    len = offset
+                }
+                Err((_, err)) => return Err(io::Error::new(io::ErrorKind::InvalidData, err)),
+            }
+        }
+        storage.truncate(len)?;
+        Ok(storage)
+    }
 
-        let bytes = buffer
-            .get(start..end)
-            .ok_or(ReadOffsetSetError::OutOfBounds)?;
-        let slice = T::slice_from_bytes(bytes).ok_or(ReadOffsetSetError::OutOfBounds)?;
+    /// Returns whether the entry at `offset`, which extends beyond the end of
+    /// `buffer`, is the last entry, and was thus cut off by a torn write.
+    ///
+    /// If any complete entry ends at the end of `buffer` within the bytes
+    /// claimed by the entry, its length was corrupted instead.
+    fn is_torn(buffer: &[u8], offset: usize) -> bool {
+        let Some((_, prefix_len)) = varint::read_u64(&buffer[offset..]) else {
+            return true;
+        };
+        let start = offset + prefix_len;
+        !(start..buffer.len()).any(|offset| {
+            matches!(Self::read_internal(buffer, offset), Ok((_, end)) if end == buffer.len())
+        })
+    }
 
-        Ok((slice, end))
+    /// Iterates over all the entries of a serialized [`OffsetSet`] in the order
+    /// in which they were inserted.
+    pub(crate) fn iter_buffer(buffer: &[u8]) -> BufferEntries<'_, T> {
+        BufferEntries {
+            buffer,
+            offset: 0,
+            _t: PhantomData,
+        }
+    }
+}
+
+impl<T: Pod, H> OffsetSet<T, Vec<u8>, H> {
+    /// Initializes an empty [`OffsetSet`] which hashes its entries with the given [`BuildHasher`].
+    ///
+    /// See [`insert_hashed`](Self::insert_hashed) for inserting entries with
+    /// precomputed hashes.
+    pub fn with_hasher(hasher: H) -> Self {
+        Self {
+            hasher,
+            offsets: Default::default(),
+            buffer: Default::default(),
+            duplicates: 0,
+            dedup_saved_bytes: 0,
+            limits: Default::default(),
+            entry_align: 1,
+            meta_len: 0,
+            observer: None,
+            _t: PhantomData,
+        }
+    }
+
+    /// Initializes an empty [`OffsetSet`] whose entries start with `meta_len`
+    /// elements of metadata, as used by [`MetaOffsetSet`](crate::MetaOffsetSet).
+    pub(crate) fn with_meta_len(hasher: H, meta_len: usize) -> Self {
+        Self {
+            meta_len,
+            ..Self::with_hasher(hasher)
+        }
+    }
+
+    /// Returns a byte vector containing the serialized representation of this [`OffsetSet`].
+    ///
+    /// This consumes the [`OffsetSet`].
+    pub fn into_bytes(self) -> Vec<u8> {
+        self.into_storage()
+    }
+
+    /// Returns the serialized representation of this [`OffsetSet`] as a shared buffer.
+    ///
+    /// This consumes the [`OffsetSet`] and hands over its buffer without copying it.
+    #[cfg(feature = "bytes")]
+    pub fn into_shared_bytes(self) -> bytes::Bytes {
+        self.into_bytes().into()
+    }
+
+    /// Moves the buffer of this [`OffsetSet`] into a [`SharedStorage`].
+    ///
+    /// Clones of the resulting table share their buffer and deduplication
+    /// index, which are only copied once one of the clones is modified.
+    pub fn into_shared(self) -> OffsetSet<T, SharedStorage, H> {
+        OffsetSet {
+            hasher: self.hasher,
+            offsets: self.offsets,
+            buffer: self.buffer.into(),
+            duplicates: self.duplicates,
+            dedup_saved_bytes: self.dedup_saved_bytes,
+            limits: self.limits,
+            entry_align: self.entry_align,
+            meta_len: self.meta_len,
+            observer: self.observer,
+            _t: PhantomData,
+        }
+    }
+}
+
+impl<T: Pod, S: Storage, H> OffsetSet<T, S, H> {
+    /// Returns the [`BuildHasher`] used to hash the entries of this [`OffsetSet`].
+    pub fn hasher(&self) -> &H {
+        &self.hasher
+    }
+
+    /// Returns the [`Limits`] enforced by [`try_insert`](Self::try_insert).
+    pub fn limits(&self) -> Limits {
+        self.limits
+    }
+
+    /// Sets the [`Limits`] enforced by [`try_insert`](Self::try_insert).
+    ///
+    /// This allows enforcing limits on tables which were not created with
+    /// [`with_limits`](OffsetSet::with_limits), like loaded tables. Entries
+    /// which are already contained are not checked against the new limits.
+    pub fn set_limits(&mut self, limits: Limits) {
+        self.limits = limits;
+    }
+
+    /// Aligns the start of every entry inserted from now on to `align` bytes,
+    /// relative to the start of the serialized table.
+    ///
+    /// This pads the table between entries, trading size for read performance
+    /// when entries are hot and accessed randomly, for example to start each
+    /// entry on its own cache line with an `align` of 64. The padding is
+    /// skipped when iterating over entries, so a table loaded from the
+    /// serialized table contains the same entries. See [`raw::padding_len`]
+    /// for its encoding.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `align` is not a power of two.
+    pub fn set_entry_alignment(&mut self, align: usize) {
+        if !align.is_power_of_two() {
+            panic!("set_entry_alignment: align is not a power-of-two");
+        }
+        self.entry_align = align;
+    }
+
+    /// Registers a [`TableObserver`] which is notified about insertions,
+    /// deduplication hits, buffer growth and serialization of this [`OffsetSet`].
+    ///
+    /// This replaces any previously registered observer.
+    pub fn set_observer(&mut self, observer: Arc<dyn TableObserver>) {
+        self.observer = Some(observer);
+    }
+
+    /// Returns the number of unique entries in this [`OffsetSet`].
+    pub fn len(&self) -> usize {
+        self.offsets.len()
+    }
+
+    /// Returns `true` if this [`OffsetSet`] is empty.
+    pub fn is_empty(&self) -> bool {
+        self.offsets.len() == 0
     }
 
     /// Iterates over all the entries is this [`OffsetSet`].
     ///
     /// This yields `(offset, slice)` pairs.
     pub fn entries(&self) -> impl Iterator<Item = (usize, &[T])> + '_ {
-        self.offsets
-            .iter()
-            .map(|&offset| (offset, Self::read(&self.buffer, offset).unwrap()))
+        // A bounded index does not hold all the entries.
+        let (indexed, all) = match &*self.offsets {
+            Index::Bounded(_) => (None, Some(self.iter_offsets())),
+            index => (Some(index.iter()), None),
+        };
+        indexed
+            .into_iter()
+            .flatten()
+            .chain(all.into_iter().flatten())
+            .map(|offset| {
+                (
+                    offset,
+                    OffsetSet::read(self.buffer.as_bytes(), offset).unwrap(),
+                )
+            })
+    }
+
+    /// Iterates over the offsets of all the entries in this [`OffsetSet`], in
+    /// the order in which they were inserted.
+    ///
+    /// This only decodes the length prefix of each entry, which is cheaper than
+    /// [`entries`](Self::entries) when only the positions are needed.
+    /// Duplicates inserted with [`insert_unique_unchecked`](Self::insert_unique_unchecked)
+    /// are yielded as well.
+    pub fn iter_offsets(&self) -> impl Iterator<Item = usize> + '_ {
+        let buffer = self.buffer.as_bytes();
+        let mut offset = 0;
+        core::iter::from_fn(move || {
+            offset += raw::padding_len(buffer.get(offset..)?);
+            if offset >= buffer.len() {
+                return None;
+            }
+            let (len, prefix_len) = varint::read_u64(&buffer[offset..]).unwrap();
+            let current = offset;
+            offset += prefix_len + len as usize * mem::size_of::<T>();
+            Some(current)
+        })
+    }
+
+    /// Returns a parallel iterator over all the entries in this [`OffsetSet`],
+    /// in the order in which they were inserted.
+    ///
+    /// This yields `(offset, slice)` pairs, like [`entries`](Self::entries).
+    /// The entry boundaries are found upfront with [`iter_offsets`](Self::iter_offsets),
+    /// and the entries are then split across threads.
+    #[cfg(feature = "rayon")]
+    pub fn par_entries(
+        &self,
+    ) -> impl rayon::iter::IndexedParallelIterator<Item = (usize, &[T])> + '_
+    where
+        T: Sync,
+        S: Sync,
+    {
+        use rayon::prelude::*;
+
+        let buffer = self.buffer.as_bytes();
+        let offsets: Vec<usize> = self.iter_offsets().collect();
+        offsets
+            .into_par_iter()
+            .map(move |offset| (offset, OffsetSet::read(buffer, offset).unwrap()))
+    }
+
+    /// Returns statistics about the contents and memory usage of this [`OffsetSet`].
+    ///
+    /// Deduplication statistics only account for insertions made into this
+    /// [`OffsetSet`], not for those of a previously serialized representation.
+    pub fn stats(&self) -> TableStats {
+        let mut max_entry_len = 0;
+        let mut total_len = 0;
+        for (_offset, entry) in self.entries() {
+            max_entry_len = max_entry_len.max(entry.len());
+            total_len += entry.len();
+        }
+
+        let entries = self.offsets.len();
+        TableStats {
+            entries,
+            serialized_size: self.buffer.as_bytes().len(),
+            duplicates: self.duplicates,
+            dedup_saved_bytes: self.dedup_saved_bytes,
+            index_heap_bytes: self.offsets.allocation_size(),
+            max_entry_len,
+            avg_entry_len: if entries == 0 {
+                0.0
+            } else {
+                total_len as f64 / entries as f64
+            },
+        }
+    }
+
+    /// Reserves room for `entry_count` more entries, holding `total_payload_bytes`
+    /// bytes of data in total.
+    ///
+    /// This accounts for the worst-case size of their length prefixes, so that
+    /// the [`Storage`] is grown exactly once, for example when rebuilding a
+    /// table from a source of known size.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the [`Storage`] can not be grown.
+    pub fn reserve_exact_bytes(&mut self, total_payload_bytes: usize, entry_count: usize) {
+        let additional = entry_count
+            .checked_mul(leb128_len(total_payload_bytes as u64))
+            .and_then(|prefixes| prefixes.checked_add(total_payload_bytes))
+            .expect("reserved size overflows");
+        if let Err(err) = self.buffer.reserve_exact(additional) {
+            panic!("failed to grow the table storage: {err}");
+        }
     }
 
     /// Returns a byte slice containing the serialized representation of this [`OffsetSet`].
     pub fn as_bytes(&self) -> &[u8] {
-        &self.buffer
+        self.buffer.as_bytes()
     }
 
-    /// Returns a byte vector containing the serialized representation of this [`OffsetSet`].
+    /// Returns the [`Storage`] containing the serialized representation of this [`OffsetSet`].
     ///
     /// This consumes the [`OffsetSet`].
-    pub fn into_bytes(self) -> Vec<u8> {
+    pub fn into_storage(self) -> S {
+        if let Some(observer) = &self.observer {
+            observer.on_serialize(self.buffer.as_bytes().len());
+        }
         self.buffer
     }
 }
 
 impl<T: Pod + PartialEq + Hash> OffsetSet<T> {
-    fn raw_entry(&mut self, items: &[T]) -> (Entry<'_, usize>, &mut Vec<u8>) {
-        let hasher = |val: &_| self.hasher.hash_one(val);
-        let hash = hasher(items);
-
-        let entry = self.offsets.entry(
-            hash,
-            |&offset| Self::read(&self.buffer, offset).unwrap() == items,
-            |&offset| hasher(Self::read(&self.buffer, offset).unwrap()),
-        );
-        (entry, &mut self.buffer)
-    }
-
     /// Initializes an [`OffsetSet`] from a previously serialized representation.
     ///
     /// This essentially reverses the [`as_bytes`](Self::as_bytes) call.
@@ -152,42 +799,881 @@ impl<T: Pod + PartialEq + Hash> OffsetSet<T> {
         E: From<ReadOffsetSetError>,
         V: Fn(&[T]) -> Result<(), E>,
     {
+        Self::from_storage_validated(buffer.into(), Default::default(), 0, validate)
+    }
+
+    /// Initializes an [`OffsetSet`] from a previously serialized representation,
+    /// reporting the progress of loading it.
+    ///
+    /// This is like [`from_bytes_validated`](Self::from_bytes_validated), with
+    /// the validation function additionally receiving the [`EntryContext`] of
+    /// each slice. The `progress` callback is invoked after every
+    /// [`PROGRESS_INTERVAL`] bytes, and once more after the last entry.
+    ///
+    /// Returning an error from either function aborts loading and returns
+    /// that error, which allows cancelling the loading of huge tables, for
+    /// example when a flag set by another thread is raised.
+    ///
+    /// # Example
+    /// ```
+    /// use watto::{LoadProgress, OffsetSet, ReadOffsetSetError};
+    ///
+    /// let mut set = OffsetSet::<u8>::new();
+    /// set.insert(b"foo");
+    /// set.insert(b"bar");
+    /// let buffer = set.into_bytes();
+    ///
+    /// let mut reported = vec![];
+    /// let set = OffsetSet::<u8>::from_bytes_with_progress(
+    ///     &buffer,
+    ///     |_, ctx| {
+    ///         assert_eq!(ctx.offset, ctx.index * 4);
+    ///         Ok::<_, ReadOffsetSetError>(())
+    ///     },
+    ///     |progress| {
+    ///         reported.push(progress);
+    ///         Ok(())
+    ///     },
+    /// )
+    /// .unwrap();
+    /// assert_eq!(set.len(), 2);
+    /// assert_eq!(reported, [LoadProgress { entries: 2, bytes: 8, total_bytes: 8 }]);
+    /// ```
+    pub fn from_bytes_with_progress<V, P, E>(
+        buffer: &[u8],
+        validate: V,
+        progress: P,
+    ) -> Result<Self, E>
+    where
+        E: From<ReadOffsetSetError>,
+        V: FnMut(&[T], EntryContext) -> Result<(), E>,
+        P: FnMut(LoadProgress) -> Result<(), E>,
+    {
+        Self::from_storage_with_hooks(buffer.into(), Default::default(), 0, validate, progress)
+    }
+
+    /// Initializes an [`OffsetSet`] from a previously serialized representation,
+    /// which is expected to contain `entries` entries.
+    ///
+    /// This allocates the deduplication index at its final size up front,
+    /// instead of growing it repeatedly while loading large tables.
+    /// The hint does not need to be exact.
+    pub fn from_bytes_with_capacity(
+        buffer: &[u8],
+        entries: usize,
+    ) -> Result<Self, ReadOffsetSetError> {
+        Self::from_storage_validated(buffer.into(), Default::default(), entries, |_| Ok(()))
+    }
+
+    /// Initializes an [`OffsetSet`] from a shared buffer holding a previously
+    /// serialized representation.
+    ///
+    /// This reuses the allocation of `buffer` without copying if it is the only
+    /// handle to a buffer that was created from a `Vec<u8>`, like the one returned
+    /// from [`into_shared_bytes`](Self::into_shared_bytes). Otherwise, the
+    /// buffer is copied.
+    #[cfg(feature = "bytes")]
+    pub fn from_shared_bytes(buffer: bytes::Bytes) -> Result<Self, ReadOffsetSetError> {
+        Self::from_storage_validated(buffer.into(), Default::default(), 0, |_| Ok(()))
+    }
+}
+
+impl<T: Pod + PartialEq + Hash> OffsetSet<T, FileStorage> {
+    /// Opens an [`OffsetSet`] which persists every inserted entry in the file
+    /// at `path`, creating the file if it does not exist.
+    ///
+    /// A partially written entry at the end of the file, as left behind by a
+    /// crash, is discarded, and the index is rebuilt from the remaining entries.
+    /// Any other corruption fails with [`InvalidData`](io::ErrorKind::InvalidData).
+    /// See [`FileStorage`] for details.
+    pub fn open<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        let storage = OffsetSet::<T>::recover(FileStorage::open(path)?)?;
+        Self::from_storage(storage).map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))
+    }
+}
+
+impl<T: Pod, H> OffsetSet<T, FileStorage, H> {
+    /// Flushes all inserted entries to disk.
+    ///
+    /// See [`FileStorage::sync`] for details.
+    pub fn sync(&self) -> io::Result<()> {
+        self.buffer.sync()
+    }
+}
+
+impl<T: Pod + PartialEq + Hash, S: Storage> OffsetSet<T, S> {
+    /// Initializes an [`OffsetSet`] which keeps its buffer in the given [`Storage`].
+    ///
+    /// Any entries already contained in the storage are loaded, and new
+    /// entries are appended to them.
+    pub fn from_storage(storage: S) -> Result<Self, ReadOffsetSetError> {
+        Self::from_storage_with_hasher(storage, Default::default())
+    }
+}
+
+impl<T: Pod + PartialEq + Hash, S: Storage, H: BuildHasher> OffsetSet<T, S, H> {
+    /// Returns the offset of the entry consisting of `meta` and `items` in the
+    /// index, if it is contained.
+    ///
+    /// The `hash` of the entry is only computed if it is needed.
+    fn find(&self, hash: Option<u64>, meta: &[T], items: &[T]) -> Option<usize> {
+        let buffer = self.buffer.as_bytes();
+        let eq = |&offset: &u32| {
+            entry_eq(
+                OffsetSet::<T>::read(buffer, offset as usize).unwrap(),
+                meta,
+                items,
+            )
+        };
+        let hash = || hash.unwrap_or_else(|| Self::hash_parts(&self.hasher, meta, items));
+        let offset = match &*self.offsets {
+            Index::Linear(offsets) => offsets.iter().find(|offset| eq(offset)).copied(),
+            Index::Hashed(offsets) => offsets.find(hash(), eq).copied(),
+            Index::Bounded(index) => index
+                .find(hash(), |offset| eq(&offset))
+                .map(|slot| index.slots[slot as usize].offset),
+        };
+        offset.map(|offset| offset as usize)
+    }
+
+    /// Returns the offset of the entry consisting of `meta` and `items` in the
+    /// index, if it is contained, and marks it as recently used in a bounded index.
+    fn find_used(&mut self, hash: Option<u64>, meta: &[T], items: &[T]) -> Option<usize> {
+        if !matches!(*self.offsets, Index::Bounded(_)) {
+            return self.find(hash, meta, items);
+        }
+        let hash = hash.unwrap_or_else(|| Self::hash_parts(&self.hasher, meta, items));
+        let buffer = self.buffer.as_bytes();
+        let Index::Bounded(index) = Arc::make_mut(&mut self.offsets) else {
+            unreachable!()
+        };
+        let slot = index.find(hash, |offset| {
+            entry_eq(
+                OffsetSet::<T>::read(buffer, offset as usize).unwrap(),
+                meta,
+                items,
+            )
+        })?;
+        index.touch(slot);
+        Some(index.slots[slot as usize].offset as usize)
+    }
+
+    /// Hashes a stored entry, whose first `meta_len` elements are metadata.
+    fn hash_entry(hasher: &H, meta_len: usize, entry: &[T]) -> u64 {
+        let (meta, items) = entry.split_at(meta_len);
+        Self::hash_parts(hasher, meta, items)
+    }
+
+    /// Hashes the entry consisting of the `meta` elements followed by `items`.
+    ///
+    /// Entries without metadata are hashed like their slice, as documented
+    /// for [`insert_hashed`](Self::insert_hashed).
+    fn hash_parts(hasher: &H, meta: &[T], items: &[T]) -> u64 {
+        if meta.is_empty() {
+            hasher.hash_one(items)
+        } else {
+            hasher.hash_one((meta, items))
+        }
+    }
+
+    /// Adds the offset of a new entry to the index, switching to a hash table
+    /// once it grows beyond [`LINEAR_SCAN_LEN`] entries.
+    ///
+    /// The `hash` of the entry is only computed if it is needed.
+    fn index_offset(
+        index: &mut Index,
+        buffer: &[u8],
+        hasher: &H,
+        meta_len: usize,
+        hash: Option<u64>,
+        offset: usize,
+    ) {
+        let hash_at = |&offset: &u32| {
+            let entry = OffsetSet::<T>::read(buffer, offset as usize).unwrap();
+            Self::hash_entry(hasher, meta_len, entry)
+        };
+        let offset = offset as u32;
+        match index {
+            Index::Linear(offsets) if offsets.len() < LINEAR_SCAN_LEN => offsets.push(offset),
+            Index::Linear(offsets) => {
+                let mut table = HashTable::with_capacity(2 * LINEAR_SCAN_LEN);
+                for offset in offsets.drain(..) {
+                    table.insert_unique(hash_at(&offset), offset, hash_at);
+                }
+                let hash = hash.unwrap_or_else(|| hash_at(&offset));
+                table.insert_unique(hash, offset, hash_at);
+                *index = Index::Hashed(table);
+            }
+            Index::Hashed(offsets) => {
+                let hash = hash.unwrap_or_else(|| hash_at(&offset));
+                offsets.insert_unique(hash, offset, hash_at);
+            }
+            Index::Bounded(index) => {
+                let hash = hash.unwrap_or_else(|| hash_at(&offset));
+                index.insert(hash, offset, |offset| hash_at(&offset));
+            }
+        }
+    }
+
+    /// Caps the deduplication index at `max_entries` entries.
+    ///
+    /// Once the index is full, inserting a new entry evicts the entry that was
+    /// least recently inserted or deduplicated from it. Evicted entries stay
+    /// in the table, but [`get`](Self::get) does not find them anymore, and
+    /// inserting them again stores them a second time. This trades some
+    /// duplicate storage for a hard ceiling on the memory used by the index,
+    /// for example when interning an unbounded stream of entries.
+    ///
+    /// The index starts out with the most recently stored entries.
+    /// The [`len`](Self::len) of the table then counts all the stored entries.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `max_entries` is zero.
+    pub fn set_max_index_entries(&mut self, max_entries: usize) {
+        assert!(max_entries > 0, "max_entries must not be zero");
+
+        let buffer = self.buffer.as_bytes();
+        let hash_at = |offset: u32| {
+            let entry = OffsetSet::<T>::read(buffer, offset as usize).unwrap();
+            Self::hash_entry(&self.hasher, self.meta_len, entry)
+        };
+        let entries = self.iter_offsets().count();
+        let mut index = BoundedIndex::new(max_entries);
+        for offset in self
+            .iter_offsets()
+            .skip(entries.saturating_sub(max_entries))
+        {
+            let offset = offset as u32;
+            index.insert(hash_at(offset), offset, hash_at);
+        }
+        index.entries = entries;
+        self.offsets = Arc::new(Index::Bounded(index));
+    }
+
+    /// Returns the offset of the given slice if it is contained in this [`OffsetSet`].
+    pub fn get(&self, items: &[T]) -> Option<usize> {
+        self.find(None, &[], items)
+    }
+
+    /// Returns the offset of the given slice if it is contained in this [`OffsetSet`],
+    /// using a precomputed hash.
+    ///
+    /// See [`insert_hashed`](Self::insert_hashed) for the requirements on `hash`.
+    pub fn get_hashed(&self, hash: u64, items: &[T]) -> Option<usize> {
+        debug_assert_eq!(hash, self.hasher.hash_one(items), "mismatched hash");
+        self.find(Some(hash), &[], items)
+    }
+
+    /// Initializes an [`OffsetSet`] which keeps its buffer in the given [`Storage`],
+    /// and hashes its entries with the given [`BuildHasher`].
+    ///
+    /// See [`from_storage`](OffsetSet::from_storage) for details.
+    pub fn from_storage_with_hasher(storage: S, hasher: H) -> Result<Self, ReadOffsetSetError> {
+        Self::from_storage_validated(storage, hasher, 0, |_| Ok(()))
+    }
+
+    /// Initializes an [`OffsetSet`] from a [`Storage`], without copying its contents.
+    ///
+    /// The index is allocated with room for `capacity` entries.
+    pub(crate) fn from_storage_validated<V, E>(
+        buffer: S,
+        hasher: H,
+        capacity: usize,
+        validate: V,
+    ) -> Result<Self, E>
+    where
+        E: From<ReadOffsetSetError>,
+        V: Fn(&[T]) -> Result<(), E>,
+    {
+        Self::from_storage_with_hooks(
+            buffer,
+            hasher,
+            capacity,
+            |item, _| validate(item),
+            |_| Ok(()),
+        )
+    }
+
+    /// Initializes an [`OffsetSet`] from a [`Storage`], passing each entry to
+    /// `validate` and reporting the progress to `progress`.
+    fn from_storage_with_hooks<V, P, E>(
+        buffer: S,
+        hasher: H,
+        capacity: usize,
+        mut validate: V,
+        mut progress: P,
+    ) -> Result<Self, E>
+    where
+        E: From<ReadOffsetSetError>,
+        V: FnMut(&[T], EntryContext) -> Result<(), E>,
+        P: FnMut(LoadProgress) -> Result<(), E>,
+    {
+        timed_span!("OffsetSet::from_bytes", size = buffer.as_bytes().len());
+
         let mut slf = Self {
-            buffer: buffer.into(),
-            ..Default::default()
+            hasher,
+            offsets: Arc::new(Index::with_capacity(capacity)),
+            buffer,
+            duplicates: 0,
+            dedup_saved_bytes: 0,
+            limits: Default::default(),
+            entry_align: 1,
+            meta_len: 0,
+            observer: None,
+            _t: PhantomData,
         };
 
+        let buffer = slf.buffer.as_bytes();
+        let offsets = Arc::make_mut(&mut slf.offsets);
         let mut offset = 0;
-        while offset < buffer.len() {
-            let (item, next_offset) = Self::read_internal(buffer, offset)?;
-            validate(item)?;
+        let mut index = 0;
+        let mut next_progress = PROGRESS_INTERVAL;
+        loop {
+            offset += raw::padding_len(&buffer[offset..]);
+            if offset >= buffer.len() {
+                break;
+            }
+            if offset > MAX_OFFSET {
+                return Err(ReadOffsetSetError::TooLarge.into());
+            }
+            if offset >= next_progress {
+                progress(LoadProgress {
+                    entries: index,
+                    bytes: offset,
+                    total_bytes: buffer.len(),
+                })?;
+                next_progress = offset + PROGRESS_INTERVAL;
+            }
+            let (item, next_offset) = OffsetSet::read_internal(buffer, offset)?;
+            validate(item, EntryContext { index, offset })?;
+            index += 1;
 
-            let (entry, _buffer) = slf.raw_entry(item);
-            entry.insert(offset);
+            // Later duplicates replace earlier ones, which is only possible
+            // for tables built with `insert_unique_unchecked`.
+            let eq = |offset: &u32| OffsetSet::<T>::read(buffer, *offset as usize).unwrap() == item;
+            let existing = match &mut *offsets {
+                Index::Linear(offsets) => offsets.iter_mut().find(|offset| eq(offset)),
+                Index::Hashed(offsets) => {
+                    offsets.find_mut(Self::hash_entry(&slf.hasher, slf.meta_len, item), eq)
+                }
+                Index::Bounded(_) => unreachable!("tables are loaded with an unbounded index"),
+            };
+            match existing {
+                Some(existing) => *existing = offset as u32,
+                None => {
+                    Self::index_offset(offsets, buffer, &slf.hasher, slf.meta_len, None, offset)
+                }
+            }
 
             offset = next_offset;
         }
+        progress(LoadProgress {
+            entries: index,
+            bytes: buffer.len(),
+            total_bytes: buffer.len(),
+        })?;
 
+        event!(entries = slf.len(), "loaded entries");
         Ok(slf)
     }
 
     /// Insert a string into this [`OffsetSet`].
     ///
     /// Returns an offset that can be used to retrieve the inserted input
-    /// with [`read`](Self::read) after serializing this table with [`as_bytes`](Self::as_bytes).
+    /// with [`read`](OffsetSet::read) after serializing this table with [`as_bytes`](Self::as_bytes).
+    ///
+    /// This does not enforce the configured [`Limits`], use [`try_insert`](Self::try_insert) for that.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the table grows beyond 4 GiB, or if the [`Storage`] can not
+    /// be grown to hold the input.
     pub fn insert(&mut self, input: &[T]) -> usize {
-        let (entry, buffer) = self.raw_entry(input);
+        unwrap_unlimited(self.insert_internal(None, &[], input, Limits::default()))
+    }
+
+    /// Insert a slice into this [`OffsetSet`], using a precomputed hash.
+    ///
+    /// This avoids hashing the slice again when the hash is already known,
+    /// for example because it is cached alongside the input.
+    /// The `hash` has to be the one computed by the [`hasher`](Self::hasher)
+    /// of this [`OffsetSet`], as in `set.hasher().hash_one(input)`. Use
+    /// [`with_hasher`](OffsetSet::with_hasher) to hash entries with the same
+    /// function as the cached hashes.
+    ///
+    /// Otherwise, duplicates may not be detected, and lookups may fail.
+    /// Debug builds assert that the hash matches.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the table grows beyond 4 GiB, or if the [`Storage`] can not
+    /// be grown to hold the input.
+    pub fn insert_hashed(&mut self, hash: u64, input: &[T]) -> usize {
+        debug_assert_eq!(hash, self.hasher.hash_one(input), "mismatched hash");
+        unwrap_unlimited(self.insert_internal(Some(hash), &[], input, Limits::default()))
+    }
+
+    /// Inserts the entry consisting of the `meta` elements followed by `input`,
+    /// as used by [`MetaOffsetSet`](crate::MetaOffsetSet).
+    pub(crate) fn insert_with_meta(&mut self, meta: &[T], input: &[T]) -> usize {
+        unwrap_unlimited(self.insert_internal(None, meta, input, Limits::default()))
+    }
+
+    /// Returns the offset of the entry consisting of the `meta` elements
+    /// followed by `items`, as used by [`MetaOffsetSet`](crate::MetaOffsetSet).
+    pub(crate) fn get_with_meta(&self, meta: &[T], items: &[T]) -> Option<usize> {
+        self.find(None, meta, items)
+    }
+
+    /// Insert a slice into this [`OffsetSet`] without checking whether it is
+    /// already contained.
+    ///
+    /// This skips the lookup of the slice, which dominates the cost of inserting,
+    /// and is intended for inserting entries known to be unique, for example
+    /// when re-encoding the entries of another [`OffsetSet`].
+    /// Like [`insert`](Self::insert), this does not enforce the configured [`Limits`].
+    ///
+    /// If the slice is already contained, it is stored a second time, and
+    /// [`get`](Self::get) may return either of the offsets.
+    /// Debug builds assert that this is not the case.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the table grows beyond 4 GiB, or if the [`Storage`] can not
+    /// be grown to hold the input.
+    pub fn insert_unique_unchecked(&mut self, input: &[T]) -> usize {
+        debug_assert!(
+            self.get(input).is_none(),
+            "inserted slice is already contained"
+        );
+
+        let index_capacity = self.offsets.capacity();
+        let buffer_capacity = self.buffer.capacity();
+        let encoded_len = leb128_len(input.len() as u64) + input.as_bytes().len();
+        let offset = unwrap_unlimited(Self::append_entry(
+            &mut self.buffer,
+            &[],
+            input,
+            encoded_len,
+            self.entry_align,
+            None,
+        ));
+
+        Self::index_offset(
+            Arc::make_mut(&mut self.offsets),
+            self.buffer.as_bytes(),
+            &self.hasher,
+            self.meta_len,
+            None,
+            offset,
+        );
+
+        self.notify_insert(offset, encoded_len, buffer_capacity, index_capacity);
+        offset
+    }
+
+    /// Insert a slice into this [`OffsetSet`], enforcing the configured [`Limits`].
+    ///
+    /// Returns an error if the input is longer than the configured maximum
+    /// entry length, or if adding it would grow the table beyond its configured
+    /// maximum size or beyond 4 GiB, or if the [`Storage`] can not be grown to hold it.
+    /// Inserting a duplicate never grows the table.
+    pub fn try_insert(&mut self, input: &[T]) -> Result<usize, InsertError> {
+        self.insert_internal(None, &[], input, self.limits)
+    }
 
-        let entry = entry.or_insert_with(|| {
-            let offset = buffer.len();
+    /// Insert all `slices` into this [`OffsetSet`], returning their offsets.
+    ///
+    /// This is equivalent to inserting them one at a time, and results in the
+    /// same serialized representation. For large batches it is faster though,
+    /// as all slices are hashed and looked up in one pass before anything is
+    /// inserted, and a shared index is only copied once an entry is new.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the table grows beyond 4 GiB, or if the [`Storage`] can not
+    /// be grown to hold the input.
+    pub fn insert_all(&mut self, slices: &[&[T]]) -> Vec<usize> {
+        let hashes: Vec<u64> = slices
+            .iter()
+            .map(|items| self.hasher.hash_one(items))
+            .collect();
 
-            let len = input.len() as u64;
-            leb128::write::unsigned(buffer, len).unwrap();
-            buffer.extend_from_slice(input.as_bytes());
+        let found: Vec<Option<usize>> = match *self.offsets {
+            Index::Hashed(_) => slices
+                .iter()
+                .zip(&hashes)
+                .map(|(items, &hash)| self.find(Some(hash), &[], items))
+                .collect(),
+            _ => vec![None; slices.len()],
+        };
 
-            offset
-        });
+        // New entries are appended in input order, to keep the layout independent
+        // of the hashes.
+        slices
+            .iter()
+            .zip(hashes)
+            .zip(found)
+            .map(|((items, hash), found)| match found {
+                Some(offset) => {
+                    self.record_duplicate(leb128_len(items.len() as u64) + items.as_bytes().len());
+                    offset
+                }
+                None => {
+                    let offset = self.insert_internal(Some(hash), &[], items, Limits::default());
+                    unwrap_unlimited(offset)
+                }
+            })
+            .collect()
+    }
 
-        *entry.get()
+    /// Insert a slice into this [`OffsetSet`], which is built by `build` in a
+    /// caller-provided bump arena.
+    ///
+    /// This avoids a heap allocation per insertion for transient buffers, like
+    /// normalized or re-encoded input. The arena can be reset once the inserted
+    /// slices are no longer needed, as they are copied into this [`OffsetSet`].
+    #[cfg(feature = "bumpalo")]
+    pub fn insert_in<'bump, F>(&mut self, bump: &'bump bumpalo::Bump, build: F) -> usize
+    where
+        T: 'bump,
+        F: FnOnce(&mut bumpalo::collections::Vec<'bump, T>),
+    {
+        let mut scratch = bumpalo::collections::Vec::new_in(bump);
+        build(&mut scratch);
+        self.insert(&scratch)
     }
+
+    /// Inserts the entry consisting of the `meta` elements followed by `input`.
+    fn insert_internal(
+        &mut self,
+        hash: Option<u64>,
+        meta: &[T],
+        input: &[T],
+        limits: Limits,
+    ) -> Result<usize, InsertError> {
+        debug_assert_eq!(meta.len(), self.meta_len, "mismatched metadata");
+        let len = meta.len() + input.len();
+        if let Some(max) = limits.max_entry_len {
+            if len > max {
+                return Err(InsertError::EntryTooLong { len, max });
+            }
+        }
+
+        let encoded_len = leb128_len(len as u64) + meta.as_bytes().len() + input.as_bytes().len();
+        if let Some(offset) = self.find_used(hash, meta, input) {
+            self.record_duplicate(encoded_len);
+            return Ok(offset);
+        }
+
+        let index_capacity = self.offsets.capacity();
+        let buffer_capacity = self.buffer.capacity();
+        let offset = Self::append_entry(
+            &mut self.buffer,
+            meta,
+            input,
+            encoded_len,
+            self.entry_align,
+            limits.max_size,
+        )?;
+        Self::index_offset(
+            Arc::make_mut(&mut self.offsets),
+            self.buffer.as_bytes(),
+            &self.hasher,
+            self.meta_len,
+            hash,
+            offset,
+        );
+
+        self.notify_insert(offset, encoded_len, buffer_capacity, index_capacity);
+        Ok(offset)
+    }
+
+    /// Updates the deduplication statistics and notifies the observer about a
+    /// duplicate insertion.
+    fn record_duplicate(&mut self, encoded_len: usize) {
+        self.duplicates += 1;
+        self.dedup_saved_bytes += encoded_len;
+        if let Some(observer) = &self.observer {
+            observer.on_dedup_hit(encoded_len);
+        }
+    }
+
+    /// Appends the entry consisting of `meta` and `input` to `buffer`,
+    /// returning its offset.
+    fn append_entry(
+        buffer: &mut S,
+        meta: &[T],
+        input: &[T],
+        encoded_len: usize,
+        align: usize,
+        max_size: Option<usize>,
+    ) -> Result<usize, InsertError> {
+        let len = buffer.as_bytes().len();
+        let mut padding = len.next_multiple_of(align) - len;
+        // Padding takes at least two bytes, so a single byte is padded to the
+        // following multiple of `align` instead.
+        if padding == 1 {
+            padding += align;
+        }
+        let offset = len + padding;
+        if offset > MAX_OFFSET {
+            return Err(InsertError::OffsetOverflow { offset });
+        }
+        if let Some(max) = max_size {
+            let size = offset + encoded_len;
+            if size > max {
+                return Err(InsertError::TableTooLarge { size, max });
+            }
+        }
+
+        buffer
+            .reserve(padding + encoded_len)
+            .map_err(InsertError::Storage)?;
+        // Padding consists of overlong encodings of empty entries, which
+        // readers skip. See `raw::padding_len`.
+        const PADDING: [u8; 64] = {
+            let mut padding = [0; 64];
+            let mut i = 0;
+            while i < padding.len() {
+                padding[i] = 0x80;
+                i += 2;
+            }
+            padding
+        };
+        if padding % 2 == 1 {
+            buffer.extend_from_slice(&[0x80, 0x80, 0]);
+            padding -= 3;
+        }
+        while padding > 0 {
+            let chunk = padding.min(PADDING.len());
+            buffer.extend_from_slice(&PADDING[..chunk]);
+            padding -= chunk;
+        }
+        let mut len_bytes = [0; varint::MAX_LEN];
+        let len_len = varint::encode_u64(&mut len_bytes, (meta.len() + input.len()) as u64);
+        buffer.extend_from_slice(&len_bytes[..len_len]);
+        buffer.extend_from_slice(meta.as_bytes());
+        buffer.extend_from_slice(input.as_bytes());
+        Ok(offset)
+    }
+
+    /// Notifies the observer and emits tracing events about an inserted entry.
+    #[cfg_attr(not(feature = "tracing"), allow(unused_variables))]
+    fn notify_insert(
+        &self,
+        offset: usize,
+        encoded_len: usize,
+        buffer_capacity: usize,
+        index_capacity: usize,
+    ) {
+        if let Some(observer) = &self.observer {
+            observer.on_insert(encoded_len);
+            if self.buffer.capacity() != buffer_capacity {
+                observer.on_buffer_growth(buffer_capacity, self.buffer.capacity());
+            }
+        }
+
+        #[cfg(feature = "tracing")]
+        {
+            if encoded_len > crate::trace::LARGE_INSERT_BYTES {
+                event!(size = encoded_len, offset, "large insert");
+            }
+            if self.offsets.capacity() != index_capacity {
+                event!(
+                    old_capacity = index_capacity,
+                    new_capacity = self.offsets.capacity(),
+                    "rehashed index"
+                );
+            }
+        }
+    }
+}
+
+/// Unwraps the result of an insertion without [`Limits`], which can only fail
+/// if the table exceeds 4 GiB or the [`Storage`] can not be grown.
+fn unwrap_unlimited(result: Result<usize, InsertError>) -> usize {
+    match result {
+        Ok(offset) => offset,
+        Err(InsertError::Storage(err)) => panic!("failed to grow the table storage: {err}"),
+        Err(InsertError::OffsetOverflow { .. }) => panic!("table exceeds 4 GiB"),
+        Err(_) => unreachable!("inserting without limits can not fail"),
+    }
+}
+
+/// Two [`OffsetSet`]s are equal if they contain the same entries, regardless of
+/// their offsets.
+impl<T: Pod + PartialEq + Hash, S: Storage, H: BuildHasher> PartialEq for OffsetSet<T, S, H> {
+    fn eq(&self, other: &Self) -> bool {
+        // A bounded index can not find all the entries, so they are compared
+        // by scanning the buffer of the bounded table instead.
+        if matches!(*other.offsets, Index::Bounded(_)) {
+            return *self == *other.buffer.as_bytes();
+        }
+        if matches!(*self.offsets, Index::Bounded(_)) {
+            return *other == *self.buffer.as_bytes();
+        }
+        self.len() == other.len() && self.entries().all(|(_, items)| other.get(items).is_some())
+    }
+}
+
+impl<T: Pod + Eq + Hash, S: Storage, H: BuildHasher> Eq for OffsetSet<T, S, H> {}
+
+/// An [`OffsetSet`] is equal to a serialized buffer if the buffer is valid and
+/// contains the same entries, regardless of their offsets.
+impl<T: Pod + PartialEq + Hash, S: Storage, H: BuildHasher> PartialEq<[u8]> for OffsetSet<T, S, H> {
+    fn eq(&self, other: &[u8]) -> bool {
+        if matches!(*self.offsets, Index::Bounded(_)) {
+            let hasher = &self.hasher;
+            let (Some(entries), Some(other)) = (
+                distinct_entries::<T>(self.buffer.as_bytes(), hasher),
+                distinct_entries::<T>(other, hasher),
+            ) else {
+                return false;
+            };
+            return entries.len() == other.len()
+                && other.iter().all(|items| {
+                    let hash = hasher.hash_one(items);
+                    entries.find(hash, |entry| entry == items).is_some()
+                });
+        }
+
+        let mut matched = HashSet::with_capacity(self.len());
+        for entry in OffsetSet::<T>::iter_buffer(other) {
+            let Ok((_, items)) = entry else {
+                return false;
+            };
+            let Some(offset) = self.get(items) else {
+                return false;
+            };
+            matched.insert(offset);
+        }
+        matched.len() == self.len()
+    }
+}
+
+/// Collects the distinct entries of a serialized [`OffsetSet`], or returns
+/// `None` if it is invalid.
+fn distinct_entries<'data, T: Pod + PartialEq + Hash>(
+    buffer: &'data [u8],
+    hasher: &impl BuildHasher,
+) -> Option<HashTable<&'data [T]>> {
+    let mut entries = HashTable::new();
+    for entry in OffsetSet::<T>::iter_buffer(buffer) {
+        let (_, items) = entry.ok()?;
+        let hash = hasher.hash_one(items);
+        if entries.find(hash, |entry| *entry == items).is_none() {
+            entries.insert_unique(hash, items, |entry| hasher.hash_one(entry));
+        }
+    }
+    Some(entries)
+}
+
+/// A serialized [`OffsetSet`] whose entries are validated on first access.
+///
+/// Returned from [`OffsetSet::validate_lazy`]. Successfully validated entries
+/// are remembered, and not validated again. An entry may still be validated
+/// more than once when it is first read from multiple threads concurrently,
+/// and an entry which fails validation is validated again on every read.
+pub struct LazyValidated<'data, T, V> {
+    buffer: &'data [u8],
+    offsets: Vec<usize>,
+    validated: Box<[AtomicU64]>,
+    validate: V,
+    _t: PhantomData<T>,
+}
+
+impl<T, V> fmt::Debug for LazyValidated<'_, T, V> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("LazyValidated")
+            .field("len", &self.offsets.len())
+            .field("size", &self.buffer.len())
+            .finish_non_exhaustive()
+    }
+}
+
+impl<'data, T: Pod, V> LazyValidated<'data, T, V> {
+    /// Returns the underlying serialized buffer.
+    pub fn as_bytes(&self) -> &'data [u8] {
+        self.buffer
+    }
+
+    /// Returns the number of entries.
+    pub fn len(&self) -> usize {
+        self.offsets.len()
+    }
+
+    /// Returns `true` if there are no entries.
+    pub fn is_empty(&self) -> bool {
+        self.offsets.is_empty()
+    }
+
+    /// Returns the entry stored at the given offset, validating it unless it
+    /// has been validated successfully before.
+    ///
+    /// Returns an error if the offset does not point to the start of an entry,
+    /// or if the entry fails validation.
+    pub fn read<E>(&self, offset: usize) -> Result<&'data [T], E>
+    where
+        V: Fn(&[T]) -> Result<(), E>,
+        E: From<ReadOffsetSetError>,
+    {
+        let index = self
+            .offsets
+            .binary_search(&offset)
+            .map_err(|_| ReadOffsetSetError::OutOfBounds)?;
+        let items = OffsetSet::<T>::read(self.buffer, offset)?;
+
+        let (word, bit) = (&self.validated[index / 64], 1 << (index % 64));
+        if word.load(Ordering::Relaxed) & bit == 0 {
+            (self.validate)(items)?;
+            word.fetch_or(bit, Ordering::Relaxed);
+        }
+        Ok(items)
+    }
+}
+
+/// An iterator over the entries of a serialized [`OffsetSet`].
+///
+/// This yields `(offset, slice)` pairs, and stops after the first error, which
+/// is yielded together with the offset of the invalid entry.
+pub(crate) struct BufferEntries<'data, T> {
+    buffer: &'data [u8],
+    offset: usize,
+    _t: PhantomData<T>,
+}
+
+impl<'data, T: Pod + 'data> Iterator for BufferEntries<'data, T> {
+    type Item = Result<(usize, &'data [T]), (usize, ReadOffsetSetError)>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let offset = self.offset + raw::padding_len(self.buffer.get(self.offset..)?);
+        if offset >= self.buffer.len() {
+            return None;
+        }
+
+        match OffsetSet::read_internal(self.buffer, offset) {
+            Ok((slice, next_offset)) => {
+                self.offset = next_offset;
+                Some(Ok((offset, slice)))
+            }
+            Err(err) => {
+                self.offset = self.buffer.len();
+                Some(Err((offset, err)))
+            }
+        }
+    }
+}
+
+/// Returns whether `entry` consists of the `meta` elements followed by `items`.
+fn entry_eq<T: PartialEq>(entry: &[T], meta: &[T], items: &[T]) -> bool {
+    entry.len() == meta.len() + items.len()
+        && entry[..meta.len()] == *meta
+        && entry[meta.len()..] == *items
+}
+
+/// Returns the number of bytes needed to LEB128-encode `value`.
+fn leb128_len(value: u64) -> usize {
+    (64 - (value | 1).leading_zeros() as usize).div_ceil(7)
 }
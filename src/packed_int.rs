@@ -0,0 +1,177 @@
+use core::{cmp, fmt, mem};
+
+use crate::describe::{Describe, LayoutKind, TypeLayout};
+use crate::{ByteSwap, Pod};
+
+macro_rules! packed_ints {
+    ($(
+        $(#[$attr:meta])*
+        $name:ident($native:ty, $len:literal, little = $little:literal);
+    )*) => {
+        $(
+            $(#[$attr])*
+            #[derive(Clone, Copy, Default, PartialEq, Eq, Hash)]
+            #[repr(transparent)]
+            pub struct $name([u8; $len]);
+
+            unsafe impl Pod for $name {}
+
+            impl $name {
+                /// The smallest value that can be represented.
+                pub const MIN: $native = if <$native>::MIN == 0 {
+                    0
+                } else {
+                    <$native>::MIN >> (mem::size_of::<$native>() * 8 - $len * 8)
+                };
+                /// The largest value that can be represented.
+                pub const MAX: $native = <$native>::MAX >> (mem::size_of::<$native>() * 8 - $len * 8);
+
+                /// Creates a new value.
+                ///
+                /// # Panics
+                ///
+                /// Panics if `value` is out of the range [`MIN`](Self::MIN)..=[`MAX`](Self::MAX).
+                pub fn new(value: $native) -> Self {
+                    match Self::try_new(value) {
+                        Some(packed) => packed,
+                        None => panic!(concat!(stringify!($name), ": value is out of range")),
+                    }
+                }
+
+                /// Creates a new value, or returns [`None`] if `value` is out of
+                /// the range [`MIN`](Self::MIN)..=[`MAX`](Self::MAX).
+                pub fn try_new(value: $native) -> Option<Self> {
+                    if !(Self::MIN..=Self::MAX).contains(&value) {
+                        return None;
+                    }
+                    let mut packed = [0; $len];
+                    packed.copy_from_slice(&value.to_le_bytes()[..$len]);
+                    if !$little {
+                        packed.reverse();
+                    }
+                    Some(Self(packed))
+                }
+
+                /// Returns the value as a native integer.
+                pub fn get(self) -> $native {
+                    let mut packed = self.0;
+                    if !$little {
+                        packed.reverse();
+                    }
+                    let negative = Self::MIN != 0 && packed[$len - 1] & 0x80 != 0;
+                    let mut bytes = [if negative { 0xff } else { 0 }; mem::size_of::<$native>()];
+                    bytes[..$len].copy_from_slice(&packed);
+                    <$native>::from_le_bytes(bytes)
+                }
+
+                /// Sets the value.
+                ///
+                /// # Panics
+                ///
+                /// Panics if `value` is out of the range [`MIN`](Self::MIN)..=[`MAX`](Self::MAX).
+                pub fn set(&mut self, value: $native) {
+                    *self = Self::new(value);
+                }
+            }
+
+            impl From<$name> for $native {
+                fn from(value: $name) -> Self {
+                    value.get()
+                }
+            }
+
+            impl PartialOrd for $name {
+                fn partial_cmp(&self, other: &Self) -> Option<cmp::Ordering> {
+                    Some(self.cmp(other))
+                }
+            }
+
+            impl Ord for $name {
+                fn cmp(&self, other: &Self) -> cmp::Ordering {
+                    self.get().cmp(&other.get())
+                }
+            }
+
+            impl fmt::Debug for $name {
+                fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                    fmt::Debug::fmt(&self.get(), f)
+                }
+            }
+
+            impl fmt::Display for $name {
+                fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                    fmt::Display::fmt(&self.get(), f)
+                }
+            }
+
+            impl ByteSwap for $name {
+                fn swap_bytes(mut self) -> Self {
+                    self.0.reverse();
+                    self
+                }
+            }
+
+            impl Describe for $name {
+                const LAYOUT: &'static TypeLayout = &TypeLayout {
+                    name: stringify!($name),
+                    size: $len,
+                    align: 1,
+                    kind: LayoutKind::Primitive,
+                };
+            }
+        )*
+    };
+}
+
+packed_ints! {
+    /// An unsigned 24-bit little-endian integer, stored in 3 bytes with an
+    /// alignment of 1.
+    ///
+    /// This is useful for compact on-disk fields like line numbers, which
+    /// rarely exceed 16 million, but would waste a byte as a `u32`.
+    ///
+    /// # Example
+    /// ```
+    /// use watto::{Pod, U24};
+    ///
+    /// let mut line = U24::new(0x123456);
+    /// assert_eq!(line.as_bytes(), [0x56, 0x34, 0x12]);
+    /// line.set(U24::MAX);
+    /// assert_eq!(line.get(), 0xff_ffff);
+    /// assert!(U24::try_new(1 << 24).is_none());
+    /// ```
+    U24(u32, 3, little = true);
+    /// An unsigned 24-bit big-endian integer, stored in 3 bytes with an
+    /// alignment of 1.
+    U24Be(u32, 3, little = false);
+    /// A signed 24-bit little-endian integer, stored in 3 bytes with an
+    /// alignment of 1.
+    I24(i32, 3, little = true);
+    /// A signed 24-bit big-endian integer, stored in 3 bytes with an
+    /// alignment of 1.
+    I24Be(i32, 3, little = false);
+    /// An unsigned 48-bit little-endian integer, stored in 6 bytes with an
+    /// alignment of 1.
+    ///
+    /// This is useful for compact on-disk fields like addresses, which
+    /// commonly fit into the 48 bits of virtual address space.
+    ///
+    /// # Example
+    /// ```
+    /// use watto::{Pod, U48};
+    ///
+    /// let addr = U48::new(0x7fff_1234_5678);
+    /// assert_eq!(addr.as_bytes(), [0x78, 0x56, 0x34, 0x12, 0xff, 0x7f]);
+    /// assert_eq!(u64::from(addr), 0x7fff_1234_5678);
+    /// ```
+    U48(u64, 6, little = true);
+    /// An unsigned 48-bit big-endian integer, stored in 6 bytes with an
+    /// alignment of 1.
+    U48Be(u64, 6, little = false);
+    /// A signed 48-bit little-endian integer, stored in 6 bytes with an
+    /// alignment of 1.
+    I48(i64, 6, little = true);
+    /// A signed 48-bit big-endian integer, stored in 6 bytes with an
+    /// alignment of 1.
+    I48Be(i64, 6, little = false);
+}
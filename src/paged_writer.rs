@@ -0,0 +1,117 @@
+use std::io::{Error, ErrorKind, Result, Write};
+
+use crate::{Pod, Writer};
+
+/// The location of a record written by a [`PagedWriter`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct PageLocation {
+    /// The index of the page the record starts in.
+    pub page: usize,
+    /// The offset of the record within its page.
+    pub offset: usize,
+}
+
+/// A [`Writer`] which partitions its output into pages of a fixed size.
+///
+/// Records written with [`write_record`](Self::write_record) never straddle a
+/// page boundary: If a record does not fit into the remainder of the current
+/// page, the page is padded with zeroes and the record starts the next one.
+/// [`finish`](Self::finish) pads the last page, so the output always consists
+/// of whole pages, as required for block-aligned storage files and `O_DIRECT` I/O.
+///
+/// # Example
+/// ```
+/// use watto::{PageLocation, PagedWriter};
+///
+/// let mut writer = PagedWriter::new(vec![], 16);
+/// writer.write_record(&[1u32; 3]).unwrap();
+///
+/// // The second record does not fit into the first page anymore.
+/// let location = writer.write_record(&[2u32; 2]).unwrap();
+/// assert_eq!(location, PageLocation { page: 1, offset: 0 });
+///
+/// let buffer = writer.finish().unwrap();
+/// assert_eq!(buffer.len(), 32);
+/// ```
+#[derive(Debug)]
+pub struct PagedWriter<W: Write> {
+    inner: Writer<W>,
+    page_size: usize,
+}
+
+impl<W: Write> PagedWriter<W> {
+    /// Creates a new [`PagedWriter`] with pages of `page_size` bytes.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `page_size` is not a power of two.
+    pub fn new(writer: W, page_size: usize) -> Self {
+        if !page_size.is_power_of_two() {
+            panic!("PagedWriter: page_size is not a power-of-two");
+        }
+        Self {
+            inner: Writer::new(writer),
+            page_size,
+        }
+    }
+
+    /// Returns the size of the pages in bytes.
+    pub fn page_size(&self) -> usize {
+        self.page_size
+    }
+
+    /// Returns the location the next byte will be written to.
+    pub fn location(&self) -> PageLocation {
+        let pos = self.inner.position();
+        PageLocation {
+            page: pos / self.page_size,
+            offset: pos % self.page_size,
+        }
+    }
+
+    /// Writes a [`Pod`] record, which is guaranteed not to straddle a page boundary.
+    ///
+    /// The record is aligned to the alignment of `T`, and starts a new page if
+    /// it does not fit into the current one.
+    /// Returns the location of the record, or an error of kind
+    /// [`InvalidInput`](ErrorKind::InvalidInput) if it is larger than a page.
+    pub fn write_record<T: Pod + ?Sized>(&mut self, record: &T) -> Result<PageLocation> {
+        let bytes = record.as_bytes();
+        if bytes.len() > self.page_size {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "record is larger than a page",
+            ));
+        }
+
+        self.inner.align_to(core::mem::align_of_val(record))?;
+        if self.location().offset + bytes.len() > self.page_size {
+            self.finish_page()?;
+        }
+        self.write_spanning(record)
+    }
+
+    /// Writes a [`Pod`] record, which is allowed to straddle page boundaries.
+    ///
+    /// The record is aligned to the alignment of `T`.
+    /// Returns the location the record starts at.
+    pub fn write_spanning<T: Pod + ?Sized>(&mut self, record: &T) -> Result<PageLocation> {
+        self.inner.align_to(core::mem::align_of_val(record))?;
+        let location = self.location();
+        self.inner.write_all(record.as_bytes())?;
+        Ok(location)
+    }
+
+    /// Pads the current page with zeroes, so that the next write starts a new page.
+    ///
+    /// Returns the number of padding bytes written.
+    pub fn finish_page(&mut self) -> Result<usize> {
+        self.inner.align_to(self.page_size)
+    }
+
+    /// Pads the last page and unwraps [`PagedWriter`] into the inner [`Write`].
+    pub fn finish(mut self) -> Result<W> {
+        self.finish_page()?;
+        Ok(self.inner.into_inner())
+    }
+}
@@ -0,0 +1,272 @@
+//! Entry-aware binary patches between serialized tables.
+//!
+//! As [`OffsetSet`]s and [`StringTable`](crate::StringTable)s are append-only,
+//! an updated table typically shares most of its entries with a previous
+//! version. [`diff`] exploits the entry boundaries of the serialized tables
+//! to produce a small [`Patch`], which copies shared entries from the old
+//! table and only contains the bytes of new entries.
+//!
+//! # Example
+//! ```
+//! # #[cfg(feature = "strings")] {
+//! use watto::{patch, StringTable};
+//!
+//! let mut table = StringTable::new();
+//! table.insert("foo");
+//! table.insert("bar");
+//! let old = table.as_bytes().to_vec();
+//!
+//! table.insert("baz");
+//! let new = table.as_bytes();
+//!
+//! let patch = patch::diff::<u8>(&old, new).unwrap();
+//! assert_eq!(patch.ops().len(), 2);
+//!
+//! let serialized = patch.to_bytes();
+//! let patch = patch::Patch::from_bytes(&serialized).unwrap();
+//! assert_eq!(patch.apply(&old).unwrap(), new);
+//! # }
+//! ```
+
+use core::fmt;
+
+use hashbrown::HashMap;
+
+use crate::{varint, OffsetSet, Pod, ReadOffsetSetError};
+
+/// A single operation of a [`Patch`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PatchOp {
+    /// Copies `len` bytes starting at `offset` from the old table.
+    Copy {
+        /// The offset within the old table.
+        offset: usize,
+        /// The number of bytes to copy.
+        len: usize,
+    },
+    /// Inserts the given bytes.
+    Insert(Vec<u8>),
+}
+
+/// A patch transforming an old serialized table into a new one.
+///
+/// Created by [`diff`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Patch {
+    old_len: usize,
+    new_len: usize,
+    ops: Vec<PatchOp>,
+}
+
+/// An error when applying a [`Patch`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PatchError {
+    /// The patch was created for an old table of a different size.
+    SizeMismatch {
+        /// The size of the old table the patch was created for.
+        expected: usize,
+        /// The size of the given old table.
+        actual: usize,
+    },
+    /// A copy operation is outside the bounds of the old table.
+    OutOfBounds,
+}
+
+impl fmt::Display for PatchError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::SizeMismatch { expected, actual } => write!(
+                f,
+                "patch expects an old table of {expected} bytes, got {actual} bytes"
+            ),
+            Self::OutOfBounds => f.write_str("patch copies out of bounds"),
+        }
+    }
+}
+
+impl std::error::Error for PatchError {}
+
+const OP_COPY: u8 = 0;
+const OP_INSERT: u8 = 1;
+
+impl Patch {
+    /// Returns the operations of this patch.
+    pub fn ops(&self) -> &[PatchOp] {
+        &self.ops
+    }
+
+    /// Returns the size of the old table this patch applies to.
+    pub fn old_len(&self) -> usize {
+        self.old_len
+    }
+
+    /// Returns the size of the table resulting from applying this patch.
+    pub fn new_len(&self) -> usize {
+        self.new_len
+    }
+
+    /// Returns `true` if the patch does not contain any new bytes.
+    pub fn is_copy_only(&self) -> bool {
+        self.ops.iter().all(|op| matches!(op, PatchOp::Copy { .. }))
+    }
+
+    fn push_copy(&mut self, offset: usize, len: usize) {
+        self.new_len += len;
+        if let Some(PatchOp::Copy {
+            offset: prev_offset,
+            len: prev_len,
+        }) = self.ops.last_mut()
+        {
+            if *prev_offset + *prev_len == offset {
+                *prev_len += len;
+                return;
+            }
+        }
+        self.ops.push(PatchOp::Copy { offset, len });
+    }
+
+    fn push_insert(&mut self, bytes: &[u8]) {
+        self.new_len += bytes.len();
+        if let Some(PatchOp::Insert(prev)) = self.ops.last_mut() {
+            prev.extend_from_slice(bytes);
+            return;
+        }
+        self.ops.push(PatchOp::Insert(bytes.to_vec()));
+    }
+
+    /// Applies this patch to the `old` table, returning the new table.
+    pub fn apply(&self, old: &[u8]) -> Result<Vec<u8>, PatchError> {
+        if old.len() != self.old_len {
+            return Err(PatchError::SizeMismatch {
+                expected: self.old_len,
+                actual: old.len(),
+            });
+        }
+
+        // The copies are within the bounds of `old_len`, and `new_len` is the
+        // sum of all operations, as checked by `from_bytes`.
+        let mut new = Vec::with_capacity(self.new_len);
+        for op in &self.ops {
+            match op {
+                PatchOp::Copy { offset, len } => {
+                    let bytes = offset
+                        .checked_add(*len)
+                        .and_then(|end| old.get(*offset..end))
+                        .ok_or(PatchError::OutOfBounds)?;
+                    new.extend_from_slice(bytes);
+                }
+                PatchOp::Insert(bytes) => new.extend_from_slice(bytes),
+            }
+        }
+        Ok(new)
+    }
+
+    /// Serializes this patch into a compact binary representation.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut buffer = Vec::new();
+        varint::write_u64(&mut buffer, self.old_len as u64);
+        for op in &self.ops {
+            match op {
+                PatchOp::Copy { offset, len } => {
+                    buffer.push(OP_COPY);
+                    varint::write_u64(&mut buffer, *offset as u64);
+                    varint::write_u64(&mut buffer, *len as u64);
+                }
+                PatchOp::Insert(bytes) => {
+                    buffer.push(OP_INSERT);
+                    varint::write_u64(&mut buffer, bytes.len() as u64);
+                    buffer.extend_from_slice(bytes);
+                }
+            }
+        }
+        buffer
+    }
+
+    /// Parses a patch serialized with [`to_bytes`](Self::to_bytes).
+    ///
+    /// Returns [`None`] if the patch is malformed, copies bytes outside of the
+    /// old table, or the size of the new table overflows `usize`.
+    pub fn from_bytes(mut bytes: &[u8]) -> Option<Self> {
+        fn read_usize(bytes: &mut &[u8]) -> Option<usize> {
+            let (value, len) = varint::read_u64(bytes)?;
+            *bytes = &bytes[len..];
+            value.try_into().ok()
+        }
+
+        let old_len = read_usize(&mut bytes)?;
+        let mut new_len = 0usize;
+        let mut ops = Vec::new();
+        while let Some((op, rest)) = bytes.split_first() {
+            bytes = rest;
+            match *op {
+                OP_COPY => {
+                    let offset = read_usize(&mut bytes)?;
+                    let len = read_usize(&mut bytes)?;
+                    if offset.checked_add(len)? > old_len {
+                        return None;
+                    }
+                    new_len = new_len.checked_add(len)?;
+                    ops.push(PatchOp::Copy { offset, len });
+                }
+                OP_INSERT => {
+                    let len = read_usize(&mut bytes)?;
+                    let data = bytes.get(..len)?;
+                    bytes = &bytes[len..];
+                    new_len = new_len.checked_add(len)?;
+                    ops.push(PatchOp::Insert(data.to_vec()));
+                }
+                _ => return None,
+            }
+        }
+        Some(Self {
+            old_len,
+            new_len,
+            ops,
+        })
+    }
+}
+
+/// Iterates over the raw bytes of all entries of a serialized [`OffsetSet`],
+/// including their length prefix.
+fn raw_entries<'data, T: Pod + 'data>(
+    buffer: &'data [u8],
+) -> impl Iterator<Item = Result<(usize, &'data [u8]), ReadOffsetSetError>> + 'data {
+    let mut entries = OffsetSet::<T>::iter_buffer(buffer).peekable();
+    core::iter::from_fn(move || {
+        let (offset, _) = match entries.next()? {
+            Ok(entry) => entry,
+            Err((_, err)) => return Some(Err(err)),
+        };
+        let end = match entries.peek() {
+            Some(Ok((next, _))) => *next,
+            Some(Err((next, _))) => *next,
+            None => buffer.len(),
+        };
+        Some(Ok((offset, &buffer[offset..end])))
+    })
+}
+
+/// Creates a [`Patch`] transforming the serialized `old` table into the `new` one.
+///
+/// Both tables need to be serialized [`OffsetSet<T>`]s, which includes
+/// [`StringTable`](crate::StringTable)s with `T = u8`.
+pub fn diff<T: Pod + 'static>(old: &[u8], new: &[u8]) -> Result<Patch, ReadOffsetSetError> {
+    let mut old_entries = HashMap::new();
+    for entry in raw_entries::<T>(old) {
+        let (offset, raw) = entry?;
+        old_entries.entry(raw).or_insert(offset);
+    }
+
+    let mut patch = Patch {
+        old_len: old.len(),
+        ..Patch::default()
+    };
+    for entry in raw_entries::<T>(new) {
+        let (_, raw) = entry?;
+        match old_entries.get(raw) {
+            Some(&offset) => patch.push_copy(offset, raw.len()),
+            None => patch.push_insert(raw),
+        }
+    }
+    Ok(patch)
+}
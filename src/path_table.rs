@@ -0,0 +1,178 @@
+use crate::{Pod, Reader, RecordId, StringOffset, StringRecordTable, StringRecordView};
+
+/// A path interned into a [`PathTable`], split into its directory and file name.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[repr(C)]
+pub struct PathEntry {
+    /// The directory of the path, including the trailing separator.
+    pub dir: StringOffset,
+    /// The file name of the path.
+    pub name: StringOffset,
+}
+
+unsafe impl Pod for PathEntry {}
+
+/// Splits a path after its last `/` or `\` separator.
+fn split_path(path: &str) -> (&str, &str) {
+    let split = path.rfind(['/', '\\']).map_or(0, |pos| pos + 1);
+    path.split_at(split)
+}
+
+/// A table interning paths by splitting them into directory and file name.
+///
+/// Paths typically share a small number of directories and file names, so
+/// both components are deduplicated separately in a [`StringTable`](crate::StringTable),
+/// and each path is stored as a compact [`PathEntry`] pair of offsets.
+/// Paths are split after the last `/` or `\`, and the directory keeps its
+/// trailing separator, so that reassembling a path is lossless.
+///
+/// The table is serialized as a [`StringRecordTable`] of [`PathEntry`]s,
+/// and can be read zero-copy with a [`PathView`].
+///
+/// # Example
+/// ```
+/// # #[cfg(feature = "writer")] {
+/// use watto::{PathTable, PathView, Writer};
+///
+/// let mut table = PathTable::new();
+/// let lib = table.insert("src/lib.rs");
+/// let main = table.insert("src/main.rs");
+/// assert_eq!(table.insert("src/lib.rs"), lib);
+///
+/// let mut writer = Writer::new(vec![]);
+/// table.write_to(&mut writer).unwrap();
+/// let buffer = writer.into_inner();
+///
+/// let view = PathView::parse(&buffer).unwrap();
+/// assert_eq!(view.components(main), Some(("src/", "main.rs")));
+/// assert_eq!(view.path(lib).as_deref(), Some("src/lib.rs"));
+/// # }
+/// ```
+#[derive(Debug, Clone)]
+pub struct PathTable {
+    inner: StringRecordTable<PathEntry>,
+}
+
+impl Default for PathTable {
+    fn default() -> Self {
+        Self {
+            inner: StringRecordTable::with_dedup(),
+        }
+    }
+}
+
+impl PathTable {
+    /// Creates a new empty [`PathTable`].
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Interns a path, returning its [`RecordId`].
+    ///
+    /// Inserting the same path again returns the same [`RecordId`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if the string table grows beyond 4 GiB.
+    pub fn insert(&mut self, path: &str) -> RecordId {
+        let (dir, name) = split_path(path);
+        let entry = PathEntry {
+            dir: self.inner.intern(dir),
+            name: self.inner.intern(name),
+        };
+        self.inner.push(entry)
+    }
+
+    /// Returns the number of unique paths in this table.
+    pub fn len(&self) -> usize {
+        self.inner.records().len()
+    }
+
+    /// Returns `true` if this table is empty.
+    pub fn is_empty(&self) -> bool {
+        self.inner.records().is_empty()
+    }
+
+    /// Returns the underlying [`StringRecordTable`].
+    pub fn as_record_table(&self) -> &StringRecordTable<PathEntry> {
+        &self.inner
+    }
+
+    /// Writes the table into the given [`Writer`](crate::Writer).
+    #[cfg(feature = "writer")]
+    pub fn write_to<W: std::io::Write>(
+        &self,
+        writer: &mut crate::Writer<W>,
+    ) -> std::io::Result<()> {
+        self.inner.write_to(writer)
+    }
+}
+
+/// A zero-copy view of a serialized [`PathTable`].
+#[derive(Debug, Clone, Copy)]
+pub struct PathView<'data> {
+    inner: StringRecordView<'data, PathEntry>,
+}
+
+impl<'data> PathView<'data> {
+    /// Parses a serialized [`PathTable`] from the start of `buffer`.
+    ///
+    /// Returns [`None`] if the buffer is too small or not properly aligned.
+    pub fn parse(buffer: &'data [u8]) -> Option<Self> {
+        Self::read(&mut Reader::new(buffer))
+    }
+
+    /// Reads a serialized [`PathTable`] from the given [`Reader`].
+    pub fn read(reader: &mut Reader<'data>) -> Option<Self> {
+        let inner = StringRecordView::read(reader)?;
+        Some(Self { inner })
+    }
+
+    /// Returns the number of paths.
+    pub fn len(&self) -> usize {
+        self.inner.records().len()
+    }
+
+    /// Returns `true` if there are no paths.
+    pub fn is_empty(&self) -> bool {
+        self.inner.records().is_empty()
+    }
+
+    /// Returns the directory and file name of the path with the given [`RecordId`].
+    ///
+    /// Returns [`None`] if there is no such path, or its strings are invalid.
+    pub fn components(&self, id: RecordId) -> Option<(&'data str, &'data str)> {
+        let entry = self.inner.get(id)?;
+        let dir = self.inner.resolve(entry.dir).ok()?;
+        let name = self.inner.resolve(entry.name).ok()?;
+        Some((dir, name))
+    }
+
+    /// Reassembles the path with the given [`RecordId`].
+    ///
+    /// Returns [`None`] if there is no such path, or its strings are invalid.
+    pub fn path(&self, id: RecordId) -> Option<String> {
+        let (dir, name) = self.components(id)?;
+        Some([dir, name].concat())
+    }
+
+    /// Iterates over the directory and file name of all paths.
+    ///
+    /// This yields [`None`] for paths with invalid strings.
+    pub fn iter(&self) -> impl Iterator<Item = Option<(&'data str, &'data str)>> + '_ {
+        (0..self.len() as u32).map(|id| self.components(RecordId(id)))
+    }
+}
+
+#[cfg(feature = "writer")]
+impl crate::WriteTo for PathTable {
+    fn write_to<W: std::io::Write>(&self, writer: &mut crate::Writer<W>) -> std::io::Result<()> {
+        self.write_to(writer)
+    }
+}
+
+impl<'data> crate::ReadFrom<'data> for PathView<'data> {
+    fn read_from(reader: &mut Reader<'data>) -> Option<Self> {
+        Self::read(reader)
+    }
+}
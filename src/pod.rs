@@ -1,4 +1,109 @@
-use core::{mem, slice};
+use core::{fmt, mem, slice};
+#[cfg(feature = "std")]
+use std::borrow::Cow;
+
+use crate::utils::{is_aligned_to, pod_failure};
+#[cfg(feature = "std")]
+use crate::Zeroable;
+
+/// The reason a [`Pod`] could not be created from a slice of bytes.
+///
+/// This is returned by the `try_` methods of [`Pod`], like
+/// [`try_ref_from_bytes`](Pod::try_ref_from_bytes).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum PodCastError {
+    /// The input does not have the exact size of the type.
+    SizeMismatch {
+        /// The size of the type in bytes.
+        expected: usize,
+        /// The size of the input in bytes.
+        actual: usize,
+    },
+    /// The input is too short for the type or the requested number of elements.
+    TooShort {
+        /// The minimum size in bytes.
+        expected: usize,
+        /// The size of the input in bytes.
+        actual: usize,
+    },
+    /// The size of the input is not a multiple of the element size.
+    TrailingBytes {
+        /// The size of one element in bytes.
+        elem_size: usize,
+        /// The number of bytes following the last complete element.
+        trailing: usize,
+    },
+    /// The input is not aligned for the type.
+    Misaligned {
+        /// The required alignment.
+        align: usize,
+        /// The offset of the input from the previous aligned address.
+        offset: usize,
+    },
+    /// The alignment of an allocation does not match the alignment of the type.
+    AlignmentMismatch {
+        /// The alignment of the type.
+        expected: usize,
+        /// The alignment of the allocation.
+        actual: usize,
+    },
+    /// The capacity of an allocation is not a multiple of the element size.
+    CapacityMismatch {
+        /// The size of one element in bytes.
+        elem_size: usize,
+        /// The capacity of the allocation in bytes.
+        capacity: usize,
+    },
+    /// The size of the requested number of elements overflows `usize`.
+    Overflow {
+        /// The size of one element in bytes.
+        elem_size: usize,
+        /// The requested number of elements.
+        elems: usize,
+    },
+}
+
+impl fmt::Display for PodCastError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::SizeMismatch { expected, actual } => {
+                write!(f, "expected {expected} bytes, got {actual}")
+            }
+            Self::TooShort { expected, actual } => {
+                write!(f, "expected at least {expected} bytes, got {actual}")
+            }
+            Self::TrailingBytes {
+                elem_size,
+                trailing,
+            } => write!(
+                f,
+                "{trailing} trailing bytes after the last element of size {elem_size}"
+            ),
+            Self::Misaligned { align, offset } => write!(
+                f,
+                "input is misaligned by {offset} bytes, expected alignment of {align}"
+            ),
+            Self::AlignmentMismatch { expected, actual } => write!(
+                f,
+                "allocation is aligned to {actual} bytes, expected alignment of {expected}"
+            ),
+            Self::CapacityMismatch {
+                elem_size,
+                capacity,
+            } => write!(
+                f,
+                "capacity of {capacity} bytes is not a multiple of the element size {elem_size}"
+            ),
+            Self::Overflow { elem_size, elems } => {
+                write!(f, "{elems} elements of size {elem_size} overflow usize")
+            }
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for PodCastError {}
 
 /// Plain Old Data
 ///
@@ -15,31 +120,120 @@ use core::{mem, slice};
 /// [Unsafe Code Guidelines](https://rust-lang.github.io/unsafe-code-guidelines/layout/structs-and-tuples.html), or
 /// [The Rustonomicon](https://doc.rust-lang.org/nomicon/other-reprs.html)
 /// for more information.
+///
+/// All [`Pod`] types are also [`Zeroable`](crate::Zeroable), which provides
+/// constructors for zero-initialized values, boxes and vectors.
+///
+/// With the `derive` feature, this can be derived for `#[repr(C)]` and
+/// `#[repr(transparent)]` structs whose fields are all [`Pod`]. The derive
+/// also checks that the struct does not contain any padding bytes, which for
+/// generic structs happens once they are instantiated and used.
+///
+/// # Example
+/// ```
+/// # #[cfg(feature = "derive")] {
+/// use watto::Pod;
+///
+/// #[derive(Clone, Copy, Pod)]
+/// #[repr(C)]
+/// struct Entry {
+///     addr: u64,
+///     len: u32,
+///     flags: [u8; 4],
+/// }
+///
+/// let entry = Entry { addr: 1, len: 2, flags: [3; 4] };
+/// assert_eq!(entry.as_bytes().len(), 16);
+/// let read = Entry::ref_from_bytes(entry.as_bytes()).unwrap();
+/// assert_eq!(read.len, 2);
+/// # }
+/// ```
 pub unsafe trait Pod {
+    /// A compile-time check of the layout of the type, which is evaluated by
+    /// all methods converting it from and to bytes.
+    ///
+    /// This is set by the `Pod` derive to check that generic structs do not
+    /// contain any padding once they are instantiated, and should not be
+    /// overridden manually.
+    #[doc(hidden)]
+    const LAYOUT_CHECK: () = ();
+
     /// This gives the raw bytes of a certain POD.
     fn as_bytes(&self) -> &[u8] {
+        let () = Self::LAYOUT_CHECK;
         unsafe {
             let len = mem::size_of_val(self);
             slice::from_raw_parts(self as *const Self as *const u8, len)
         }
     }
 
+    /// This gives the raw bytes of a certain POD, which can be modified in place.
+    ///
+    /// # Example
+    /// ```
+    /// use watto::Pod;
+    ///
+    /// let mut values = [1u16, 2];
+    /// values.as_bytes_mut()[2..].copy_from_slice(&5u16.to_ne_bytes());
+    /// assert_eq!(values, [1, 5]);
+    /// ```
+    fn as_bytes_mut(&mut self) -> &mut [u8] {
+        let () = Self::LAYOUT_CHECK;
+        unsafe {
+            let len = mem::size_of_val(self);
+            slice::from_raw_parts_mut(self as *mut Self as *mut u8, len)
+        }
+    }
+
     /// Creates a reference to [`Self`] from a slice of bytes.
     ///
     /// This checks that `bytes` has proper alignment and exact size.
+    /// Use [`try_ref_from_bytes`](Self::try_ref_from_bytes) to find out why
+    /// this failed.
     fn ref_from_bytes(bytes: &[u8]) -> Option<&Self>
     where
         Self: Sized,
     {
-        if bytes.len() != mem::size_of::<Self>()
-            || !crate::utils::is_aligned_to(bytes, mem::align_of::<Self>())
-        {
-            return None;
+        report::<Self, _>(Self::try_ref_from_bytes(bytes), "ref_from_bytes")
+    }
+
+    /// Creates a reference to [`Self`] from a slice of bytes.
+    ///
+    /// This checks that `bytes` has proper alignment and exact size, and
+    /// returns a [`PodCastError`] describing the failed check otherwise.
+    ///
+    /// # Example
+    /// ```
+    /// use watto::{Pod, PodCastError};
+    ///
+    /// let buffer = [0u32; 2];
+    /// let bytes = buffer.as_bytes();
+    /// assert_eq!(u32::try_ref_from_bytes(&bytes[..4]), Ok(&0));
+    /// assert_eq!(
+    ///     u32::try_ref_from_bytes(&bytes[..3]),
+    ///     Err(PodCastError::SizeMismatch { expected: 4, actual: 3 })
+    /// );
+    /// assert_eq!(
+    ///     u32::try_ref_from_bytes(&bytes[1..5]),
+    ///     Err(PodCastError::Misaligned { align: 4, offset: 1 })
+    /// );
+    /// ```
+    fn try_ref_from_bytes(bytes: &[u8]) -> Result<&Self, PodCastError>
+    where
+        Self: Sized,
+    {
+        let () = Self::LAYOUT_CHECK;
+        if bytes.len() != mem::size_of::<Self>() {
+            return Err(PodCastError::SizeMismatch {
+                expected: mem::size_of::<Self>(),
+                actual: bytes.len(),
+            });
         }
+        check_alignment::<Self>(bytes)?;
 
         // SAFETY:
         // We have checked size and alignment, and our type is a `Pod`.
-        Some(unsafe { &*(bytes.as_ptr() as *const Self) })
+        Ok(unsafe { &*(bytes.as_ptr() as *const Self) })
     }
 
     /// Creates a reference to [`Self`] from a slice of bytes.
@@ -50,17 +244,231 @@ pub unsafe trait Pod {
     where
         Self: Sized,
     {
-        if bytes.len() < mem::size_of::<Self>()
-            || !crate::utils::is_aligned_to(bytes, mem::align_of::<Self>())
-        {
-            return None;
+        report::<Self, _>(Self::try_ref_from_prefix(bytes), "ref_from_prefix")
+    }
+
+    /// Creates a reference to [`Self`] from a slice of bytes.
+    ///
+    /// This checks that `bytes` has proper alignment and is large enough, and
+    /// returns a [`PodCastError`] describing the failed check otherwise.
+    /// It also returns the trailing bytes as a new slice.
+    fn try_ref_from_prefix(bytes: &[u8]) -> Result<(&Self, &[u8]), PodCastError>
+    where
+        Self: Sized,
+    {
+        let () = Self::LAYOUT_CHECK;
+        if bytes.len() < mem::size_of::<Self>() {
+            return Err(PodCastError::TooShort {
+                expected: mem::size_of::<Self>(),
+                actual: bytes.len(),
+            });
         }
+        check_alignment::<Self>(bytes)?;
 
         let (bytes, suffix) = bytes.split_at(mem::size_of::<Self>());
 
         // SAFETY:
         // We have checked size and alignment, and our type is a `Pod`.
-        Some((unsafe { &*(bytes.as_ptr() as *const Self) }, suffix))
+        Ok((unsafe { &*(bytes.as_ptr() as *const Self) }, suffix))
+    }
+
+    /// Creates a mutable reference to [`Self`] from a slice of bytes.
+    ///
+    /// This checks that `bytes` has proper alignment and exact size, like
+    /// [`ref_from_bytes`](Self::ref_from_bytes).
+    ///
+    /// # Example
+    /// ```
+    /// use watto::Pod;
+    ///
+    /// let mut buffer = [1u32, 2];
+    /// let bytes = buffer.as_bytes_mut();
+    /// *u32::mut_from_bytes(&mut bytes[4..]).unwrap() += 40;
+    /// assert_eq!(buffer, [1, 42]);
+    /// ```
+    fn mut_from_bytes(bytes: &mut [u8]) -> Option<&mut Self>
+    where
+        Self: Sized,
+    {
+        report::<Self, _>(Self::try_mut_from_bytes(bytes), "mut_from_bytes")
+    }
+
+    /// Creates a mutable reference to [`Self`] from a slice of bytes.
+    ///
+    /// This checks that `bytes` has proper alignment and exact size, like
+    /// [`try_ref_from_bytes`](Self::try_ref_from_bytes).
+    fn try_mut_from_bytes(bytes: &mut [u8]) -> Result<&mut Self, PodCastError>
+    where
+        Self: Sized,
+    {
+        Self::try_ref_from_bytes(bytes)?;
+
+        // SAFETY:
+        // We have checked size and alignment, and our type is a `Pod`.
+        Ok(unsafe { &mut *(bytes.as_mut_ptr() as *mut Self) })
+    }
+
+    /// Creates a mutable reference to [`Self`] from a slice of bytes.
+    ///
+    /// This checks that `bytes` has proper alignment and is large enough, like
+    /// [`ref_from_prefix`](Self::ref_from_prefix).
+    /// It also returns the trailing bytes as a new slice.
+    fn mut_from_prefix(bytes: &mut [u8]) -> Option<(&mut Self, &mut [u8])>
+    where
+        Self: Sized,
+    {
+        report::<Self, _>(Self::try_mut_from_prefix(bytes), "mut_from_prefix")
+    }
+
+    /// Creates a mutable reference to [`Self`] from a slice of bytes.
+    ///
+    /// This checks that `bytes` has proper alignment and is large enough, like
+    /// [`try_ref_from_prefix`](Self::try_ref_from_prefix).
+    /// It also returns the trailing bytes as a new slice.
+    fn try_mut_from_prefix(bytes: &mut [u8]) -> Result<(&mut Self, &mut [u8]), PodCastError>
+    where
+        Self: Sized,
+    {
+        Self::try_ref_from_prefix(bytes)?;
+        let (bytes, suffix) = bytes.split_at_mut(mem::size_of::<Self>());
+
+        // SAFETY:
+        // We have checked size and alignment, and our type is a `Pod`.
+        Ok((unsafe { &mut *(bytes.as_mut_ptr() as *mut Self) }, suffix))
+    }
+
+    /// Reads a copy of [`Self`] from a slice of bytes.
+    ///
+    /// This checks that `bytes` has the exact size of [`Self`], but as the value
+    /// is copied, `bytes` does not need to be aligned.
+    ///
+    /// # Example
+    /// ```
+    /// use watto::Pod;
+    ///
+    /// let buffer = [0, 1, 0, 0, 0];
+    /// assert_eq!(u32::read_from_bytes(&buffer[1..]), Some(1u32.to_le()));
+    /// assert_eq!(u32::read_from_bytes(&buffer), None);
+    /// ```
+    fn read_from_bytes(bytes: &[u8]) -> Option<Self>
+    where
+        Self: Sized,
+    {
+        report::<Self, _>(Self::try_read_from_bytes(bytes), "read_from_bytes")
+    }
+
+    /// Reads a copy of [`Self`] from a slice of bytes.
+    ///
+    /// This checks that `bytes` has the exact size of [`Self`], and returns a
+    /// [`PodCastError::SizeMismatch`] otherwise. As the value is copied,
+    /// `bytes` does not need to be aligned.
+    fn try_read_from_bytes(bytes: &[u8]) -> Result<Self, PodCastError>
+    where
+        Self: Sized,
+    {
+        let () = Self::LAYOUT_CHECK;
+        if bytes.len() != mem::size_of::<Self>() {
+            return Err(PodCastError::SizeMismatch {
+                expected: mem::size_of::<Self>(),
+                actual: bytes.len(),
+            });
+        }
+
+        // SAFETY:
+        // We have checked the size, and our type is a `Pod`.
+        Ok(unsafe { core::ptr::read_unaligned(bytes.as_ptr() as *const Self) })
+    }
+
+    /// Reads a copy of [`Self`] from the start of a slice of bytes.
+    ///
+    /// This checks that `bytes` is large enough, but as the value is copied,
+    /// `bytes` does not need to be aligned.
+    /// It also returns the trailing bytes as a new slice.
+    fn read_from_prefix(bytes: &[u8]) -> Option<(Self, &[u8])>
+    where
+        Self: Sized,
+    {
+        report::<Self, _>(Self::try_read_from_prefix(bytes), "read_from_prefix")
+    }
+
+    /// Reads a copy of [`Self`] from the start of a slice of bytes.
+    ///
+    /// This checks that `bytes` is large enough, and returns a
+    /// [`PodCastError::TooShort`] otherwise. As the value is copied, `bytes`
+    /// does not need to be aligned.
+    /// It also returns the trailing bytes as a new slice.
+    fn try_read_from_prefix(bytes: &[u8]) -> Result<(Self, &[u8]), PodCastError>
+    where
+        Self: Sized,
+    {
+        let () = Self::LAYOUT_CHECK;
+        if bytes.len() < mem::size_of::<Self>() {
+            return Err(PodCastError::TooShort {
+                expected: mem::size_of::<Self>(),
+                actual: bytes.len(),
+            });
+        }
+        let (bytes, suffix) = bytes.split_at(mem::size_of::<Self>());
+
+        // SAFETY:
+        // We have checked the size, and our type is a `Pod`.
+        Ok((
+            unsafe { core::ptr::read_unaligned(bytes.as_ptr() as *const Self) },
+            suffix,
+        ))
+    }
+
+    /// Reads a copy of [`Self`] from a prefix of `stored_size` bytes.
+    ///
+    /// This supports formats which append new fields to a struct in newer
+    /// versions, and store the size of the struct alongside it:
+    /// If `stored_size` is smaller than [`Self`], the missing trailing bytes are
+    /// filled with zeroes. If it is larger, the additional bytes are skipped.
+    ///
+    /// As the value is copied, `bytes` does not need to be aligned.
+    /// It also returns the bytes trailing the `stored_size` prefix.
+    ///
+    /// # Example
+    /// ```
+    /// use watto::Pod;
+    ///
+    /// #[repr(C)]
+    /// struct HeaderV2 {
+    ///     version: u32,
+    ///     // added in version 2
+    ///     flags: u32,
+    /// }
+    /// unsafe impl Pod for HeaderV2 {}
+    ///
+    /// let v1_bytes = 1u32.to_ne_bytes();
+    /// let (header, rest) = HeaderV2::read_from_prefix_padded(&v1_bytes, 4).unwrap();
+    /// assert_eq!((header.version, header.flags), (1, 0));
+    /// assert!(rest.is_empty());
+    /// ```
+    fn read_from_prefix_padded(bytes: &[u8], stored_size: usize) -> Option<(Self, &[u8])>
+    where
+        Self: Sized,
+    {
+        let () = Self::LAYOUT_CHECK;
+        if bytes.len() < stored_size {
+            pod_failure!(
+                "{}::read_from_prefix_padded: expected at least {stored_size} bytes, got {}",
+                core::any::type_name::<Self>(),
+                bytes.len(),
+            );
+            return None;
+        }
+        let (stored, suffix) = bytes.split_at(stored_size);
+        let len = stored_size.min(mem::size_of::<Self>());
+
+        let mut value = mem::MaybeUninit::<Self>::zeroed();
+        // SAFETY:
+        // We copy at most `size_of::<Self>()` bytes into the zeroed value,
+        // and our type is a `Pod`, so any bit pattern is valid.
+        Some(unsafe {
+            core::ptr::copy_nonoverlapping(stored.as_ptr(), value.as_mut_ptr() as *mut u8, len);
+            (value.assume_init(), suffix)
+        })
     }
 
     /// Creates a slice of [`Self`] from a slice of bytes.
@@ -73,20 +481,156 @@ pub unsafe trait Pod {
     where
         Self: Sized,
     {
+        report::<Self, _>(Self::try_slice_from_bytes(bytes), "slice_from_bytes")
+    }
+
+    /// Creates a slice of [`Self`] from a slice of bytes.
+    ///
+    /// This checks that `bytes` has proper alignment and its size is a multiple
+    /// of the size of [`Self`], and returns a [`PodCastError`] describing the
+    /// failed check otherwise.
+    ///
+    /// # Example
+    /// ```
+    /// use watto::{Pod, PodCastError};
+    ///
+    /// let buffer = [1u32, 2, 3];
+    /// let bytes = buffer.as_bytes();
+    /// assert_eq!(u32::try_slice_from_bytes(&bytes[4..]), Ok(&[2, 3][..]));
+    /// assert_eq!(
+    ///     u32::try_slice_from_bytes(&bytes[..6]),
+    ///     Err(PodCastError::TrailingBytes { elem_size: 4, trailing: 2 })
+    /// );
+    /// ```
+    fn try_slice_from_bytes(bytes: &[u8]) -> Result<&[Self], PodCastError>
+    where
+        Self: Sized,
+    {
+        let () = Self::LAYOUT_CHECK;
         assert_ne!(mem::size_of::<Self>(), 0);
 
         let len = bytes.len();
         let elem_size = mem::size_of::<Self>();
 
-        if len % elem_size != 0 || !crate::utils::is_aligned_to(bytes, mem::align_of::<Self>()) {
-            return None;
+        let trailing = len % elem_size;
+        if trailing != 0 {
+            return Err(PodCastError::TrailingBytes {
+                elem_size,
+                trailing,
+            });
         }
+        check_alignment::<Self>(bytes)?;
 
         let elems = len / elem_size;
 
         // SAFETY:
         // We have checked size and alignment, and our type is a `Pod`.
-        Some(unsafe { slice::from_raw_parts(bytes.as_ptr() as *const Self, elems) })
+        Ok(unsafe { slice::from_raw_parts(bytes.as_ptr() as *const Self, elems) })
+    }
+
+    /// Copies a slice of bytes into a newly allocated boxed slice of [`Self`].
+    ///
+    /// This checks that the size of `bytes` is a multiple of the size of
+    /// [`Self`], but as the elements are copied into an allocation aligned for
+    /// [`Self`], `bytes` does not need to be aligned.
+    ///
+    /// # Example
+    /// ```
+    /// use watto::Pod;
+    ///
+    /// let mut buffer = vec![0xff];
+    /// buffer.extend_from_slice([1u32, 2].as_bytes());
+    /// assert!(u32::slice_from_bytes(&buffer[1..]).is_none());
+    /// let values = u32::boxed_slice_from_bytes(&buffer[1..]).unwrap();
+    /// assert_eq!(*values, [1, 2]);
+    /// ```
+    #[cfg(feature = "std")]
+    fn boxed_slice_from_bytes(bytes: &[u8]) -> Option<Box<[Self]>>
+    where
+        Self: Sized,
+    {
+        report::<Self, _>(
+            Self::try_boxed_slice_from_bytes(bytes),
+            "boxed_slice_from_bytes",
+        )
+    }
+
+    /// Copies a slice of bytes into a newly allocated boxed slice of [`Self`].
+    ///
+    /// This checks that the size of `bytes` is a multiple of the size of
+    /// [`Self`], and returns a [`PodCastError::TrailingBytes`] otherwise.
+    /// As the elements are copied, `bytes` does not need to be aligned.
+    #[cfg(feature = "std")]
+    fn try_boxed_slice_from_bytes(bytes: &[u8]) -> Result<Box<[Self]>, PodCastError>
+    where
+        Self: Sized,
+    {
+        assert_ne!(mem::size_of::<Self>(), 0);
+
+        let len = bytes.len();
+        let elem_size = mem::size_of::<Self>();
+
+        if !len.is_multiple_of(elem_size) {
+            return Err(PodCastError::TrailingBytes {
+                elem_size,
+                trailing: len % elem_size,
+            });
+        }
+
+        let mut elems = <Self as Zeroable>::boxed_slice_zeroed(len / elem_size);
+        elems.as_bytes_mut().copy_from_slice(bytes);
+        Ok(elems)
+    }
+
+    /// Creates a slice of [`Self`] from a slice of bytes, copying it if needed.
+    ///
+    /// This checks that the size of `bytes` is a multiple of the size of
+    /// [`Self`]. If `bytes` is properly aligned, the slice is borrowed from it
+    /// like with [`slice_from_bytes`](Self::slice_from_bytes), otherwise the
+    /// elements are copied into an aligned [`Vec`].
+    ///
+    /// # Example
+    /// ```
+    /// use std::borrow::Cow;
+    /// use watto::Pod;
+    ///
+    /// let mut buffer = vec![0u8; 4];
+    /// buffer.extend_from_slice([1u32, 2].as_bytes());
+    ///
+    /// let aligned = u32::slice_from_bytes_unaligned(&buffer[4..]).unwrap();
+    /// assert!(matches!(aligned, Cow::Borrowed(&[1, 2])));
+    /// let unaligned = u32::slice_from_bytes_unaligned(&buffer[3..11]).unwrap();
+    /// assert!(matches!(unaligned, Cow::Owned(_)));
+    /// ```
+    #[cfg(feature = "std")]
+    fn slice_from_bytes_unaligned(bytes: &[u8]) -> Option<Cow<'_, [Self]>>
+    where
+        Self: Sized + Clone,
+    {
+        report::<Self, _>(
+            Self::try_slice_from_bytes_unaligned(bytes),
+            "slice_from_bytes_unaligned",
+        )
+    }
+
+    /// Creates a slice of [`Self`] from a slice of bytes, copying it if needed.
+    ///
+    /// This checks that the size of `bytes` is a multiple of the size of
+    /// [`Self`], and returns a [`PodCastError::TrailingBytes`] otherwise.
+    /// The slice is borrowed if `bytes` is properly aligned, and copied otherwise.
+    #[cfg(feature = "std")]
+    fn try_slice_from_bytes_unaligned(bytes: &[u8]) -> Result<Cow<'_, [Self]>, PodCastError>
+    where
+        Self: Sized + Clone,
+    {
+        match Self::try_slice_from_bytes(bytes) {
+            Ok(elems) => Ok(Cow::Borrowed(elems)),
+            Err(PodCastError::Misaligned { .. }) => {
+                let elems = Self::try_boxed_slice_from_bytes(bytes)?;
+                Ok(Cow::Owned(elems.into_vec()))
+            }
+            Err(err) => Err(err),
+        }
     }
 
     /// Creates a slice of [`Self`] from a slice of bytes.
@@ -99,30 +643,198 @@ pub unsafe trait Pod {
     where
         Self: Sized,
     {
+        report::<Self, _>(
+            Self::try_slice_from_prefix(bytes, elems),
+            "slice_from_prefix",
+        )
+    }
+
+    /// Creates a slice of [`Self`] from a slice of bytes.
+    ///
+    /// This checks that `bytes` has proper alignment and is large enough to hold
+    /// `elems` elements of [`Self`], and returns a [`PodCastError`] describing
+    /// the failed check otherwise.
+    ///
+    /// It also returns the trailing bytes as a new slice.
+    fn try_slice_from_prefix(bytes: &[u8], elems: usize) -> Result<(&[Self], &[u8]), PodCastError>
+    where
+        Self: Sized,
+    {
+        let () = Self::LAYOUT_CHECK;
         assert_ne!(mem::size_of::<Self>(), 0);
 
         let elem_size = mem::size_of::<Self>();
-        let expected_len = elem_size.checked_mul(elems)?;
+        let expected_len = elem_size
+            .checked_mul(elems)
+            .ok_or(PodCastError::Overflow { elem_size, elems })?;
 
-        if bytes.len() < expected_len
-            || !crate::utils::is_aligned_to(bytes, mem::align_of::<Self>())
-        {
-            return None;
+        if bytes.len() < expected_len {
+            return Err(PodCastError::TooShort {
+                expected: expected_len,
+                actual: bytes.len(),
+            });
         }
+        check_alignment::<Self>(bytes)?;
 
         let (bytes, suffix) = bytes.split_at(expected_len);
 
         // SAFETY:
         // We have checked size and alignment, and our type is a `Pod`.
-        Some((
+        Ok((
             unsafe { slice::from_raw_parts(bytes.as_ptr() as *const Self, elems) },
             suffix,
         ))
     }
+
+    /// Creates a mutable slice of [`Self`] from a slice of bytes.
+    ///
+    /// This checks that `bytes` has proper alignment and its size is a multiple
+    /// of the size of [`Self`], like [`slice_from_bytes`](Self::slice_from_bytes).
+    ///
+    /// # Example
+    /// ```
+    /// use watto::Pod;
+    ///
+    /// let mut buffer = [1u32, 2, 3];
+    /// for offset in u32::slice_from_bytes_mut(buffer.as_bytes_mut()).unwrap() {
+    ///     *offset += 100;
+    /// }
+    /// assert_eq!(buffer, [101, 102, 103]);
+    /// ```
+    fn slice_from_bytes_mut(bytes: &mut [u8]) -> Option<&mut [Self]>
+    where
+        Self: Sized,
+    {
+        report::<Self, _>(
+            Self::try_slice_from_bytes_mut(bytes),
+            "slice_from_bytes_mut",
+        )
+    }
+
+    /// Creates a mutable slice of [`Self`] from a slice of bytes.
+    ///
+    /// This checks that `bytes` has proper alignment and its size is a multiple
+    /// of the size of [`Self`], like [`try_slice_from_bytes`](Self::try_slice_from_bytes).
+    fn try_slice_from_bytes_mut(bytes: &mut [u8]) -> Result<&mut [Self], PodCastError>
+    where
+        Self: Sized,
+    {
+        let elems = Self::try_slice_from_bytes(bytes)?.len();
+
+        // SAFETY:
+        // We have checked size and alignment, and our type is a `Pod`.
+        Ok(unsafe { slice::from_raw_parts_mut(bytes.as_mut_ptr() as *mut Self, elems) })
+    }
+
+    /// Creates a mutable slice of [`Self`] from a slice of bytes.
+    ///
+    /// This checks that `bytes` has proper alignment and is large enough to hold
+    /// `elems` elements of [`Self`], like [`slice_from_prefix`](Self::slice_from_prefix).
+    ///
+    /// It also returns the trailing bytes as a new slice.
+    fn slice_from_prefix_mut(bytes: &mut [u8], elems: usize) -> Option<(&mut [Self], &mut [u8])>
+    where
+        Self: Sized,
+    {
+        report::<Self, _>(
+            Self::try_slice_from_prefix_mut(bytes, elems),
+            "slice_from_prefix_mut",
+        )
+    }
+
+    /// Creates a mutable slice of [`Self`] from a slice of bytes.
+    ///
+    /// This checks that `bytes` has proper alignment and is large enough to hold
+    /// `elems` elements of [`Self`], like [`try_slice_from_prefix`](Self::try_slice_from_prefix).
+    ///
+    /// It also returns the trailing bytes as a new slice.
+    fn try_slice_from_prefix_mut(
+        bytes: &mut [u8],
+        elems: usize,
+    ) -> Result<(&mut [Self], &mut [u8]), PodCastError>
+    where
+        Self: Sized,
+    {
+        Self::try_slice_from_prefix(bytes, elems)?;
+        let (bytes, suffix) = bytes.split_at_mut(elems * mem::size_of::<Self>());
+
+        // SAFETY:
+        // We have checked size and alignment, and our type is a `Pod`.
+        Ok((
+            unsafe { slice::from_raw_parts_mut(bytes.as_mut_ptr() as *mut Self, elems) },
+            suffix,
+        ))
+    }
+}
+
+/// Checks that `bytes` is aligned for `T`.
+fn check_alignment<T>(bytes: &[u8]) -> Result<(), PodCastError> {
+    let align = mem::align_of::<T>();
+    if is_aligned_to(bytes, align) {
+        Ok(())
+    } else {
+        Err(PodCastError::Misaligned {
+            align,
+            offset: bytes.as_ptr() as usize & (align - 1),
+        })
+    }
+}
+
+/// Converts the result of a fallible [`Pod`] method into an [`Option`],
+/// reporting the error in `strict` mode.
+#[cfg_attr(
+    not(feature = "strict"),
+    allow(unused_variables, clippy::extra_unused_type_parameters)
+)]
+fn report<T, U>(result: Result<U, PodCastError>, method: &str) -> Option<U> {
+    if let Err(err) = &result {
+        pod_failure!("{}::{method}: {err}", core::any::type_name::<T>());
+    }
+    result.ok()
 }
 
-unsafe impl<T: Pod> Pod for [T] {}
-unsafe impl<T: Pod, const N: usize> Pod for [T; N] {}
+/// The `Pod` derive rejects generic structs with padding once they are used.
+///
+/// ```compile_fail,E0080
+/// use watto::Pod;
+///
+/// #[derive(Clone, Copy, Pod)]
+/// #[repr(C)]
+/// struct Entry<T> {
+///     tag: u8,
+///     value: T,
+/// }
+///
+/// let entry = Entry { tag: 1, value: 2u32 };
+/// entry.as_bytes();
+/// ```
+#[cfg(all(doctest, feature = "derive"))]
+pub struct PaddedGenericPod;
+
+/// This also applies to const-generic structs.
+///
+/// ```compile_fail,E0080
+/// use watto::Pod;
+///
+/// #[derive(Clone, Copy, Pod)]
+/// #[repr(C)]
+/// struct Block<const N: usize> {
+///     len: u32,
+///     data: [u8; N],
+/// }
+///
+/// let block = Block { len: 3, data: [1, 2, 3] };
+/// block.as_bytes();
+/// ```
+#[cfg(all(doctest, feature = "derive"))]
+pub struct PaddedConstGenericPod;
+
+unsafe impl<T: Pod> Pod for [T] {
+    const LAYOUT_CHECK: () = T::LAYOUT_CHECK;
+}
+unsafe impl<T: Pod, const N: usize> Pod for [T; N] {
+    const LAYOUT_CHECK: () = T::LAYOUT_CHECK;
+}
 
 /// Implements `$trait` for one or more `$type`s.
 macro_rules! impl_for_types {
@@ -0,0 +1,198 @@
+use core::ops::Range;
+
+use crate::{Pod, Reader};
+
+/// A builder for a table of sorted, non-overlapping address ranges mapping to [`Pod`] values.
+///
+/// The table is serialized as the number of ranges as a `u32`, followed by
+/// the aligned slices of `u64` range starts, `u64` range ends, and values.
+/// It can be read zero-copy with a [`RangeView`], which finds the range
+/// covering an address using binary search directly over the serialized data.
+///
+/// # Example
+/// ```
+/// use watto::{RangeTable, Writer};
+///
+/// let mut table = RangeTable::new();
+/// table.insert(0x1000..0x1010, 1u32);
+/// table.insert(0x1020..0x1030, 2);
+///
+/// let mut writer = Writer::new(vec![]);
+/// table.write_to(&mut writer).unwrap();
+/// let buffer = writer.into_inner();
+///
+/// assert_eq!(RangeTable::lookup(&buffer, 0x1008), Some(&1u32));
+/// assert_eq!(RangeTable::<u32>::lookup(&buffer, 0x1010), None);
+/// assert_eq!(RangeTable::<u32>::lookup(&buffer, 0x102f), Some(&2));
+/// ```
+#[cfg(feature = "writer")]
+#[derive(Debug, Clone)]
+pub struct RangeTable<V> {
+    ranges: Vec<(Range<u64>, V)>,
+}
+
+#[cfg(feature = "writer")]
+impl<V> Default for RangeTable<V> {
+    fn default() -> Self {
+        Self { ranges: Vec::new() }
+    }
+}
+
+#[cfg(feature = "writer")]
+impl<V: Pod> RangeTable<V> {
+    /// Creates a new empty [`RangeTable`].
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Inserts a range with its value into this [`RangeTable`].
+    ///
+    /// Ranges can be inserted in any order, but must not overlap.
+    pub fn insert(&mut self, range: Range<u64>, value: V) {
+        self.ranges.push((range, value));
+    }
+
+    /// Returns the number of inserted ranges.
+    pub fn len(&self) -> usize {
+        self.ranges.len()
+    }
+
+    /// Returns `true` if no ranges have been inserted.
+    pub fn is_empty(&self) -> bool {
+        self.ranges.is_empty()
+    }
+
+    /// Writes the sorted ranges into the given [`Writer`](crate::Writer).
+    ///
+    /// Fails with [`InvalidInput`](std::io::ErrorKind::InvalidInput) if any
+    /// of the ranges overlap, or a range ends before it starts.
+    pub fn write_to<W: std::io::Write>(
+        &self,
+        writer: &mut crate::Writer<W>,
+    ) -> std::io::Result<()> {
+        use std::io::{Error, ErrorKind, Write};
+
+        let mut ranges: Vec<&(Range<u64>, V)> = self.ranges.iter().collect();
+        ranges.sort_by_key(|(range, _)| range.start);
+
+        let mut prev_end = 0;
+        for (range, _) in &ranges {
+            if range.end < range.start {
+                return Err(Error::new(ErrorKind::InvalidInput, "invalid range"));
+            }
+            if range.start < prev_end {
+                return Err(Error::new(ErrorKind::InvalidInput, "overlapping ranges"));
+            }
+            prev_end = range.end;
+        }
+
+        let len = u32::try_from(ranges.len())
+            .map_err(|_| Error::new(ErrorKind::InvalidInput, "too many ranges"))?;
+        writer.align_to_type::<u32>()?;
+        writer.write_all(len.as_bytes())?;
+        writer.align_to_type::<u64>()?;
+        for (range, _) in &ranges {
+            writer.write_all(range.start.as_bytes())?;
+        }
+        for (range, _) in &ranges {
+            writer.write_all(range.end.as_bytes())?;
+        }
+        writer.align_to_type::<V>()?;
+        for (_, value) in &ranges {
+            writer.write_all(value.as_bytes())?;
+        }
+        Ok(())
+    }
+
+    /// Looks up the value of the range covering `addr` in a serialized [`RangeTable`].
+    ///
+    /// This is a shortcut for [`RangeView::parse`] followed by [`RangeView::lookup`].
+    pub fn lookup<'data>(buffer: &'data [u8], addr: u64) -> Option<&'data V>
+    where
+        V: 'data,
+    {
+        RangeView::parse(buffer)?.lookup(addr)
+    }
+}
+
+/// A zero-copy view of a serialized [`RangeTable`](crate::RangeTable).
+#[derive(Debug, Clone, Copy)]
+pub struct RangeView<'data, V> {
+    starts: &'data [u64],
+    ends: &'data [u64],
+    values: &'data [V],
+}
+
+impl<'data, V: Pod> RangeView<'data, V> {
+    /// Parses a serialized range table from the start of `buffer`.
+    ///
+    /// Returns [`None`] if the buffer is too small or not properly aligned.
+    /// The ranges are *not* verified to be sorted and non-overlapping.
+    pub fn parse(buffer: &'data [u8]) -> Option<Self> {
+        Self::read(&mut Reader::new(buffer))
+    }
+
+    /// Reads a serialized range table from the given [`Reader`].
+    pub fn read(reader: &mut Reader<'data>) -> Option<Self> {
+        reader.align_to_type::<u32>()?;
+        let len = *reader.read_ref::<u32>()? as usize;
+        reader.align_to_type::<u64>()?;
+        let starts = reader.read_slice::<u64>(len)?;
+        let ends = reader.read_slice::<u64>(len)?;
+        reader.align_to_type::<V>()?;
+        let values = reader.read_slice::<V>(len)?;
+        Some(Self {
+            starts,
+            ends,
+            values,
+        })
+    }
+
+    /// Returns the number of ranges.
+    pub fn len(&self) -> usize {
+        self.starts.len()
+    }
+
+    /// Returns `true` if there are no ranges.
+    pub fn is_empty(&self) -> bool {
+        self.starts.is_empty()
+    }
+
+    /// Returns the value of the range covering `addr`, if any.
+    pub fn lookup(&self, addr: u64) -> Option<&'data V> {
+        self.lookup_range(addr).map(|(_, value)| value)
+    }
+
+    /// Returns the range covering `addr` along with its value, if any.
+    pub fn lookup_range(&self, addr: u64) -> Option<(Range<u64>, &'data V)> {
+        let index = self.starts.partition_point(|start| *start <= addr);
+        let index = index.checked_sub(1)?;
+        let end = self.ends[index];
+        if addr >= end {
+            return None;
+        }
+        Some((self.starts[index]..end, &self.values[index]))
+    }
+
+    /// Iterates over all the ranges and their values, sorted by start address.
+    pub fn iter(&self) -> impl Iterator<Item = (Range<u64>, &'data V)> + 'data {
+        self.starts
+            .iter()
+            .zip(self.ends)
+            .zip(self.values)
+            .map(|((start, end), value)| (*start..*end, value))
+    }
+}
+
+#[cfg(feature = "writer")]
+impl<V: Pod> crate::WriteTo for RangeTable<V> {
+    fn write_to<W: std::io::Write>(&self, writer: &mut crate::Writer<W>) -> std::io::Result<()> {
+        self.write_to(writer)
+    }
+}
+
+impl<'data, V: Pod> crate::ReadFrom<'data> for RangeView<'data, V> {
+    fn read_from(reader: &mut Reader<'data>) -> Option<Self> {
+        Self::read(reader)
+    }
+}
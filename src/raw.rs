@@ -0,0 +1,200 @@
+//! Allocation-free reading of serialized tables.
+//!
+//! The functions in this module read entries of serialized `OffsetSet`s and
+//! `StringTable`s without requiring `std` or `alloc`. They are available
+//! regardless of crate features, so that embedded consumers can parse
+//! watto-formatted blobs, for example baked into flash, without pulling in
+//! the hashing and deduplication machinery needed to build tables.
+//!
+//! # Example
+//! ```
+//! use watto::raw;
+//!
+//! // A serialized table with the strings "foo" and "bar".
+//! let buffer = b"\x03foo\x03bar";
+//!
+//! assert_eq!(raw::read_str(buffer, 4), Ok("bar"));
+//! assert_eq!(raw::read_str(buffer, 9), Err(raw::RawReadError::OutOfBounds));
+//!
+//! let strings = raw::entries::<u8>(buffer).map(|entry| entry.unwrap().1);
+//! assert!(strings.eq([&b"foo"[..], b"bar"]));
+//! ```
+
+use core::marker::PhantomData;
+use core::str::Utf8Error;
+use core::{fmt, mem};
+
+use crate::{varint, Pod};
+
+/// An error when reading an entry of a serialized table.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RawReadError {
+    /// The entry's length prefix is truncated or overflows.
+    InvalidLength,
+    /// The entry's offset or length is outside the bounds of the buffer.
+    OutOfBounds,
+    /// The string is not valid UTF-8.
+    Utf8(Utf8Error),
+}
+
+impl fmt::Display for RawReadError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidLength => f.write_str("invalid entry length prefix"),
+            Self::OutOfBounds => f.write_str("entry offset or length is out of bounds"),
+            Self::Utf8(err) => write!(f, "string is not valid UTF-8: {err}"),
+        }
+    }
+}
+
+#[cfg(feature = "defmt")]
+impl defmt::Format for RawReadError {
+    fn format(&self, f: defmt::Formatter<'_>) {
+        match self {
+            Self::InvalidLength => defmt::write!(f, "InvalidLength"),
+            Self::OutOfBounds => defmt::write!(f, "OutOfBounds"),
+            Self::Utf8(err) => {
+                defmt::write!(f, "Utf8 {{ valid_up_to: {=usize} }}", err.valid_up_to())
+            }
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for RawReadError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Utf8(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+/// The reason reading an entry failed.
+///
+/// This is converted into the error types of the public reading functions,
+/// which share [`read_internal`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum EntryError {
+    /// The entry's length prefix is not valid LEB128.
+    Length(varint::VarintError),
+    /// The entry's offset or length is outside the bounds of the buffer.
+    OutOfBounds,
+}
+
+impl From<EntryError> for RawReadError {
+    fn from(err: EntryError) -> Self {
+        match err {
+            EntryError::Length(_) => Self::InvalidLength,
+            EntryError::OutOfBounds => Self::OutOfBounds,
+        }
+    }
+}
+
+/// Reads the entry at `offset`, returning it and the offset following it.
+#[inline]
+pub(crate) fn read_internal<T: Pod>(
+    buffer: &[u8],
+    offset: usize,
+) -> Result<(&[T], usize), EntryError> {
+    let bytes = buffer.get(offset..).ok_or(EntryError::OutOfBounds)?;
+    let (len, prefix_len) = varint::try_read_u64(bytes).map_err(EntryError::Length)?;
+
+    let start = offset + prefix_len;
+    let end = usize::try_from(len)
+        .ok()
+        .and_then(|len| len.checked_mul(mem::size_of::<T>()))
+        .and_then(|len| len.checked_add(start))
+        .ok_or(EntryError::OutOfBounds)?;
+
+    let bytes = buffer.get(start..end).ok_or(EntryError::OutOfBounds)?;
+    let slice = T::slice_from_bytes(bytes).ok_or(EntryError::OutOfBounds)?;
+    Ok((slice, end))
+}
+
+/// Returns the number of padding bytes at the start of `bytes`.
+///
+/// Tables with an entry alignment pad the space between entries with overlong
+/// encodings of an empty entry, like `[0x80, 0x00]`, which are never written
+/// for actual entries. Iterating over entries skips this padding.
+///
+/// # Example
+/// ```
+/// use watto::raw;
+///
+/// assert_eq!(raw::padding_len(b"\x80\x00\x80\x80\x00\x03foo"), 5);
+/// assert_eq!(raw::padding_len(b"\x00\x03foo"), 0);
+/// assert_eq!(raw::padding_len(b"\x80\x01"), 0);
+/// ```
+pub fn padding_len(bytes: &[u8]) -> usize {
+    let mut len = 0;
+    loop {
+        let rest = &bytes[len..];
+        let continuations = rest.iter().take_while(|byte| **byte == 0x80).count();
+        if continuations == 0 || rest.get(continuations) != Some(&0) {
+            return len;
+        }
+        len += continuations + 1;
+    }
+}
+
+/// Returns the slice stored at the given offset of a serialized `OffsetSet`.
+///
+/// This is equivalent to `OffsetSet::read`.
+pub fn read<T: Pod>(buffer: &[u8], offset: usize) -> Result<&[T], RawReadError> {
+    Ok(read_internal(buffer, offset)?.0)
+}
+
+/// Returns the string stored at the given offset of a serialized `StringTable`.
+///
+/// This is equivalent to `StringTable::read`.
+pub fn read_str(buffer: &[u8], offset: usize) -> Result<&str, RawReadError> {
+    let bytes = read(buffer, offset)?;
+    core::str::from_utf8(bytes).map_err(RawReadError::Utf8)
+}
+
+/// Iterates over all the entries of a serialized `OffsetSet` in the order
+/// in which they were inserted.
+pub fn entries<T: Pod>(buffer: &[u8]) -> Entries<'_, T> {
+    Entries {
+        buffer,
+        offset: 0,
+        _t: PhantomData,
+    }
+}
+
+/// An iterator over the entries of a serialized `OffsetSet`.
+///
+/// This yields `(offset, slice)` pairs, and stops after the first error.
+///
+/// Created by [`entries`].
+#[derive(Debug, Clone)]
+pub struct Entries<'data, T> {
+    buffer: &'data [u8],
+    offset: usize,
+    _t: PhantomData<T>,
+}
+
+impl<'data, T: Pod + 'data> Iterator for Entries<'data, T> {
+    type Item = Result<(usize, &'data [T]), RawReadError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let offset = self.offset + padding_len(self.buffer.get(self.offset..)?);
+        if offset >= self.buffer.len() {
+            return None;
+        }
+
+        match read_internal(self.buffer, offset) {
+            Ok((slice, next_offset)) => {
+                self.offset = next_offset;
+                Some(Ok((offset, slice)))
+            }
+            Err(err) => {
+                self.offset = self.buffer.len();
+                Some(Err(err.into()))
+            }
+        }
+    }
+}
+
+impl<'data, T: Pod + 'data> core::iter::FusedIterator for Entries<'data, T> {}
@@ -0,0 +1,149 @@
+use core::mem;
+
+use crate::varint::{self, VarintValue};
+use crate::Pod;
+
+/// A type which can be read from a [`Reader`], aligning its parts as necessary.
+///
+/// This is the reading counterpart to [`WriteTo`](crate::WriteTo), and is
+/// implemented for references to [`Pod`] values and the zero-copy views of
+/// tables, like [`MapView`](crate::MapView) for a [`MapTable`](crate::MapTable).
+/// Slices of [`Pod`] values are written without their length, so they are read
+/// with [`Reader::read_slice`] instead.
+///
+/// # Example
+/// ```
+/// use watto::{ReadFrom, Reader};
+///
+/// let buffer: &[u32] = &[1, 2];
+/// let mut reader = Reader::new(watto::Pod::as_bytes(buffer));
+///
+/// let a = <&u32>::read_from(&mut reader).unwrap();
+/// let b: &u32 = ReadFrom::read_from(&mut reader).unwrap();
+/// assert_eq!((*a, *b), (1, 2));
+/// assert!(<&u64>::read_from(&mut reader).is_none());
+/// ```
+pub trait ReadFrom<'data>: Sized {
+    /// Reads a `Self` from the given [`Reader`].
+    ///
+    /// Returns [`None`] if the remaining buffer does not hold a valid `Self`.
+    fn read_from(reader: &mut Reader<'data>) -> Option<Self>;
+}
+
+impl<'data, T: Pod> ReadFrom<'data> for &'data T {
+    fn read_from(reader: &mut Reader<'data>) -> Option<Self> {
+        let mut inner = reader.clone();
+        inner.align_to_type::<T>()?;
+        let value = inner.read_ref()?;
+        *reader = inner;
+        Some(value)
+    }
+}
+
+/// A cursor over a byte slice for reading zero-copy [`Pod`] values.
+///
+/// This is the reading counterpart to the [`Writer`](crate::Writer), keeping
+/// track of the current position and allowing explicit alignment of the
+/// remaining buffer.
+///
+/// All reading methods return [`None`] if the remaining buffer is too small
+/// or not properly aligned, in which case the position is left unchanged.
+///
+/// # Example
+/// ```
+/// use watto::Reader;
+///
+/// let buffer: &[u32] = &[1, 2, 3];
+/// let mut reader = Reader::new(watto::Pod::as_bytes(buffer));
+///
+/// assert_eq!(reader.read_ref::<u32>(), Some(&1));
+/// assert_eq!(reader.read_slice::<u32>(2), Some(&[2, 3][..]));
+/// assert!(reader.is_empty());
+/// ```
+#[derive(Debug, Clone)]
+pub struct Reader<'data> {
+    buffer: &'data [u8],
+    pos: usize,
+}
+
+impl<'data> Reader<'data> {
+    /// Creates a new [`Reader`] at the start of `buffer`.
+    pub fn new(buffer: &'data [u8]) -> Self {
+        Self { buffer, pos: 0 }
+    }
+
+    /// Returns the current position within the buffer.
+    pub fn position(&self) -> usize {
+        self.pos
+    }
+
+    /// Returns the remaining, not yet read bytes.
+    pub fn remaining(&self) -> &'data [u8] {
+        &self.buffer[self.pos..]
+    }
+
+    /// Returns `true` if the whole buffer has been read.
+    pub fn is_empty(&self) -> bool {
+        self.pos == self.buffer.len()
+    }
+
+    /// Skips the padding bytes necessary to align the remaining buffer to `align` bytes.
+    ///
+    /// Returns the number of skipped bytes.
+    pub fn align_to(&mut self, align: usize) -> Option<usize> {
+        let (padding, _) = crate::align_to(self.remaining(), align)?;
+        self.pos += padding.len();
+        Some(padding.len())
+    }
+
+    /// Skips the padding bytes necessary to align the remaining buffer for `T`.
+    ///
+    /// Returns the number of skipped bytes.
+    pub fn align_to_type<T>(&mut self) -> Option<usize> {
+        self.align_to(mem::align_of::<T>())
+    }
+
+    /// Reads `len` raw bytes.
+    pub fn read_bytes(&mut self, len: usize) -> Option<&'data [u8]> {
+        let bytes = self.remaining().get(..len)?;
+        self.pos += len;
+        Some(bytes)
+    }
+
+    /// Reads a UTF-8 string prefixed with its LEB128 encoded length in bytes.
+    ///
+    /// This matches the encoding of `StringTable` entries, so inline strings
+    /// and string table entries can be read the same way.
+    pub fn read_length_prefixed_str(&mut self) -> Option<&'data str> {
+        let (len, prefix_len) = varint::read_u64(self.remaining())?;
+        let start = self.pos + prefix_len;
+        let end = usize::try_from(len).ok()?.checked_add(start)?;
+        let s = core::str::from_utf8(self.buffer.get(start..end)?).ok()?;
+        self.pos = end;
+        Some(s)
+    }
+
+    /// Reads a varint encoded integer.
+    ///
+    /// Returns [`None`] if the encoding is truncated or the value does not fit into a `T`.
+    pub fn read_varint<T: VarintValue>(&mut self) -> Option<T> {
+        let (value, len) = varint::read_u64(self.remaining())?;
+        let value = T::from_varint(value)?;
+        self.pos += len;
+        Some(value)
+    }
+
+    /// Reads a reference to a `T`, which needs to be properly aligned.
+    pub fn read_ref<T: Pod>(&mut self) -> Option<&'data T> {
+        let (value, _) = T::ref_from_prefix(self.remaining())?;
+        self.pos += mem::size_of::<T>();
+        Some(value)
+    }
+
+    /// Reads a slice of `len` `T`s, which needs to be properly aligned.
+    pub fn read_slice<T: Pod>(&mut self, len: usize) -> Option<&'data [T]> {
+        let (slice, _) = T::slice_from_prefix(self.remaining(), len)?;
+        self.pos += mem::size_of_val(slice);
+        Some(slice)
+    }
+}
@@ -0,0 +1,159 @@
+use core::hash::{BuildHasher, Hash};
+use core::{fmt, mem};
+
+use hashbrown::{DefaultHashBuilder, HashTable};
+
+use crate::Pod;
+
+/// The index of a record within a [`RecordTable`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[repr(transparent)]
+pub struct RecordId(pub u32);
+
+unsafe impl Pod for RecordId {}
+
+/// A table of fixed-size [`Pod`] records, indexed by [`RecordId`].
+///
+/// This complements the [`OffsetSet`](crate::OffsetSet), which stores
+/// variable-length entries, for the common case of an array of structs that
+/// is referenced by `u32` indices.
+///
+/// The serialized representation is a plain slice of records, which needs to
+/// be aligned to the alignment of `T`.
+///
+/// # Example
+/// ```
+/// use watto::{RecordId, RecordTable};
+///
+/// let mut table = RecordTable::<u64>::with_dedup();
+/// assert_eq!(table.push(42), RecordId(0));
+/// assert_eq!(table.push(7), RecordId(1));
+/// assert_eq!(table.push(42), RecordId(0));
+///
+/// let buffer = table.as_bytes();
+/// assert_eq!(RecordTable::<u64>::get(buffer, RecordId(1)), Some(&7));
+/// assert_eq!(RecordTable::<u64>::get(buffer, RecordId(2)), None);
+/// ```
+#[derive(Clone)]
+pub struct RecordTable<T> {
+    hasher: DefaultHashBuilder,
+    index: Option<HashTable<u32>>,
+    records: Vec<T>,
+}
+
+impl<T: fmt::Debug> fmt::Debug for RecordTable<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(&self.records).finish()
+    }
+}
+
+impl<T> Default for RecordTable<T> {
+    fn default() -> Self {
+        Self {
+            hasher: Default::default(),
+            index: None,
+            records: Vec::new(),
+        }
+    }
+}
+
+impl<T: Pod> RecordTable<T> {
+    /// Initializes an empty [`RecordTable`] which stores every pushed record.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Initializes an empty [`RecordTable`] which deduplicates pushed records.
+    ///
+    /// Pushing a record equal to an existing one returns the existing [`RecordId`].
+    pub fn with_dedup() -> Self {
+        Self {
+            index: Some(HashTable::new()),
+            ..Default::default()
+        }
+    }
+
+    /// Returns the number of records in this [`RecordTable`].
+    pub fn len(&self) -> usize {
+        self.records.len()
+    }
+
+    /// Returns `true` if this [`RecordTable`] is empty.
+    pub fn is_empty(&self) -> bool {
+        self.records.is_empty()
+    }
+
+    /// Returns all the records in this [`RecordTable`].
+    pub fn records(&self) -> &[T] {
+        &self.records
+    }
+
+    /// Returns a byte slice containing the serialized representation of this [`RecordTable`].
+    pub fn as_bytes(&self) -> &[u8] {
+        self.records.as_slice().as_bytes()
+    }
+
+    /// Returns the record with the given [`RecordId`] from the serialized representation.
+    ///
+    /// Returns [`None`] if the `buffer` is not properly aligned or sized, or
+    /// the `id` is out of bounds.
+    pub fn get(buffer: &[u8], id: RecordId) -> Option<&T> {
+        T::slice_from_bytes(buffer)?.get(id.0 as usize)
+    }
+
+    /// Writes the serialized representation into the given [`Writer`](crate::Writer),
+    /// aligning it to the alignment of `T`.
+    #[cfg(feature = "writer")]
+    pub fn write_to<W: std::io::Write>(
+        &self,
+        writer: &mut crate::Writer<W>,
+    ) -> std::io::Result<()> {
+        writer.align_to_type::<T>()?;
+        std::io::Write::write_all(writer, self.as_bytes())
+    }
+}
+
+impl<T: Pod + PartialEq + Hash> RecordTable<T> {
+    /// Pushes a record into this [`RecordTable`], returning its [`RecordId`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if the table grows beyond `u32::MAX` records.
+    pub fn push(&mut self, record: T) -> RecordId {
+        assert!(mem::size_of::<T>() != 0, "records can not be zero-sized");
+        let next_id: u32 = self
+            .records
+            .len()
+            .try_into()
+            .expect("record table exceeds u32::MAX records");
+
+        let Some(index) = &mut self.index else {
+            self.records.push(record);
+            return RecordId(next_id);
+        };
+
+        let records = &mut self.records;
+        let hasher = &self.hasher;
+        let hash = hasher.hash_one(&record);
+        let id = *index
+            .entry(
+                hash,
+                |&id| records[id as usize] == record,
+                |&id| hasher.hash_one(&records[id as usize]),
+            )
+            .or_insert_with(|| {
+                records.push(record);
+                next_id
+            })
+            .get();
+
+        RecordId(id)
+    }
+}
+
+#[cfg(feature = "writer")]
+impl<T: Pod> crate::WriteTo for RecordTable<T> {
+    fn write_to<W: std::io::Write>(&self, writer: &mut crate::Writer<W>) -> std::io::Result<()> {
+        self.write_to(writer)
+    }
+}
@@ -0,0 +1,540 @@
+use std::fs::{File, OpenOptions};
+use std::io::{self, Read, Write};
+use std::path::Path;
+use std::sync::Arc;
+
+/// The backing storage of the buffer of an [`OffsetSet`](crate::OffsetSet) or
+/// [`StringTable`](crate::StringTable).
+///
+/// Tables are append-only, so a storage only needs to support reading its
+/// contents and appending to them. Implementing this for a file-backed or
+/// shared memory buffer allows building very large tables directly in their
+/// final location, instead of building them in memory and writing them out
+/// afterwards.
+///
+/// # Example
+/// ```
+/// use watto::{OffsetSet, Storage};
+///
+/// /// A storage which refuses to grow beyond a fixed capacity.
+/// #[derive(Default)]
+/// struct Fixed(Vec<u8>);
+///
+/// impl Storage for Fixed {
+///     fn as_bytes(&self) -> &[u8] {
+///         &self.0
+///     }
+///
+///     fn reserve(&mut self, additional: usize) -> std::io::Result<()> {
+///         if self.0.len() + additional > 8 {
+///             return Err(std::io::ErrorKind::StorageFull.into());
+///         }
+///         Ok(())
+///     }
+///
+///     fn extend_from_slice(&mut self, bytes: &[u8]) {
+///         self.0.extend_from_slice(bytes);
+///     }
+/// }
+///
+/// let mut set = OffsetSet::<u8, _>::from_storage(Fixed::default()).unwrap();
+/// assert!(set.try_insert(b"foo").is_ok());
+/// assert!(set.try_insert(b"bar baz").is_err());
+/// ```
+pub trait Storage {
+    /// Returns the contents of this storage.
+    fn as_bytes(&self) -> &[u8];
+
+    /// Makes room for at least `additional` more bytes.
+    ///
+    /// Tables call this before appending an entry, so that failing to grow the
+    /// storage does not leave a partially written entry behind.
+    fn reserve(&mut self, additional: usize) -> io::Result<()>;
+
+    /// Makes room for exactly `additional` more bytes, without reserving extra
+    /// room for future growth.
+    ///
+    /// This is used when the final size of a table is known upfront, and
+    /// defaults to [`reserve`](Self::reserve).
+    fn reserve_exact(&mut self, additional: usize) -> io::Result<()> {
+        self.reserve(additional)
+    }
+
+    /// Appends `bytes` to the end of this storage.
+    ///
+    /// The space for `bytes` has been [reserved](Self::reserve) before.
+    fn extend_from_slice(&mut self, bytes: &[u8]);
+
+    /// Returns the number of bytes this storage can hold without growing.
+    ///
+    /// This is only used to report buffer growth to a [`TableObserver`](crate::TableObserver).
+    fn capacity(&self) -> usize {
+        self.as_bytes().len()
+    }
+}
+
+impl Storage for Vec<u8> {
+    fn as_bytes(&self) -> &[u8] {
+        self
+    }
+
+    fn reserve(&mut self, additional: usize) -> io::Result<()> {
+        self.try_reserve(additional)
+            .map_err(|_| io::ErrorKind::OutOfMemory.into())
+    }
+
+    fn reserve_exact(&mut self, additional: usize) -> io::Result<()> {
+        self.try_reserve_exact(additional)
+            .map_err(|_| io::ErrorKind::OutOfMemory.into())
+    }
+
+    fn extend_from_slice(&mut self, bytes: &[u8]) {
+        Vec::extend_from_slice(self, bytes);
+    }
+
+    fn capacity(&self) -> usize {
+        Vec::capacity(self)
+    }
+}
+
+/// A [`Storage`] which is shared between clones, and copied on write.
+///
+/// Cloning a [`SharedStorage`] only increments a reference count. The buffer
+/// is copied once a clone which shares it is appended to, so the tables
+/// created by [`OffsetSet::into_shared`](crate::OffsetSet::into_shared) can be
+/// cheaply snapshotted, for example once per output shard.
+///
+/// # Example
+/// ```
+/// # #[cfg(feature = "strings")] {
+/// use watto::StringTable;
+///
+/// let mut table = StringTable::new().into_shared();
+/// table.insert("foo");
+///
+/// let snapshot = table.clone();
+/// assert_eq!(snapshot.as_bytes().as_ptr(), table.as_bytes().as_ptr());
+///
+/// table.insert("bar");
+/// assert_eq!(snapshot.as_bytes(), b"\x03foo");
+/// assert_eq!(table.as_bytes(), b"\x03foo\x03bar");
+/// # }
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct SharedStorage(Arc<Vec<u8>>);
+
+impl SharedStorage {
+    /// Returns the contained buffer, copying it if it is still shared.
+    pub fn into_vec(self) -> Vec<u8> {
+        Arc::unwrap_or_clone(self.0)
+    }
+
+    /// Makes room for `additional` more bytes, copying the buffer if it is shared.
+    fn reserve_unshared(&mut self, additional: usize, exact: bool) -> io::Result<()> {
+        let buffer = match Arc::get_mut(&mut self.0) {
+            Some(buffer) => buffer,
+            None => {
+                let mut buffer = Vec::new();
+                Storage::reserve_exact(&mut buffer, self.0.len() + additional)?;
+                buffer.extend_from_slice(&self.0);
+                self.0 = Arc::new(buffer);
+                return Ok(());
+            }
+        };
+        if exact {
+            Storage::reserve_exact(buffer, additional)
+        } else {
+            Storage::reserve(buffer, additional)
+        }
+    }
+}
+
+impl From<Vec<u8>> for SharedStorage {
+    fn from(buffer: Vec<u8>) -> Self {
+        Self(Arc::new(buffer))
+    }
+}
+
+impl Storage for SharedStorage {
+    fn as_bytes(&self) -> &[u8] {
+        &self.0
+    }
+
+    fn reserve(&mut self, additional: usize) -> io::Result<()> {
+        self.reserve_unshared(additional, false)
+    }
+
+    fn reserve_exact(&mut self, additional: usize) -> io::Result<()> {
+        self.reserve_unshared(additional, true)
+    }
+
+    fn extend_from_slice(&mut self, bytes: &[u8]) {
+        Arc::make_mut(&mut self.0).extend_from_slice(bytes);
+    }
+
+    fn capacity(&self) -> usize {
+        self.0.capacity()
+    }
+}
+
+/// A [`Storage`] which appends all data to a file as soon as it is written.
+///
+/// Tables built in a [`FileStorage`] persist every inserted entry right away,
+/// in a write-ahead fashion, so they survive crashes of the process. Use
+/// [`OffsetSet::open`](crate::OffsetSet::open) or
+/// [`StringTable::open`](crate::StringTable::open) to reopen such a table,
+/// which discards a partially written entry left behind by a crash.
+/// The contents are kept in memory as well.
+///
+/// As [`extend_from_slice`](Storage::extend_from_slice) can not fail, an error
+/// when writing to the file is reported by all following calls to
+/// [`reserve`](Storage::reserve), which makes further insertions fail, and by
+/// [`sync`](Self::sync).
+#[derive(Debug)]
+pub struct FileStorage {
+    file: File,
+    buffer: Vec<u8>,
+    error: Option<io::Error>,
+}
+
+impl FileStorage {
+    /// Opens the file at `path` and reads its contents, creating the file if
+    /// it does not exist.
+    pub fn open<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        let mut file = OpenOptions::new()
+            .read(true)
+            .append(true)
+            .create(true)
+            .open(path)?;
+        let mut buffer = vec![];
+        file.read_to_end(&mut buffer)?;
+        Ok(Self {
+            file,
+            buffer,
+            error: None,
+        })
+    }
+
+    /// Discards all contents after the first `len` bytes, both in memory and in the file.
+    pub fn truncate(&mut self, len: usize) -> io::Result<()> {
+        if len < self.buffer.len() {
+            self.file.set_len(len as u64)?;
+            self.buffer.truncate(len);
+        }
+        Ok(())
+    }
+
+    /// Flushes the contents of the file to disk.
+    ///
+    /// Returns an error if any previous write to the file failed.
+    pub fn sync(&self) -> io::Result<()> {
+        self.check()?;
+        self.file.sync_data()
+    }
+
+    /// Returns an error if any previous write to the file failed.
+    fn check(&self) -> io::Result<()> {
+        match &self.error {
+            Some(err) => Err(io::Error::new(
+                err.kind(),
+                format!("previous write to the file failed: {err}"),
+            )),
+            None => Ok(()),
+        }
+    }
+}
+
+impl Storage for FileStorage {
+    fn as_bytes(&self) -> &[u8] {
+        &self.buffer
+    }
+
+    fn reserve(&mut self, additional: usize) -> io::Result<()> {
+        self.check()?;
+        Storage::reserve(&mut self.buffer, additional)
+    }
+
+    fn reserve_exact(&mut self, additional: usize) -> io::Result<()> {
+        self.check()?;
+        Storage::reserve_exact(&mut self.buffer, additional)
+    }
+
+    fn extend_from_slice(&mut self, bytes: &[u8]) {
+        if self.error.is_none() {
+            self.error = self.file.write_all(bytes).err();
+        }
+        self.buffer.extend_from_slice(bytes);
+    }
+
+    fn capacity(&self) -> usize {
+        self.buffer.capacity()
+    }
+}
+
+/// A [`Storage`] in a fixed-size region of memory, like a shared memory segment.
+///
+/// Tables are built directly into the region, which is never reallocated, so
+/// other processes which have mapped the same segment can read the entries
+/// as they are inserted. Inserting fails with
+/// [`StorageFull`](io::ErrorKind::StorageFull) once the region is full.
+///
+/// # Example
+/// ```
+/// use watto::{OffsetSet, SegmentStorage};
+///
+/// let mut segment = [0; 8];
+/// let mut set = OffsetSet::<u8, _>::from_storage(SegmentStorage::new(&mut segment)).unwrap();
+/// let foo = set.try_insert(b"foo").unwrap();
+/// assert!(set.try_insert(b"bar baz").is_err());
+///
+/// let len = set.as_bytes().len();
+/// drop(set);
+/// assert_eq!(&segment[..len], b"\x03foo");
+///
+/// let storage = SegmentStorage::with_len(&mut segment, len);
+/// let set = OffsetSet::<u8, _>::from_storage(storage).unwrap();
+/// assert_eq!(set.get(b"foo"), Some(foo));
+/// ```
+#[derive(Debug)]
+pub struct SegmentStorage<'a> {
+    segment: &'a mut [u8],
+    len: usize,
+}
+
+impl<'a> SegmentStorage<'a> {
+    /// Creates an empty storage in `segment`.
+    pub fn new(segment: &'a mut [u8]) -> Self {
+        Self { segment, len: 0 }
+    }
+
+    /// Creates a storage in `segment` whose first `len` bytes are its contents.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `len` is larger than `segment`.
+    pub fn with_len(segment: &'a mut [u8], len: usize) -> Self {
+        assert!(len <= segment.len(), "len exceeds the segment");
+        Self { segment, len }
+    }
+
+    /// Returns the underlying region of memory.
+    pub fn into_inner(self) -> &'a mut [u8] {
+        self.segment
+    }
+}
+
+impl Storage for SegmentStorage<'_> {
+    fn as_bytes(&self) -> &[u8] {
+        &self.segment[..self.len]
+    }
+
+    fn reserve(&mut self, additional: usize) -> io::Result<()> {
+        if additional > self.segment.len() - self.len {
+            return Err(io::ErrorKind::StorageFull.into());
+        }
+        Ok(())
+    }
+
+    /// # Panics
+    ///
+    /// Panics if `bytes` do not fit into the segment.
+    fn extend_from_slice(&mut self, bytes: &[u8]) {
+        let new_len = self.len + bytes.len();
+        self.segment[self.len..new_len].copy_from_slice(bytes);
+        self.len = new_len;
+    }
+
+    fn capacity(&self) -> usize {
+        self.segment.len()
+    }
+}
+
+#[cfg(feature = "mmap")]
+pub use mmap::MmapStorage;
+
+#[cfg(feature = "mmap")]
+mod mmap {
+    use std::fs::{File, OpenOptions};
+    use std::io;
+    use std::path::Path;
+
+    use memmap2::MmapMut;
+
+    use super::Storage;
+
+    /// The minimum size a [`MmapStorage`] grows its file to.
+    const MIN_CAPACITY: usize = 64 * 1024;
+
+    /// Marks the trailer of a file which has not been [finished](MmapStorage::finish).
+    const TRAILER_MAGIC: [u8; 8] = *b"WATTOLEN";
+
+    /// The size of the trailer, consisting of [`TRAILER_MAGIC`] followed by
+    /// the length of the contents as a little-endian `u64`.
+    const TRAILER_LEN: usize = 16;
+
+    /// A [`Storage`] backed by a memory-mapped file, which grows as needed.
+    ///
+    /// The file is grown in exponentially larger steps, and truncated to the
+    /// size of its contents in [`finish`](Self::finish).
+    /// Until then, the length of the contents is kept in a trailer following
+    /// the zero-filled trailing capacity, so that a storage which was dropped
+    /// without calling `finish` can be reopened with [`open`](Self::open).
+    #[derive(Debug)]
+    pub struct MmapStorage {
+        file: File,
+        map: Option<MmapMut>,
+        len: usize,
+    }
+
+    impl MmapStorage {
+        /// Creates a new empty storage at `path`, truncating any existing file.
+        ///
+        /// # Safety
+        ///
+        /// The file is mapped into memory, so it must not be modified, truncated
+        /// or mapped by anything else while the storage exists, including other
+        /// processes. See [`MmapMut::map_mut`] for details.
+        pub unsafe fn create<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+            let file = OpenOptions::new()
+                .read(true)
+                .write(true)
+                .create(true)
+                .truncate(true)
+                .open(path)?;
+            Ok(Self {
+                file,
+                map: None,
+                len: 0,
+            })
+        }
+
+        /// Opens the existing file at `path`, and appends to its current contents.
+        ///
+        /// If the file was not [finished](Self::finish), its trailing capacity
+        /// is not part of the contents.
+        ///
+        /// # Safety
+        ///
+        /// The same requirements as for [`create`](Self::create) apply.
+        pub unsafe fn open<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+            let file = OpenOptions::new().read(true).write(true).open(path)?;
+            let file_len = file.metadata()?.len() as usize;
+            let mut storage = Self {
+                file,
+                map: None,
+                len: file_len,
+            };
+            if file_len == 0 {
+                return Ok(storage);
+            }
+
+            // SAFETY:
+            // Modifying the file while it is mapped is undefined behavior,
+            // which the caller has to rule out.
+            let map = unsafe { MmapMut::map_mut(&storage.file)? };
+            if let Some(len) = read_trailer(&map) {
+                storage.len = len;
+                storage.map = Some(map);
+            } else {
+                // A finished file is grown to make room for its trailer.
+                drop(map);
+                storage.grow(file_len)?;
+            }
+            Ok(storage)
+        }
+
+        /// Flushes the contents to disk and truncates the file to their size.
+        ///
+        /// Returns the underlying file.
+        pub fn finish(self) -> io::Result<File> {
+            if let Some(map) = self.map {
+                map.flush()?;
+            }
+            self.file.set_len(self.len as u64)?;
+            Ok(self.file)
+        }
+
+        fn grow(&mut self, min_capacity: usize) -> io::Result<()> {
+            let capacity = min_capacity
+                .max(self.capacity().saturating_mul(2))
+                .max(MIN_CAPACITY);
+            let file_len = capacity
+                .checked_add(TRAILER_LEN)
+                .ok_or(io::ErrorKind::OutOfMemory)?;
+
+            let mut previous_trailer = None;
+            if let Some(map) = &self.map {
+                map.flush()?;
+                previous_trailer = Some(map.len() - TRAILER_LEN..map.len());
+            }
+            self.file.set_len(file_len as u64)?;
+            // SAFETY: see `open`.
+            let mut map = unsafe { MmapMut::map_mut(&self.file)? };
+            // The previous trailer is now part of the zero-filled capacity.
+            if let Some(range) = previous_trailer {
+                map[range].fill(0);
+            }
+            map[capacity..capacity + TRAILER_MAGIC.len()].copy_from_slice(&TRAILER_MAGIC);
+            self.map = Some(map);
+            self.write_len();
+            Ok(())
+        }
+
+        /// Updates the length of the contents in the trailer.
+        fn write_len(&mut self) {
+            if let Some(map) = &mut self.map {
+                let end = map.len();
+                map[end - 8..].copy_from_slice(&(self.len as u64).to_le_bytes());
+            }
+        }
+    }
+
+    /// Returns the length of the contents stored in the trailer of an unfinished file.
+    fn read_trailer(map: &[u8]) -> Option<usize> {
+        let capacity = map.len().checked_sub(TRAILER_LEN)?;
+        let (magic, len) = map[capacity..].split_at(TRAILER_MAGIC.len());
+        if magic != TRAILER_MAGIC {
+            return None;
+        }
+        let len = u64::from_le_bytes(len.try_into().ok()?);
+        usize::try_from(len).ok().filter(|len| *len <= capacity)
+    }
+
+    impl Storage for MmapStorage {
+        fn as_bytes(&self) -> &[u8] {
+            match &self.map {
+                Some(map) => &map[..self.len],
+                None => &[],
+            }
+        }
+
+        fn reserve(&mut self, additional: usize) -> io::Result<()> {
+            let min_capacity = self
+                .len
+                .checked_add(additional)
+                .ok_or(io::ErrorKind::OutOfMemory)?;
+            if min_capacity > self.capacity() {
+                self.grow(min_capacity)?;
+            }
+            Ok(())
+        }
+
+        /// # Panics
+        ///
+        /// Panics if the file has to grow and growing it fails.
+        fn extend_from_slice(&mut self, bytes: &[u8]) {
+            self.reserve(bytes.len())
+                .expect("failed to grow memory-mapped storage");
+            let new_len = self.len + bytes.len();
+            if let Some(map) = &mut self.map {
+                map[self.len..new_len].copy_from_slice(bytes);
+            }
+            self.len = new_len;
+            self.write_len();
+        }
+
+        fn capacity(&self) -> usize {
+            self.map.as_ref().map_or(0, |map| map.len() - TRAILER_LEN)
+        }
+    }
+}
@@ -0,0 +1,114 @@
+use crate::{OffsetSet, ReadStringError, StringTable};
+
+/// A struct for storing lists of strings without duplicates.
+///
+/// The individual strings are deduplicated in a [`StringTable`], and each list
+/// is stored as a deduplicated slice of string offsets in an [`OffsetSet`].
+/// The string offsets are encoded as little-endian `u32`s.
+///
+/// Both the [`lists`](Self::lists) and the [`strings`](Self::strings) need to
+/// be serialized in order to [`read`](Self::read) a list back.
+///
+/// # Example
+/// ```
+/// use watto::StringListTable;
+///
+/// let mut table = StringListTable::new();
+/// let offset = table.insert(["foo", "bar", "foo"]);
+///
+/// let list: Result<Vec<_>, _> = StringListTable::read(table.lists(), table.strings(), offset)
+///     .unwrap()
+///     .collect();
+/// assert_eq!(list.unwrap(), ["foo", "bar", "foo"]);
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct StringListTable {
+    strings: StringTable,
+    lists: OffsetSet<[u8; 4]>,
+    scratch: Vec<[u8; 4]>,
+}
+
+impl StringListTable {
+    /// Initializes an empty [`StringListTable`].
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Insert a list of strings into this [`StringListTable`].
+    ///
+    /// Returns an offset that can be used to retrieve the inserted list with
+    /// [`read`](Self::read) after serializing this table.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the string table grows beyond 4 GiB.
+    pub fn insert<I>(&mut self, list: I) -> usize
+    where
+        I: IntoIterator,
+        I::Item: AsRef<str>,
+    {
+        self.scratch.clear();
+        for s in list {
+            let offset = self.strings.insert(s.as_ref());
+            let offset: u32 = offset.try_into().expect("string table exceeds 4 GiB");
+            self.scratch.push(offset.to_le_bytes());
+        }
+        self.lists.insert(&self.scratch)
+    }
+
+    /// Returns a byte slice containing the serialized lists.
+    pub fn lists(&self) -> &[u8] {
+        self.lists.as_bytes()
+    }
+
+    /// Returns a byte slice containing the serialized strings.
+    pub fn strings(&self) -> &[u8] {
+        self.strings.as_bytes()
+    }
+
+    /// Returns byte vectors containing the serialized lists and strings.
+    ///
+    /// This consumes the [`StringListTable`].
+    pub fn into_bytes(self) -> (Vec<u8>, Vec<u8>) {
+        (self.lists.into_bytes(), self.strings.into_bytes())
+    }
+
+    /// Returns an iterator over the list of strings stored at the given offset.
+    ///
+    /// The individual strings are resolved lazily while iterating.
+    pub fn read<'data>(
+        lists: &'data [u8],
+        strings: &'data [u8],
+        offset: usize,
+    ) -> Result<StringListIter<'data>, ReadStringError> {
+        let offsets = OffsetSet::<[u8; 4]>::read(lists, offset)?;
+        Ok(StringListIter {
+            strings,
+            offsets: offsets.iter(),
+        })
+    }
+}
+
+/// An iterator over a list of strings stored in a [`StringListTable`].
+///
+/// Returned from [`StringListTable::read`].
+#[derive(Debug, Clone)]
+pub struct StringListIter<'data> {
+    strings: &'data [u8],
+    offsets: core::slice::Iter<'data, [u8; 4]>,
+}
+
+impl<'data> Iterator for StringListIter<'data> {
+    type Item = Result<&'data str, ReadStringError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let offset = u32::from_le_bytes(*self.offsets.next()?);
+        Some(StringTable::read(self.strings, offset as usize))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.offsets.size_hint()
+    }
+}
+
+impl ExactSizeIterator for StringListIter<'_> {}
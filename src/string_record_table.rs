@@ -0,0 +1,191 @@
+use core::hash::Hash;
+
+use crate::{Pod, ReadStringError, Reader, RecordId, RecordTable, StringTable};
+
+/// The offset of a string within a [`StringTable`], for use as a field of [`Pod`] records.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[repr(transparent)]
+pub struct StringOffset(pub u32);
+
+unsafe impl Pod for StringOffset {}
+
+impl StringOffset {
+    /// Reads the string at this offset from a serialized [`StringTable`].
+    pub fn resolve(self, strings: &[u8]) -> Result<&str, ReadStringError> {
+        StringTable::read(strings, self.0 as usize)
+    }
+}
+
+/// A [`RecordTable`] of [`Pod`] records together with a [`StringTable`] of
+/// the strings the records refer to.
+///
+/// Strings are [`intern`](Self::intern)ed into the table, and the returned
+/// [`StringOffset`] is stored as a field of the records.
+///
+/// The table is serialized as the number of records and the size of the
+/// strings as `u32`s, followed by the aligned slice of records, and the
+/// serialized [`StringTable`]. It can be read zero-copy with a [`StringRecordView`].
+///
+/// # Example
+/// ```
+/// # #[cfg(feature = "writer")] {
+/// use watto::{Pod, RecordId, StringOffset, StringRecordTable, StringRecordView, Writer};
+///
+/// #[derive(Clone, Copy, PartialEq, Eq, Hash)]
+/// #[repr(C)]
+/// struct Function {
+///     addr: u32,
+///     name: StringOffset,
+/// }
+/// unsafe impl Pod for Function {}
+///
+/// let mut table = StringRecordTable::new();
+/// let name = table.intern("main");
+/// let id = table.push(Function { addr: 0x1000, name });
+///
+/// let mut writer = Writer::new(vec![]);
+/// table.write_to(&mut writer).unwrap();
+/// let buffer = writer.into_inner();
+///
+/// let view = StringRecordView::<Function>::parse(&buffer).unwrap();
+/// let function = view.get(id).unwrap();
+/// assert_eq!(function.addr, 0x1000);
+/// assert_eq!(view.resolve(function.name).unwrap(), "main");
+/// # }
+/// ```
+#[derive(Debug, Clone)]
+pub struct StringRecordTable<T> {
+    records: RecordTable<T>,
+    strings: StringTable,
+}
+
+impl<T> Default for StringRecordTable<T> {
+    fn default() -> Self {
+        Self {
+            records: Default::default(),
+            strings: Default::default(),
+        }
+    }
+}
+
+impl<T: Pod> StringRecordTable<T> {
+    /// Initializes an empty [`StringRecordTable`] which stores every pushed record.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Initializes an empty [`StringRecordTable`] which deduplicates pushed records.
+    pub fn with_dedup() -> Self {
+        Self {
+            records: RecordTable::with_dedup(),
+            strings: Default::default(),
+        }
+    }
+
+    /// Interns a string, returning the [`StringOffset`] to store in a record.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the string table grows beyond 4 GiB.
+    pub fn intern(&mut self, s: &str) -> StringOffset {
+        let offset = self.strings.insert(s);
+        StringOffset(offset.try_into().expect("string table exceeds 4 GiB"))
+    }
+
+    /// Returns the underlying [`RecordTable`].
+    pub fn records(&self) -> &RecordTable<T> {
+        &self.records
+    }
+
+    /// Returns the underlying [`StringTable`].
+    pub fn strings(&self) -> &StringTable {
+        &self.strings
+    }
+
+    /// Writes the records and strings into the given [`Writer`](crate::Writer).
+    #[cfg(feature = "writer")]
+    pub fn write_to<W: std::io::Write>(
+        &self,
+        writer: &mut crate::Writer<W>,
+    ) -> std::io::Result<()> {
+        use std::io::{Error, ErrorKind, Write};
+
+        let too_large = || Error::new(ErrorKind::InvalidInput, "table is too large");
+        let len = u32::try_from(self.records.len()).map_err(|_| too_large())?;
+        let strings = self.strings.as_bytes();
+        let strings_len = u32::try_from(strings.len()).map_err(|_| too_large())?;
+
+        writer.align_to_type::<u32>()?;
+        writer.write_all(len.as_bytes())?;
+        writer.write_all(strings_len.as_bytes())?;
+        self.records.write_to(writer)?;
+        writer.write_all(strings)
+    }
+}
+
+impl<T: Pod + PartialEq + Hash> StringRecordTable<T> {
+    /// Pushes a record, returning its [`RecordId`].
+    pub fn push(&mut self, record: T) -> RecordId {
+        self.records.push(record)
+    }
+}
+
+/// A zero-copy view of a serialized [`StringRecordTable`].
+#[derive(Debug, Clone, Copy)]
+pub struct StringRecordView<'data, T> {
+    records: &'data [T],
+    strings: &'data [u8],
+}
+
+impl<'data, T: Pod> StringRecordView<'data, T> {
+    /// Parses a serialized [`StringRecordTable`] from the start of `buffer`.
+    ///
+    /// Returns [`None`] if the buffer is too small or not properly aligned.
+    pub fn parse(buffer: &'data [u8]) -> Option<Self> {
+        Self::read(&mut Reader::new(buffer))
+    }
+
+    /// Reads a serialized [`StringRecordTable`] from the given [`Reader`].
+    pub fn read(reader: &mut Reader<'data>) -> Option<Self> {
+        reader.align_to_type::<u32>()?;
+        let len = *reader.read_ref::<u32>()? as usize;
+        let strings_len = *reader.read_ref::<u32>()? as usize;
+        reader.align_to_type::<T>()?;
+        let records = reader.read_slice::<T>(len)?;
+        let strings = reader.read_bytes(strings_len)?;
+        Some(Self { records, strings })
+    }
+
+    /// Returns all the records.
+    pub fn records(&self) -> &'data [T] {
+        self.records
+    }
+
+    /// Returns the serialized [`StringTable`].
+    pub fn strings(&self) -> &'data [u8] {
+        self.strings
+    }
+
+    /// Returns the record with the given [`RecordId`], if any.
+    pub fn get(&self, id: RecordId) -> Option<&'data T> {
+        self.records.get(id.0 as usize)
+    }
+
+    /// Reads the string at the given [`StringOffset`].
+    pub fn resolve(&self, offset: StringOffset) -> Result<&'data str, ReadStringError> {
+        offset.resolve(self.strings)
+    }
+}
+
+#[cfg(feature = "writer")]
+impl<T: Pod> crate::WriteTo for StringRecordTable<T> {
+    fn write_to<W: std::io::Write>(&self, writer: &mut crate::Writer<W>) -> std::io::Result<()> {
+        self.write_to(writer)
+    }
+}
+
+impl<'data, T: Pod> crate::ReadFrom<'data> for StringRecordView<'data, T> {
+    fn read_from(reader: &mut Reader<'data>) -> Option<Self> {
+        Self::read(reader)
+    }
+}
@@ -1,22 +1,42 @@
 use core::fmt;
+use core::hash::BuildHasher;
 use core::str::Utf8Error;
+use std::borrow::Cow;
+use std::cell::Cell;
+use std::collections::HashSet;
+use std::io;
+use std::path::Path;
+use std::sync::Arc;
 
+use hashbrown::DefaultHashBuilder;
 use thiserror::Error;
 
-use crate::{OffsetSet, ReadOffsetSetError};
+use crate::varint::VarintError;
+use crate::{
+    ArcStr, FileStorage, InsertError, LazyValidated, Limits, OffsetSet, ReadOffsetSetError,
+    SharedStorage, Storage, TableObserver, TableStats, TaggedOffset,
+};
+
+std::thread_local! {
+    /// The buffer strings are formatted into by [`StringTable::insert_fmt`].
+    static FMT_SCRATCH: Cell<String> = const { Cell::new(String::new()) };
+}
 
 /// An error when trying to read a string from a serialized [`StringTable`].
 #[derive(Debug, Error)]
 pub enum ReadStringError {
     /// The string's length prefix is not valid LEB128.
     #[error("error reading LEB128 encoded number")]
-    Leb128(#[from] leb128::read::Error),
+    Leb128(#[from] VarintError),
     /// The string data is not valid UTF-8.
     #[error("error reading UTF-8 string data")]
     Utf8(#[from] Utf8Error),
     /// The string's offset or length is outside the bounds of the data blob.
     #[error("string offset or length is out of bounds")]
     OutOfBounds,
+    /// The serialized table has strings beyond the maximum offset of 4 GiB.
+    #[error("table exceeds the maximum offset of 4 GiB")]
+    TooLarge,
 }
 
 impl From<ReadOffsetSetError> for ReadStringError {
@@ -24,10 +44,224 @@ impl From<ReadOffsetSetError> for ReadStringError {
         match value {
             ReadOffsetSetError::Leb128(error) => Self::Leb128(error),
             ReadOffsetSetError::OutOfBounds => Self::OutOfBounds,
+            ReadOffsetSetError::TooLarge => Self::TooLarge,
+        }
+    }
+}
+
+/// The difference between two serialized [`StringTable`]s.
+///
+/// Returned from [`StringTable::diff`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct StringTableDiff<'data> {
+    /// Strings that only exist in the new table, in their serialized order.
+    pub added: Vec<&'data str>,
+    /// Strings that only exist in the old table, in their serialized order.
+    pub removed: Vec<&'data str>,
+    /// The size of the old table in bytes.
+    pub old_size: usize,
+    /// The size of the new table in bytes.
+    pub new_size: usize,
+}
+
+impl StringTableDiff<'_> {
+    /// Returns `true` if both tables contain the same strings.
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty()
+    }
+
+    /// Returns the change in size from the old to the new table in bytes.
+    pub fn size_delta(&self) -> isize {
+        self.new_size as isize - self.old_size as isize
+    }
+
+    /// Returns the total length of all added strings in bytes.
+    pub fn added_bytes(&self) -> usize {
+        self.added.iter().map(|s| s.len()).sum()
+    }
+
+    /// Returns the total length of all removed strings in bytes.
+    pub fn removed_bytes(&self) -> usize {
+        self.removed.iter().map(|s| s.len()).sum()
+    }
+}
+
+/// A problem found when validating a serialized [`StringTable`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ValidationProblem {
+    /// The length prefix of the entry at `offset` is not valid LEB128.
+    ///
+    /// As entry boundaries can not be determined past this point, validation stops here.
+    InvalidLengthPrefix {
+        /// The offset of the entry.
+        offset: usize,
+    },
+    /// The entry at `offset` extends past the end of the buffer.
+    TruncatedEntry {
+        /// The offset of the entry.
+        offset: usize,
+        /// The number of bytes remaining in the buffer starting at `offset`.
+        remaining: usize,
+    },
+    /// The entry at `offset` is not valid UTF-8.
+    InvalidUtf8 {
+        /// The offset of the entry.
+        offset: usize,
+        /// The underlying UTF-8 error, relative to the start of the string data.
+        error: Utf8Error,
+        /// The offending bytes.
+        bytes: Vec<u8>,
+    },
+}
+
+impl fmt::Display for ValidationProblem {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidLengthPrefix { offset } => {
+                write!(f, "invalid LEB128 length prefix at offset {offset}")
+            }
+            Self::TruncatedEntry { offset, remaining } => write!(
+                f,
+                "truncated entry at offset {offset} ({remaining} bytes remaining)"
+            ),
+            Self::InvalidUtf8 {
+                offset,
+                error,
+                bytes,
+            } => write!(
+                f,
+                "invalid UTF-8 at offset {offset}: {error} ({bytes:02x?})"
+            ),
         }
     }
 }
 
+/// A report listing all the problems found in a serialized [`StringTable`].
+///
+/// Returned from [`StringTable::validate_report`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ValidationReport {
+    /// The number of entries that could be read, including ones with invalid UTF-8.
+    pub entries: usize,
+    /// All the problems that were found, in buffer order.
+    pub problems: Vec<ValidationProblem>,
+}
+
+impl ValidationReport {
+    /// Returns `true` if no problems were found.
+    pub fn is_valid(&self) -> bool {
+        self.problems.is_empty()
+    }
+}
+
+/// An annotated dump of a serialized [`StringTable`].
+///
+/// Returned from [`StringTable::dump`]. The [`Display`](fmt::Display)
+/// implementation prints one line per entry, containing its offset, the raw bytes
+/// of its length prefix, its length, and the escaped string contents.
+/// Entries which are not valid UTF-8 are printed with escaped bytes instead.
+#[derive(Debug, Clone, Copy)]
+pub struct StringTableDump<'data> {
+    buffer: &'data [u8],
+}
+
+impl fmt::Display for StringTableDump<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for entry in OffsetSet::<u8>::iter_buffer(self.buffer) {
+            let (offset, bytes) = match entry {
+                Ok(entry) => entry,
+                Err((offset, err)) => {
+                    return writeln!(f, "{offset:#010x}  <{err}>");
+                }
+            };
+
+            let start = bytes.as_ptr() as usize - self.buffer.as_ptr() as usize;
+            write!(f, "{offset:#010x} ")?;
+            for byte in &self.buffer[offset..start] {
+                write!(f, " {byte:02x}")?;
+            }
+            let padding = 3 * (10 - (start - offset)).min(10);
+            write!(f, "{:padding$}  len {:<6} ", "", bytes.len())?;
+
+            match std::str::from_utf8(bytes) {
+                Ok(s) => writeln!(f, "\"{}\"", s.escape_debug())?,
+                Err(_) => writeln!(f, "b\"{}\" (invalid UTF-8)", bytes.escape_ascii())?,
+            }
+        }
+        Ok(())
+    }
+}
+
+/// A serialized [`StringTable`] which has been validated up front.
+///
+/// Returned from [`StringTable::validate`]. Reading from this does not need to
+/// re-validate the UTF-8 string data, but only verifies that the offset points
+/// to the start of an entry.
+#[derive(Debug, Clone)]
+pub struct ValidatedStringTable<'data> {
+    buffer: &'data [u8],
+    offsets: Vec<usize>,
+}
+
+impl<'data> ValidatedStringTable<'data> {
+    /// Returns the underlying serialized buffer.
+    pub fn as_bytes(&self) -> &'data [u8] {
+        self.buffer
+    }
+
+    /// Returns the string stored at the given offset.
+    ///
+    /// Returns an error if the offset does not point to the start of an entry.
+    pub fn read(&self, offset: usize) -> Result<&'data str, ReadStringError> {
+        if self.offsets.binary_search(&offset).is_err() {
+            return Err(ReadStringError::OutOfBounds);
+        }
+        // SAFETY:
+        // All the entries have been validated as UTF-8 in `StringTable::validate`.
+        unsafe { StringTable::read_unchecked(self.buffer, offset) }
+    }
+}
+
+/// The validation function of a [`LazyValidatedStringTable`].
+type ValidateFn = fn(&[u8]) -> Result<(), ReadStringError>;
+
+/// A serialized [`StringTable`] whose strings are validated on first access.
+///
+/// Returned from [`StringTable::validate_lazy`]. This only scans the entry
+/// boundaries up front, and validates each string as UTF-8 the first time it
+/// is read. See [`LazyValidated`] for details.
+#[derive(Debug)]
+pub struct LazyValidatedStringTable<'data> {
+    inner: LazyValidated<'data, u8, ValidateFn>,
+}
+
+impl<'data> LazyValidatedStringTable<'data> {
+    /// Returns the underlying serialized buffer.
+    pub fn as_bytes(&self) -> &'data [u8] {
+        self.inner.as_bytes()
+    }
+
+    /// Returns the number of strings.
+    pub fn len(&self) -> usize {
+        self.inner.len()
+    }
+
+    /// Returns `true` if there are no strings.
+    pub fn is_empty(&self) -> bool {
+        self.inner.is_empty()
+    }
+
+    /// Returns the string stored at the given offset, validating it on first access.
+    ///
+    /// Returns an error if the offset does not point to the start of an entry,
+    /// or if the string is not valid UTF-8.
+    pub fn read(&self, offset: usize) -> Result<&'data str, ReadStringError> {
+        let bytes = self.inner.read(offset)?;
+        // SAFETY: `read` has validated the string as UTF-8.
+        Ok(unsafe { std::str::from_utf8_unchecked(bytes) })
+    }
+}
+
 /// A struct for storing strings without duplicates.
 ///
 /// Add strings to the table with [`insert`](StringTable::insert). The
@@ -39,6 +273,8 @@ impl From<ReadOffsetSetError> for ReadStringError {
 /// with each individual string prefixed with its length in [LEB128 encoding](https://en.wikipedia.org/wiki/LEB128).
 /// The byte representation contains each string only once.
 ///
+/// Two `StringTable`s compare equal if they contain the same strings, regardless of their offsets.
+///
 /// # Example
 /// ```
 /// use watto::StringTable;
@@ -52,11 +288,11 @@ impl From<ReadOffsetSetError> for ReadStringError {
 /// assert_eq!(StringTable::read(string_bytes, bar_offset).unwrap(), "bar");
 /// ```
 #[derive(Clone, Default)]
-pub struct StringTable {
-    inner: OffsetSet<u8>,
+pub struct StringTable<S = Vec<u8>, H = DefaultHashBuilder> {
+    inner: OffsetSet<u8, S, H>,
 }
 
-impl fmt::Debug for StringTable {
+impl<S: Storage, H> fmt::Debug for StringTable<S, H> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         let iter = self
             .inner
@@ -66,26 +302,440 @@ impl fmt::Debug for StringTable {
     }
 }
 
+impl<S: Storage, H: BuildHasher> PartialEq for StringTable<S, H> {
+    fn eq(&self, other: &Self) -> bool {
+        self.inner == other.inner
+    }
+}
+
+impl<S: Storage, H: BuildHasher> Eq for StringTable<S, H> {}
+
+/// A [`StringTable`] is equal to a serialized buffer if the buffer is valid and
+/// contains the same strings, regardless of their offsets.
+impl<S: Storage, H: BuildHasher> PartialEq<[u8]> for StringTable<S, H> {
+    fn eq(&self, other: &[u8]) -> bool {
+        self.inner == *other
+    }
+}
+
 impl StringTable {
     /// Initializes an empty `StringTable`.
     pub fn new() -> Self {
         Self::default()
     }
 
+    /// Initializes an empty `StringTable` which enforces the given [`Limits`]
+    /// when inserting with [`try_insert`](Self::try_insert).
+    ///
+    /// The maximum entry length is given in bytes.
+    pub fn with_limits(limits: Limits) -> Self {
+        Self {
+            inner: OffsetSet::with_limits(limits),
+        }
+    }
+
     /// Initializes a [`StringTable`] from a previously serialized representation.
     ///
     /// This essentially reverses the [`as_bytes`](Self::as_bytes) call.
     pub fn from_bytes(buffer: &[u8]) -> Result<Self, ReadStringError> {
-        let inner =
-            OffsetSet::from_bytes_validated(buffer, |string_bytes| {
-                match std::str::from_utf8(string_bytes) {
-                    Ok(_) => Ok(()),
-                    Err(err) => Err(ReadStringError::Utf8(err)),
+        let inner = OffsetSet::from_bytes_validated(buffer, validate_utf8)?;
+        Ok(Self { inner })
+    }
+
+    /// Initializes a [`StringTable`] from a previously serialized representation,
+    /// which is expected to contain `entries` strings.
+    ///
+    /// See [`OffsetSet::from_bytes_with_capacity`] for details.
+    pub fn from_bytes_with_capacity(
+        buffer: &[u8],
+        entries: usize,
+    ) -> Result<Self, ReadStringError> {
+        let inner = OffsetSet::from_storage_validated(
+            buffer.into(),
+            Default::default(),
+            entries,
+            validate_utf8,
+        )?;
+        Ok(Self { inner })
+    }
+
+    /// Initializes a [`StringTable`] from a shared buffer holding a previously
+    /// serialized representation.
+    ///
+    /// See [`OffsetSet::from_shared_bytes`] for when this avoids copying the buffer.
+    #[cfg(feature = "bytes")]
+    pub fn from_shared_bytes(buffer: bytes::Bytes) -> Result<Self, ReadStringError> {
+        let inner = OffsetSet::from_storage_validated(
+            Vec::from(buffer),
+            Default::default(),
+            0,
+            validate_utf8,
+        )?;
+        Ok(Self { inner })
+    }
+
+    /// Returns the string stored at the given offset in the byte slice, if any.
+    ///
+    /// Use this to retrieve a string that was previously [inserted](StringTable::insert) into a `StringTable`.
+    pub fn read(buffer: &[u8], offset: usize) -> Result<&str, ReadStringError> {
+        let bytes = OffsetSet::read(buffer, offset)?;
+        Ok(std::str::from_utf8(bytes)?)
+    }
+
+    /// Returns the string stored at the given offset in the byte slice, without
+    /// validating that it is UTF-8.
+    ///
+    /// This still validates the length prefix and bounds of the string.
+    /// Prefer [`validate`](Self::validate) to check the whole buffer up front
+    /// and read from it safely without per-read UTF-8 validation.
+    ///
+    /// # Safety
+    ///
+    /// The string data at `offset` must be valid UTF-8. This is the case for
+    /// offsets returned from [`insert`](Self::insert), as well as the entries
+    /// of a buffer that was successfully loaded with [`from_bytes`](Self::from_bytes).
+    pub unsafe fn read_unchecked(buffer: &[u8], offset: usize) -> Result<&str, ReadStringError> {
+        let bytes = OffsetSet::read(buffer, offset)?;
+        // SAFETY: upheld by the caller.
+        Ok(unsafe { std::str::from_utf8_unchecked(bytes) })
+    }
+
+    /// Validates all the strings of a serialized [`StringTable`] once, allowing
+    /// subsequent reads to skip UTF-8 validation.
+    pub fn validate(buffer: &[u8]) -> Result<ValidatedStringTable<'_>, ReadStringError> {
+        let offsets = OffsetSet::<u8>::iter_buffer(buffer)
+            .map(|entry| {
+                let (offset, bytes) = entry.map_err(|(_offset, err)| err)?;
+                std::str::from_utf8(bytes)?;
+                Ok(offset)
+            })
+            .collect::<Result<_, ReadStringError>>()?;
+        Ok(ValidatedStringTable { buffer, offsets })
+    }
+
+    /// Scans the entry boundaries of a serialized [`StringTable`], deferring the
+    /// UTF-8 validation of each string to its first access.
+    ///
+    /// This loads huge tables much faster than [`validate`](Self::validate),
+    /// at a small cost on the read path.
+    pub fn validate_lazy(buffer: &[u8]) -> Result<LazyValidatedStringTable<'_>, ReadStringError> {
+        let validate: ValidateFn = validate_utf8;
+        let inner = OffsetSet::<u8>::validate_lazy(buffer, validate)?;
+        Ok(LazyValidatedStringTable { inner })
+    }
+
+    /// Returns the string stored at the given offset in the byte slice, replacing
+    /// invalid UTF-8 sequences with `U+FFFD REPLACEMENT CHARACTER`.
+    ///
+    /// This still fails if the string's length prefix or bounds are invalid.
+    pub fn read_lossy(buffer: &[u8], offset: usize) -> Result<Cow<'_, str>, ReadStringError> {
+        let bytes = OffsetSet::read(buffer, offset)?;
+        Ok(String::from_utf8_lossy(bytes))
+    }
+
+    /// Returns an owned copy of the string stored at the given offset in the byte slice.
+    pub fn read_owned(buffer: &[u8], offset: usize) -> Result<String, ReadStringError> {
+        Self::read(buffer, offset).map(String::from)
+    }
+
+    /// Appends the string stored at the given offset in the byte slice to `out`.
+    ///
+    /// This allows reusing the allocation of `out` across multiple reads.
+    /// On error, `out` is left unchanged.
+    pub fn read_into(
+        buffer: &[u8],
+        offset: usize,
+        out: &mut String,
+    ) -> Result<(), ReadStringError> {
+        out.push_str(Self::read(buffer, offset)?);
+        Ok(())
+    }
+
+    /// Returns the strings stored at the given offsets in the byte slice,
+    /// joined with `separator`.
+    ///
+    /// This is useful to reconstruct paths or qualified names whose components
+    /// are interned separately.
+    ///
+    /// # Example
+    /// ```
+    /// use watto::StringTable;
+    ///
+    /// let mut table = StringTable::new();
+    /// let usr = table.insert("usr");
+    /// let lib = table.insert("lib");
+    /// let buffer = table.as_bytes();
+    ///
+    /// let path = StringTable::concat_read(buffer, &[usr, lib, lib], "/").unwrap();
+    /// assert_eq!(path, "usr/lib/lib");
+    /// ```
+    pub fn concat_read(
+        buffer: &[u8],
+        offsets: &[usize],
+        separator: &str,
+    ) -> Result<String, ReadStringError> {
+        let mut out = String::new();
+        Self::concat_read_into(buffer, offsets, separator, &mut out)?;
+        Ok(out)
+    }
+
+    /// Appends the strings stored at the given offsets in the byte slice to
+    /// `out`, joined with `separator`.
+    ///
+    /// This allows reusing the allocation of `out` across multiple reads.
+    /// On error, `out` is left unchanged.
+    pub fn concat_read_into(
+        buffer: &[u8],
+        offsets: &[usize],
+        separator: &str,
+        out: &mut String,
+    ) -> Result<(), ReadStringError> {
+        let len = out.len();
+        for (i, offset) in offsets.iter().enumerate() {
+            if i > 0 {
+                out.push_str(separator);
+            }
+            if let Err(err) = Self::read_into(buffer, *offset, out) {
+                out.truncate(len);
+                return Err(err);
+            }
+        }
+        Ok(())
+    }
+
+    /// Returns an annotated dump of a serialized [`StringTable`], suitable for debugging.
+    ///
+    /// # Example
+    /// ```
+    /// use watto::StringTable;
+    ///
+    /// let mut table = StringTable::new();
+    /// table.insert("foo");
+    /// table.insert("bar\n");
+    ///
+    /// let dump = StringTable::dump(table.as_bytes()).to_string();
+    /// assert!(dump.contains("0x00000004  04"));
+    /// assert!(dump.contains(r#""bar\n""#));
+    /// ```
+    pub fn dump(buffer: &[u8]) -> StringTableDump<'_> {
+        StringTableDump { buffer }
+    }
+
+    /// Compares the strings contained in two serialized [`StringTable`]s.
+    ///
+    /// Offsets are not compared, so two tables containing the same strings in
+    /// a different order do not show any difference.
+    pub fn diff<'data>(
+        old: &'data [u8],
+        new: &'data [u8],
+    ) -> Result<StringTableDiff<'data>, ReadStringError> {
+        let old_strings = Self::collect_strings(old)?;
+        let new_strings = Self::collect_strings(new)?;
+
+        let old_set: HashSet<_> = old_strings.iter().copied().collect();
+        let new_set: HashSet<_> = new_strings.iter().copied().collect();
+
+        Ok(StringTableDiff {
+            added: new_strings
+                .into_iter()
+                .filter(|s| !old_set.contains(s))
+                .collect(),
+            removed: old_strings
+                .into_iter()
+                .filter(|s| !new_set.contains(s))
+                .collect(),
+            old_size: old.len(),
+            new_size: new.len(),
+        })
+    }
+
+    /// Validates a serialized [`StringTable`], collecting all the problems found.
+    ///
+    /// Unlike [`from_bytes`](Self::from_bytes), this does not stop at the first
+    /// invalid UTF-8 string. It does however stop at the first entry whose
+    /// boundaries can not be determined.
+    pub fn validate_report(buffer: &[u8]) -> ValidationReport {
+        let mut report = ValidationReport::default();
+        for entry in OffsetSet::<u8>::iter_buffer(buffer) {
+            let problem = match entry {
+                Ok((offset, bytes)) => {
+                    report.entries += 1;
+                    let Err(error) = std::str::from_utf8(bytes) else {
+                        continue;
+                    };
+                    let start = error.valid_up_to();
+                    let end = error.error_len().map_or(bytes.len(), |len| start + len);
+                    ValidationProblem::InvalidUtf8 {
+                        offset,
+                        error,
+                        bytes: bytes[start..end].to_vec(),
+                    }
+                }
+                Err((offset, ReadOffsetSetError::OutOfBounds)) => {
+                    ValidationProblem::TruncatedEntry {
+                        offset,
+                        remaining: buffer.len() - offset,
+                    }
+                }
+                Err((offset, ReadOffsetSetError::Leb128(VarintError::Truncated))) => {
+                    ValidationProblem::TruncatedEntry {
+                        offset,
+                        remaining: buffer.len() - offset,
+                    }
                 }
-            })?;
+                Err((offset, ReadOffsetSetError::Leb128(_))) => {
+                    ValidationProblem::InvalidLengthPrefix { offset }
+                }
+                Err((_offset, ReadOffsetSetError::TooLarge)) => {
+                    unreachable!("reading entries does not limit their offsets")
+                }
+            };
+            report.problems.push(problem);
+        }
+        report
+    }
+
+    fn collect_strings(buffer: &[u8]) -> Result<Vec<&str>, ReadStringError> {
+        OffsetSet::<u8>::iter_buffer(buffer)
+            .map(|entry| {
+                let (_offset, bytes) = entry.map_err(|(_offset, err)| err)?;
+                Ok(std::str::from_utf8(bytes)?)
+            })
+            .collect()
+    }
+
+    /// Returns an owned [`ArcStr`] handle to the string stored at the given offset
+    /// in the shared buffer.
+    ///
+    /// The returned handle keeps the buffer alive and dereferences to `&str`
+    /// without copying the string data.
+    pub fn read_arc(buffer: &Arc<[u8]>, offset: usize) -> Result<ArcStr, ReadStringError> {
+        ArcStr::new(buffer, offset)
+    }
+}
+
+impl<H> StringTable<Vec<u8>, H> {
+    /// Initializes an empty `StringTable` which hashes its strings with the given [`BuildHasher`].
+    ///
+    /// See [`OffsetSet::with_hasher`] for details.
+    pub fn with_hasher(hasher: H) -> Self {
+        Self {
+            inner: OffsetSet::with_hasher(hasher),
+        }
+    }
+
+    /// Returns a byte vector containing the concatenation of the strings that have been
+    /// added to this `StringTable`.
+    ///
+    /// This consumes the `StringTable`.
+    pub fn into_bytes(self) -> Vec<u8> {
+        self.inner.into_bytes()
+    }
+
+    /// Returns the serialized representation of this `StringTable` as a shared buffer.
+    ///
+    /// This consumes the `StringTable` and hands over its buffer without copying it.
+    #[cfg(feature = "bytes")]
+    pub fn into_shared_bytes(self) -> bytes::Bytes {
+        self.inner.into_shared_bytes()
+    }
+
+    /// Moves the buffer of this `StringTable` into a [`SharedStorage`].
+    ///
+    /// See [`OffsetSet::into_shared`] for details.
+    pub fn into_shared(self) -> StringTable<SharedStorage, H> {
+        StringTable {
+            inner: self.inner.into_shared(),
+        }
+    }
+}
+
+impl StringTable<FileStorage> {
+    /// Opens a `StringTable` which persists every inserted string in the file
+    /// at `path`, creating the file if it does not exist.
+    ///
+    /// See [`OffsetSet::open`] for details.
+    pub fn open<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        let storage = OffsetSet::<u8>::recover(FileStorage::open(path)?)?;
+        Self::from_storage(storage).map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))
+    }
+}
+
+impl<H> StringTable<FileStorage, H> {
+    /// Flushes all inserted strings to disk.
+    ///
+    /// See [`FileStorage::sync`] for details.
+    pub fn sync(&self) -> io::Result<()> {
+        self.inner.sync()
+    }
+}
+
+impl<S: Storage> StringTable<S> {
+    /// Initializes a `StringTable` which keeps its buffer in the given [`Storage`].
+    ///
+    /// Any strings already contained in the storage are validated and loaded,
+    /// and new strings are appended to them.
+    pub fn from_storage(storage: S) -> Result<Self, ReadStringError> {
+        Self::from_storage_with_hasher(storage, Default::default())
+    }
+}
+
+impl<S: Storage, H: BuildHasher> StringTable<S, H> {
+    /// Initializes a `StringTable` which keeps its buffer in the given [`Storage`],
+    /// and hashes its strings with the given [`BuildHasher`].
+    ///
+    /// See [`from_storage`](StringTable::from_storage) for details.
+    pub fn from_storage_with_hasher(storage: S, hasher: H) -> Result<Self, ReadStringError> {
+        let inner = OffsetSet::from_storage_validated(storage, hasher, 0, validate_utf8)?;
         Ok(Self { inner })
     }
 
+    /// Returns the [`BuildHasher`] used to hash the strings of this `StringTable`.
+    pub fn hasher(&self) -> &H {
+        self.inner.hasher()
+    }
+
+    /// Returns a parallel iterator over all the strings in this `StringTable`,
+    /// in the order in which they were inserted.
+    ///
+    /// This yields `(offset, string)` pairs.
+    /// See [`OffsetSet::par_entries`] for details.
+    #[cfg(feature = "rayon")]
+    pub fn par_iter(&self) -> impl rayon::iter::IndexedParallelIterator<Item = (usize, &str)> + '_
+    where
+        S: Sync,
+    {
+        use rayon::prelude::*;
+
+        self.inner.par_entries().map(|(offset, bytes)| {
+            // Strings are validated when they are inserted or loaded.
+            (offset, std::str::from_utf8(bytes).unwrap())
+        })
+    }
+
+    /// Sets the [`Limits`] enforced by [`try_insert`](Self::try_insert).
+    ///
+    /// The maximum entry length is given in bytes.
+    /// See [`OffsetSet::set_limits`] for details.
+    pub fn set_limits(&mut self, limits: Limits) {
+        self.inner.set_limits(limits);
+    }
+
+    /// Aligns the start of every string inserted from now on to `align` bytes.
+    ///
+    /// See [`OffsetSet::set_entry_alignment`] for details.
+    pub fn set_entry_alignment(&mut self, align: usize) {
+        self.inner.set_entry_alignment(align);
+    }
+
+    /// Registers a [`TableObserver`] which is notified about insertions,
+    /// deduplication hits, buffer growth and serialization of this `StringTable`.
+    ///
+    /// See [`OffsetSet::set_observer`] for details.
+    pub fn set_observer(&mut self, observer: Arc<dyn TableObserver>) {
+        self.inner.set_observer(observer);
+    }
+
     /// Insert a string into this `StringTable`.
     ///
     /// Returns an offset that can be used to retrieve the inserted string
@@ -94,25 +744,159 @@ impl StringTable {
         self.inner.insert(s.as_bytes())
     }
 
+    /// Insert a string into this `StringTable`, unless it is short enough to
+    /// be stored inline in the returned [`TaggedOffset`].
+    ///
+    /// The string can be retrieved with [`TaggedOffset::resolve`].
+    pub fn insert_tagged(&mut self, s: &str) -> TaggedOffset {
+        TaggedOffset::inline(s).unwrap_or_else(|| TaggedOffset::from_offset(self.insert(s)))
+    }
+
+    /// Insert all `strings` into this `StringTable`, returning their offsets.
+    ///
+    /// See [`OffsetSet::insert_all`] for details.
+    pub fn insert_all(&mut self, strings: &[&str]) -> Vec<usize> {
+        let slices: Vec<&[u8]> = strings.iter().map(|s| s.as_bytes()).collect();
+        self.inner.insert_all(&slices)
+    }
+
+    /// Insert a formatted string into this `StringTable`.
+    ///
+    /// The string is formatted into a thread-local scratch buffer which is
+    /// reused across calls, instead of allocating a new `String` per call.
+    ///
+    /// # Example
+    /// ```
+    /// use watto::StringTable;
+    ///
+    /// let mut table = StringTable::new();
+    /// let offset = table.insert_fmt(format_args!("{}::{}", "foo", 42));
+    /// assert_eq!(table.get("foo::42"), Some(offset));
+    /// ```
+    pub fn insert_fmt(&mut self, args: fmt::Arguments<'_>) -> usize {
+        if let Some(s) = args.as_str() {
+            return self.insert(s);
+        }
+
+        // The buffer is taken out of the thread-local, so that nested calls
+        // from within a `Display` implementation simply use a fresh buffer.
+        let mut scratch = FMT_SCRATCH.take();
+        scratch.clear();
+        fmt::Write::write_fmt(&mut scratch, args)
+            .expect("a formatting trait implementation returned an error");
+        let offset = self.insert(&scratch);
+        FMT_SCRATCH.set(scratch);
+        offset
+    }
+
+    /// Insert a string into this `StringTable`, using a precomputed hash.
+    ///
+    /// The `hash` has to be the hash of the bytes of the string, as in
+    /// `table.hasher().hash_one(s.as_bytes())`.
+    /// See [`OffsetSet::insert_hashed`] for details.
+    pub fn insert_hashed(&mut self, hash: u64, s: &str) -> usize {
+        self.inner.insert_hashed(hash, s.as_bytes())
+    }
+
+    /// Caps the deduplication index at `max_entries` strings, evicting the
+    /// least recently used ones once it is full.
+    ///
+    /// See [`OffsetSet::set_max_index_entries`] for details.
+    pub fn set_max_index_entries(&mut self, max_entries: usize) {
+        self.inner.set_max_index_entries(max_entries);
+    }
+
+    /// Returns the offset of the given string if it is contained in this `StringTable`.
+    pub fn get(&self, s: &str) -> Option<usize> {
+        self.inner.get(s.as_bytes())
+    }
+
+    /// Returns the offset of the given string if it is contained in this `StringTable`,
+    /// using a precomputed hash.
+    ///
+    /// See [`insert_hashed`](Self::insert_hashed) for the requirements on `hash`.
+    pub fn get_hashed(&self, hash: u64, s: &str) -> Option<usize> {
+        self.inner.get_hashed(hash, s.as_bytes())
+    }
+
+    /// Insert a string into this `StringTable` without checking whether it is
+    /// already contained.
+    ///
+    /// See [`OffsetSet::insert_unique_unchecked`] for details.
+    pub fn insert_unique_unchecked(&mut self, s: &str) -> usize {
+        self.inner.insert_unique_unchecked(s.as_bytes())
+    }
+
+    /// Insert a string into this `StringTable`, which is built by `build` in a
+    /// caller-provided bump arena.
+    ///
+    /// See [`OffsetSet::insert_in`] for details.
+    ///
+    /// # Example
+    /// ```
+    /// # #[cfg(feature = "bumpalo")] {
+    /// use bumpalo::Bump;
+    /// use watto::StringTable;
+    ///
+    /// let mut bump = Bump::new();
+    /// let mut table = StringTable::new();
+    /// for name in ["Foo", "FOO", "bar"] {
+    ///     table.insert_in(&bump, |s| s.extend(name.chars().flat_map(char::to_lowercase)));
+    /// }
+    /// bump.reset();
+    ///
+    /// assert_eq!(table.stats().entries, 2);
+    /// # }
+    /// ```
+    #[cfg(feature = "bumpalo")]
+    pub fn insert_in<'bump, F>(&mut self, bump: &'bump bumpalo::Bump, build: F) -> usize
+    where
+        F: FnOnce(&mut bumpalo::collections::String<'bump>),
+    {
+        let mut scratch = bumpalo::collections::String::new_in(bump);
+        build(&mut scratch);
+        self.insert(&scratch)
+    }
+
+    /// Insert a string into this `StringTable`, enforcing the configured [`Limits`].
+    ///
+    /// See [`OffsetSet::try_insert`] for details.
+    pub fn try_insert(&mut self, s: &str) -> Result<usize, InsertError> {
+        self.inner.try_insert(s.as_bytes())
+    }
+
+    /// Reserves room for `entry_count` more strings, holding `total_payload_bytes`
+    /// bytes in total.
+    ///
+    /// See [`OffsetSet::reserve_exact_bytes`] for details.
+    pub fn reserve_exact_bytes(&mut self, total_payload_bytes: usize, entry_count: usize) {
+        self.inner
+            .reserve_exact_bytes(total_payload_bytes, entry_count);
+    }
+
+    /// Returns statistics about the contents and memory usage of this `StringTable`.
+    ///
+    /// Entry lengths are reported in bytes.
+    pub fn stats(&self) -> TableStats {
+        self.inner.stats()
+    }
+
     /// Returns a byte slice containing the concatenation of the strings that have been
     /// added to this `StringTable`.
     pub fn as_bytes(&self) -> &[u8] {
         self.inner.as_bytes()
     }
 
-    /// Returns a byte vector containing the concatenation of the strings that have been
-    /// added to this `StringTable`.
+    /// Returns the [`Storage`] containing the concatenation of the strings that
+    /// have been added to this `StringTable`.
     ///
     /// This consumes the `StringTable`.
-    pub fn into_bytes(self) -> Vec<u8> {
-        self.inner.into_bytes()
+    pub fn into_storage(self) -> S {
+        self.inner.into_storage()
     }
+}
 
-    /// Returns the string stored at the given offset in the byte slice, if any.
-    ///
-    /// Use this to retrieve a string that was previously [inserted](StringTable::insert) into a `StringTable`.
-    pub fn read(buffer: &[u8], offset: usize) -> Result<&str, ReadStringError> {
-        let bytes = OffsetSet::read(buffer, offset)?;
-        Ok(std::str::from_utf8(bytes)?)
-    }
+fn validate_utf8(string_bytes: &[u8]) -> Result<(), ReadStringError> {
+    std::str::from_utf8(string_bytes)?;
+    Ok(())
 }
@@ -0,0 +1,90 @@
+//! Substring search over serialized [`StringTable`]s.
+//!
+//! A substring index is a sorted suffix array of all the strings in a table,
+//! stored as a separate section next to it. It is searched directly from the
+//! serialized bytes, so strings can be found by any part of them without
+//! loading the table into a separate search structure.
+//!
+//! The index consists of `[u32; 2]` pairs, the offset of a string and the
+//! position of one of its suffixes within the table, and has to be aligned to
+//! 4 bytes. It holds one pair per character of the table, so it is about eight
+//! times as large as the string contents.
+//!
+//! # Example
+//! ```
+//! use watto::{substring, StringTable};
+//!
+//! let mut table = StringTable::new();
+//! let foo = table.insert("foo::bar");
+//! let baz = table.insert("baz::foo");
+//! table.insert("qux");
+//!
+//! let buffer = table.as_bytes();
+//! let index = substring::build_index(buffer).unwrap();
+//!
+//! let found: Vec<_> = substring::search(buffer, &index, "foo").collect();
+//! assert_eq!(found, [foo, baz]);
+//! assert_eq!(substring::search(buffer, &index, "::b").count(), 1);
+//! ```
+
+use crate::{OffsetSet, Pod, ReadStringError, StringTable};
+
+/// Builds a substring index of the serialized [`StringTable`] in `buffer`.
+///
+/// Fails if the `buffer` is not a valid [`StringTable`].
+pub fn build_index(buffer: &[u8]) -> Result<Vec<u8>, ReadStringError> {
+    crate::trace::timed_span!("substring::build_index", size = buffer.len());
+
+    let mut suffixes: Vec<[u32; 2]> = vec![];
+    for entry in OffsetSet::<u8>::iter_buffer(buffer) {
+        let (offset, bytes) = entry.map_err(|(_, err)| err)?;
+        let string = std::str::from_utf8(bytes)?;
+        let start = bytes.as_ptr() as usize - buffer.as_ptr() as usize;
+        suffixes.extend(
+            string
+                .char_indices()
+                .map(|(pos, _)| [offset as u32, (start + pos) as u32]),
+        );
+    }
+
+    suffixes.sort_by(|a, b| suffix(buffer, *a).cmp(suffix(buffer, *b)));
+    Ok(suffixes.as_bytes().to_vec())
+}
+
+/// Returns the offsets of all strings in the serialized [`StringTable`] in
+/// `buffer` that contain `needle`, in ascending order.
+///
+/// The `index` has to be built by [`build_index`] from the same `buffer`.
+/// An empty `needle` matches all non-empty strings.
+/// Nothing is found if the `index` is not properly aligned, and malformed
+/// entries are skipped.
+pub fn search<'data>(
+    buffer: &'data [u8],
+    index: &'data [u8],
+    needle: &str,
+) -> impl Iterator<Item = usize> + 'data {
+    let suffixes = <[u32; 2]>::slice_from_bytes(index).unwrap_or_default();
+
+    let start = suffixes.partition_point(|entry| suffix(buffer, *entry) < needle);
+    let len = suffixes[start..].partition_point(|entry| suffix(buffer, *entry).starts_with(needle));
+
+    let mut offsets: Vec<usize> = suffixes[start..start + len]
+        .iter()
+        .map(|[offset, _]| *offset as usize)
+        .collect();
+    offsets.sort_unstable();
+    offsets.dedup();
+    offsets.into_iter()
+}
+
+/// Returns the suffix referenced by an index entry, or an empty string if it is invalid.
+fn suffix(buffer: &[u8], [offset, pos]: [u32; 2]) -> &str {
+    let Ok(string) = StringTable::read(buffer, offset as usize) else {
+        return "";
+    };
+    let start = string.as_ptr() as usize - buffer.as_ptr() as usize;
+    (pos as usize)
+        .checked_sub(start)
+        .and_then(|pos| string.get(pos..))
+        .unwrap_or_default()
+}
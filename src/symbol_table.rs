@@ -0,0 +1,110 @@
+use hashbrown::HashMap;
+
+use crate::{Pod, ReadStringError, StringTable};
+
+/// A dense index of a string interned into a [`SymbolTable`].
+///
+/// Symbols are handed out sequentially starting at `0`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[repr(transparent)]
+pub struct Sym(pub u32);
+
+unsafe impl Pod for Sym {}
+
+/// A string interner handing out dense [`Sym`] indices instead of byte offsets.
+///
+/// The strings themselves are stored in a [`StringTable`], and the table
+/// additionally keeps an index mapping each [`Sym`] to its offset within the
+/// string table.
+///
+/// When serializing, the [`strings`](Self::strings) and [`index`](Self::index)
+/// sections need to be written separately. The index is a slice of `u32`
+/// offsets and needs to be properly aligned.
+///
+/// # Example
+/// ```
+/// use watto::{Pod, Sym, SymbolTable};
+///
+/// let mut table = SymbolTable::new();
+/// assert_eq!(table.insert("foo"), Sym(0));
+/// assert_eq!(table.insert("bar"), Sym(1));
+/// assert_eq!(table.insert("foo"), Sym(0));
+///
+/// let index = table.index().as_bytes();
+/// let strings = table.strings();
+/// assert_eq!(SymbolTable::read(index, strings, Sym(1)).unwrap(), "bar");
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct SymbolTable {
+    strings: StringTable,
+    offsets: Vec<u32>,
+    syms: HashMap<u32, Sym>,
+}
+
+impl SymbolTable {
+    /// Initializes an empty [`SymbolTable`].
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the number of unique strings in this [`SymbolTable`].
+    pub fn len(&self) -> usize {
+        self.offsets.len()
+    }
+
+    /// Returns `true` if this [`SymbolTable`] is empty.
+    pub fn is_empty(&self) -> bool {
+        self.offsets.is_empty()
+    }
+
+    /// Insert a string into this [`SymbolTable`].
+    ///
+    /// Returns the [`Sym`] of the string, which is assigned sequentially the
+    /// first time a string is inserted.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the string table grows beyond 4 GiB.
+    pub fn insert(&mut self, s: &str) -> Sym {
+        let offset = self.strings.insert(s);
+        let offset: u32 = offset.try_into().expect("string table exceeds 4 GiB");
+
+        let offsets = &mut self.offsets;
+        *self.syms.entry(offset).or_insert_with(|| {
+            let sym = Sym(offsets.len() as u32);
+            offsets.push(offset);
+            sym
+        })
+    }
+
+    /// Returns the string table offset for the given [`Sym`], if any.
+    pub fn offset(&self, sym: Sym) -> Option<usize> {
+        self.offsets
+            .get(sym.0 as usize)
+            .map(|&offset| offset as usize)
+    }
+
+    /// Returns the index mapping each [`Sym`] to its string table offset.
+    pub fn index(&self) -> &[u32] {
+        &self.offsets
+    }
+
+    /// Returns a byte slice containing the serialized string table.
+    pub fn strings(&self) -> &[u8] {
+        self.strings.as_bytes()
+    }
+
+    /// Returns the string for the given [`Sym`] from a serialized index and
+    /// string table.
+    pub fn read<'data>(
+        index: &[u8],
+        strings: &'data [u8],
+        sym: Sym,
+    ) -> Result<&'data str, ReadStringError> {
+        let index = u32::slice_from_bytes(index).ok_or(ReadStringError::OutOfBounds)?;
+        let offset = index
+            .get(sym.0 as usize)
+            .ok_or(ReadStringError::OutOfBounds)?;
+        StringTable::read(strings, *offset as usize)
+    }
+}
@@ -0,0 +1,123 @@
+use core::fmt;
+
+use crate::{Pod, ReadStringError, StringTable};
+
+/// The tag bit marking a [`TaggedOffset`] which holds its string inline.
+const INLINE_TAG: u8 = 0x80;
+
+/// An offset into a [`StringTable`], or a short string stored inline.
+///
+/// Strings of up to [`MAX_INLINE_LEN`](Self::MAX_INLINE_LEN) bytes are packed
+/// directly into the 8 bytes of the offset, and are never stored in the table.
+/// For tables dominated by tiny strings, this avoids most of the buffer growth
+/// and deduplication work.
+///
+/// The last byte holds the tag bit and the length of an inline string, and
+/// the other bytes its contents. Offsets are stored as little-endian `u64`,
+/// with the tag bit clear.
+///
+/// # Example
+/// ```
+/// use watto::{StringTable, TaggedOffset};
+///
+/// let mut table = StringTable::new();
+/// let short = table.insert_tagged("foo");
+/// let long = table.insert_tagged("foo::bar");
+/// assert!(short.is_inline());
+/// assert_eq!(long.offset(), Some(0));
+///
+/// let buffer = table.into_bytes();
+/// assert_eq!(buffer, b"\x08foo::bar");
+/// assert_eq!(short.resolve(&buffer).unwrap(), "foo");
+/// assert_eq!(long.resolve(&buffer).unwrap(), "foo::bar");
+/// ```
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+#[repr(transparent)]
+pub struct TaggedOffset([u8; 8]);
+
+unsafe impl Pod for TaggedOffset {}
+
+impl TaggedOffset {
+    /// The maximum length of a string stored inline, in bytes.
+    pub const MAX_INLINE_LEN: usize = 7;
+
+    /// Creates a [`TaggedOffset`] referring to the string at `offset` in a [`StringTable`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if `offset` does not fit into 63 bits.
+    pub fn from_offset(offset: usize) -> Self {
+        let bytes = (offset as u64).to_le_bytes();
+        if bytes[7] & INLINE_TAG != 0 {
+            panic!("TaggedOffset: offset is too large");
+        }
+        Self(bytes)
+    }
+
+    /// Creates a [`TaggedOffset`] holding `s` inline, if it is short enough.
+    pub fn inline(s: &str) -> Option<Self> {
+        if s.len() > Self::MAX_INLINE_LEN {
+            return None;
+        }
+        let mut bytes = [0; 8];
+        bytes[..s.len()].copy_from_slice(s.as_bytes());
+        bytes[7] = INLINE_TAG | s.len() as u8;
+        Some(Self(bytes))
+    }
+
+    /// Creates a [`TaggedOffset`] from its raw representation.
+    pub fn from_bits(bits: u64) -> Self {
+        Self(bits.to_le_bytes())
+    }
+
+    /// Returns the raw representation of this [`TaggedOffset`].
+    pub fn to_bits(self) -> u64 {
+        u64::from_le_bytes(self.0)
+    }
+
+    /// Returns `true` if the string is stored inline.
+    pub fn is_inline(&self) -> bool {
+        self.0[7] & INLINE_TAG != 0
+    }
+
+    /// Returns the offset of the string in its [`StringTable`], or [`None`] if
+    /// it is stored inline.
+    pub fn offset(&self) -> Option<usize> {
+        if self.is_inline() {
+            return None;
+        }
+        usize::try_from(self.to_bits()).ok()
+    }
+
+    /// Returns the bytes of the string if it is stored inline.
+    fn inline_bytes(&self) -> Option<&[u8]> {
+        let len = (self.0[7] & !INLINE_TAG) as usize;
+        (self.is_inline() && len <= Self::MAX_INLINE_LEN).then(|| &self.0[..len])
+    }
+
+    /// Reads the string, either inline or at this offset from a serialized [`StringTable`].
+    pub fn resolve<'data>(
+        &'data self,
+        strings: &'data [u8],
+    ) -> Result<&'data str, ReadStringError> {
+        match self.offset() {
+            Some(offset) => StringTable::read(strings, offset),
+            None => {
+                let bytes = self.inline_bytes().ok_or(ReadStringError::OutOfBounds)?;
+                Ok(core::str::from_utf8(bytes)?)
+            }
+        }
+    }
+}
+
+impl fmt::Debug for TaggedOffset {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.offset() {
+            Some(offset) => f.debug_tuple("Offset").field(&offset).finish(),
+            None => match self.inline_bytes().map(core::str::from_utf8) {
+                Some(Ok(s)) => f.debug_tuple("Inline").field(&s).finish(),
+                _ => f.debug_tuple("Invalid").field(&self.to_bits()).finish(),
+            },
+        }
+    }
+}
@@ -0,0 +1,153 @@
+//! Utilities for testing formats built with watto.
+//!
+//! This includes round-trip assertions for [`Pod`] types and tables, and
+//! [`Corruption`]s which systematically truncate and bit-flip serialized
+//! buffers in order to test the robustness of parsers against malformed input.
+//!
+//! # Example
+//! ```
+//! # #[cfg(feature = "strings")] {
+//! use watto::testing;
+//! use watto::{Pod, StringTable};
+//!
+//! testing::assert_roundtrip(&42u64);
+//!
+//! let mut table = StringTable::new();
+//! let offset = table.insert("watto");
+//!
+//! // Reading from a corrupted table may fail, but must never panic.
+//! testing::assert_robust(table.as_bytes(), |bytes| {
+//!     let _ = StringTable::read(bytes, offset);
+//! });
+//! # }
+//! ```
+
+use core::fmt;
+use std::panic::{self, AssertUnwindSafe};
+
+use crate::{AlignedVec, Pod};
+
+/// The alignment of the buffers produced by [`Corruption::apply`] and [`aligned_copy`].
+pub const TEST_ALIGN: usize = 16;
+
+/// Copies `bytes` into a new buffer aligned to [`TEST_ALIGN`].
+pub fn aligned_copy(bytes: &[u8]) -> AlignedVec {
+    AlignedVec::from_slice(bytes, TEST_ALIGN)
+}
+
+/// Asserts that `value` can be read back from its own bytes.
+///
+/// # Panics
+///
+/// Panics if the value read back is not equal to `value`.
+pub fn assert_roundtrip<T: Pod + PartialEq + fmt::Debug>(value: &T) {
+    let bytes = aligned_copy(value.as_bytes());
+    let read = T::ref_from_bytes(&bytes).expect("reading back the bytes failed");
+    assert_eq!(read, value, "round-tripped value differs");
+
+    let (read, rest) = T::ref_from_prefix(&bytes).expect("reading back the prefix failed");
+    assert_eq!(read, value, "round-tripped prefix differs");
+    assert!(rest.is_empty());
+}
+
+/// Asserts that the `values` slice can be read back from its own bytes.
+///
+/// # Panics
+///
+/// Panics if the slice read back is not equal to `values`.
+pub fn assert_slice_roundtrip<T: Pod + PartialEq + fmt::Debug>(values: &[T]) {
+    let bytes = aligned_copy(values.as_bytes());
+    let read = T::slice_from_bytes(&bytes).expect("reading back the bytes failed");
+    assert_eq!(read, values, "round-tripped slice differs");
+
+    let (read, rest) =
+        T::slice_from_prefix(&bytes, values.len()).expect("reading back the prefix failed");
+    assert_eq!(read, values, "round-tripped slice prefix differs");
+    assert!(rest.is_empty());
+}
+
+/// Asserts that all `strings` can be read back from a [`StringTable`](crate::StringTable)
+/// they have been inserted into.
+///
+/// # Panics
+///
+/// Panics if any of the strings read back differs.
+#[cfg(feature = "strings")]
+pub fn assert_string_table_roundtrip<S: AsRef<str>>(strings: &[S]) {
+    let mut table = crate::StringTable::new();
+    let offsets: Vec<_> = strings.iter().map(|s| table.insert(s.as_ref())).collect();
+
+    let bytes = table.into_bytes();
+    for (s, offset) in strings.iter().zip(offsets) {
+        let read = crate::StringTable::read(&bytes, offset)
+            .unwrap_or_else(|err| panic!("reading string at offset {offset} failed: {err}"));
+        assert_eq!(read, s.as_ref(), "round-tripped string differs");
+    }
+}
+
+/// A systematic corruption of a serialized buffer.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Corruption {
+    /// Truncates the buffer to the given length.
+    Truncate(usize),
+    /// Flips a single bit of the buffer.
+    BitFlip {
+        /// The offset of the byte.
+        offset: usize,
+        /// The bit to flip, `0` being the least significant bit.
+        bit: u8,
+    },
+}
+
+impl Corruption {
+    /// Returns all the corruptions of a buffer of length `len`.
+    ///
+    /// These are all truncations to a shorter length, followed by all single bit flips.
+    pub fn all(len: usize) -> impl Iterator<Item = Corruption> {
+        let truncations = (0..len).map(Corruption::Truncate);
+        let flips =
+            (0..len).flat_map(|offset| (0..8).map(move |bit| Corruption::BitFlip { offset, bit }));
+        truncations.chain(flips)
+    }
+
+    /// Applies this corruption to a copy of `buffer` aligned to [`TEST_ALIGN`].
+    pub fn apply(&self, buffer: &[u8]) -> AlignedVec {
+        match *self {
+            Self::Truncate(len) => aligned_copy(&buffer[..len.min(buffer.len())]),
+            Self::BitFlip { offset, bit } => {
+                let mut copy = aligned_copy(buffer);
+                if let Some(byte) = copy.get_mut(offset) {
+                    *byte ^= 1 << bit;
+                }
+                copy
+            }
+        }
+    }
+}
+
+impl fmt::Display for Corruption {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Truncate(len) => write!(f, "truncated to {len} bytes"),
+            Self::BitFlip { offset, bit } => write!(f, "flipped bit {bit} at offset {offset}"),
+        }
+    }
+}
+
+/// Asserts that `f` does not panic for any [`Corruption`] of `buffer`.
+///
+/// `f` is called with each corrupted buffer, aligned to [`TEST_ALIGN`].
+/// It is expected to parse or read the buffer, and may fail gracefully.
+///
+/// # Panics
+///
+/// Panics with the offending [`Corruption`] if `f` panics.
+pub fn assert_robust<F: FnMut(&[u8])>(buffer: &[u8], mut f: F) {
+    for corruption in Corruption::all(buffer.len()) {
+        let corrupted = corruption.apply(buffer);
+        let result = panic::catch_unwind(AssertUnwindSafe(|| f(&corrupted)));
+        if result.is_err() {
+            panic!("panicked on corrupted buffer ({corruption})");
+        }
+    }
+}
@@ -0,0 +1,52 @@
+//! Optional instrumentation of expensive operations using [`tracing`](https://docs.rs/tracing).
+//!
+//! The macros in this module expand to nothing unless the `tracing` feature is enabled.
+
+/// Inserts bigger than this many bytes are reported with an event.
+#[cfg(feature = "tracing")]
+pub(crate) const LARGE_INSERT_BYTES: usize = 64 * 1024;
+
+/// Enters a `debug` span until the end of the enclosing block.
+///
+/// When leaving the span, an event with the elapsed time in microseconds is emitted.
+macro_rules! timed_span {
+    ($($span:tt)*) => {
+        #[cfg(feature = "tracing")]
+        let _span = $crate::trace::TimedSpan::enter(::tracing::debug_span!($($span)*));
+    };
+}
+
+/// Emits a `debug` event.
+macro_rules! event {
+    ($($event:tt)*) => {
+        #[cfg(feature = "tracing")]
+        ::tracing::debug!($($event)*);
+    };
+}
+
+pub(crate) use {event, timed_span};
+
+/// An entered span which reports its duration when dropped.
+#[cfg(feature = "tracing")]
+pub(crate) struct TimedSpan {
+    _span: tracing::span::EnteredSpan,
+    start: std::time::Instant,
+}
+
+#[cfg(feature = "tracing")]
+impl TimedSpan {
+    pub(crate) fn enter(span: tracing::Span) -> Self {
+        Self {
+            _span: span.entered(),
+            start: std::time::Instant::now(),
+        }
+    }
+}
+
+#[cfg(feature = "tracing")]
+impl Drop for TimedSpan {
+    fn drop(&mut self) {
+        let elapsed_us = self.start.elapsed().as_micros() as u64;
+        tracing::debug!(elapsed_us, "done");
+    }
+}
@@ -0,0 +1,128 @@
+use core::{cmp, fmt, hash, ptr};
+
+use crate::{ByteSwap, Pod};
+
+/// A [`Pod`] value stored without its alignment requirement.
+///
+/// [`Unalign<T>`] has the size of `T`, but an alignment of 1, so it can be
+/// embedded anywhere within tightly packed records, and stored in an
+/// [`OffsetSet`](crate::OffsetSet), which is limited to types with an
+/// alignment of 1. As its value might be misaligned, it can not be borrowed,
+/// but is copied out with [`get`](Self::get) and written with
+/// [`set`](Self::set) instead.
+///
+/// # Example
+/// ```
+/// use watto::{Pod, Unalign};
+///
+/// #[repr(C)]
+/// struct Entry {
+///     kind: u8,
+///     offset: Unalign<u64>,
+/// }
+/// unsafe impl Pod for Entry {}
+///
+/// assert_eq!(std::mem::size_of::<Entry>(), 9);
+///
+/// let mut entry = Entry { kind: 1, offset: Unalign::new(1024) };
+/// entry.offset.set(entry.offset.get() + 1);
+/// let read = Entry::ref_from_bytes(entry.as_bytes()).unwrap();
+/// assert_eq!(read.offset.get(), 1025);
+/// ```
+#[repr(C, packed)]
+pub struct Unalign<T: Pod>(T);
+
+unsafe impl<T: Pod> Pod for Unalign<T> {
+    const LAYOUT_CHECK: () = T::LAYOUT_CHECK;
+}
+
+impl<T: Pod> Unalign<T> {
+    /// Wraps a value.
+    pub const fn new(value: T) -> Self {
+        Self(value)
+    }
+
+    /// Replaces the value.
+    pub fn set(&mut self, value: T) {
+        // SAFETY: The pointer is valid for writes.
+        unsafe { ptr::write_unaligned(ptr::addr_of_mut!(self.0), value) }
+    }
+}
+
+impl<T: Pod + Copy> Unalign<T> {
+    /// Returns a copy of the value.
+    pub fn get(&self) -> T {
+        // SAFETY: The pointer is valid for reads, and `T` is `Copy`, so its
+        // bits can be copied without a double drop.
+        unsafe { ptr::read_unaligned(ptr::addr_of!(self.0)) }
+    }
+
+    /// Unwraps the value.
+    pub fn into_inner(self) -> T {
+        self.get()
+    }
+}
+
+impl<T: Pod + Copy> Clone for Unalign<T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T: Pod + Copy> Copy for Unalign<T> {}
+
+impl<T: Pod + Default> Default for Unalign<T> {
+    fn default() -> Self {
+        Self(T::default())
+    }
+}
+
+impl<T: Pod + Copy + PartialEq> PartialEq for Unalign<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.get() == other.get()
+    }
+}
+
+impl<T: Pod + Copy + Eq> Eq for Unalign<T> {}
+
+impl<T: Pod + Copy + PartialOrd> PartialOrd for Unalign<T> {
+    fn partial_cmp(&self, other: &Self) -> Option<cmp::Ordering> {
+        self.get().partial_cmp(&other.get())
+    }
+}
+
+impl<T: Pod + Copy + Ord> Ord for Unalign<T> {
+    fn cmp(&self, other: &Self) -> cmp::Ordering {
+        self.get().cmp(&other.get())
+    }
+}
+
+impl<T: Pod + Copy + hash::Hash> hash::Hash for Unalign<T> {
+    fn hash<H: hash::Hasher>(&self, state: &mut H) {
+        self.get().hash(state);
+    }
+}
+
+impl<T: Pod> From<T> for Unalign<T> {
+    fn from(value: T) -> Self {
+        Self(value)
+    }
+}
+
+impl<T: Pod + Copy + fmt::Debug> fmt::Debug for Unalign<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(&self.get(), f)
+    }
+}
+
+impl<T: Pod + Copy + fmt::Display> fmt::Display for Unalign<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(&self.get(), f)
+    }
+}
+
+impl<T: ByteSwap + Copy> ByteSwap for Unalign<T> {
+    fn swap_bytes(self) -> Self {
+        Self(self.get().swap_bytes())
+    }
+}
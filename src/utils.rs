@@ -10,6 +10,30 @@ pub(crate) fn is_aligned_to(bytes: &[u8], align: usize) -> bool {
     bytes.as_ptr() as usize & (align - 1) == 0
 }
 
+/// Reports why a [`Pod`](crate::Pod) method returned [`None`].
+///
+/// This expands to nothing unless the `strict` feature is enabled.
+macro_rules! pod_failure {
+    ($($arg:tt)*) => {
+        #[cfg(feature = "strict")]
+        $crate::utils::report_pod_failure(format_args!($($arg)*));
+    };
+}
+
+pub(crate) use pod_failure;
+
+/// Logs the reason for a failed [`Pod`](crate::Pod) conversion.
+///
+/// This emits a `warn` event with the `tracing` feature, and prints to stderr otherwise.
+#[cfg(feature = "strict")]
+#[cold]
+pub(crate) fn report_pod_failure(reason: core::fmt::Arguments<'_>) {
+    #[cfg(feature = "tracing")]
+    tracing::warn!("{reason}");
+    #[cfg(not(feature = "tracing"))]
+    std::eprintln!("watto: {reason}");
+}
+
 /// Splits the given `bytes` into padding and a slice that is properly aligned
 /// to `align` bytes.
 ///
@@ -0,0 +1,289 @@
+//! Variable length integer encoding.
+//!
+//! Unsigned integers are encoded as [LEB128](https://en.wikipedia.org/wiki/LEB128),
+//! using 7 bits per byte with the high bit signalling continuation.
+//! Signed integers are first [zigzag](https://protobuf.dev/programming-guides/encoding/#signed-ints)
+//! encoded, so that values of small magnitude have a short encoding regardless of sign.
+//!
+//! The reading functions are available in `no_std` environments.
+//!
+//! # Example
+//! ```
+//! # #[cfg(feature = "std")] {
+//! use watto::varint;
+//!
+//! let mut buffer = vec![];
+//! varint::write_u64(&mut buffer, 300);
+//! varint::write_i64(&mut buffer, -2);
+//! assert_eq!(buffer, [0xac, 0x02, 0x03]);
+//!
+//! let (value, len) = varint::read_u64(&buffer).unwrap();
+//! assert_eq!((value, len), (300, 2));
+//! assert_eq!(varint::read_i64(&buffer[len..]), Some((-2, 1)));
+//! # }
+//! ```
+//!
+//! Within records read by a [`Reader`] and written by a [`Writer`](crate::Writer),
+//! varint fields are read with [`Reader::read_varint`] and written with
+//! [`Writer::write_varint`](crate::Writer::write_varint), or through the
+//! [`Varint`] wrapper, which implements [`ReadFrom`] and [`WriteTo`](crate::WriteTo).
+
+use core::fmt;
+
+use crate::{ReadFrom, Reader};
+
+/// The maximum number of bytes a varint encoded `u64` can occupy.
+pub const MAX_LEN: usize = 10;
+
+/// An error when decoding a varint with [`try_read_u64`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum VarintError {
+    /// The input ends before the last byte of the encoding.
+    Truncated,
+    /// The encoded value does not fit into a `u64`.
+    Overflow,
+}
+
+impl fmt::Display for VarintError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Truncated => f.write_str("varint is truncated"),
+            Self::Overflow => f.write_str("varint overflows a u64"),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for VarintError {}
+
+/// Reads a LEB128 encoded `u64` from the start of `bytes`.
+///
+/// Returns the value and the number of bytes it occupied, or [`None`] if the
+/// encoding is truncated or overflows a `u64`.
+#[inline]
+pub fn read_u64(bytes: &[u8]) -> Option<(u64, usize)> {
+    match bytes {
+        [byte, ..] if byte & 0x80 == 0 => Some((*byte as u64, 1)),
+        [_, _, _, _, _, _, _, _, ..] => {
+            read_u64_word(bytes[..8].try_into().unwrap()).or_else(|| read_u64_slow(bytes))
+        }
+        _ => read_u64_slow(bytes),
+    }
+}
+
+/// Reads a LEB128 encoded `u64` from the start of `bytes`.
+///
+/// This is the same as [`read_u64`], but tells apart why decoding failed.
+#[inline]
+pub fn try_read_u64(bytes: &[u8]) -> Result<(u64, usize), VarintError> {
+    read_u64(bytes).ok_or_else(|| {
+        let terminated = bytes.iter().take(MAX_LEN).any(|byte| byte & 0x80 == 0);
+        if terminated || bytes.len() >= MAX_LEN {
+            VarintError::Overflow
+        } else {
+            VarintError::Truncated
+        }
+    })
+}
+
+/// Decodes a value of up to 8 bytes from a single little-endian word.
+///
+/// The terminating byte is found by looking at all continuation bits at
+/// once, and the 7-bit groups are then compacted without any branches.
+/// Returns [`None`] if the encoding is longer than 8 bytes.
+#[inline]
+fn read_u64_word(bytes: [u8; 8]) -> Option<(u64, usize)> {
+    let word = u64::from_le_bytes(bytes);
+    let stops = !word & 0x8080_8080_8080_8080;
+    if stops == 0 {
+        return None;
+    }
+    let len = (stops.trailing_zeros() as usize + 1) / 8;
+
+    let word = word & (u64::MAX >> (64 - 8 * len)) & 0x7f7f_7f7f_7f7f_7f7f;
+    let word = (word & 0x007f_007f_007f_007f) | ((word & 0x7f00_7f00_7f00_7f00) >> 1);
+    let word = (word & 0x0000_3fff_0000_3fff) | ((word & 0x3fff_0000_3fff_0000) >> 2);
+    let word = (word & 0x0000_0000_0fff_ffff) | ((word & 0x0fff_ffff_0000_0000) >> 4);
+    Some((word, len))
+}
+
+fn read_u64_slow(bytes: &[u8]) -> Option<(u64, usize)> {
+    let mut value = 0u64;
+    for (i, byte) in bytes.iter().take(MAX_LEN).enumerate() {
+        let low = (byte & 0x7f) as u64;
+        // The last byte may only contribute the single remaining bit.
+        if i == MAX_LEN - 1 && low > 1 {
+            return None;
+        }
+        value |= low << (i * 7);
+        if byte & 0x80 == 0 {
+            return Some((value, i + 1));
+        }
+    }
+    None
+}
+
+/// Reads a zigzag and LEB128 encoded `i64` from the start of `bytes`.
+///
+/// Returns the value and the number of bytes it occupied, or [`None`] if the
+/// encoding is truncated or overflows.
+pub fn read_i64(bytes: &[u8]) -> Option<(i64, usize)> {
+    let (value, len) = read_u64(bytes)?;
+    Some((zigzag_decode(value), len))
+}
+
+/// Returns the number of bytes `value` occupies when LEB128 encoded.
+pub const fn encoded_len(value: u64) -> usize {
+    let bits = 64 - (value | 1).leading_zeros() as usize;
+    bits.div_ceil(7)
+}
+
+/// Zigzag encodes a signed integer, mapping `0, -1, 1, -2, …` to `0, 1, 2, 3, …`.
+pub const fn zigzag_encode(value: i64) -> u64 {
+    ((value << 1) ^ (value >> 63)) as u64
+}
+
+/// Decodes a zigzag encoded integer, the inverse of [`zigzag_encode`].
+pub const fn zigzag_decode(value: u64) -> i64 {
+    ((value >> 1) as i64) ^ -((value & 1) as i64)
+}
+
+/// Writes the LEB128 encoding of `value` to the start of `out`.
+///
+/// Returns the number of bytes written. This is available in `no_std` environments.
+pub fn encode_u64(out: &mut [u8; MAX_LEN], mut value: u64) -> usize {
+    let mut len = 0;
+    loop {
+        let byte = (value & 0x7f) as u8;
+        value >>= 7;
+        if value == 0 {
+            out[len] = byte;
+            return len + 1;
+        }
+        out[len] = byte | 0x80;
+        len += 1;
+    }
+}
+
+/// Appends the LEB128 encoding of `value` to `buffer`.
+///
+/// Returns the number of bytes written.
+#[cfg(feature = "std")]
+pub fn write_u64(buffer: &mut Vec<u8>, value: u64) -> usize {
+    let mut encoded = [0; MAX_LEN];
+    let len = encode_u64(&mut encoded, value);
+    buffer.extend_from_slice(&encoded[..len]);
+    len
+}
+
+/// Appends the zigzag and LEB128 encoding of `value` to `buffer`.
+///
+/// Returns the number of bytes written.
+#[cfg(feature = "std")]
+pub fn write_i64(buffer: &mut Vec<u8>, value: i64) -> usize {
+    write_u64(buffer, zigzag_encode(value))
+}
+
+/// An integer which can be varint encoded.
+///
+/// Unsigned integers are LEB128 encoded, and signed integers are additionally
+/// zigzag encoded.
+pub trait VarintValue: Copy {
+    /// Converts the value to the `u64` which is LEB128 encoded.
+    fn to_varint(self) -> u64;
+
+    /// Converts a decoded `u64` back to the value, or returns [`None`] if it
+    /// is out of range.
+    fn from_varint(value: u64) -> Option<Self>;
+}
+
+macro_rules! varint_unsigned {
+    ($($ty:ty),*) => {
+        $(
+            impl VarintValue for $ty {
+                fn to_varint(self) -> u64 {
+                    self as u64
+                }
+
+                fn from_varint(value: u64) -> Option<Self> {
+                    value.try_into().ok()
+                }
+            }
+        )*
+    };
+}
+
+macro_rules! varint_signed {
+    ($($ty:ty),*) => {
+        $(
+            impl VarintValue for $ty {
+                fn to_varint(self) -> u64 {
+                    zigzag_encode(self as i64)
+                }
+
+                fn from_varint(value: u64) -> Option<Self> {
+                    zigzag_decode(value).try_into().ok()
+                }
+            }
+        )*
+    };
+}
+
+varint_unsigned!(u8, u16, u32, u64, usize);
+varint_signed!(i8, i16, i32, i64, isize);
+
+/// A varint encoded field of a record.
+///
+/// This allows mixing fixed-size [`Pod`](crate::Pod) fields with variable
+/// length integers, for example in a [`WriteTo`](crate::WriteTo) derive or
+/// when reading with [`ReadFrom`].
+///
+/// # Example
+/// ```
+/// # #[cfg(feature = "writer")] {
+/// use watto::varint::Varint;
+/// use watto::{ReadFrom, Reader, WriteTo, Writer};
+///
+/// let mut writer = Writer::new(vec![]);
+/// 1u8.write_to(&mut writer).unwrap();
+/// Varint(300u32).write_to(&mut writer).unwrap();
+/// Varint(-2i64).write_to(&mut writer).unwrap();
+/// let buffer = writer.into_inner();
+/// assert_eq!(buffer, [1, 0xac, 0x02, 0x03]);
+///
+/// let mut reader = Reader::new(&buffer);
+/// assert_eq!(reader.read_ref::<u8>(), Some(&1));
+/// assert_eq!(Varint::<u32>::read_from(&mut reader), Some(Varint(300)));
+/// assert_eq!(reader.read_varint::<i64>(), Some(-2));
+/// # }
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Varint<T>(pub T);
+
+impl<T: VarintValue> Varint<T> {
+    /// Returns the number of bytes this value occupies when encoded.
+    pub fn encoded_len(self) -> usize {
+        encoded_len(self.0.to_varint())
+    }
+}
+
+impl<T> From<T> for Varint<T> {
+    fn from(value: T) -> Self {
+        Self(value)
+    }
+}
+
+impl<'data, T: VarintValue> ReadFrom<'data> for Varint<T> {
+    fn read_from(reader: &mut Reader<'data>) -> Option<Self> {
+        reader.read_varint().map(Self)
+    }
+}
+
+#[cfg(feature = "writer")]
+impl<T: VarintValue> crate::WriteTo for Varint<T> {
+    fn write_to<W: std::io::Write>(&self, writer: &mut crate::Writer<W>) -> std::io::Result<()> {
+        writer.write_varint(self.0)?;
+        Ok(())
+    }
+}
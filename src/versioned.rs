@@ -0,0 +1,183 @@
+use core::fmt;
+
+use crate::{check_header, Header, HeaderError};
+
+type Migration<T> = Box<dyn Fn(&Header, &[u8]) -> Option<T> + Send + Sync>;
+
+/// An error when reading a format using [`Versioned`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VersionedError {
+    /// The [`Header`] is invalid, or its version is neither the current one nor registered.
+    Header(HeaderError),
+    /// The parser or migration for the given version rejected the data.
+    InvalidData {
+        /// The major version found in the header.
+        major: u16,
+        /// The minor version found in the header.
+        minor: u16,
+    },
+}
+
+impl fmt::Display for VersionedError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Header(err) => write!(f, "invalid header: {err}"),
+            Self::InvalidData { major, minor } => {
+                write!(f, "invalid data for version {major}.{minor}")
+            }
+        }
+    }
+}
+
+impl std::error::Error for VersionedError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Header(err) => Some(err),
+            Self::InvalidData { .. } => None,
+        }
+    }
+}
+
+impl From<HeaderError> for VersionedError {
+    fn from(value: HeaderError) -> Self {
+        Self::Header(value)
+    }
+}
+
+/// A reader for a format identified by a [`Header`], which can upgrade older
+/// versions of the format into the current in-memory representation `T`.
+///
+/// The current major version is handled by the parser given to [`new`](Self::new).
+/// Older versions can be supported by [`register`](Self::register)ing a migration,
+/// which parses the data of that version and converts it to `T`.
+///
+/// Both the parser and the migrations get passed the [`Header`], which gives
+/// access to the `minor` version and `flags`, and the data following the header.
+///
+/// # Example
+/// ```
+/// use watto::{Header, Pod, Versioned};
+///
+/// #[derive(Debug, PartialEq)]
+/// struct Config {
+///     width: u32,
+///     height: u32,
+/// }
+///
+/// let reader = Versioned::new(*b"CONF", 2, |_header, data| {
+///     let [width, height] = u32::slice_from_bytes(data)? else {
+///         return None;
+///     };
+///     Some(Config { width: *width, height: *height })
+/// })
+/// // Version 1 only stored a square size.
+/// .register(1, |_header, data| {
+///     let (size, _) = u32::ref_from_prefix(data)?;
+///     Some(Config { width: *size, height: *size })
+/// });
+///
+/// let mut v1 = Header::new(*b"CONF", 1, 0).as_bytes().to_vec();
+/// v1.extend_from_slice(7u32.as_bytes());
+/// let mut v2 = Header::new(*b"CONF", 2, 0).as_bytes().to_vec();
+/// v2.extend_from_slice([3u32, 4].as_bytes());
+///
+/// assert_eq!(reader.read(&v1).unwrap(), Config { width: 7, height: 7 });
+/// assert_eq!(reader.read(&v2).unwrap(), Config { width: 3, height: 4 });
+/// ```
+pub struct Versioned<T> {
+    magic: [u8; 4],
+    current: u16,
+    parse: Migration<T>,
+    migrations: Vec<(u16, Migration<T>)>,
+}
+
+impl<T> Versioned<T> {
+    /// Creates a new [`Versioned`] reader for the format with the given magic,
+    /// parsing the `current` major version using `parse`.
+    pub fn new<F>(magic: [u8; 4], current: u16, parse: F) -> Self
+    where
+        F: Fn(&Header, &[u8]) -> Option<T> + Send + Sync + 'static,
+    {
+        Self {
+            magic,
+            current,
+            parse: Box::new(parse),
+            migrations: Vec::new(),
+        }
+    }
+
+    /// Registers a migration which upgrades the given older `major` version
+    /// into the current representation.
+    ///
+    /// Registering a migration for an already registered version replaces it.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `major` is the current version.
+    pub fn register<F>(mut self, major: u16, migrate: F) -> Self
+    where
+        F: Fn(&Header, &[u8]) -> Option<T> + Send + Sync + 'static,
+    {
+        if major == self.current {
+            panic!("register: can not register a migration for the current version");
+        }
+        let migrate: Migration<T> = Box::new(migrate);
+        match self.migrations.iter_mut().find(|(v, _)| *v == major) {
+            Some(migration) => migration.1 = migrate,
+            None => self.migrations.push((major, migrate)),
+        }
+        self
+    }
+
+    /// Returns the current major version of the format.
+    pub fn current_version(&self) -> u16 {
+        self.current
+    }
+
+    /// Returns `true` if the given major version can be read, either directly
+    /// or through a registered migration.
+    pub fn supports(&self, major: u16) -> bool {
+        major == self.current || self.migrations.iter().any(|(v, _)| *v == major)
+    }
+
+    /// Reads the format from `buffer`, migrating older versions as necessary.
+    pub fn read(&self, buffer: &[u8]) -> Result<T, VersionedError> {
+        let (header, data) = match check_header(buffer, self.magic, self.current) {
+            Ok((header, data)) => (header, data),
+            Err(HeaderError::UnsupportedVersion { major, minor }) => {
+                if !self.supports(major) {
+                    return Err(HeaderError::UnsupportedVersion { major, minor }.into());
+                }
+                check_header(buffer, self.magic, major)?
+            }
+            Err(err) => return Err(err.into()),
+        };
+
+        let parse = if header.major == self.current {
+            &self.parse
+        } else {
+            let (_, migrate) = self
+                .migrations
+                .iter()
+                .find(|(v, _)| *v == header.major)
+                .expect("version support was checked above");
+            migrate
+        };
+
+        parse(header, data).ok_or(VersionedError::InvalidData {
+            major: header.major,
+            minor: header.minor,
+        })
+    }
+}
+
+impl<T> fmt::Debug for Versioned<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let versions: Vec<_> = self.migrations.iter().map(|(v, _)| v).collect();
+        f.debug_struct("Versioned")
+            .field("magic", &self.magic)
+            .field("current", &self.current)
+            .field("migrations", &versions)
+            .finish()
+    }
+}
@@ -1,4 +1,63 @@
-use std::io::{Result, Write};
+use std::fmt;
+use std::io::{BufWriter, Result, Write};
+
+use crate::varint::{self, VarintValue};
+use crate::Pod;
+
+/// A type which can be written into a [`Writer`], aligning its parts as necessary.
+///
+/// This is implemented for [`Pod`] values and slices and the table builders,
+/// and can be derived for structs consisting of those and other [`WriteTo`]
+/// types with the `derive` feature. This is the writing counterpart to
+/// [`layout!`](crate::layout) and [`ReadFrom`](crate::ReadFrom).
+///
+/// # Example
+/// ```
+/// # #[cfg(feature = "derive")] {
+/// use watto::{Pod, WriteTo, Writer};
+///
+/// #[repr(C)]
+/// struct Header {
+///     version: u32,
+///     num_entries: u32,
+/// }
+/// unsafe impl Pod for Header {}
+///
+/// #[derive(WriteTo)]
+/// struct Format<'a> {
+///     header: Header,
+///     name: &'a [u8],
+///     entries: Vec<u64>,
+/// }
+///
+/// let format = Format {
+///     header: Header { version: 1, num_entries: 2 },
+///     name: b"abc",
+///     entries: vec![1, 2],
+/// };
+/// let mut writer = Writer::new(vec![]);
+/// format.write_to(&mut writer).unwrap();
+/// assert_eq!(writer.into_inner().len(), 8 + 3 + 5 + 16);
+/// # }
+/// ```
+pub trait WriteTo {
+    /// Writes `self` into the given [`Writer`].
+    fn write_to<W: Write>(&self, writer: &mut Writer<W>) -> Result<()>;
+}
+
+impl<T: Pod> WriteTo for T {
+    fn write_to<W: Write>(&self, writer: &mut Writer<W>) -> Result<()> {
+        writer.align_to_type::<T>()?;
+        writer.write_all(self.as_bytes())
+    }
+}
+
+impl<T: Pod> WriteTo for [T] {
+    fn write_to<W: Write>(&self, writer: &mut Writer<W>) -> Result<()> {
+        writer.align_to_type::<T>()?;
+        writer.write_all(self.as_bytes())
+    }
+}
 
 /// A wrapper around [`Write`] that keeps track of the bytes written.
 ///
@@ -8,6 +67,92 @@ use std::io::{Result, Write};
 pub struct Writer<W: Write> {
     inner: W,
     pos: usize,
+    audit: Option<PaddingReport>,
+}
+
+/// An alignment of a [`Writer`], recorded in a [`PaddingReport`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PaddingRecord {
+    /// The index of the section in [`PaddingReport::sections`].
+    pub section: usize,
+    /// The position of the writer before aligning it.
+    pub position: usize,
+    /// The requested alignment.
+    pub align: usize,
+    /// The number of padding bytes written.
+    pub padding: usize,
+}
+
+/// A report of all the padding written by a [`Writer`].
+///
+/// Enable recording it with [`Writer::enable_padding_audit`], and group the
+/// alignments by section with [`Writer::begin_section`]. Its [`Display`](fmt::Display)
+/// implementation prints the padding per section, which helps finding layouts
+/// that waste less space, for example by reordering fields or sections.
+///
+/// # Example
+/// ```
+/// use std::io::Write;
+///
+/// let mut writer = watto::Writer::new(vec![]);
+/// writer.enable_padding_audit();
+/// writer.begin_section("header");
+/// writer.write_all(&[1, 2, 3]).unwrap();
+/// writer.begin_section("entries");
+/// writer.align_to(8).unwrap();
+/// writer.write_all(&[4; 8]).unwrap();
+/// writer.align_to(8).unwrap();
+///
+/// let report = writer.padding_report().unwrap();
+/// assert_eq!(report.total(), 5);
+/// assert!(report.per_section().eq([("", 0), ("header", 0), ("entries", 5)]));
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PaddingReport {
+    sections: Vec<String>,
+    records: Vec<PaddingRecord>,
+}
+
+impl PaddingReport {
+    /// Returns the names of all sections, starting with an unnamed one for
+    /// alignments before the first call to [`Writer::begin_section`].
+    pub fn sections(&self) -> &[String] {
+        &self.sections
+    }
+
+    /// Returns all recorded alignments, in the order they were made.
+    pub fn records(&self) -> &[PaddingRecord] {
+        &self.records
+    }
+
+    /// Returns the total number of padding bytes written.
+    pub fn total(&self) -> usize {
+        self.records.iter().map(|record| record.padding).sum()
+    }
+
+    /// Iterates over all sections and the number of padding bytes written in them.
+    pub fn per_section(&self) -> impl Iterator<Item = (&str, usize)> + '_ {
+        self.sections.iter().enumerate().map(|(index, name)| {
+            let padding = self
+                .records
+                .iter()
+                .filter(|record| record.section == index)
+                .map(|record| record.padding)
+                .sum();
+            (name.as_str(), padding)
+        })
+    }
+}
+
+impl fmt::Display for PaddingReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (index, (name, padding)) in self.per_section().enumerate() {
+            let alignments = self.records.iter().filter(|r| r.section == index).count();
+            let name = if name.is_empty() { "(start)" } else { name };
+            writeln!(f, "{name}: {padding} bytes in {alignments} alignments")?;
+        }
+        writeln!(f, "total: {} bytes", self.total())
+    }
 }
 
 impl<W: Write> Writer<W> {
@@ -16,31 +161,100 @@ impl<W: Write> Writer<W> {
         Self {
             inner: writer,
             pos: 0,
+            audit: None,
+        }
+    }
+
+    /// Starts recording every call to [`align_to`](Self::align_to) in a [`PaddingReport`].
+    pub fn enable_padding_audit(&mut self) {
+        self.audit.get_or_insert_with(|| PaddingReport {
+            sections: vec![String::new()],
+            records: vec![],
+        });
+    }
+
+    /// Starts a new section, which the following alignments are attributed to
+    /// in the [`PaddingReport`].
+    ///
+    /// This does nothing unless the [padding audit](Self::enable_padding_audit) is enabled.
+    pub fn begin_section(&mut self, name: &str) {
+        if let Some(audit) = &mut self.audit {
+            audit.sections.push(name.to_owned());
         }
     }
 
+    /// Returns the [`PaddingReport`], if the [padding audit](Self::enable_padding_audit) is enabled.
+    pub fn padding_report(&self) -> Option<&PaddingReport> {
+        self.audit.as_ref()
+    }
+
     /// Unwraps [`Writer`] into the inner [`Write`].
     pub fn into_inner(self) -> W {
         self.inner
     }
 
+    /// Returns the number of bytes written so far.
+    pub(crate) fn position(&self) -> usize {
+        self.pos
+    }
+
     /// Explicitly aligns the output buffer to `align` bytes by writing the
     /// necessary amount of padding bytes.
     pub fn align_to(&mut self, align: usize) -> Result<usize> {
+        let len = self.misalignment(align);
+        let len = if len == 0 { 0 } else { align - len };
+
+        if let Some(audit) = &mut self.audit {
+            audit.records.push(PaddingRecord {
+                section: audit.sections.len() - 1,
+                position: self.pos,
+                align,
+                padding: len,
+            });
+        }
+        self.write_zeroes(len)?;
+        Ok(len)
+    }
+
+    /// Returns `true` if the output is currently aligned to `align` bytes.
+    ///
+    /// This allows asserting layout invariants before writing data that is
+    /// required to be aligned.
+    pub fn is_aligned(&self, align: usize) -> bool {
+        self.misalignment(align) == 0
+    }
+
+    /// Returns the number of bytes written past the last multiple of `align`.
+    ///
+    /// This is `0` if the output is aligned, and otherwise the output needs
+    /// `align - misalignment` padding bytes to become aligned.
+    pub fn misalignment(&self, align: usize) -> usize {
         if !align.is_power_of_two() {
             panic!("aligned_to: align is not a power-of-two");
         }
+        self.pos % align
+    }
 
-        const PADDING_BYTES: &[u8] = &[0; 16];
-
-        let len = self.pos % align;
-        let len = if len == 0 { return Ok(0) } else { align - len };
+    /// Writes `len` zero bytes, for example to fill a reserved region.
+    pub fn write_zeroes(&mut self, mut len: usize) -> Result<()> {
+        const ZEROES: &[u8] = &[0; 64];
 
-        for _ in 0..(len / PADDING_BYTES.len()) {
-            let _ = self.write(PADDING_BYTES)?;
+        while len > 0 {
+            let chunk = len.min(ZEROES.len());
+            self.write_all(&ZEROES[..chunk])?;
+            len -= chunk;
         }
+        Ok(())
+    }
 
-        self.write(&PADDING_BYTES[0..len % PADDING_BYTES.len()])
+    /// Writes a varint encoded integer, without any alignment.
+    ///
+    /// Returns the number of bytes written.
+    pub fn write_varint<T: VarintValue>(&mut self, value: T) -> Result<usize> {
+        let mut encoded = [0; varint::MAX_LEN];
+        let len = varint::encode_u64(&mut encoded, value.to_varint());
+        self.write_all(&encoded[..len])?;
+        Ok(len)
     }
 
     /// Explicitly aligns the output buffer to the alignment of `T` by writing the
@@ -50,6 +264,47 @@ impl<W: Write> Writer<W> {
     }
 }
 
+impl<W: Write> Writer<BufWriter<W>> {
+    /// Creates a new [`Writer`] which buffers up to `capacity` bytes before
+    /// writing them to `writer`.
+    ///
+    /// This avoids a syscall per write when writing many small records to a
+    /// `File` or socket. The position used by [`align_to`](Self::align_to)
+    /// accounts for the buffered bytes.
+    /// Use [`finish`](Self::finish) to flush the buffer and unwrap the inner writer.
+    pub fn buffered(writer: W, capacity: usize) -> Self {
+        Self::new(BufWriter::with_capacity(capacity, writer))
+    }
+
+    /// Flushes the buffer and unwraps [`Writer`] into the inner [`Write`].
+    pub fn finish(self) -> Result<W> {
+        self.inner.into_inner().map_err(|err| err.into_error())
+    }
+}
+
+/// Allows writing formatted text, like embedded notes or JSON sidecars,
+/// while keeping track of the position for subsequent alignments.
+///
+/// The [`Write`] implementation provides a `write_fmt` method as well, so
+/// only one of the two traits may be in scope when using [`write!`].
+/// An I/O error of the inner writer is reported as [`fmt::Error`].
+///
+/// # Example
+/// ```
+/// use std::fmt::Write;
+///
+/// let mut writer = watto::Writer::new(vec![]);
+/// write!(writer, "{} entries", 3).unwrap();
+/// writer.align_to(4).unwrap();
+///
+/// assert_eq!(writer.into_inner(), b"3 entries\0\0\0");
+/// ```
+impl<W: Write> fmt::Write for Writer<W> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        self.write_all(s.as_bytes()).map_err(|_| fmt::Error)
+    }
+}
+
 impl<W: Write> Write for Writer<W> {
     fn write(&mut self, buf: &[u8]) -> Result<usize> {
         let written = self.inner.write(buf)?;
@@ -0,0 +1,133 @@
+use core::mem;
+use core::num::{
+    NonZeroI128, NonZeroI16, NonZeroI32, NonZeroI64, NonZeroI8, NonZeroIsize, NonZeroU128,
+    NonZeroU16, NonZeroU32, NonZeroU64, NonZeroU8, NonZeroUsize,
+};
+
+use crate::Pod;
+
+/// A type for which the all-zero bit pattern is a valid value.
+///
+/// This is a weaker guarantee than [`Pod`], which requires *every* bit pattern
+/// to be valid. It is implemented for all [`Pod`] types, as well as for types
+/// like `bool`, `char`, raw pointers, `Option<&T>` or `Option<NonZeroU32>`,
+/// which can be zero-initialized, but not read from arbitrary bytes.
+///
+/// This provides the zero-initialized constructors [`zeroed`](Self::zeroed),
+/// [`box_zeroed`](Self::box_zeroed), [`vec_zeroed`](Self::vec_zeroed) and
+/// [`boxed_slice_zeroed`](Self::boxed_slice_zeroed) for all these types.
+///
+/// With the `derive` feature, this can be derived for structs whose fields
+/// are all [`Zeroable`], and for enums with a primitive or `C` representation
+/// whose first variant has the discriminant `0`.
+///
+/// # Safety
+///
+/// The all-zero bit pattern has to be a valid representation for the type.
+///
+/// # Example
+/// ```
+/// # #[cfg(all(feature = "derive", feature = "std"))] {
+/// use watto::Zeroable;
+///
+/// #[derive(Debug, PartialEq, Zeroable)]
+/// #[repr(u8)]
+/// enum Kind {
+///     Unknown,
+///     File,
+///     Directory,
+/// }
+///
+/// #[derive(Zeroable)]
+/// struct Entry {
+///     kind: Kind,
+///     hidden: bool,
+///     size: u64,
+/// }
+///
+/// let entry = Entry::zeroed();
+/// assert_eq!(entry.kind, Kind::Unknown);
+/// assert!(!entry.hidden);
+///
+/// let entries = Entry::vec_zeroed(3);
+/// assert!(entries.iter().all(|entry| entry.size == 0));
+/// # }
+/// ```
+pub unsafe trait Zeroable: Sized {
+    /// Returns a value with all bits set to zero.
+    fn zeroed() -> Self {
+        // SAFETY: The all-zero bit pattern is valid for `Zeroable` types.
+        unsafe { mem::zeroed() }
+    }
+
+    /// Returns a vector of `len` values with all bits set to zero.
+    #[cfg(feature = "std")]
+    fn vec_zeroed(len: usize) -> Vec<Self> {
+        let mut vec = Vec::with_capacity(len);
+        // SAFETY: The vector has room for `len` elements, which are valid
+        // after being zeroed as the type is `Zeroable`.
+        unsafe {
+            core::ptr::write_bytes(vec.as_mut_ptr(), 0, len);
+            vec.set_len(len);
+        }
+        vec
+    }
+
+    /// Returns a boxed value with all bits set to zero.
+    ///
+    /// The value is zeroed directly on the heap, so this also works for types
+    /// which are too large for the stack.
+    #[cfg(feature = "std")]
+    fn box_zeroed() -> Box<Self> {
+        let layout = std::alloc::Layout::new::<Self>();
+        // SAFETY: The allocation has the layout of `Self`, and is valid after
+        // being zeroed as the type is `Zeroable`. Boxes of zero-sized types
+        // do not allocate, and use a dangling pointer instead.
+        unsafe {
+            if layout.size() == 0 {
+                return Box::from_raw(core::ptr::NonNull::dangling().as_ptr());
+            }
+            let ptr = std::alloc::alloc_zeroed(layout) as *mut Self;
+            if ptr.is_null() {
+                std::alloc::handle_alloc_error(layout);
+            }
+            Box::from_raw(ptr)
+        }
+    }
+
+    /// Returns a boxed slice of `len` values with all bits set to zero.
+    #[cfg(feature = "std")]
+    fn boxed_slice_zeroed(len: usize) -> Box<[Self]> {
+        Self::vec_zeroed(len).into_boxed_slice()
+    }
+}
+
+unsafe impl<T: Pod> Zeroable for T {}
+
+/// Implements [`Zeroable`] for one or more types.
+macro_rules! impl_zeroable {
+    ($($type:ty),*) => {
+        $(unsafe impl Zeroable for $type {})*
+    };
+}
+
+impl_zeroable!(bool, char);
+impl_zeroable!(
+    Option<NonZeroU8>,
+    Option<NonZeroU16>,
+    Option<NonZeroU32>,
+    Option<NonZeroU64>,
+    Option<NonZeroU128>,
+    Option<NonZeroUsize>,
+    Option<NonZeroI8>,
+    Option<NonZeroI16>,
+    Option<NonZeroI32>,
+    Option<NonZeroI64>,
+    Option<NonZeroI128>,
+    Option<NonZeroIsize>
+);
+
+unsafe impl<T> Zeroable for Option<&T> {}
+unsafe impl<T> Zeroable for Option<&mut T> {}
+unsafe impl<T> Zeroable for *const T {}
+unsafe impl<T> Zeroable for *mut T {}
@@ -41,6 +41,308 @@ fn test_slice() {
     assert_eq!(n, None);
 }
 
+#[test]
+fn test_read_from_bytes() {
+    #[derive(Debug, Clone, Copy, PartialEq)]
+    #[repr(C)]
+    struct Header {
+        magic: u32,
+        len: u64,
+    }
+    unsafe impl Pod for Header {}
+
+    let header = Header { magic: 7, len: 100 };
+    let mut buffer = vec![0xff];
+    buffer.extend_from_slice(header.as_bytes());
+    buffer.extend_from_slice(&[1, 2]);
+
+    let unaligned = &buffer[1..];
+    assert!(Header::ref_from_prefix(unaligned).is_none());
+    let (read, rest) = Header::read_from_prefix(unaligned).unwrap();
+    assert_eq!(read, header);
+    assert_eq!(rest, [1, 2]);
+    assert!(Header::read_from_prefix(&unaligned[..15]).is_none());
+
+    assert_eq!(Header::read_from_bytes(&unaligned[..16]), Some(header));
+    assert_eq!(Header::read_from_bytes(unaligned), None);
+}
+
+#[test]
+fn test_pod_cast_errors() {
+    use watto::PodCastError;
+
+    let storage = [1u32, 2, 3, 4];
+    let bytes = storage.as_bytes();
+
+    assert_eq!(u32::try_ref_from_bytes(&bytes[4..8]), Ok(&2));
+    assert_eq!(
+        u32::try_ref_from_bytes(&bytes[..8]),
+        Err(PodCastError::SizeMismatch {
+            expected: 4,
+            actual: 8
+        })
+    );
+    assert_eq!(
+        u32::try_ref_from_prefix(&bytes[2..]).unwrap_err(),
+        PodCastError::Misaligned {
+            align: 4,
+            offset: 2
+        }
+    );
+    assert_eq!(
+        u64::try_ref_from_prefix(&bytes[..4]).unwrap_err(),
+        PodCastError::TooShort {
+            expected: 8,
+            actual: 4
+        }
+    );
+
+    assert_eq!(
+        u32::try_slice_from_bytes(&bytes[..10]),
+        Err(PodCastError::TrailingBytes {
+            elem_size: 4,
+            trailing: 2
+        })
+    );
+    assert_eq!(
+        u32::try_slice_from_prefix(bytes, 5).unwrap_err(),
+        PodCastError::TooShort {
+            expected: 20,
+            actual: 16
+        }
+    );
+    assert_eq!(
+        u32::try_slice_from_prefix(bytes, usize::MAX).unwrap_err(),
+        PodCastError::Overflow {
+            elem_size: 4,
+            elems: usize::MAX
+        }
+    );
+    let (values, rest) = u32::try_slice_from_prefix(bytes, 3).unwrap();
+    assert_eq!(values, [1, 2, 3]);
+    assert_eq!(rest.len(), 4);
+
+    assert_eq!(u32::try_read_from_bytes(&bytes[2..6]).map(|_| ()), Ok(()));
+    assert_eq!(
+        u32::try_read_from_prefix(&bytes[14..]).unwrap_err(),
+        PodCastError::TooShort {
+            expected: 4,
+            actual: 2
+        }
+    );
+
+    let err = u32::try_ref_from_bytes(&bytes[1..5]).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "input is misaligned by 1 bytes, expected alignment of 4"
+    );
+    #[cfg(feature = "std")]
+    {
+        let err = watto::Error::from(err);
+        assert_eq!(err.kind(), watto::ErrorKind::Mismatch);
+        assert_eq!(err.context(), "pod");
+    }
+}
+
+#[test]
+fn test_cast() {
+    #[derive(Debug, Clone, Copy, PartialEq)]
+    #[repr(C)]
+    struct Record {
+        id: u32,
+        len: u32,
+    }
+    unsafe impl Pod for Record {}
+
+    let record: Record = watto::cast([1u32, 2]);
+    assert_eq!(record, Record { id: 1, len: 2 });
+    assert_eq!(
+        watto::cast::<Record, u64>(record).to_ne_bytes(),
+        *record.as_bytes()
+    );
+
+    let mut records = [record, Record { id: 3, len: 4 }];
+    let words: &[u32] = watto::cast_slice(&records);
+    assert_eq!(words, [1, 2, 3, 4]);
+    let blocks: &[[u8; 8]] = watto::cast_slice(&records);
+    assert_eq!(blocks.len(), 2);
+    assert_eq!(watto::cast_slice::<Record, u8>(&[]), []);
+
+    for word in watto::cast_slice_mut::<Record, u32>(&mut records) {
+        *word *= 10;
+    }
+    assert_eq!(records[1], Record { id: 30, len: 40 });
+    watto::cast_mut::<Record, [u32; 2]>(&mut records[0])[1] = 5;
+    assert_eq!(records[0].len, 5);
+    assert_eq!(watto::cast_ref::<Record, [u32; 2]>(&records[0]), &[10, 5]);
+
+    let bytes = [0u8; 16];
+    let result: Result<&[Record], _> = watto::try_cast_slice(&bytes[..12]);
+    assert_eq!(
+        result.unwrap_err(),
+        watto::PodCastError::TrailingBytes {
+            elem_size: 8,
+            trailing: 4
+        }
+    );
+    assert!(watto::try_cast_ref::<u32, u16>(&0).is_err());
+}
+
+#[test]
+#[should_panic(expected = "cast_slice: ")]
+fn test_cast_slice_mismatch() {
+    let _: &[u32] = watto::cast_slice(&[0u8; 3]);
+}
+
+#[test]
+#[cfg(feature = "std")]
+fn test_cast_vec() {
+    use watto::PodCastError;
+
+    let words = vec![1u32, 2, 3, 4];
+    let ptr = words.as_ptr() as usize;
+    let pairs: Vec<[u32; 2]> = watto::try_cast_vec(words).unwrap();
+    assert_eq!(pairs, [[1, 2], [3, 4]]);
+    assert_eq!(pairs.as_ptr() as usize, ptr);
+
+    let words: Vec<u32> = watto::try_cast_vec(pairs).unwrap();
+    assert_eq!(words, [1, 2, 3, 4]);
+
+    let (err, words) = watto::try_cast_vec::<u32, [u32; 3]>(words).unwrap_err();
+    assert_eq!(
+        err,
+        PodCastError::TrailingBytes {
+            elem_size: 12,
+            trailing: 4
+        }
+    );
+
+    let mut short = words;
+    short.truncate(2);
+    short.reserve_exact(1);
+    if !short.capacity().is_multiple_of(2) {
+        let (err, _) = watto::try_cast_vec::<u32, [u32; 2]>(short).unwrap_err();
+        assert!(matches!(
+            err,
+            PodCastError::CapacityMismatch { elem_size: 8, .. }
+        ));
+    }
+
+    let (err, _) = watto::try_cast_vec::<u8, u16>(vec![0; 4]).unwrap_err();
+    assert_eq!(
+        err,
+        PodCastError::AlignmentMismatch {
+            expected: 2,
+            actual: 1
+        }
+    );
+}
+
+#[test]
+#[cfg(feature = "std")]
+fn test_zeroed_constructors() {
+    use watto::Zeroable;
+
+    #[derive(Debug, Clone, Copy, PartialEq)]
+    #[repr(C)]
+    struct Header {
+        magic: u32,
+        len: u32,
+    }
+    unsafe impl Pod for Header {}
+
+    assert_eq!(Header::zeroed(), Header { magic: 0, len: 0 });
+    assert_eq!(*Header::box_zeroed(), Header::zeroed());
+    assert_eq!(Header::vec_zeroed(3), [Header::zeroed(); 3]);
+
+    let records = <[u64; 4]>::boxed_slice_zeroed(5);
+    assert_eq!(records.len(), 5);
+    assert!(records.iter().flatten().all(|&value| value == 0));
+
+    // too large for the default stack of a test thread
+    let large = <[u64; 1 << 20]>::box_zeroed();
+    assert!(large.iter().all(|&value| value == 0));
+    assert_eq!(*<[u8; 0]>::box_zeroed(), []);
+}
+
+#[test]
+#[cfg(feature = "std")]
+fn test_boxed_slice_from_bytes() {
+    use watto::PodCastError;
+
+    let mut buffer = vec![0xff];
+    buffer.extend_from_slice([1u64, 2, 3].as_bytes());
+    let unaligned = &buffer[1..];
+
+    assert!(u64::slice_from_bytes(unaligned).is_none());
+    let values = u64::boxed_slice_from_bytes(unaligned).unwrap();
+    assert_eq!(*values, [1, 2, 3]);
+    assert_eq!(values.as_ptr() as usize % mem::align_of::<u64>(), 0);
+
+    assert_eq!(
+        u64::try_boxed_slice_from_bytes(&unaligned[..20]),
+        Err(PodCastError::TrailingBytes {
+            elem_size: 8,
+            trailing: 4
+        })
+    );
+    assert!(u64::boxed_slice_from_bytes(&[]).unwrap().is_empty());
+}
+
+#[test]
+#[cfg(feature = "std")]
+fn test_slice_from_bytes_unaligned() {
+    use std::borrow::Cow;
+    use watto::PodCastError;
+
+    let mut buffer = vec![0u8; 8];
+    buffer.extend_from_slice([1u64, 2].as_bytes());
+    let offset = buffer.as_ptr().align_offset(mem::align_of::<u64>());
+    let buffer = &buffer[offset..offset + 17];
+
+    let aligned = u64::slice_from_bytes_unaligned(&buffer[..16]).unwrap();
+    assert!(matches!(aligned, Cow::Borrowed(_)));
+    let unaligned = u64::slice_from_bytes_unaligned(&buffer[1..17]).unwrap();
+    assert!(matches!(unaligned, Cow::Owned(_)));
+    assert_eq!(unaligned.len(), 2);
+
+    assert_eq!(
+        u64::try_slice_from_bytes_unaligned(&buffer[1..13]),
+        Err(PodCastError::TrailingBytes {
+            elem_size: 8,
+            trailing: 4
+        })
+    );
+}
+
+#[test]
+fn test_mut_accessors() {
+    let mut storage = [0u64; 3];
+    let bytes = storage.as_bytes_mut();
+
+    *u64::mut_from_bytes(&mut bytes[8..16]).unwrap() = 5;
+    assert!(u64::mut_from_bytes(&mut bytes[8..]).is_none());
+    assert!(u32::mut_from_bytes(&mut bytes[1..5]).is_none());
+
+    let (first, rest) = u32::mut_from_prefix(bytes).unwrap();
+    *first = 1;
+    assert_eq!(rest.len(), 20);
+    assert!(u64::mut_from_prefix(&mut rest[..7]).is_none());
+
+    let halves = u32::slice_from_bytes_mut(&mut bytes[16..]).unwrap();
+    halves.copy_from_slice(&[7, 7]);
+    assert!(u32::slice_from_bytes_mut(&mut bytes[..6]).is_none());
+
+    let (values, rest) = u16::slice_from_prefix_mut(bytes, 2).unwrap();
+    values[1] = 2;
+    assert_eq!(rest.len(), 20);
+    assert!(u16::slice_from_prefix_mut(bytes, 13).is_none());
+
+    assert_eq!(storage[0].as_bytes(), [1u16, 2, 0, 0].as_bytes());
+    assert_eq!(storage[1], 5);
+    assert_eq!(storage[2].as_bytes(), [7u32, 7].as_bytes());
+}
+
 #[test]
 fn test_ref_from_prefix() {
     let bytes = vec![0x0, 0x1, 0x2, 0x3, 0x4, 0x5, 0x6, 0x7, 0x8, 0x9];
@@ -80,7 +382,10 @@ fn test_slice_from_prefix() {
 
 #[test]
 fn test_align_to() {
-    let bytes = &[0x0, 0x1, 0x2, 0x3, 0x4, 0x5, 0x6, 0x7, 0x8, 0x9];
+    // The assertions below rely on the buffer starting at a 4-byte boundary.
+    #[repr(align(4))]
+    struct Aligned([u8; 10]);
+    let bytes = &Aligned([0x0, 0x1, 0x2, 0x3, 0x4, 0x5, 0x6, 0x7, 0x8, 0x9]).0;
     let (num, bytes) = u16::ref_from_prefix(bytes).unwrap();
 
     assert_eq!(*num, u16::from_ne_bytes([0x0, 0x1]));
@@ -94,116 +399,3166 @@ fn test_align_to() {
     assert_eq!(bytes, &[0x8, 0x9]);
 }
 
-#[cfg(feature = "writer")]
-mod writer_tests {
-    use std::io::Write;
+#[test]
+fn test_packed_ints() {
+    use watto::{ByteSwap, I24Be, U24Be, I48, U24};
 
-    use super::*;
+    #[repr(C)]
+    struct Line {
+        line: U24,
+        column: u8,
+    }
+    unsafe impl Pod for Line {}
 
-    #[test]
-    fn test_writer() {
-        let mut writer = watto::Writer::new(vec![]);
+    assert_eq!(mem::size_of::<Line>(), 4);
+    assert_eq!(mem::align_of::<I48>(), 1);
 
-        let num = u16::from_ne_bytes([0x0, 0x1]);
-        writer.write_all(num.as_bytes()).unwrap();
+    let line = Line::ref_from_bytes(&[0x01, 0x02, 0x03, 4]).unwrap();
+    assert_eq!(line.line.get(), 0x030201);
+    assert_eq!(line.column, 4);
 
-        writer.align_to(mem::align_of::<u32>()).unwrap();
+    let mut value = U24Be::new(0x010203);
+    assert_eq!(value.as_bytes(), [1, 2, 3]);
+    assert_eq!(value.swap_bytes().as_bytes(), [3, 2, 1]);
+    value.set(0);
+    assert!(value < U24Be::new(1));
 
-        let nums = &[
-            u32::from_ne_bytes([0x2, 0x3, 0x4, 0x5]),
-            u32::from_ne_bytes([0x6, 0x7, 0x8, 0x9]),
-        ];
-        writer.write_all(nums.as_bytes()).unwrap();
+    assert_eq!((I24Be::MIN, I24Be::MAX), (-0x80_0000, 0x7f_ffff));
+    assert_eq!(I24Be::new(-2).as_bytes(), [0xff, 0xff, 0xfe]);
+    assert_eq!(I24Be::new(-2).get(), -2);
+    assert!(I24Be::try_new(0x80_0000).is_none());
+    assert_eq!(I48::new(I48::MIN).get(), -(1 << 47));
+    assert_eq!(format!("{:?}", I48::new(-5)), "-5");
+}
 
-        writer.align_to(32).unwrap();
+#[test]
+fn test_endian_types() {
+    use watto::endian::{NativeEndian, BE, F64, I16, LE, U128, U32};
+    use watto::ByteSwap;
 
-        let buffer = writer.into_inner();
+    assert_eq!(mem::align_of::<U128<LE>>(), 1);
+    assert_eq!(mem::size_of::<F64<BE>>(), 8);
 
-        assert_eq!(
-            buffer,
-            &[
-                0x0, 0x1, 0x0, 0x0, 0x2, 0x3, 0x4, 0x5, 0x6, 0x7, 0x8, 0x9, 0x0, 0x0, 0x0, 0x0,
-                0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0
-            ]
-        )
+    let value = U32::<BE>::new(0x0102_0304);
+    assert_eq!(value.as_bytes(), [1, 2, 3, 4]);
+    assert_eq!(U32::<LE>::new(0x0102_0304).as_bytes(), [4, 3, 2, 1]);
+    assert_eq!(U32::<NativeEndian>::new(7).as_bytes(), 7u32.as_bytes());
+    assert_eq!(value.swap_bytes().get(), 0x0403_0201);
+
+    let mut small = I16::<BE>::from(-2);
+    assert_eq!(small.as_bytes(), [0xff, 0xfe]);
+    small.set(small.get() * 2);
+    assert_eq!(i16::from(small), -4);
+    assert!(I16::<BE>::new(-5) < small);
+    assert_eq!(format!("{small:?}"), "-4");
+
+    let float = F64::<LE>::new(1.5);
+    assert_eq!(float.as_bytes(), 1.5f64.to_le_bytes());
+    assert_eq!(
+        F64::<BE>::ref_from_bytes(&1.5f64.to_be_bytes())
+            .unwrap()
+            .get(),
+        1.5
+    );
+}
+
+#[test]
+fn test_unalign() {
+    use watto::{ByteSwap, Unalign};
+
+    assert_eq!(mem::align_of::<Unalign<u64>>(), 1);
+    assert_eq!(mem::size_of::<Unalign<[u32; 3]>>(), 12);
+
+    #[derive(Debug, Clone, Copy, PartialEq)]
+    #[repr(C)]
+    struct Record {
+        tag: u8,
+        value: Unalign<u32>,
+        id: Unalign<u64>,
     }
+    unsafe impl Pod for Record {}
+
+    assert_eq!(mem::size_of::<Record>(), 13);
+    let mut record = Record {
+        tag: 1,
+        value: Unalign::new(7),
+        id: 42.into(),
+    };
+    record.value.set(record.value.get() * 3);
+    assert_eq!(record.value.into_inner(), 21);
+
+    let mut buffer = vec![0xff];
+    buffer.extend_from_slice(record.as_bytes());
+    let read = Record::ref_from_bytes(&buffer[1..]).unwrap();
+    assert_eq!(read, &record);
+    assert_eq!(read.id.get(), 42);
+
+    assert!(Unalign::new(1u16) < Unalign::new(2));
+    assert_eq!(format!("{:?}", read.id), "42");
+    assert_eq!(Unalign::new(1u16).swap_bytes().get(), 0x100);
 }
 
-#[cfg(feature = "offset_set")]
-mod offset_set_tests {
-    use watto::{OffsetSet, Pod};
+#[test]
+fn test_editor() {
+    use watto::{EditError, Editor};
 
-    #[test]
-    fn test_offset_set() {
-        #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
-        #[repr(C)]
-        struct CommitHash([u8; 20]);
-        unsafe impl Pod for CommitHash {}
+    #[repr(C)]
+    struct Header {
+        magic: [u8; 4],
+        num_entries: u32,
+    }
+    unsafe impl Pod for Header {}
 
-        let sha_1 = CommitHash([1; 20]);
-        let sha_2 = CommitHash([2; 20]);
-        let sha_3 = CommitHash([3; 20]);
+    let mut buffer = vec![0u8; 16];
+    buffer[..4].copy_from_slice(b"TEST");
+    let mut editor = Editor::new(&mut buffer);
 
-        let mut table = OffsetSet::new();
+    let mut header: Header = editor.read_pod(0).unwrap();
+    assert_eq!(&header.magic, b"TEST");
+    header.num_entries = 2;
+    editor.patch_pod(0, &header).unwrap();
+    editor.patch_slice(8, &[1u32, 2]).unwrap();
+    editor.patch_slice::<u64>(8, &[]).unwrap();
 
-        let offset_empty = table.insert(&[]);
-        let offset_12 = table.insert(&[sha_1, sha_2]);
-        let offset_23 = table.insert(&[sha_2, sha_3]);
+    assert_eq!(
+        editor.patch_pod(12, &0u64),
+        Err(EditError::Misaligned {
+            offset: 12,
+            align: 8
+        })
+    );
+    assert_eq!(
+        editor.patch_slice(12, &[0u32; 2]),
+        Err(EditError::OutOfBounds {
+            offset: 12,
+            len: 8,
+            buffer_len: 16
+        })
+    );
+    assert!(editor.read_pod::<u8>(usize::MAX).is_err());
 
-        assert_eq!(table.insert(&[sha_1, sha_2]), offset_12);
+    let buffer = editor.into_inner();
+    assert_eq!(buffer[4..], [2, 0, 0, 0, 1, 0, 0, 0, 2, 0, 0, 0]);
+}
 
-        let buffer = table.as_bytes();
-        let read_empty = OffsetSet::<CommitHash>::read(buffer, offset_empty).unwrap();
-        let read_12 = OffsetSet::<CommitHash>::read(buffer, offset_12).unwrap();
-        let read_23 = OffsetSet::<CommitHash>::read(buffer, offset_23).unwrap();
-        assert_eq!(read_empty, &[]);
-        assert_eq!(read_12, &[sha_1, sha_2]);
-        assert_eq!(read_23, &[sha_2, sha_3]);
+#[test]
+fn test_header() {
+    use watto::{check_header, Header, HeaderError};
 
-        // re-create the table using a serialized buffer
-        let mut table = OffsetSet::<CommitHash>::from_bytes(buffer).unwrap();
+    let header = Header::new(*b"TEST", 3, 1).with_flags(0b101);
+    let mut bytes = header.as_bytes().to_vec();
+    bytes.extend_from_slice(&[1, 2, 3]);
 
-        assert_eq!(table.insert(&[sha_1, sha_2]), offset_12);
+    let (read, rest) = check_header(&bytes, *b"TEST", 3).unwrap();
+    assert_eq!(*read, header);
+    assert_eq!(rest, &[1, 2, 3]);
 
-        let string_bytes = table.as_bytes();
-        let read_12 = OffsetSet::<CommitHash>::read(string_bytes, offset_12).unwrap();
-        let read_23 = OffsetSet::<CommitHash>::read(string_bytes, offset_23).unwrap();
-        assert_eq!(read_12, &[sha_1, sha_2]);
-        assert_eq!(read_23, &[sha_2, sha_3]);
+    assert_eq!(
+        check_header(&bytes, *b"NOPE", 3),
+        Err(HeaderError::WrongMagic { magic: *b"TEST" })
+    );
+    assert_eq!(
+        check_header(&bytes, *b"TEST", 2),
+        Err(HeaderError::UnsupportedVersion { major: 3, minor: 1 })
+    );
+    assert_eq!(
+        check_header(&bytes[..8], *b"TEST", 3),
+        Err(HeaderError::Invalid)
+    );
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn test_error() {
+    use std::error::Error as _;
+    use watto::{check_header, Error, ErrorKind, Header, HeaderError};
+
+    let header = Header::new(*b"TEST", 3, 1);
+    let err = Error::from(check_header(header.as_bytes(), *b"TEST", 2).unwrap_err());
+    assert_eq!(err.kind(), ErrorKind::UnsupportedVersion);
+    assert_eq!(err.context(), "header");
+    assert_eq!(
+        err.downcast_ref::<HeaderError>(),
+        Some(&HeaderError::UnsupportedVersion { major: 3, minor: 1 })
+    );
+    assert!(err.source().is_some());
+
+    let err = Error::from(watto::varint::try_read_u64(&[0x80]).unwrap_err());
+    assert_eq!(err.kind(), ErrorKind::OutOfBounds);
+    assert_eq!(err.to_string(), "varint: varint is truncated");
+}
+
+#[test]
+fn test_read_from_prefix_padded() {
+    #[derive(Debug, PartialEq)]
+    #[repr(C)]
+    struct V2 {
+        a: u32,
+        b: u16,
+        c: u16,
+    }
+    unsafe impl Pod for V2 {}
+
+    let mut bytes = vec![0u8];
+    bytes.extend_from_slice(&1u32.to_ne_bytes());
+    bytes.extend_from_slice(&2u16.to_ne_bytes());
+    bytes.extend_from_slice(b"rest");
+    // unaligned, older and smaller version
+    let (v, rest) = V2::read_from_prefix_padded(&bytes[1..], 6).unwrap();
+    assert_eq!(v, V2 { a: 1, b: 2, c: 0 });
+    assert_eq!(rest, b"rest");
+
+    // newer and larger version
+    let (v, rest) = V2::read_from_prefix_padded(&bytes[1..], 8).unwrap();
+    assert_eq!(v.a, 1);
+    assert_eq!(rest, b"st");
+
+    assert!(V2::read_from_prefix_padded(&bytes[1..], 11).is_none());
+}
+
+#[test]
+fn test_varint() {
+    use watto::varint;
+
+    assert_eq!(varint::read_u64(&[0x00]), Some((0, 1)));
+    assert_eq!(
+        varint::read_u64(&[0xe5, 0x8e, 0x26, 0xff]),
+        Some((624485, 3))
+    );
+    assert_eq!(varint::read_u64(&[0x80]), None);
+    assert_eq!(varint::read_u64(&[]), None);
+
+    let max = [0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0x01];
+    assert_eq!(varint::read_u64(&max), Some((u64::MAX, 10)));
+    let overflow = [0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0x02];
+    assert_eq!(varint::read_u64(&overflow), None);
+    assert_eq!(
+        varint::try_read_u64(&overflow),
+        Err(varint::VarintError::Overflow)
+    );
+    assert_eq!(
+        varint::try_read_u64(&[0x80, 0x80]),
+        Err(varint::VarintError::Truncated)
+    );
+    assert_eq!(varint::try_read_u64(&[0xac, 0x02]), Ok((300, 2)));
+
+    // Covers every encoded length, with and without trailing bytes.
+    for bits in 0..64 {
+        let value = (1u64 << bits) | 0x55;
+        let mut encoded = [0; varint::MAX_LEN];
+        let len = varint::encode_u64(&mut encoded, value);
+        assert_eq!(varint::read_u64(&encoded[..len]), Some((value, len)));
+        assert_eq!(varint::read_u64(&encoded), Some((value, len)));
+        assert_eq!(varint::read_u64(&encoded[..len - 1]), None);
+    }
+
+    for value in [0i64, -1, 1, -64, 64, i64::MIN, i64::MAX] {
+        let encoded = varint::zigzag_encode(value);
+        assert_eq!(varint::zigzag_decode(encoded), value);
+    }
+    assert_eq!(varint::zigzag_encode(-1), 1);
+    assert_eq!(varint::zigzag_encode(1), 2);
+
+    #[cfg(feature = "std")]
+    {
+        let mut buffer = vec![];
+        for value in [0, 1, 127, 128, 300, u32::MAX as u64, u64::MAX] {
+            buffer.clear();
+            let len = varint::write_u64(&mut buffer, value);
+            assert_eq!(len, varint::encoded_len(value));
+            assert_eq!(varint::read_u64(&buffer), Some((value, len)));
+        }
+        for value in [0, -1, 1, i64::MIN, i64::MAX] {
+            buffer.clear();
+            let len = varint::write_i64(&mut buffer, value);
+            assert_eq!(varint::read_i64(&buffer), Some((value, len)));
+        }
     }
 }
 
-#[cfg(feature = "strings")]
-mod string_tests {
-    use watto::StringTable;
+#[test]
+fn test_read_length_prefixed_str() {
+    use watto::Reader;
 
-    #[test]
-    fn test_string_table() {
-        let mut string_table = StringTable::new();
+    let buffer = [3, b'f', b'o', b'o', 0, 2, 0xff, 0xfe, 5, b'a'];
+    let mut reader = Reader::new(&buffer);
+    assert_eq!(reader.read_length_prefixed_str(), Some("foo"));
+    assert_eq!(reader.read_length_prefixed_str(), Some(""));
+    assert_eq!(reader.read_length_prefixed_str(), None);
+    assert_eq!(reader.position(), 5);
+    reader.read_bytes(3).unwrap();
+    assert_eq!(reader.read_length_prefixed_str(), None);
+    assert_eq!(reader.position(), 8);
+}
 
-        let offset_empty = string_table.insert("");
-        let offset_abc = string_table.insert("abc");
-        let offset_def = string_table.insert("def");
+#[test]
+fn test_delta_codec() {
+    use watto::codec::{self, DecodeError, DeltaIter};
 
-        assert_eq!(string_table.insert("abc"), offset_abc);
+    let truncated = [0x05, 0x80];
+    let mut out = [0u32; 2];
+    assert_eq!(
+        codec::decode_delta_into(&truncated, &mut out),
+        Err(DecodeError { offset: 1 })
+    );
+    let mut iter = DeltaIter::<u32>::new(&truncated);
+    assert_eq!(iter.next(), Some(Ok(5)));
+    assert_eq!(iter.next(), Some(Err(DecodeError { offset: 1 })));
+    assert_eq!(iter.next(), None);
 
-        let string_bytes = string_table.as_bytes();
-        let read_empty = StringTable::read(string_bytes, offset_empty).unwrap();
-        let read_abc = StringTable::read(string_bytes, offset_abc).unwrap();
-        let read_def = StringTable::read(string_bytes, offset_def).unwrap();
-        assert_eq!(read_empty, "");
-        assert_eq!(read_abc, "abc");
-        assert_eq!(read_def, "def");
+    // values which overflow a `u32`
+    let large = [0xff, 0xff, 0xff, 0xff, 0x0f, 0x01];
+    let mut iter = DeltaIter::<u32>::new(&large);
+    assert_eq!(iter.next(), Some(Ok(u32::MAX)));
+    assert_eq!(iter.next(), Some(Err(DecodeError { offset: 5 })));
 
-        // re-create the table using a serialized buffer
-        let mut string_table = StringTable::from_bytes(string_bytes).unwrap();
+    #[cfg(feature = "std")]
+    {
+        let values = [0u32, 0, 1, 200, 70_000, u32::MAX];
+        let mut buffer = vec![];
+        let len = codec::encode_delta(&values, &mut buffer);
+        assert_eq!(len, buffer.len());
+        buffer.extend_from_slice(b"rest");
 
-        assert_eq!(string_table.insert("abc"), offset_abc);
+        let mut out = [0u32; 6];
+        let rest = codec::decode_delta_into(&buffer, &mut out).unwrap();
+        assert_eq!(out, values);
+        assert_eq!(rest, b"rest");
+    }
+}
 
-        let string_bytes = string_table.as_bytes();
-        let read_abc = StringTable::read(string_bytes, offset_abc).unwrap();
-        let read_def = StringTable::read(string_bytes, offset_def).unwrap();
-        assert_eq!(read_abc, "abc");
-        assert_eq!(read_def, "def");
+#[cfg(feature = "std")]
+#[test]
+fn test_annotate() {
+    use watto::annotate::{AnnotationKind, Count, Primitive, Schema, Type};
+
+    let schema = Schema::new()
+        .value(
+            "header",
+            Type::Struct(vec![
+                (
+                    "magic".into(),
+                    Type::Array(Box::new(Type::Primitive(Primitive::U8)), 4),
+                ),
+                ("count".into(), Type::Primitive(Primitive::U8)),
+            ]),
+        )
+        .array(
+            "entries",
+            Type::Primitive(Primitive::I16),
+            Count::Field("header.count".into()),
+        );
+
+    let mut buffer = b"WATO\x02".to_vec();
+    buffer.push(0);
+    buffer.extend_from_slice(&(-1i16).to_ne_bytes());
+    buffer.extend_from_slice(&5i16.to_ne_bytes());
+    buffer.extend_from_slice(b"xyz");
+
+    let annotated = schema.annotate(&buffer);
+    let regions: Vec<_> = annotated
+        .annotations()
+        .iter()
+        .map(|a| (a.offset, a.len, a.label.as_str()))
+        .collect();
+    assert_eq!(
+        regions,
+        [
+            (0, 1, "header.magic[0]"),
+            (1, 1, "header.magic[1]"),
+            (2, 1, "header.magic[2]"),
+            (3, 1, "header.magic[3]"),
+            (4, 1, "header.count"),
+            (5, 1, "entries"),
+            (6, 2, "entries[0]"),
+            (8, 2, "entries[1]"),
+            (10, 3, ""),
+        ]
+    );
+    assert_eq!(
+        annotated.annotations()[6].kind,
+        AnnotationKind::Value("i16 = -1".into())
+    );
+    assert_eq!(annotated.annotations()[5].kind, AnnotationKind::Padding);
+    assert_eq!(annotated.annotations()[8].kind, AnnotationKind::Trailing);
+    assert!(annotated.to_string().contains("<trailing, 3 bytes>"));
+
+    let truncated = schema.annotate(&buffer[..9]);
+    assert_eq!(truncated.errors().count(), 1);
+    assert_eq!(truncated.errors().next().unwrap().label, "entries");
+}
+
+#[cfg(all(feature = "derive", feature = "std"))]
+#[test]
+fn test_derive_describe() {
+    use watto::annotate::{Primitive, Schema, Type};
+    use watto::describe::{describe, LayoutKind};
+    use watto::Describe;
+
+    #[derive(Clone, Copy, Describe)]
+    #[repr(C)]
+    struct Inner(u16, [u8; 2]);
+    unsafe impl Pod for Inner {}
+
+    #[derive(Clone, Copy, Describe)]
+    #[repr(C)]
+    struct Outer {
+        tag: u32,
+        inner: Inner,
+        value: u32,
+    }
+    unsafe impl Pod for Outer {}
+
+    let layout = describe::<Outer>();
+    assert_eq!((layout.name, layout.size, layout.align), ("Outer", 12, 4));
+    let LayoutKind::Struct(fields) = layout.kind else {
+        panic!("expected a struct");
+    };
+    let fields: Vec<_> = fields
+        .iter()
+        .map(|f| (f.name, f.offset, f.size()))
+        .collect();
+    assert_eq!(fields, [("tag", 0, 4), ("inner", 4, 4), ("value", 8, 4)]);
+    assert_eq!(Inner::layout().to_string(), "Inner");
+
+    let ty = Type::of::<Outer>();
+    assert_eq!(ty.size(), 12);
+    let Type::Struct(fields) = &ty else {
+        panic!("expected a struct");
+    };
+    assert_eq!(
+        fields[2],
+        ("value".to_owned(), Type::Primitive(Primitive::U32))
+    );
+
+    let outer = Outer {
+        tag: 1,
+        inner: Inner(2, [3, 4]),
+        value: 6,
+    };
+    let listing = Schema::new()
+        .value("outer", ty)
+        .annotate(outer.as_bytes())
+        .to_string();
+    assert!(listing.contains("outer.inner.1[1]: u8 = 4"), "{listing}");
+}
+
+#[cfg(all(feature = "derive", feature = "c_header"))]
+#[test]
+fn test_c_header() {
+    use watto::c_header::CHeader;
+    use watto::Describe;
+
+    #[derive(Clone, Copy, Describe)]
+    #[repr(C)]
+    struct Range(u32, u32);
+    unsafe impl Pod for Range {}
+
+    #[derive(Clone, Copy, Describe)]
+    #[repr(C)]
+    struct Function {
+        addr: u64,
+        ranges: [Range; 2],
+        name: [[u8; 4]; 2],
+        size: f32,
+    }
+    unsafe impl Pod for Function {}
+
+    let mut header = CHeader::new("FUNCTIONS_H");
+    header.add::<Function>().add::<Range>();
+    let header = header.to_string();
+
+    let range = header.find("struct Range {").unwrap();
+    let function = header.find("struct Function {").unwrap();
+    assert!(range < function);
+    assert_eq!(header.matches("struct Range {").count(), 1);
+    assert!(header.contains("    uint32_t _0;\n    uint32_t _1;\n"));
+    assert!(
+        header.contains("    struct Range ranges[2];\n    uint8_t name[2][4];\n    float size;\n")
+    );
+    assert!(header.contains("offsetof(struct Function, size) == 32"));
+    assert!(header.ends_with("#endif /* FUNCTIONS_H */\n"));
+}
+
+#[cfg(feature = "strings")]
+#[test]
+fn test_raw_read() {
+    use watto::raw::{self, RawReadError};
+    use watto::StringTable;
+
+    let mut table = StringTable::new();
+    let foo = table.insert("foo");
+    let long = table.insert(&"x".repeat(200));
+    let buffer = table.as_bytes();
+
+    assert_eq!(raw::read_str(buffer, foo), Ok("foo"));
+    assert_eq!(raw::read_str(buffer, long).unwrap().len(), 200);
+    assert_eq!(raw::read::<u8>(buffer, foo).unwrap(), b"foo");
+    assert_eq!(
+        raw::read_str(buffer, buffer.len() + 1),
+        Err(RawReadError::OutOfBounds)
+    );
+    assert!(raw::entries::<u8>(buffer)
+        .map(|e| e.unwrap().0)
+        .eq([foo, long]));
+
+    assert_eq!(raw::read_str(&[0x80], 0), Err(RawReadError::InvalidLength));
+    assert!(matches!(
+        raw::read_str(&[1, 0xff], 0),
+        Err(RawReadError::Utf8(_))
+    ));
+    let mut entries = raw::entries::<u8>(&[1, b'a', 5]);
+    assert!(entries.next().unwrap().is_ok());
+    assert_eq!(entries.next(), Some(Err(RawReadError::OutOfBounds)));
+    assert_eq!(entries.next(), None);
+}
+
+#[cfg(feature = "derive")]
+#[test]
+fn test_derive_byteswap() {
+    use watto::ByteSwap;
+
+    #[derive(Debug, Clone, Copy, PartialEq, ByteSwap)]
+    #[repr(C)]
+    struct Pair(u16, i16);
+    unsafe impl Pod for Pair {}
+
+    #[derive(Debug, Clone, Copy, PartialEq, ByteSwap)]
+    #[watto(endian = "little")]
+    #[repr(C)]
+    struct Record {
+        pairs: [Pair; 2],
+        value: f32,
+        tag: u8,
+    }
+    unsafe impl Pod for Record {}
+
+    let pair = Pair(0x0102, -2);
+    assert_eq!(pair.swap_bytes(), Pair(0x0201, -257));
+    assert_eq!(pair.swap_bytes().swap_bytes(), pair);
+
+    let record = Record {
+        pairs: [pair, Pair(3, 4)],
+        value: 1.5,
+        tag: 7,
+    };
+    let disk = record.to_disk();
+    assert_eq!(&disk.as_bytes()[..4], &[0x02, 0x01, 0xfe, 0xff]);
+    assert_eq!(&disk.as_bytes()[8..12], &1.5f32.to_le_bytes());
+    assert_eq!(disk.to_native(), record);
+    assert_eq!(record.swap_bytes().tag, 7);
+}
+
+#[cfg(feature = "derive")]
+#[test]
+fn test_derive_pod() {
+    #[derive(Debug, Clone, Copy, PartialEq, Pod)]
+    #[repr(C)]
+    struct Entry {
+        addr: u64,
+        len: u32,
+        kind: [u8; 2],
+        flags: u16,
+    }
+
+    #[derive(Debug, Clone, Copy, PartialEq, Pod)]
+    #[repr(transparent)]
+    struct Id(u32);
+
+    #[derive(Debug, Clone, Copy, PartialEq, Pod)]
+    #[repr(C)]
+    struct Pair<T: Pod, const N: usize> {
+        keys: [T; N],
+        values: [T; N],
+    }
+
+    let entries = [Entry {
+        addr: 0x1000,
+        len: 16,
+        kind: *b"fn",
+        flags: 3,
+    }; 2];
+    let bytes = entries.as_bytes();
+    assert_eq!(bytes.len(), 32);
+    let read = Entry::slice_from_bytes(bytes).unwrap();
+    assert_eq!(read, entries);
+
+    assert_eq!(Id::ref_from_bytes(Id(7).as_bytes()), Some(&Id(7)));
+
+    let pair = Pair {
+        keys: [1u16, 2],
+        values: [3, 4],
+    };
+    assert_eq!(pair.as_bytes(), [1, 0, 2, 0, 3, 0, 4, 0]);
+    assert_eq!(Pair::<u16, 2>::ref_from_bytes(pair.as_bytes()), Some(&pair));
+}
+
+#[cfg(feature = "derive")]
+#[test]
+fn test_derive_generics() {
+    use watto::describe::{describe, LayoutKind};
+    use watto::{ByteSwap, Describe, Zeroable};
+
+    #[derive(Debug, Clone, Copy, PartialEq, Pod, ByteSwap, Describe)]
+    #[repr(C)]
+    struct Entry<T> {
+        tag: u16,
+        value: T,
+    }
+
+    #[derive(Debug, Clone, Copy, PartialEq, Pod, ByteSwap, Describe)]
+    #[repr(C)]
+    struct Block<const N: usize> {
+        len: u32,
+        data: [u8; N],
+    }
+
+    #[derive(Debug, Clone, Copy, PartialEq, Pod)]
+    #[repr(C)]
+    struct Bytes<const N: usize> {
+        data: [u8; N],
+    }
+
+    let entry = Entry {
+        tag: 1,
+        value: 2u16,
+    };
+    assert_eq!(entry.as_bytes(), 0x0002_0001u32.to_ne_bytes());
+    assert_eq!(Entry::<u16>::ref_from_bytes(entry.as_bytes()), Some(&entry));
+    assert_eq!(entry.swap_bytes().swap_bytes(), entry);
+    assert_eq!(entry.swap_bytes().tag, 0x0100);
+
+    let layout = describe::<Entry<[u16; 3]>>();
+    assert_eq!((layout.size, layout.align), (8, 2));
+    let LayoutKind::Struct(fields) = layout.kind else {
+        unreachable!()
+    };
+    assert_eq!(fields[1].offset, 2);
+    assert_eq!(Entry::<[u8; 2]>::zeroed().value, [0; 2]);
+
+    let block = Block::<4> {
+        len: 4,
+        data: [1, 2, 3, 4],
+    };
+    assert_eq!(block.swap_bytes().data, [1, 2, 3, 4]);
+    assert_eq!(block.as_bytes().len(), 8);
+    let blocks = Block::<4>::slice_from_bytes([0u32; 4].as_bytes()).unwrap();
+    assert_eq!(blocks, [Block::zeroed(); 2]);
+    assert_eq!(describe::<Block<8>>().size, 12);
+
+    let bytes = Bytes { data: *b"watto" };
+    assert_eq!(bytes.as_bytes(), b"watto");
+    assert_eq!(Bytes::<5>::ref_from_bytes(b"watto"), Some(&bytes));
+    assert_eq!(Bytes::<3>::slice_from_bytes(b"abcdef").unwrap().len(), 2);
+}
+
+#[cfg(feature = "derive")]
+#[test]
+fn test_derive_zeroable() {
+    use std::num::NonZeroU32;
+
+    use watto::Zeroable;
+
+    #[derive(Debug, PartialEq, Zeroable)]
+    #[repr(i16)]
+    enum Op {
+        Nop = 0,
+        Push(u32) = 3,
+        Pop = -1,
+    }
+
+    #[derive(Debug, PartialEq, Zeroable)]
+    #[repr(C)]
+    enum Shape {
+        Point { x: f32, y: f32 },
+        Circle(f32),
+    }
+
+    #[derive(Zeroable)]
+    struct Instr<'a, T> {
+        op: Op,
+        arg: Option<NonZeroU32>,
+        name: Option<&'a u8>,
+        shape: Shape,
+        extra: T,
+    }
+
+    let instr = Instr::<'_, [u64; 2]>::zeroed();
+    assert_eq!(instr.op, Op::Nop);
+    assert_eq!(instr.arg, None);
+    assert_eq!(instr.name, None);
+    assert_eq!(instr.shape, Shape::Point { x: 0.0, y: 0.0 });
+    assert_eq!(instr.extra, [0; 2]);
+    assert_ne!(Op::Push(1), Op::Pop);
+    assert_ne!(Shape::Circle(1.0), instr.shape);
+
+    #[cfg(feature = "std")]
+    {
+        assert_eq!(bool::vec_zeroed(3), [false; 3]);
+        assert!(char::vec_zeroed(0).is_empty());
+    }
+    assert_eq!(u64::zeroed(), 0);
+}
+
+#[cfg(feature = "derive")]
+#[test]
+fn test_layout_fingerprint() {
+    use watto::{check_layout_fingerprint, Describe, Header, HeaderError};
+
+    mod v1 {
+        #[derive(Clone, Copy, watto::Describe)]
+        #[repr(C)]
+        pub struct Entry {
+            pub addr: u64,
+            pub len: u32,
+        }
+        unsafe impl watto::Pod for Entry {}
+    }
+    mod renamed {
+        #[derive(Clone, Copy, watto::Describe)]
+        #[repr(C)]
+        pub struct Record {
+            pub address: u64,
+            pub size: u32,
+        }
+        unsafe impl watto::Pod for Record {}
+    }
+    mod v2 {
+        #[derive(Clone, Copy, watto::Describe)]
+        #[repr(C)]
+        pub struct Entry {
+            pub addr: u64,
+            pub len: f32,
+        }
+        unsafe impl watto::Pod for Entry {}
+    }
+
+    const FINGERPRINT: u64 = v1::Entry::LAYOUT_FINGERPRINT;
+    assert_eq!(FINGERPRINT, renamed::Record::LAYOUT_FINGERPRINT);
+    assert_ne!(FINGERPRINT, v2::Entry::LAYOUT_FINGERPRINT);
+    assert_ne!(FINGERPRINT, <[u64; 2]>::LAYOUT_FINGERPRINT);
+    assert_ne!(u32::LAYOUT_FINGERPRINT, i32::LAYOUT_FINGERPRINT);
+
+    let header = Header::new(*b"TEST", 1, 0).with_layout_fingerprint::<v1::Entry>();
+    let bytes = header.as_bytes().to_vec();
+    let (header, _) = watto::check_header(&bytes, *b"TEST", 1).unwrap();
+    assert_eq!(check_layout_fingerprint::<renamed::Record>(header), Ok(()));
+    assert!(matches!(
+        check_layout_fingerprint::<v2::Entry>(header),
+        Err(HeaderError::LayoutMismatch { .. })
+    ));
+}
+
+#[cfg(feature = "glam")]
+#[test]
+fn test_glam_pod() {
+    use glam::{Mat4, Vec3};
+
+    let vertices = [Vec3::new(1.0, 2.0, 3.0), Vec3::new(4.0, 5.0, 6.0)];
+    let bytes = vertices.as_bytes();
+    assert_eq!(bytes.len(), 24);
+    assert_eq!(&bytes[..4], &1.0f32.to_ne_bytes());
+    assert_eq!(Vec3::slice_from_bytes(bytes), Some(&vertices[..]));
+
+    let transform = Mat4::from_translation(Vec3::new(1.0, 2.0, 3.0));
+    let (read, rest) = Mat4::read_from_prefix_padded(transform.as_bytes(), 64).unwrap();
+    assert_eq!(read, transform);
+    assert!(rest.is_empty());
+}
+
+#[cfg(feature = "mint")]
+#[test]
+fn test_mint_pod() {
+    let point = mint::Point3 {
+        x: 1u16,
+        y: 2,
+        z: 3,
+    };
+    let bytes = point.as_bytes();
+    assert_eq!(bytes.len(), 6);
+    assert_eq!(mint::Point3::<u16>::ref_from_bytes(bytes), Some(&point));
+
+    let rotation = mint::Quaternion {
+        v: mint::Vector3 {
+            x: 0.0f32,
+            y: 0.0,
+            z: 0.0,
+        },
+        s: 1.0,
+    };
+    assert_eq!(rotation.as_bytes()[12..], 1.0f32.to_ne_bytes());
+    assert_eq!(std::mem::size_of::<mint::ColumnMatrix4<f64>>(), 128);
+}
+
+#[test]
+fn test_fixed_cap() {
+    use watto::{raw, CapacityError, FixedCapOffsetSet, FixedCapStringTable};
+
+    let mut table = FixedCapStringTable::<12>::new();
+    let foo = table.insert("foo").unwrap();
+    let bar = table.insert("bar").unwrap();
+    assert_eq!(table.insert("foo"), Ok(foo));
+    assert_eq!(table.get("bar"), Some(bar));
+    assert_eq!(table.len(), 2);
+    assert_eq!(
+        table.insert("quux"),
+        Err(CapacityError {
+            required: 13,
+            capacity: 12
+        })
+    );
+    assert_eq!(table.insert("baz"), Ok(8));
+    assert_eq!(table.remaining(), 0);
+    assert_eq!(table.as_bytes(), b"\x03foo\x03bar\x03baz");
+    assert!(table.entries().map(|(_, s)| s).eq(["foo", "bar", "baz"]));
+
+    let mut set = FixedCapOffsetSet::<[u8; 2], 300>::new();
+    let long = [[7u8; 2]; 130];
+    let offset = set.insert(&long).unwrap();
+    assert_eq!(set.as_bytes().len(), 262);
+    assert_eq!(raw::read::<[u8; 2]>(set.as_bytes(), offset), Ok(&long[..]));
+    assert_eq!(set.get(&[]), None);
+}
+
+#[cfg(feature = "writer")]
+mod writer_tests {
+    use std::io::Write;
+
+    use super::*;
+
+    #[test]
+    fn test_writer() {
+        let mut writer = watto::Writer::new(vec![]);
+
+        let num = u16::from_ne_bytes([0x0, 0x1]);
+        writer.write_all(num.as_bytes()).unwrap();
+
+        writer.align_to(mem::align_of::<u32>()).unwrap();
+
+        let nums = &[
+            u32::from_ne_bytes([0x2, 0x3, 0x4, 0x5]),
+            u32::from_ne_bytes([0x6, 0x7, 0x8, 0x9]),
+        ];
+        writer.write_all(nums.as_bytes()).unwrap();
+
+        writer.align_to(32).unwrap();
+
+        let buffer = writer.into_inner();
+
+        assert_eq!(
+            buffer,
+            &[
+                0x0, 0x1, 0x0, 0x0, 0x2, 0x3, 0x4, 0x5, 0x6, 0x7, 0x8, 0x9, 0x0, 0x0, 0x0, 0x0,
+                0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0
+            ]
+        )
+    }
+
+    #[cfg(all(feature = "derive", feature = "offset_set"))]
+    #[test]
+    fn test_derive_write_to() {
+        use watto::{RecordTable, WriteTo};
+
+        #[derive(WriteTo)]
+        struct Inner(u8, [u16; 2]);
+
+        #[derive(WriteTo)]
+        struct Format<'a> {
+            inner: Inner,
+            bytes: &'a [u8],
+            records: RecordTable<u64>,
+        }
+
+        let mut records = RecordTable::new();
+        records.push(7u64);
+        let format = Format {
+            inner: Inner(1, [2, 3]),
+            bytes: &[4, 5, 6],
+            records,
+        };
+        let mut writer = watto::Writer::new(vec![]);
+        format.write_to(&mut writer).unwrap();
+
+        let buffer = writer.into_inner();
+        assert_eq!(buffer.len(), 24);
+        assert_eq!(buffer[0], 1);
+        assert_eq!(u16::slice_from_bytes(&buffer[2..6]), Some(&[2, 3][..]));
+        assert_eq!(buffer[6..9], [4, 5, 6]);
+        assert_eq!(u64::ref_from_bytes(&buffer[16..]), Some(&7));
+    }
+
+    #[test]
+    fn test_write_zeroes() {
+        let mut writer = watto::Writer::new(vec![]);
+        writer.write_all(&[1]).unwrap();
+        writer.write_zeroes(150).unwrap();
+        writer.write_all(&[2]).unwrap();
+        assert!(!writer.is_aligned(16));
+        assert_eq!(writer.misalignment(64), 24);
+        assert_eq!(writer.align_to(64).unwrap(), 40);
+        assert!(writer.is_aligned(64));
+        assert!(writer.is_aligned(1));
+
+        let buffer = writer.into_inner();
+        assert_eq!(buffer.len(), 192);
+        assert_eq!(buffer[151], 2);
+        assert_eq!(buffer.iter().filter(|b| **b != 0).count(), 2);
+    }
+
+    #[test]
+    fn test_buffered_writer() {
+        let mut writer = watto::Writer::buffered(vec![], 64);
+        writer.write_all(&[1, 2, 3]).unwrap();
+        assert_eq!(writer.align_to(8).unwrap(), 5);
+        writer.write_all(&[0xff; 100]).unwrap();
+
+        let buffer = writer.finish().unwrap();
+        assert_eq!(buffer.len(), 108);
+        assert_eq!(buffer[..8], [1, 2, 3, 0, 0, 0, 0, 0]);
+    }
+
+    #[test]
+    fn test_paged_writer() {
+        use watto::{PageLocation, PagedWriter};
+
+        let mut writer = PagedWriter::new(vec![], 32);
+        assert_eq!(writer.page_size(), 32);
+        writer.write_record(&1u8).unwrap();
+        let location = writer.write_record(&[2u64; 3]).unwrap();
+        assert_eq!(location, PageLocation { page: 0, offset: 8 });
+
+        let location = writer.write_record(&[3u16; 4]).unwrap();
+        assert_eq!(location, PageLocation { page: 1, offset: 0 });
+        assert!(writer.write_record(&[0u8; 33]).is_err());
+
+        let location = writer.write_spanning(&[4u8; 30]).unwrap();
+        assert_eq!(location, PageLocation { page: 1, offset: 8 });
+        assert_eq!(writer.location(), PageLocation { page: 2, offset: 6 });
+
+        let buffer = writer.finish().unwrap();
+        assert_eq!(buffer.len(), 96);
+        assert_eq!(buffer[32..40], [3, 0, 3, 0, 3, 0, 3, 0]);
+        assert_eq!(buffer[70..], [0; 26]);
+    }
+
+    #[test]
+    fn test_varint_fields() {
+        use watto::varint::Varint;
+        use watto::{ReadFrom, Reader, WriteTo};
+
+        let mut writer = watto::Writer::new(vec![]);
+        assert_eq!(writer.write_varint(127u8).unwrap(), 1);
+        assert_eq!(writer.write_varint(-65i32).unwrap(), 2);
+        2u16.write_to(&mut writer).unwrap();
+        Varint(u64::MAX).write_to(&mut writer).unwrap();
+        assert_eq!(Varint(u64::MAX).encoded_len(), 10);
+        let buffer = writer.into_inner();
+        assert_eq!(buffer.len(), 1 + 2 + 1 + 2 + 10);
+
+        let mut reader = Reader::new(&buffer);
+        assert_eq!(reader.read_varint::<u8>(), Some(127));
+        assert_eq!(reader.read_varint::<i8>(), Some(-65));
+        assert_eq!(<&u16>::read_from(&mut reader), Some(&2));
+        let position = reader.position();
+        assert_eq!(reader.read_varint::<u32>(), None);
+        assert_eq!(reader.position(), position);
+        assert_eq!(
+            Varint::<u64>::read_from(&mut reader),
+            Some(Varint(u64::MAX))
+        );
+        assert!(reader.is_empty());
+        assert_eq!(Reader::new(&[0x80]).read_varint::<u64>(), None);
+    }
+
+    #[test]
+    fn test_fmt_write() {
+        use std::fmt::Write as _;
+
+        use watto::WriteTo;
+
+        fn write_note(out: &mut impl std::fmt::Write, entries: usize) -> std::fmt::Result {
+            writeln!(out, "entries: {entries}")
+        }
+
+        let mut writer = watto::Writer::new(vec![]);
+        write_note(&mut writer, 12).unwrap();
+        writer.write_str("ü").unwrap();
+        assert_eq!(writer.misalignment(8), 6);
+        writer.align_to(8).unwrap();
+        1u64.write_to(&mut writer).unwrap();
+
+        let buffer = writer.into_inner();
+        assert_eq!(&buffer[..14], "entries: 12\nü".as_bytes());
+        assert_eq!(buffer[14..], [0, 0, 1, 0, 0, 0, 0, 0, 0, 0]);
+    }
+
+    #[test]
+    fn test_padding_audit() {
+        use watto::{PaddingRecord, WriteTo};
+
+        let mut writer = watto::Writer::new(vec![]);
+        writer.align_to(8).unwrap();
+        assert!(writer.padding_report().is_none());
+
+        writer.enable_padding_audit();
+        writer.write_all(&[1]).unwrap();
+        writer.align_to(4).unwrap();
+        writer.begin_section("values");
+        2u16.write_to(&mut writer).unwrap();
+        writer.align_to(8).unwrap();
+
+        let report = writer.padding_report().unwrap();
+        assert_eq!(report.sections(), ["", "values"]);
+        assert_eq!(
+            report.records(),
+            [
+                PaddingRecord {
+                    section: 0,
+                    position: 1,
+                    align: 4,
+                    padding: 3
+                },
+                PaddingRecord {
+                    section: 1,
+                    position: 4,
+                    align: 2,
+                    padding: 0
+                },
+                PaddingRecord {
+                    section: 1,
+                    position: 6,
+                    align: 8,
+                    padding: 2
+                },
+            ]
+        );
+        assert_eq!(report.total(), 5);
+        assert_eq!(
+            report.to_string(),
+            "(start): 3 bytes in 1 alignments\nvalues: 2 bytes in 2 alignments\ntotal: 5 bytes\n"
+        );
+    }
+
+    #[test]
+    fn test_write_header() {
+        use watto::Header;
+
+        let mut writer = watto::Writer::new(vec![]);
+        writer.write_all(&[0xff]).unwrap();
+
+        let header = Header::new(*b"TEST", 1, 0);
+        watto::write_header(&mut writer, &header).unwrap();
+
+        let buffer = writer.into_inner();
+        assert_eq!(buffer.len(), 20);
+        assert_eq!(&buffer[4..], header.as_bytes());
+    }
+
+    watto::layout! {
+        struct TestFormat<'data> {
+            header: [u8; 4],
+            num: u32,
+            items: [u16; *num as usize],
+            rest: [u64],
+        }
+    }
+
+    #[test]
+    fn test_layout() {
+        let format = TestFormat {
+            header: b"TEST",
+            num: &3,
+            items: &[1, 2, 3],
+            rest: &[4, 5],
+        };
+        let mut writer = watto::Writer::new(vec![]);
+        format.write_to(&mut writer).unwrap();
+        let buffer = writer.into_inner();
+        assert_eq!(buffer.len(), 4 + 4 + 6 + 2 + 16);
+
+        let parsed = TestFormat::parse(&buffer).unwrap();
+        assert_eq!(parsed.header, b"TEST");
+        assert_eq!(*parsed.num, 3);
+        assert_eq!(parsed.items, &[1, 2, 3]);
+        assert_eq!(parsed.rest, &[4, 5]);
+
+        assert!(TestFormat::parse(&buffer[..10]).is_none());
+    }
+
+    #[test]
+    fn test_versioned() {
+        use watto::{Header, HeaderError, Versioned, VersionedError};
+
+        let reader = Versioned::new(*b"TEST", 3, |_, data| {
+            u64::ref_from_prefix(data).map(|(v, _)| *v)
+        })
+        .register(1, |_, data| {
+            u16::ref_from_prefix(data).map(|(v, _)| *v as u64)
+        })
+        .register(2, |header, _| Some(header.minor as u64));
+
+        let write = |major: u16, minor: u16, data: &[u8]| {
+            let mut writer = watto::Writer::new(vec![]);
+            watto::write_header(&mut writer, &Header::new(*b"TEST", major, minor)).unwrap();
+            writer.align_to(8).unwrap();
+            writer.write_all(data).unwrap();
+            writer.into_inner()
+        };
+
+        assert_eq!(reader.read(&write(3, 0, 42u64.as_bytes())).unwrap(), 42);
+        assert_eq!(reader.read(&write(1, 0, 7u16.as_bytes())).unwrap(), 7);
+        assert_eq!(reader.read(&write(2, 5, &[])).unwrap(), 5);
+        assert_eq!(
+            reader.read(&write(1, 1, &[])),
+            Err(VersionedError::InvalidData { major: 1, minor: 1 })
+        );
+        assert_eq!(
+            reader.read(&write(4, 0, &[])),
+            Err(VersionedError::Header(HeaderError::UnsupportedVersion {
+                major: 4,
+                minor: 0
+            }))
+        );
+        assert!(reader.supports(2));
+        assert!(!reader.supports(4));
+    }
+
+    #[test]
+    fn test_rle() {
+        use watto::{codec, Reader};
+
+        let mut values = vec![7u64; 1000];
+        values[10] = 1;
+        values.extend([2, 2, 3]);
+
+        let mut writer = watto::Writer::new(vec![]);
+        writer.write_all(b"x").unwrap();
+        assert_eq!(codec::write_rle(&mut writer, &values).unwrap(), 5);
+        assert_eq!(codec::write_rle::<u64, _>(&mut writer, &[]).unwrap(), 0);
+        writer.write_all(b"end").unwrap();
+        let buffer = writer.into_inner();
+
+        let mut reader = Reader::new(&buffer);
+        assert_eq!(reader.read_bytes(1), Some(&b"x"[..]));
+        let runs = codec::read_rle::<u64>(&mut reader).unwrap();
+        assert_eq!(runs.values(), &[7, 1, 7, 2, 3]);
+        assert_eq!(runs.counts(), &[10, 1, 989, 2, 1]);
+        assert_eq!(runs.len(), values.len());
+        assert!(runs.iter().eq(values.iter()));
+
+        let empty = codec::read_rle::<u64>(&mut reader).unwrap();
+        assert!(empty.is_empty());
+        assert_eq!(reader.remaining(), b"end");
+
+        assert!(codec::read_rle::<u64>(&mut Reader::new(&buffer[..20])).is_none());
+    }
+
+    #[test]
+    fn test_bitset() {
+        use watto::{BitSetBuilder, BitSlice};
+
+        let bits: Vec<bool> = (0..20).map(|i| i % 3 == 0 || i == 19).collect();
+        let builder: BitSetBuilder = bits.iter().copied().collect();
+        assert_eq!(builder.len(), 20);
+
+        let mut writer = watto::Writer::new(vec![]);
+        builder.write_to(&mut writer).unwrap();
+        let buffer = writer.into_inner();
+        assert_eq!(buffer.len(), 3);
+
+        let slice = BitSlice::from_bytes(&buffer, 20).unwrap();
+        assert_eq!(slice, builder.as_bit_slice());
+        for (i, bit) in bits.iter().enumerate() {
+            assert_eq!(slice.get(i), Some(*bit));
+        }
+        assert_eq!(slice.get(20), None);
+        assert!(slice.iter_ones().eq([0, 3, 6, 9, 12, 15, 18, 19]));
+        assert_eq!(slice.count_ones(), 8);
+
+        // trailing bits beyond the length are ignored
+        let slice = BitSlice::from_bytes(&[0xff], 3).unwrap();
+        assert!(slice.iter_ones().eq([0, 1, 2]));
+        assert!(BitSlice::from_bytes(&buffer, 25).is_none());
+        assert!(BitSlice::from_bytes(&[], 0)
+            .unwrap()
+            .iter_ones()
+            .next()
+            .is_none());
+    }
+
+    #[test]
+    fn test_read_from_write_to() {
+        use watto::{MapTable, MapView, RangeTable, RangeView, ReadFrom, Reader, WriteTo};
+
+        fn write_all<A: WriteTo + ?Sized, B: WriteTo + ?Sized>(a: &A, b: &B) -> Vec<u8> {
+            let mut writer = watto::Writer::new(vec![]);
+            a.write_to(&mut writer).unwrap();
+            b.write_to(&mut writer).unwrap();
+            writer.into_inner()
+        }
+
+        fn read_all<'data, A: ReadFrom<'data>, B: ReadFrom<'data>>(buffer: &'data [u8]) -> (A, B) {
+            let mut reader = Reader::new(buffer);
+            let a = A::read_from(&mut reader).unwrap();
+            let b = B::read_from(&mut reader).unwrap();
+            assert!(reader.is_empty());
+            (a, b)
+        }
+
+        let mut map = MapTable::new();
+        map.insert(1u32, 2u16);
+        let buffer = write_all(&7u8, &map);
+        let (value, view) = read_all::<&u8, MapView<u32, u16>>(&buffer);
+        assert_eq!(*value, 7);
+        assert_eq!(view.lookup(&1), Some(&2));
+
+        let mut ranges = RangeTable::new();
+        ranges.insert(10..20, 1u8);
+        let buffer = write_all(&ranges, &3u64);
+        let (view, value) = read_all::<RangeView<u8>, &u64>(&buffer);
+        assert_eq!(view.lookup(15), Some(&1));
+        assert_eq!(*value, 3);
+
+        let mut reader = Reader::new(&buffer[..4]);
+        assert!(<&u64>::read_from(&mut reader).is_none());
+        assert_eq!(reader.position(), 0);
+    }
+
+    #[test]
+    fn test_map_table() {
+        use watto::{MapTable, MapView, Reader};
+
+        let mut map = MapTable::new();
+        for i in (0..100u32).rev() {
+            map.insert(i * 3, i as u8);
+        }
+        map.insert(30, 42);
+        assert_eq!(map.len(), 101);
+
+        let mut writer = watto::Writer::new(vec![]);
+        writer.write_all(b"a").unwrap();
+        map.write_to(&mut writer).unwrap();
+        MapTable::<u64, u16>::new().write_to(&mut writer).unwrap();
+        let buffer = writer.into_inner();
+
+        let mut reader = Reader::new(&buffer);
+        reader.read_bytes(1).unwrap();
+        let view = MapView::<u32, u8>::read(&mut reader).unwrap();
+        assert_eq!(view.len(), 100);
+        assert_eq!(view.lookup(&30), Some(&42));
+        assert_eq!(view.lookup(&33), Some(&11));
+        assert_eq!(view.lookup(&31), None);
+        assert!(view.keys().windows(2).all(|w| w[0] < w[1]));
+        assert_eq!(view.iter().next(), Some((&0, &0)));
+
+        let empty = MapView::<u64, u16>::read(&mut reader).unwrap();
+        assert!(empty.is_empty());
+        assert_eq!(empty.lookup(&0), None);
+        assert!(reader.is_empty());
+    }
+
+    #[test]
+    fn test_range_table() {
+        use watto::{RangeTable, RangeView};
+
+        let mut table = RangeTable::new();
+        table.insert(20..30, 2u16);
+        table.insert(0..10, 0);
+        table.insert(10..15, 1);
+        table.insert(30..30, 3);
+
+        let mut writer = watto::Writer::new(vec![]);
+        table.write_to(&mut writer).unwrap();
+        let buffer = writer.into_inner();
+
+        let view = RangeView::<u16>::parse(&buffer).unwrap();
+        assert_eq!(view.len(), 4);
+        assert_eq!(view.lookup(0), Some(&0));
+        assert_eq!(view.lookup(9), Some(&0));
+        assert_eq!(view.lookup(10), Some(&1));
+        assert_eq!(view.lookup(15), None);
+        assert_eq!(view.lookup_range(25), Some((20..30, &2)));
+        assert_eq!(view.lookup(30), None);
+        assert_eq!(view.lookup(u64::MAX), None);
+        assert_eq!(view.iter().count(), 4);
+
+        let mut overlapping = RangeTable::new();
+        overlapping.insert(0..10, 0u8);
+        overlapping.insert(5..15, 1);
+        let mut writer = watto::Writer::new(vec![]);
+        let err = overlapping.write_to(&mut writer).unwrap_err();
+        assert_eq!(err.kind(), std::io::ErrorKind::InvalidInput);
+    }
+}
+
+#[cfg(feature = "offset_set")]
+mod offset_set_tests {
+    use watto::{OffsetSet, Pod};
+
+    #[test]
+    fn test_offset_set() {
+        #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+        #[repr(C)]
+        struct CommitHash([u8; 20]);
+        unsafe impl Pod for CommitHash {}
+
+        let sha_1 = CommitHash([1; 20]);
+        let sha_2 = CommitHash([2; 20]);
+        let sha_3 = CommitHash([3; 20]);
+
+        let mut table = OffsetSet::new();
+
+        let offset_empty = table.insert(&[]);
+        let offset_12 = table.insert(&[sha_1, sha_2]);
+        let offset_23 = table.insert(&[sha_2, sha_3]);
+
+        assert_eq!(table.insert(&[sha_1, sha_2]), offset_12);
+
+        let buffer = table.as_bytes();
+        let read_empty = OffsetSet::<CommitHash>::read(buffer, offset_empty).unwrap();
+        let read_12 = OffsetSet::<CommitHash>::read(buffer, offset_12).unwrap();
+        let read_23 = OffsetSet::<CommitHash>::read(buffer, offset_23).unwrap();
+        assert_eq!(read_empty, &[]);
+        assert_eq!(read_12, &[sha_1, sha_2]);
+        assert_eq!(read_23, &[sha_2, sha_3]);
+
+        // re-create the table using a serialized buffer
+        let mut table = OffsetSet::<CommitHash>::from_bytes(buffer).unwrap();
+
+        assert_eq!(table.insert(&[sha_1, sha_2]), offset_12);
+
+        let string_bytes = table.as_bytes();
+        let read_12 = OffsetSet::<CommitHash>::read(string_bytes, offset_12).unwrap();
+        let read_23 = OffsetSet::<CommitHash>::read(string_bytes, offset_23).unwrap();
+        assert_eq!(read_12, &[sha_1, sha_2]);
+        assert_eq!(read_23, &[sha_2, sha_3]);
+    }
+
+    #[test]
+    fn test_offset_set_unalign() {
+        use watto::Unalign;
+
+        let mut table = OffsetSet::new();
+        let offset = table.insert(&[Unalign::new(1u64), Unalign::new(2)]);
+        let read = OffsetSet::<Unalign<u64>>::read(table.as_bytes(), offset).unwrap();
+        assert_eq!(read, [Unalign::new(1), Unalign::new(2)]);
+    }
+
+    #[test]
+    fn test_insert_hashed() {
+        use std::collections::hash_map::DefaultHasher;
+        use std::hash::{BuildHasher, BuildHasherDefault};
+
+        let hasher = BuildHasherDefault::<DefaultHasher>::default();
+        let entries: Vec<_> = (0u32..1000).map(|i| i.to_le_bytes()).collect();
+        let hashes: Vec<_> = entries
+            .iter()
+            .map(|entry| hasher.hash_one(&entry[..]))
+            .collect();
+
+        let mut set = OffsetSet::<u8, _, _>::with_hasher(hasher);
+        let offsets: Vec<_> = entries
+            .iter()
+            .zip(&hashes)
+            .map(|(entry, hash)| set.insert_hashed(*hash, entry))
+            .collect();
+
+        for ((entry, hash), offset) in entries.iter().zip(&hashes).zip(&offsets) {
+            assert_eq!(set.get_hashed(*hash, entry), Some(*offset));
+            assert_eq!(set.insert(entry), *offset);
+        }
+        assert_eq!(set.len(), 1000);
+    }
+
+    #[test]
+    fn test_insert_unique_unchecked() {
+        let mut source = OffsetSet::<u8>::new();
+        for entry in [&b"foo"[..], b"bar", b"", b"foo", b"baz"] {
+            source.insert(entry);
+        }
+
+        let mut entries: Vec<_> = source.entries().collect();
+        entries.sort_by_key(|(offset, _)| *offset);
+
+        let mut copy = OffsetSet::<u8>::new();
+        for (offset, entry) in entries {
+            assert_eq!(copy.insert_unique_unchecked(entry), offset);
+        }
+        assert_eq!(copy.as_bytes(), source.as_bytes());
+        assert_eq!(copy.len(), 4);
+        assert_eq!(copy.get(b"baz"), source.get(b"baz"));
+        assert_eq!(copy.insert(b"bar"), source.get(b"bar").unwrap());
+    }
+
+    #[test]
+    fn test_small_table_upgrade() {
+        let mut set = OffsetSet::<u8>::new();
+        let offsets: Vec<_> = (0..100u8).map(|i| set.insert(&[i; 3])).collect();
+        for (i, offset) in offsets.iter().enumerate() {
+            assert_eq!(set.insert(&[i as u8; 3]), *offset);
+            assert_eq!(set.get(&[i as u8; 3]), Some(*offset));
+        }
+        assert_eq!(set.len(), 100);
+        assert_eq!(set.stats().duplicates, 100);
+
+        let small = OffsetSet::<u8>::from_bytes(&set.as_bytes()[..4 * 5]).unwrap();
+        assert_eq!(small.len(), 5);
+        assert_eq!(OffsetSet::<u8>::from_bytes(set.as_bytes()).unwrap(), set);
+
+        for hint in [0, 5, 100, 1000] {
+            let loaded = OffsetSet::<u8>::from_bytes_with_capacity(set.as_bytes(), hint).unwrap();
+            assert_eq!(loaded, set);
+        }
+        let loaded = OffsetSet::<u8>::from_bytes_with_capacity(set.as_bytes(), 100).unwrap();
+        assert!(loaded.stats().index_heap_bytes >= 100 * 4);
+    }
+
+    #[test]
+    fn test_iter_offsets() {
+        let mut set = OffsetSet::<u8>::new();
+        let offsets: Vec<_> = (0..40u8)
+            .map(|i| set.insert(&vec![i; i as usize % 5 + 1]))
+            .collect();
+        assert_eq!(set.insert(&[3, 3, 3, 3]), offsets[3]);
+
+        assert!(set.iter_offsets().eq(offsets.iter().copied()));
+        assert_eq!(OffsetSet::<u8>::new().iter_offsets().count(), 0);
+    }
+
+    #[test]
+    fn test_read_range() {
+        use watto::ReadOffsetSetError;
+
+        let mut set = OffsetSet::<u8>::new();
+        let offsets: Vec<_> = (0..10u8).map(|i| set.insert(&[i; 3])).collect();
+        let buffer = set.into_bytes();
+
+        let entries = OffsetSet::<u8>::read_range(&buffer, offsets[4], 5).unwrap();
+        assert_eq!(entries.len(), 5);
+        for (i, entry) in entries.iter().enumerate() {
+            assert_eq!(*entry, [i as u8 + 4; 3]);
+        }
+        assert!(OffsetSet::<u8>::read_range(&buffer, buffer.len(), 0)
+            .unwrap()
+            .is_empty());
+        assert!(matches!(
+            OffsetSet::<u8>::read_range(&buffer, offsets[8], 3),
+            Err(ReadOffsetSetError::OutOfBounds)
+        ));
+        assert!(matches!(
+            OffsetSet::<u8>::read_range(&buffer[..buffer.len() - 1], offsets[8], 2),
+            Err(ReadOffsetSetError::OutOfBounds)
+        ));
+        assert!(OffsetSet::<u8>::read_range(&buffer, usize::MAX, 0).is_err());
+    }
+
+    #[test]
+    fn test_load_progress() {
+        use std::sync::atomic::{AtomicBool, Ordering};
+
+        use watto::{EntryContext, ReadOffsetSetError, PROGRESS_INTERVAL};
+
+        #[derive(Debug)]
+        enum LoadError {
+            Read,
+            Cancelled(usize),
+        }
+        impl From<ReadOffsetSetError> for LoadError {
+            fn from(_: ReadOffsetSetError) -> Self {
+                Self::Read
+            }
+        }
+
+        let mut set = OffsetSet::<u8>::new();
+        for i in 0..100_000u32 {
+            set.insert(format!("entry {i:025}").as_bytes());
+        }
+        let buffer = set.into_bytes();
+        assert!(buffer.len() > 2 * PROGRESS_INTERVAL);
+
+        let mut reported = vec![];
+        let mut last = EntryContext {
+            index: 0,
+            offset: 0,
+        };
+        let set = OffsetSet::<u8>::from_bytes_with_progress(
+            &buffer,
+            |_, ctx| {
+                last = ctx;
+                Ok::<_, LoadError>(())
+            },
+            |progress| {
+                reported.push(progress);
+                Ok(())
+            },
+        )
+        .unwrap();
+        assert_eq!(set.len(), 100_000);
+        assert_eq!(last.index, 99_999);
+        assert_eq!(last.offset, buffer.len() - 32);
+        assert_eq!(reported.len(), buffer.len() / PROGRESS_INTERVAL + 1);
+        assert!(reported.windows(2).all(|w| w[0].bytes < w[1].bytes));
+        assert_eq!(reported[0].entries, PROGRESS_INTERVAL.div_ceil(32));
+        assert_eq!(reported.last().unwrap().bytes, buffer.len());
+
+        let cancelled = AtomicBool::new(false);
+        let result = OffsetSet::<u8>::from_bytes_with_progress(
+            &buffer,
+            |_, ctx| {
+                if ctx.index == 40_000 {
+                    cancelled.store(true, Ordering::Relaxed);
+                }
+                Ok(())
+            },
+            |progress| match cancelled.load(Ordering::Relaxed) {
+                true => Err(LoadError::Cancelled(progress.entries)),
+                false => Ok(()),
+            },
+        );
+        assert!(matches!(result, Err(LoadError::Cancelled(n)) if n > 40_000 && n < 100_000));
+    }
+
+    #[test]
+    fn test_entry_meta() {
+        use watto::{MetaOffsetSet, ReadOffsetSetError, U24};
+
+        #[derive(Debug, Clone, Copy, PartialEq)]
+        #[repr(C)]
+        struct Meta {
+            kind: u8,
+            line: U24,
+        }
+        unsafe impl Pod for Meta {}
+
+        let meta = Meta {
+            kind: 1,
+            line: U24::new(42),
+        };
+        let mut set = MetaOffsetSet::<Meta, [u8; 2]>::new();
+        let a = set.insert(&meta, &[[1, 2], [3, 4]]);
+        assert_eq!(set.insert(&meta, &[[1, 2], [3, 4]]), a);
+        let b = set.insert(&Meta { kind: 2, ..meta }, &[[1, 2], [3, 4]]);
+        let c = set.insert(&meta, &[]);
+        assert_eq!(set.len(), 3);
+        assert_eq!(set.get(&meta, &[[1, 2], [3, 4]]), Some(a));
+        assert_eq!(set.get(&meta, &[[1, 2]]), None);
+
+        // Enough entries to switch to a hashed index.
+        let offsets: Vec<_> = (0..100u8).map(|i| set.insert(&meta, &[[i, 0]])).collect();
+        for (i, offset) in offsets.iter().enumerate() {
+            assert_eq!(set.get(&meta, &[[i as u8, 0]]), Some(*offset));
+        }
+        assert_eq!(
+            set.get(&Meta { kind: 2, ..meta }, &[[1, 2], [3, 4]]),
+            Some(b)
+        );
+        let entry = set.entries().find(|(offset, ..)| *offset == b);
+        assert_eq!(
+            entry,
+            Some((b, &Meta { kind: 2, ..meta }, &[[1, 2], [3, 4]][..]))
+        );
+        let buffer = set.into_bytes();
+
+        let (read, items) = MetaOffsetSet::<Meta, [u8; 2]>::read(&buffer, a).unwrap();
+        assert_eq!((*read, items), (meta, &[[1, 2], [3, 4]][..]));
+        let (read, _) = MetaOffsetSet::<Meta, [u8; 2]>::read(&buffer, b).unwrap();
+        assert_eq!(read.kind, 2);
+        let (read, items) = MetaOffsetSet::<Meta, [u8; 2]>::read(&buffer, c).unwrap();
+        assert_eq!((read.line.get(), items.len()), (42, 0));
+
+        let mut plain = OffsetSet::<[u8; 2]>::new();
+        let short = plain.insert(&[[0, 0]]);
+        assert!(matches!(
+            MetaOffsetSet::<Meta, [u8; 2]>::read(plain.as_bytes(), short),
+            Err(ReadOffsetSetError::OutOfBounds)
+        ));
+    }
+
+    #[test]
+    #[should_panic = "multiple of the size of T"]
+    fn test_entry_meta_size() {
+        watto::MetaOffsetSet::<[u8; 3], [u8; 2]>::new();
+    }
+
+    #[test]
+    fn test_shared_snapshot() {
+        let mut set = OffsetSet::<u8>::new().into_shared();
+        let offsets: Vec<_> = (0..50u8).map(|i| set.insert(&[i; 2])).collect();
+
+        let snapshot = set.clone();
+        assert_eq!(snapshot.as_bytes().as_ptr(), set.as_bytes().as_ptr());
+
+        let new = set.insert(b"new");
+        assert_ne!(snapshot.as_bytes().as_ptr(), set.as_bytes().as_ptr());
+        assert_eq!(snapshot.len(), 50);
+        assert_eq!(snapshot.get(b"new"), None);
+        assert_eq!(set.get(b"new"), Some(new));
+        assert_eq!(set.get(&[7; 2]), Some(offsets[7]));
+        assert_eq!(snapshot.get(&[7; 2]), Some(offsets[7]));
+
+        let bytes = set.into_storage().into_vec();
+        assert_eq!(OffsetSet::<u8>::from_bytes(&bytes).unwrap().len(), 51);
+    }
+
+    #[test]
+    fn test_bounded_index() {
+        let mut set = OffsetSet::<u8>::new();
+        let a = set.insert(b"a");
+        let b = set.insert(b"b");
+        set.set_max_index_entries(2);
+        assert_eq!(set.len(), 2);
+
+        // Deduplicating `a` makes `b` the least recently used entry.
+        assert_eq!(set.insert(b"a"), a);
+        let c = set.insert(b"c");
+        assert_eq!(set.get(b"b"), None);
+        assert_eq!(set.get(b"a"), Some(a));
+        assert_eq!(set.get(b"c"), Some(c));
+
+        let b2 = set.insert(b"b");
+        assert_ne!(b2, b);
+        assert_eq!(set.get(b"a"), None);
+        assert_eq!(set.len(), 4);
+        assert_eq!(set.as_bytes(), b"\x01a\x01b\x01c\x01b");
+        let offsets: Vec<_> = set.entries().map(|(offset, _)| offset).collect();
+        assert_eq!(offsets, [a, b, c, b2]);
+
+        let mut set = OffsetSet::<u8>::new();
+        set.set_max_index_entries(10);
+        for i in 0..1000u32 {
+            set.insert(&(i % 100).to_le_bytes());
+        }
+        assert_eq!(set.stats().entries, 1000);
+        assert!(set.stats().index_heap_bytes < 1024);
+        for i in 90..100u32 {
+            assert!(set.get(&i.to_le_bytes()).is_some());
+        }
+
+        // Evicted entries are still compared.
+        let mut unbounded = OffsetSet::<u8>::new();
+        for i in (0..100u32).rev() {
+            unbounded.insert(&i.to_le_bytes());
+        }
+        assert_eq!(set, unbounded);
+        assert_eq!(unbounded, set);
+        assert_eq!(set, *unbounded.as_bytes());
+        assert_eq!(unbounded, *set.as_bytes());
+        assert_eq!(set, *set.as_bytes());
+
+        let mut bounded = unbounded.clone();
+        bounded.set_max_index_entries(3);
+        assert_eq!(set, bounded);
+        unbounded.insert(b"x");
+        bounded.insert(b"x");
+        assert_ne!(set, unbounded);
+        assert_ne!(unbounded, set);
+        assert_ne!(set, bounded);
+        assert_ne!(set, *unbounded.as_bytes());
+        assert_ne!(set, [0x80][..]);
+    }
+
+    #[test]
+    fn test_insert_all() {
+        let owned: Vec<Vec<u8>> = (0..200u32)
+            .map(|i| (i % 150).to_le_bytes().into())
+            .collect();
+        let slices: Vec<&[u8]> = owned.iter().map(|s| &s[..]).collect();
+
+        let mut expected = OffsetSet::<u8>::new();
+        let expected_offsets: Vec<_> = slices.iter().map(|s| expected.insert(s)).collect();
+
+        let mut set = OffsetSet::<u8>::new();
+        let mut offsets = set.insert_all(&slices[..10]);
+        offsets.extend(set.insert_all(&slices[10..]));
+        assert_eq!(offsets, expected_offsets);
+        assert_eq!(set.as_bytes(), expected.as_bytes());
+        assert_eq!(set.stats().duplicates, 50);
+        assert_eq!(set.insert_all(&slices), expected_offsets);
+    }
+
+    #[test]
+    #[cfg(debug_assertions)]
+    #[should_panic = "already contained"]
+    fn test_insert_unique_unchecked_duplicate() {
+        let mut set = OffsetSet::<u8>::new();
+        set.insert(b"foo");
+        set.insert_unique_unchecked(b"foo");
+    }
+
+    #[test]
+    fn test_offset_set_get() {
+        let mut table = OffsetSet::<u8>::new();
+        let offset = table.insert(b"abc");
+        assert_eq!(table.get(b"abc"), Some(offset));
+        assert_eq!(table.get(b"def"), None);
+        assert_eq!(table.len(), 1);
+        assert!(!table.is_empty());
+    }
+
+    #[test]
+    fn test_record_table() {
+        use watto::{RecordId, RecordTable};
+
+        #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+        #[repr(C)]
+        struct Record {
+            addr: u64,
+            line: u32,
+            file: u32,
+        }
+        unsafe impl Pod for Record {}
+
+        let a = Record {
+            addr: 1,
+            line: 2,
+            file: 3,
+        };
+        let b = Record {
+            addr: 4,
+            line: 5,
+            file: 6,
+        };
+
+        let mut plain = RecordTable::new();
+        assert_eq!(plain.push(a), RecordId(0));
+        assert_eq!(plain.push(a), RecordId(1));
+        assert_eq!(plain.len(), 2);
+
+        let mut dedup = RecordTable::with_dedup();
+        assert_eq!(dedup.push(a), RecordId(0));
+        assert_eq!(dedup.push(b), RecordId(1));
+        assert_eq!(dedup.push(a), RecordId(0));
+        assert_eq!(dedup.records(), &[a, b]);
+
+        let buffer = dedup.as_bytes().to_vec();
+        assert_eq!(buffer.len(), 2 * std::mem::size_of::<Record>());
+        assert_eq!(RecordTable::<Record>::get(&buffer, RecordId(1)), Some(&b));
+        assert_eq!(RecordTable::<Record>::get(&buffer, RecordId(2)), None);
+        assert_eq!(RecordTable::<Record>::get(&buffer[..20], RecordId(0)), None);
+    }
+
+    #[test]
+    fn test_patch() {
+        use watto::patch::{self, Patch, PatchError, PatchOp};
+
+        let mut set = OffsetSet::<u8>::new();
+        set.insert(b"one");
+        set.insert(b"two");
+        set.insert(b"three");
+        let old = set.as_bytes().to_vec();
+
+        // appending only shares the whole old table
+        set.insert(b"four");
+        let appended = set.as_bytes().to_vec();
+        let p = patch::diff::<u8>(&old, &appended).unwrap();
+        assert_eq!(
+            p.ops(),
+            &[
+                PatchOp::Copy {
+                    offset: 0,
+                    len: old.len()
+                },
+                PatchOp::Insert(b"\x04four".to_vec())
+            ]
+        );
+        assert_eq!(p.apply(&old).unwrap(), appended);
+
+        // a rebuilt table in a different order reuses the old entries
+        let mut rebuilt = OffsetSet::<u8>::new();
+        rebuilt.insert(b"three");
+        rebuilt.insert(b"new");
+        rebuilt.insert(b"one");
+        rebuilt.insert(b"two");
+        let rebuilt = rebuilt.into_bytes();
+        let p = patch::diff::<u8>(&old, &rebuilt).unwrap();
+        assert_eq!(p.ops().len(), 3);
+        assert_eq!(p.new_len(), rebuilt.len());
+        let p = Patch::from_bytes(&p.to_bytes()).unwrap();
+        assert_eq!(p.apply(&old).unwrap(), rebuilt);
+
+        assert_eq!(
+            p.apply(&appended),
+            Err(PatchError::SizeMismatch {
+                expected: old.len(),
+                actual: appended.len()
+            })
+        );
+        assert!(patch::diff::<u8>(&old, &old).unwrap().is_copy_only());
+        assert!(patch::diff::<u8>(&old, &[0x05, b'a']).is_err());
+        assert!(Patch::from_bytes(&[0x00, 0x07]).is_none());
+
+        // copies outside of the old table are rejected before allocating
+        let mut crafted = vec![0x03, 0x00, 0x00];
+        crafted.extend_from_slice(&[0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0x7f]);
+        assert_eq!(crafted.len(), 12);
+        assert!(Patch::from_bytes(&crafted).is_none());
+        // as well as new tables whose size overflows
+        let mut crafted = vec![0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0x01];
+        for _ in 0..2 {
+            crafted.extend_from_slice(&[0x00, 0x00]);
+            crafted
+                .extend_from_slice(&[0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0x01]);
+        }
+        assert!(Patch::from_bytes(&crafted).is_none());
+        let copy = Patch::from_bytes(&[0x03, 0x00, 0x01, 0x02]).unwrap();
+        assert_eq!(copy.new_len(), 2);
+        assert_eq!(copy.apply(b"abc").unwrap(), b"bc");
+    }
+
+    #[test]
+    fn test_shared_dictionary() {
+        use watto::{DictionarySection, SharedDictionary};
+
+        let mut dictionary = SharedDictionary::<u8>::new();
+        let mut first = DictionarySection::new();
+        let mut second = DictionarySection::new();
+
+        assert_eq!(first.insert(&mut dictionary, b"foo"), 0);
+        assert_eq!(first.insert(&mut dictionary, b"bar"), 1);
+        assert_eq!(first.insert(&mut dictionary, b"foo"), 0);
+        assert_eq!(second.insert(&mut dictionary, b"bar"), 0);
+        assert_eq!(second.insert(&mut dictionary, b"baz"), 1);
+        assert_eq!(dictionary.len(), 3);
+        assert_eq!(second.offsets()[0], first.offsets()[1]);
+
+        let dictionary_bytes = dictionary.into_bytes();
+        let first = first.into_bytes();
+        let second = second.into_bytes();
+        let read =
+            |section, index| DictionarySection::read::<u8>(&dictionary_bytes, section, index);
+        assert_eq!(read(&first, 1).unwrap(), b"bar");
+        assert_eq!(read(&second, 0).unwrap(), b"bar");
+        assert_eq!(read(&second, 1).unwrap(), b"baz");
+        assert!(read(&second, 2).is_err());
+
+        let mut dictionary = SharedDictionary::<u8>::from_bytes(&dictionary_bytes).unwrap();
+        let mut third = DictionarySection::new();
+        third.insert(&mut dictionary, b"baz");
+        assert_eq!(dictionary.as_bytes(), dictionary_bytes);
+    }
+}
+
+#[cfg(feature = "strings")]
+mod string_tests {
+    use watto::StringTable;
+
+    #[test]
+    fn test_string_table() {
+        let mut string_table = StringTable::new();
+
+        let offset_empty = string_table.insert("");
+        let offset_abc = string_table.insert("abc");
+        let offset_def = string_table.insert("def");
+
+        assert_eq!(string_table.insert("abc"), offset_abc);
+
+        let string_bytes = string_table.as_bytes();
+        let read_empty = StringTable::read(string_bytes, offset_empty).unwrap();
+        let read_abc = StringTable::read(string_bytes, offset_abc).unwrap();
+        let read_def = StringTable::read(string_bytes, offset_def).unwrap();
+        assert_eq!(read_empty, "");
+        assert_eq!(read_abc, "abc");
+        assert_eq!(read_def, "def");
+
+        // re-create the table using a serialized buffer
+        let mut string_table = StringTable::from_bytes(string_bytes).unwrap();
+
+        assert_eq!(string_table.insert("abc"), offset_abc);
+
+        let string_bytes = string_table.as_bytes();
+        let read_abc = StringTable::read(string_bytes, offset_abc).unwrap();
+        let read_def = StringTable::read(string_bytes, offset_def).unwrap();
+        assert_eq!(read_abc, "abc");
+        assert_eq!(read_def, "def");
+    }
+
+    #[test]
+    fn test_string_table_insert_hashed() {
+        use std::collections::hash_map::DefaultHasher;
+        use std::hash::{BuildHasher, BuildHasherDefault};
+
+        let hasher = BuildHasherDefault::<DefaultHasher>::default();
+        let mut table = StringTable::with_hasher(hasher);
+        let hash = table.hasher().hash_one("foo".as_bytes());
+
+        assert_eq!(table.get_hashed(hash, "foo"), None);
+        let foo = table.insert_hashed(hash, "foo");
+        assert_eq!(table.insert("foo"), foo);
+        assert_eq!(table.get("foo"), Some(foo));
+        assert_eq!(table.get_hashed(hash, "foo"), Some(foo));
+        assert_eq!(table.get("bar"), None);
+    }
+
+    #[cfg(feature = "bumpalo")]
+    #[test]
+    fn test_insert_in() {
+        use watto::OffsetSet;
+
+        let mut bump = bumpalo::Bump::new();
+        let mut table = StringTable::new();
+        let foo = table.insert_in(&bump, |s| s.push_str("foo"));
+        let foobar = table.insert_in(&bump, |s| {
+            s.push_str("foo");
+            s.push_str("bar");
+        });
+        assert_eq!(table.insert("foobar"), foobar);
+        bump.reset();
+        assert_eq!(table.insert_in(&bump, |s| s.push_str("foo")), foo);
+        assert_eq!(
+            StringTable::read(table.as_bytes(), foobar).unwrap(),
+            "foobar"
+        );
+
+        let mut set = OffsetSet::<u8>::new();
+        let offset = set.insert_in(&bump, |v| v.extend_from_slice(&[1, 2, 3]));
+        assert_eq!(set.get(&[1, 2, 3]), Some(offset));
+    }
+
+    #[test]
+    fn test_file_storage_recovery() {
+        use std::io::Write;
+
+        let path = std::env::temp_dir().join(format!("watto-file-{}", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+
+        let mut table = StringTable::open(&path).unwrap();
+        let foo = table.insert("foo");
+        let bar = table.insert("bar");
+        assert_eq!(table.insert("foo"), foo);
+        table.sync().unwrap();
+        // Entries are persisted without serializing the table.
+        assert_eq!(std::fs::read(&path).unwrap(), b"\x03foo\x03bar");
+
+        // Simulate a crash while writing an entry.
+        drop(table);
+        let mut file = std::fs::OpenOptions::new()
+            .append(true)
+            .open(&path)
+            .unwrap();
+        file.write_all(b"\x05ba").unwrap();
+        drop(file);
+
+        let mut table = StringTable::open(&path).unwrap();
+        assert_eq!(table.insert("bar"), bar);
+        let baz = table.insert("baz");
+        assert_eq!(baz, 8);
+        drop(table);
+        assert_eq!(std::fs::read(&path).unwrap(), b"\x03foo\x03bar\x03baz");
+
+        // A corrupted length in the middle of the file is not mistaken for a
+        // torn write, which would discard the entries following it.
+        let corrupted = b"\x03foo\x7fbar\x03baz";
+        std::fs::write(&path, corrupted).unwrap();
+        let err = StringTable::open(&path).unwrap_err();
+        assert_eq!(err.kind(), std::io::ErrorKind::InvalidData);
+        assert_eq!(std::fs::read(&path).unwrap(), corrupted);
+
+        std::fs::write(
+            &path,
+            b"\x03foo\xff\xff\xff\xff\xff\xff\xff\xff\xff\xff\x01",
+        )
+        .unwrap();
+        let err = StringTable::open(&path).unwrap_err();
+        assert_eq!(err.kind(), std::io::ErrorKind::InvalidData);
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[cfg(feature = "mmap")]
+    #[test]
+    fn test_mmap_storage() {
+        use watto::MmapStorage;
+
+        let path = std::env::temp_dir().join(format!("watto-mmap-{}", std::process::id()));
+
+        let storage = unsafe { MmapStorage::create(&path) }.unwrap();
+        let mut table = StringTable::from_storage(storage).unwrap();
+        let foo = table.insert("foo");
+        let long = "x".repeat(100_000);
+        let long_offset = table.insert(&long);
+        assert_eq!(table.insert("foo"), foo);
+        table.into_storage().finish().unwrap();
+
+        let storage = unsafe { MmapStorage::open(&path) }.unwrap();
+        let mut table = StringTable::from_storage(storage).unwrap();
+        assert_eq!(table.insert("foo"), foo);
+        let bar = table.insert("bar");
+        let len = table.as_bytes().len();
+        // The trailing capacity of an unfinished file is not read as entries.
+        drop(table);
+        let storage = unsafe { MmapStorage::open(&path) }.unwrap();
+        let mut table = StringTable::from_storage(storage).unwrap();
+        assert_eq!(table.as_bytes().len(), len);
+        assert_eq!(table.insert("bar"), bar);
+        table.into_storage().finish().unwrap();
+
+        let buffer = std::fs::read(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(StringTable::read(&buffer, foo).unwrap(), "foo");
+        assert_eq!(StringTable::read(&buffer, long_offset).unwrap(), long);
+        assert_eq!(StringTable::read(&buffer, bar).unwrap(), "bar");
+        assert_eq!(buffer.len(), bar + 4);
+    }
+
+    #[test]
+    fn test_segment_storage() {
+        use watto::{InsertError, SegmentStorage};
+
+        let mut segment = vec![0; 16];
+        let mut table = StringTable::from_storage(SegmentStorage::new(&mut segment)).unwrap();
+        let foo = table.insert("foo");
+        let bar = table.insert("bar");
+        let err = table.try_insert("foobar baz").unwrap_err();
+        assert!(matches!(err, InsertError::Storage(_)));
+        assert_eq!(table.try_insert("bar").unwrap(), bar);
+        let len = table.as_bytes().len();
+        drop(table);
+
+        assert_eq!(&segment[..len], b"\x03foo\x03bar");
+        let storage = SegmentStorage::with_len(&mut segment, len);
+        let mut table = StringTable::from_storage(storage).unwrap();
+        assert_eq!(table.insert("foo"), foo);
+        let baz = table.insert("baz");
+        assert_eq!(baz, len);
+        assert_eq!(
+            &*table.into_storage().into_inner(),
+            b"\x03foo\x03bar\x03baz\0\0\0\0"
+        );
+    }
+
+    #[test]
+    fn test_substring_search() {
+        use watto::substring;
+
+        let mut table = StringTable::new();
+        let offsets: Vec<_> = ["std::vec::Vec", "vecmap", "größe", "", "Vec"]
+            .iter()
+            .map(|s| table.insert(s))
+            .collect();
+        let buffer = table.as_bytes();
+        let index = substring::build_index(buffer).unwrap();
+
+        let search = |needle| substring::search(buffer, &index, needle).collect::<Vec<_>>();
+        assert_eq!(search("vec"), [offsets[0], offsets[1]]);
+        assert_eq!(search("Vec"), [offsets[0], offsets[4]]);
+        assert_eq!(search("öß"), [offsets[2]]);
+        assert_eq!(search("::vec::V"), [offsets[0]]);
+        assert_eq!(search("vecs"), []);
+        assert_eq!(search("").len(), 4);
+
+        assert!(substring::build_index(b"\x05abc").is_err());
+        assert_eq!(substring::search(buffer, &index[1..], "vec").count(), 0);
+    }
+
+    #[test]
+    fn test_tagged_offset() {
+        use watto::TaggedOffset;
+
+        let mut table = StringTable::new();
+        let strings = ["", "a", "1234567", "12345678", "ü", "größer"];
+        let offsets: Vec<_> = strings.iter().map(|s| table.insert_tagged(s)).collect();
+        assert_eq!(table.as_bytes(), "\x0812345678\x08größer".as_bytes());
+        assert_eq!(table.insert_tagged("12345678").offset(), Some(0));
+
+        let buffer = table.into_bytes();
+        for (s, offset) in strings.iter().zip(&offsets) {
+            assert_eq!(offset.is_inline(), s.len() <= TaggedOffset::MAX_INLINE_LEN);
+            assert_eq!(offset.resolve(&buffer).unwrap(), *s);
+            assert_eq!(TaggedOffset::from_bits(offset.to_bits()), *offset);
+        }
+        assert_eq!(format!("{:?}", offsets[1]), r#"Inline("a")"#);
+        assert_eq!(format!("{:?}", offsets[3]), "Offset(0)");
+
+        assert!(TaggedOffset::from_bits(0xff << 56)
+            .resolve(&buffer)
+            .is_err());
+        assert!(TaggedOffset::from_bits(0x81 << 56 | 0xff)
+            .resolve(&buffer)
+            .is_err());
+        assert!(TaggedOffset::from_offset(5).resolve(&buffer).is_err());
+    }
+
+    #[test]
+    fn test_entry_alignment() {
+        use watto::OffsetSet;
+
+        let mut table = StringTable::new();
+        table.insert("a");
+        table.set_entry_alignment(16);
+        let offsets: Vec<_> = ["foo", "x".repeat(20).as_str(), "bar"]
+            .iter()
+            .map(|s| table.insert(s))
+            .collect();
+        assert_eq!(offsets, [16, 32, 64]);
+        assert_eq!(table.insert("foo"), 16);
+
+        let buffer = table.into_bytes();
+        assert_eq!(buffer.len(), 68);
+        assert_eq!(StringTable::read(&buffer, 32).unwrap(), "x".repeat(20));
+        let loaded = StringTable::from_bytes(&buffer).unwrap();
+        assert_eq!(loaded.get("bar"), Some(64));
+        // The padding is not loaded as empty strings.
+        assert_eq!(loaded.get(""), None);
+
+        let mut set = OffsetSet::<u8>::new();
+        set.insert(b"");
+        set.set_entry_alignment(4);
+        // Padding of a single byte is extended to the following multiple.
+        let offsets: Vec<_> = [&b"ab"[..], b"c", b"def"]
+            .iter()
+            .map(|entry| set.insert(entry))
+            .collect();
+        assert_eq!(offsets, [4, 12, 16]);
+        let entries: Vec<_> = set.entries().map(|(o, e)| (o, e.to_vec())).collect();
+
+        let buffer = set.into_bytes();
+        assert_eq!(&buffer[..4], b"\x00\x80\x80\x00");
+        assert_eq!(&buffer[7..12], b"\x80\x80\x00\x80\x00");
+        let loaded = OffsetSet::<u8>::from_bytes(&buffer).unwrap();
+        assert_eq!(loaded.len(), 4);
+        let loaded_entries: Vec<_> = loaded.entries().map(|(o, e)| (o, e.to_vec())).collect();
+        assert_eq!(loaded_entries, entries);
+        let range = OffsetSet::<u8>::read_range(&buffer, 4, 3).unwrap();
+        assert_eq!(range, [&b"ab"[..], b"c", b"def"]);
+        let raw: Vec<_> = watto::raw::entries::<u8>(&buffer)
+            .map(|entry| entry.unwrap().0)
+            .collect();
+        assert_eq!(raw, [0, 4, 12, 16]);
+    }
+
+    #[cfg(feature = "rayon")]
+    #[test]
+    fn test_par_iter() {
+        use rayon::prelude::*;
+
+        let mut table = StringTable::new();
+        let strings: Vec<String> = (0..1000).map(|i| format!("string {i}")).collect();
+        let offsets: Vec<_> = strings.iter().map(|s| table.insert(s)).collect();
+
+        let entries: Vec<_> = table.par_iter().collect();
+        let expected: Vec<_> = offsets
+            .into_iter()
+            .zip(strings.iter().map(|s| s.as_str()))
+            .collect();
+        assert_eq!(entries, expected);
+        let total: usize = table.par_iter().map(|(_, s)| s.len()).sum();
+        assert_eq!(total, strings.iter().map(|s| s.len()).sum());
+    }
+
+    #[test]
+    fn test_insert_fmt() {
+        struct Nested<'a>(&'a std::cell::RefCell<StringTable>);
+
+        impl std::fmt::Display for Nested<'_> {
+            fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                let offset = self.0.borrow_mut().insert_fmt(format_args!("inner {}", 1));
+                write!(f, "outer {offset}")
+            }
+        }
+
+        let mut table = StringTable::new();
+        let foo = table.insert("foo");
+        assert_eq!(table.insert_fmt(format_args!("foo")), foo);
+        assert_eq!(table.insert_fmt(format_args!("{}o", "fo")), foo);
+        let bar = table.insert_fmt(format_args!("bar {:>3}", 7));
+        assert_eq!(StringTable::read(table.as_bytes(), bar).unwrap(), "bar   7");
+
+        let inner = std::cell::RefCell::new(StringTable::new());
+        let outer = table.insert_fmt(format_args!("{}", Nested(&inner)));
+        assert_eq!(
+            StringTable::read(table.as_bytes(), outer).unwrap(),
+            "outer 0"
+        );
+        assert_eq!(inner.borrow().get("inner 1"), Some(0));
+    }
+
+    #[test]
+    fn test_reserve_exact_bytes() {
+        let source = ["foo", "bar", "baz"];
+        let mut table = StringTable::new();
+        table.reserve_exact_bytes(source.iter().map(|s| s.len()).sum(), source.len());
+        for s in source {
+            table.insert(s);
+        }
+
+        let buffer = table.into_bytes();
+        assert_eq!(buffer.len(), 12);
+        assert_eq!(buffer.capacity(), 12);
+    }
+
+    #[cfg(feature = "bytes")]
+    #[test]
+    fn test_shared_bytes() {
+        let mut table = StringTable::new();
+        let foo = table.insert("foo");
+        let bar = table.insert("bar");
+        let ptr = table.as_bytes().as_ptr();
+
+        let bytes = table.into_shared_bytes();
+        assert_eq!(bytes.as_ptr(), ptr);
+        assert_eq!(StringTable::read(&bytes, bar).unwrap(), "bar");
+
+        let table = StringTable::from_shared_bytes(bytes).unwrap();
+        assert_eq!(table.as_bytes().as_ptr(), ptr);
+        assert_eq!(StringTable::read(table.as_bytes(), foo).unwrap(), "foo");
+
+        let bytes = table.into_shared_bytes();
+        let shared = bytes.clone();
+        let table = StringTable::from_shared_bytes(bytes).unwrap();
+        assert_eq!(table.as_bytes(), &shared[..]);
+
+        let invalid = bytes::Bytes::from_static(b"\x01\xff");
+        assert!(StringTable::from_shared_bytes(invalid).is_err());
+    }
+
+    #[test]
+    fn test_read_arc() {
+        use std::collections::HashSet;
+        use std::sync::Arc;
+
+        let mut string_table = StringTable::new();
+        let offset_abc = string_table.insert("abc");
+        let offset_def = string_table.insert("def");
+
+        let buffer: Arc<[u8]> = string_table.into_bytes().into();
+        let abc = StringTable::read_arc(&buffer, offset_abc).unwrap();
+        let def = StringTable::read_arc(&buffer, offset_def).unwrap();
+        drop(buffer);
+
+        assert_eq!(abc, "abc");
+        assert_eq!(def.len(), 3);
+        assert!(Arc::ptr_eq(abc.buffer(), def.buffer()));
+
+        let set: HashSet<_> = [abc.clone(), def].into_iter().collect();
+        assert!(set.contains("abc"));
+    }
+
+    #[test]
+    fn test_concurrent_string_table() {
+        use watto::ConcurrentStringTable;
+
+        let table = ConcurrentStringTable::with_shards(4);
+        let offsets: Vec<_> = std::thread::scope(|s| {
+            let handles: Vec<_> = (0..8)
+                .map(|i| {
+                    let table = &table;
+                    s.spawn(move || {
+                        (0..100)
+                            .map(|j| {
+                                let s = format!("string {}", (i * 100 + j) % 250);
+                                (table.insert(&s), s)
+                            })
+                            .collect::<Vec<_>>()
+                    })
+                })
+                .collect();
+            handles
+                .into_iter()
+                .flat_map(|h| h.join().unwrap())
+                .collect()
+        });
+
+        let (buffer, shards) = table.into_bytes();
+        let merged = StringTable::from_bytes(&buffer).unwrap();
+        assert_eq!(merged.as_bytes(), buffer);
+
+        for (offset, s) in offsets {
+            assert_eq!(
+                StringTable::read(&buffer, shards.resolve(offset)).unwrap(),
+                s
+            );
+        }
+    }
+
+    #[test]
+    fn test_symbol_table() {
+        use watto::{Pod, Sym, SymbolTable};
+
+        let mut table = SymbolTable::new();
+        let sym_empty = table.insert("");
+        let sym_abc = table.insert("abc");
+        let sym_def = table.insert("def");
+        assert_eq!(table.insert("abc"), sym_abc);
+        assert_eq!([sym_empty, sym_abc, sym_def], [Sym(0), Sym(1), Sym(2)]);
+        assert_eq!(table.len(), 3);
+
+        let index = table.index().as_bytes();
+        let strings = table.strings();
+        assert_eq!(SymbolTable::read(index, strings, sym_empty).unwrap(), "");
+        assert_eq!(SymbolTable::read(index, strings, sym_abc).unwrap(), "abc");
+        assert_eq!(SymbolTable::read(index, strings, sym_def).unwrap(), "def");
+        assert!(SymbolTable::read(index, strings, Sym(3)).is_err());
+    }
+
+    #[test]
+    fn test_string_table_stats() {
+        let mut string_table = StringTable::new();
+        string_table.insert("a");
+        string_table.insert("abc");
+        string_table.insert("abc");
+        string_table.insert("abcdef");
+        string_table.insert("abcdef");
+
+        let stats = string_table.stats();
+        assert_eq!(stats.entries, 3);
+        assert_eq!(stats.serialized_size, 13);
+        assert_eq!(stats.duplicates, 2);
+        assert_eq!(stats.dedup_saved_bytes, 11);
+        assert!(stats.index_heap_bytes > 0);
+        assert_eq!(stats.max_entry_len, 6);
+        assert_eq!(stats.avg_entry_len, 10.0 / 3.0);
+
+        let loaded = StringTable::from_bytes(string_table.as_bytes()).unwrap();
+        let stats = loaded.stats();
+        assert_eq!(stats.entries, 3);
+        assert_eq!(stats.duplicates, 0);
+    }
+
+    #[test]
+    fn test_read_owned() {
+        let mut string_table = StringTable::new();
+        let offset_abc = string_table.insert("abc");
+        let offset_def = string_table.insert("def");
+        let string_bytes = string_table.as_bytes();
+
+        assert_eq!(
+            StringTable::read_owned(string_bytes, offset_abc).unwrap(),
+            "abc"
+        );
+
+        let mut out = String::from("> ");
+        StringTable::read_into(string_bytes, offset_abc, &mut out).unwrap();
+        StringTable::read_into(string_bytes, offset_def, &mut out).unwrap();
+        assert!(StringTable::read_into(string_bytes, 100, &mut out).is_err());
+        assert_eq!(out, "> abcdef");
+    }
+
+    #[test]
+    fn test_concat_read() {
+        let mut string_table = StringTable::new();
+        let src = string_table.insert("src");
+        let main = string_table.insert("main.rs");
+        let string_bytes = string_table.as_bytes();
+
+        assert_eq!(
+            StringTable::concat_read(string_bytes, &[src, main], "/").unwrap(),
+            "src/main.rs"
+        );
+        assert_eq!(
+            StringTable::concat_read(string_bytes, &[], "/").unwrap(),
+            ""
+        );
+
+        let mut out = String::from("/");
+        StringTable::concat_read_into(string_bytes, &[src, src], "::", &mut out).unwrap();
+        assert!(StringTable::concat_read_into(string_bytes, &[main, 100], "/", &mut out).is_err());
+        assert_eq!(out, "/src::src");
+    }
+
+    #[test]
+    fn test_read_lossy() {
+        use std::borrow::Cow;
+
+        let mut string_table = StringTable::new();
+        let offset_abc = string_table.insert("abc");
+        let mut string_bytes = string_table.into_bytes();
+        let offset_invalid = string_bytes.len();
+        string_bytes.extend_from_slice(&[3, b'a', 0xff, b'b']);
+
+        let abc = StringTable::read_lossy(&string_bytes, offset_abc).unwrap();
+        assert!(matches!(abc, Cow::Borrowed("abc")));
+
+        assert!(StringTable::read(&string_bytes, offset_invalid).is_err());
+        let invalid = StringTable::read_lossy(&string_bytes, offset_invalid).unwrap();
+        assert_eq!(invalid, "a\u{FFFD}b");
+
+        assert!(StringTable::read_lossy(&string_bytes, 100).is_err());
+    }
+
+    #[test]
+    fn test_string_table_limits() {
+        use watto::{InsertError, Limits};
+
+        let mut string_table = StringTable::with_limits(Limits {
+            max_entry_len: Some(4),
+            max_size: Some(10),
+        });
+
+        let offset_abc = string_table.try_insert("abc").unwrap();
+        assert!(matches!(
+            string_table.try_insert("abcde"),
+            Err(InsertError::EntryTooLong { len: 5, max: 4 })
+        ));
+        string_table.try_insert("def").unwrap();
+        assert!(matches!(
+            string_table.try_insert("ghi"),
+            Err(InsertError::TableTooLarge { size: 12, max: 10 })
+        ));
+        // duplicates never grow the table
+        assert_eq!(string_table.try_insert("abc").unwrap(), offset_abc);
+        assert_eq!(string_table.as_bytes().len(), 8);
+
+        // `insert` does not enforce limits
+        string_table.insert("ghijkl");
+        assert_eq!(string_table.as_bytes().len(), 15);
+
+        // Limits can be set on loaded tables.
+        let mut loaded = StringTable::from_bytes(string_table.as_bytes()).unwrap();
+        assert_eq!(loaded.try_insert("mnopqr").unwrap(), 15);
+        loaded.set_limits(Limits {
+            max_entry_len: Some(4),
+            max_size: Some(26),
+        });
+        assert!(matches!(
+            loaded.try_insert("abcde"),
+            Err(InsertError::EntryTooLong { len: 5, max: 4 })
+        ));
+        assert_eq!(loaded.try_insert("def").unwrap(), 4);
+        assert_eq!(loaded.try_insert("stu").unwrap(), 22);
+        assert!(matches!(
+            loaded.try_insert("vwx"),
+            Err(InsertError::TableTooLarge { size: 30, max: 26 })
+        ));
+    }
+
+    #[test]
+    fn test_string_list_table() {
+        use watto::StringListTable;
+
+        let mut table = StringListTable::new();
+        let offset_empty = table.insert([""; 0]);
+        let offset_abc = table.insert(["a", "b", "c"]);
+        let offset_cba = table.insert(vec![String::from("c"), "b".into(), "a".into()]);
+        assert_eq!(table.insert(["a", "b", "c"]), offset_abc);
+
+        let (lists, strings) = table.into_bytes();
+        let read = |offset| {
+            StringListTable::read(&lists, &strings, offset)
+                .unwrap()
+                .collect::<Result<Vec<_>, _>>()
+                .unwrap()
+        };
+        assert!(read(offset_empty).is_empty());
+        assert_eq!(read(offset_abc), ["a", "b", "c"]);
+        assert_eq!(read(offset_cba), ["c", "b", "a"]);
+        assert_eq!(
+            StringTable::from_bytes(&strings).unwrap().stats().entries,
+            3
+        );
+    }
+
+    #[test]
+    fn test_string_table_diff() {
+        let mut old = StringTable::new();
+        old.insert("abc");
+        old.insert("def");
+        let mut new = StringTable::new();
+        new.insert("def");
+        new.insert("ghi");
+        new.insert("jklm");
+
+        let diff = StringTable::diff(old.as_bytes(), new.as_bytes()).unwrap();
+        assert_eq!(diff.added, ["ghi", "jklm"]);
+        assert_eq!(diff.removed, ["abc"]);
+        assert_eq!(diff.size_delta(), 5);
+        assert_eq!(diff.added_bytes(), 7);
+        assert_eq!(diff.removed_bytes(), 3);
+
+        let mut reordered = StringTable::new();
+        reordered.insert("def");
+        reordered.insert("abc");
+        let diff = StringTable::diff(old.as_bytes(), reordered.as_bytes()).unwrap();
+        assert!(diff.is_empty());
+
+        assert!(StringTable::diff(old.as_bytes(), &[0x80]).is_err());
+    }
+
+    #[test]
+    fn test_validate_report() {
+        use watto::ValidationProblem;
+
+        let mut string_table = StringTable::new();
+        string_table.insert("abc");
+        let mut string_bytes = string_table.into_bytes();
+        assert!(StringTable::validate_report(&string_bytes).is_valid());
+
+        string_bytes.extend_from_slice(&[2, 0xc3, 0x28, 1, b'a', 2, 0xff, b'b', 5, b'x']);
+        let report = StringTable::validate_report(&string_bytes);
+        assert_eq!(report.entries, 4);
+        assert_eq!(report.problems.len(), 3);
+        assert!(matches!(
+            &report.problems[0],
+            ValidationProblem::InvalidUtf8 { offset: 4, bytes, .. } if bytes == &[0xc3]
+        ));
+        assert!(matches!(
+            &report.problems[1],
+            ValidationProblem::InvalidUtf8 { offset: 9, bytes, .. } if bytes == &[0xff]
+        ));
+        assert_eq!(
+            report.problems[2],
+            ValidationProblem::TruncatedEntry {
+                offset: 12,
+                remaining: 2
+            }
+        );
+
+        let overlong = [0x80, 0x80, 0x80, 0x80, 0x80, 0x80, 0x80, 0x80, 0x80, 0x02];
+        let report = StringTable::validate_report(&overlong);
+        assert_eq!(
+            report.problems,
+            [ValidationProblem::InvalidLengthPrefix { offset: 0 }]
+        );
+        let report = StringTable::validate_report(&[0x80]);
+        assert_eq!(
+            report.problems,
+            [ValidationProblem::TruncatedEntry {
+                offset: 0,
+                remaining: 1
+            }]
+        );
+    }
+
+    #[test]
+    fn test_string_table_eq() {
+        let mut a = StringTable::new();
+        a.insert("abc");
+        a.insert("def");
+        let mut b = StringTable::new();
+        b.insert("def");
+        b.insert("abc");
+        b.insert("def");
+
+        assert_eq!(a, b);
+        assert_eq!(a, *b.as_bytes());
+        assert_eq!(StringTable::from_bytes(a.as_bytes()).unwrap(), a);
+
+        b.insert("ghi");
+        assert_ne!(a, b);
+        assert_ne!(a, *b.as_bytes());
+
+        let mut duplicated = a.as_bytes().to_vec();
+        duplicated.extend_from_slice(&duplicated.clone());
+        assert_eq!(a, *duplicated);
+        assert_ne!(a, [0x80][..]);
+        assert_ne!(StringTable::new(), [4, b'a'][..]);
+    }
+
+    #[test]
+    fn test_string_table_dump() {
+        let mut string_table = StringTable::new();
+        string_table.insert("abc");
+        string_table.insert(&"x".repeat(200));
+        let mut string_bytes = string_table.into_bytes();
+        string_bytes.extend_from_slice(&[2, 0xff, b'\t', 3, b'a']);
+
+        let dump = StringTable::dump(&string_bytes).to_string();
+        let lines: Vec<_> = dump.lines().collect();
+        assert_eq!(lines.len(), 4);
+        assert_eq!(
+            lines[0],
+            r#"0x00000000  03                             len 3      "abc""#
+        );
+        assert!(lines[1].starts_with("0x00000004  c8 01                          len 200    \"xxx"));
+        assert_eq!(
+            lines[2],
+            r#"0x000000ce  02                             len 2      b"\xff\t" (invalid UTF-8)"#
+        );
+        assert_eq!(
+            lines[3],
+            "0x000000d1  <element offset or length is out of bounds>"
+        );
+    }
+
+    #[test]
+    fn test_validated_string_table() {
+        let mut string_table = StringTable::new();
+        let offset_abc = string_table.insert("abc");
+        let offset_def = string_table.insert("def");
+        let string_bytes = string_table.as_bytes();
+
+        assert_eq!(
+            unsafe { StringTable::read_unchecked(string_bytes, offset_def) }.unwrap(),
+            "def"
+        );
+
+        let validated = StringTable::validate(string_bytes).unwrap();
+        assert_eq!(validated.read(offset_abc).unwrap(), "abc");
+        assert_eq!(validated.read(offset_def).unwrap(), "def");
+        // not the start of an entry
+        assert!(validated.read(offset_abc + 1).is_err());
+        assert!(validated.read(100).is_err());
+
+        assert!(StringTable::validate(&[2, 0xff, 0xfe]).is_err());
+    }
+
+    #[test]
+    fn test_validate_lazy() {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        let buffer = [3, b'a', b'b', b'c', 2, 0xff, 0xfe];
+        let lazy = StringTable::validate_lazy(&buffer).unwrap();
+        assert_eq!(lazy.len(), 2);
+        assert_eq!(lazy.read(0).unwrap(), "abc");
+        assert!(lazy.read(4).is_err());
+        assert!(lazy.read(4).is_err());
+        assert!(lazy.read(1).is_err());
+        assert!(StringTable::validate_lazy(&buffer[..6]).is_err());
+
+        let calls = AtomicUsize::new(0);
+        let lazy = watto::OffsetSet::<u8>::validate_lazy(&buffer, |_| {
+            calls.fetch_add(1, Ordering::Relaxed);
+            Ok::<_, watto::ReadOffsetSetError>(())
+        })
+        .unwrap();
+        assert_eq!(calls.load(Ordering::Relaxed), 0);
+        for _ in 0..3 {
+            assert_eq!(lazy.read(4).unwrap(), [0xff, 0xfe]);
+        }
+        assert_eq!(calls.load(Ordering::Relaxed), 1);
+    }
+
+    #[cfg(feature = "writer")]
+    #[test]
+    fn test_string_record_table() {
+        use watto::{Pod, RecordId, StringOffset, StringRecordTable, StringRecordView};
+
+        #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+        #[repr(C)]
+        struct Line {
+            file: StringOffset,
+            line: u32,
+        }
+        unsafe impl Pod for Line {}
+
+        let mut table = StringRecordTable::with_dedup();
+        let a = table.intern("a.rs");
+        let b = table.intern("b.rs");
+        assert_eq!(table.intern("a.rs"), a);
+        assert_eq!(table.push(Line { file: a, line: 1 }), RecordId(0));
+        assert_eq!(table.push(Line { file: b, line: 2 }), RecordId(1));
+        assert_eq!(table.push(Line { file: a, line: 1 }), RecordId(0));
+
+        let mut writer = watto::Writer::new(vec![]);
+        table.write_to(&mut writer).unwrap();
+        let buffer = writer.into_inner();
+
+        let view = StringRecordView::<Line>::parse(&buffer).unwrap();
+        assert_eq!(view.records().len(), 2);
+        let line = view.get(RecordId(1)).unwrap();
+        assert_eq!(line.line, 2);
+        assert_eq!(view.resolve(line.file).unwrap(), "b.rs");
+        assert_eq!(line.file.resolve(view.strings()).unwrap(), "b.rs");
+        assert!(view.get(RecordId(2)).is_none());
+        assert!(view.resolve(StringOffset(100)).is_err());
+    }
+
+    #[test]
+    fn test_table_observer() {
+        use std::sync::{Arc, Mutex};
+        use watto::TableObserver;
+
+        #[derive(Default)]
+        struct Recorder(Mutex<Vec<String>>);
+
+        impl TableObserver for Recorder {
+            fn on_insert(&self, size: usize) {
+                self.0.lock().unwrap().push(format!("insert {size}"));
+            }
+            fn on_dedup_hit(&self, saved_bytes: usize) {
+                self.0.lock().unwrap().push(format!("dedup {saved_bytes}"));
+            }
+            fn on_buffer_growth(&self, _old_capacity: usize, _new_capacity: usize) {
+                self.0.lock().unwrap().push("growth".into());
+            }
+            fn on_serialize(&self, size: usize) {
+                self.0.lock().unwrap().push(format!("serialize {size}"));
+            }
+        }
+
+        let recorder = Arc::new(Recorder::default());
+        let mut table = StringTable::new();
+        table.set_observer(recorder.clone());
+
+        table.insert("foo");
+        table.insert("foo");
+        table.insert("");
+        assert_eq!(table.into_bytes().len(), 5);
+
+        assert_eq!(
+            *recorder.0.lock().unwrap(),
+            ["insert 4", "growth", "dedup 4", "insert 1", "serialize 5"]
+        );
+    }
+
+    #[cfg(feature = "writer")]
+    #[test]
+    fn test_path_table() {
+        use watto::{PathTable, PathView, Writer};
+
+        let paths = [
+            "src/lib.rs",
+            "src/main.rs",
+            "C:\\src\\lib.rs",
+            "Cargo.toml",
+            "src/",
+        ];
+        let mut table = PathTable::new();
+        let ids: Vec<_> = paths.iter().map(|path| table.insert(path)).collect();
+        assert_eq!(table.insert("src/main.rs"), ids[1]);
+        assert_eq!(table.len(), 5);
+        // "src/", "lib.rs", "main.rs", "C:\src\", "", "Cargo.toml"
+        assert_eq!(table.as_record_table().strings().stats().entries, 6);
+
+        let mut writer = Writer::new(vec![]);
+        table.write_to(&mut writer).unwrap();
+        let buffer = writer.into_inner();
+
+        let view = PathView::parse(&buffer).unwrap();
+        for (path, id) in paths.iter().zip(&ids) {
+            assert_eq!(view.path(*id).as_deref(), Some(*path));
+        }
+        assert_eq!(view.components(ids[2]), Some(("C:\\src\\", "lib.rs")));
+        assert_eq!(view.components(ids[3]), Some(("", "Cargo.toml")));
+        assert_eq!(view.path(watto::RecordId(5)), None);
+        assert_eq!(view.iter().count(), 5);
+    }
+}
+
+#[cfg(feature = "zstd")]
+mod compressed_string_tests {
+    use watto::{CompressedStringTable, ReadCompressedStringError};
+
+    #[test]
+    fn test_compressed_string_table() {
+        let long = "abcdefgh".repeat(64);
+
+        let mut table = CompressedStringTable::new(32);
+        let offset_empty = table.insert("");
+        let offset_abc = table.insert("abc");
+        let offset_long = table.insert(&long);
+        assert_eq!(table.insert(&long), offset_long);
+        assert!(table.as_bytes().len() < 128);
+
+        let buffer = table.into_bytes();
+        let mut scratch = Vec::new();
+        let read = |offset, scratch: &mut Vec<u8>| {
+            CompressedStringTable::read(&buffer, offset, scratch).map(String::from)
+        };
+        assert_eq!(read(offset_empty, &mut scratch).unwrap(), "");
+        assert_eq!(read(offset_abc, &mut scratch).unwrap(), "abc");
+        assert_eq!(read(offset_long, &mut scratch).unwrap(), long);
+
+        let mut corrupted = buffer.clone();
+        corrupted[offset_long + 1] = 2;
+        assert!(matches!(
+            CompressedStringTable::read(&corrupted, offset_long, &mut scratch),
+            Err(ReadCompressedStringError::InvalidEncoding)
+        ));
+        let mut corrupted = buffer.clone();
+        let last = corrupted.len() - 1;
+        corrupted[last] ^= 0xff;
+        assert!(CompressedStringTable::read(&corrupted, offset_long, &mut scratch).is_err());
+
+        // A length prefix claiming a huge size fails without allocating it.
+        let compressed = zstd::bulk::compress(b"abc", 0).unwrap();
+        let mut set = watto::OffsetSet::<u8>::new();
+        let mut entry = vec![1];
+        watto::varint::write_u64(&mut entry, u64::MAX >> 1);
+        entry.extend_from_slice(&compressed);
+        let offset = set.insert(&entry);
+        let huge = set.into_bytes();
+        assert!(matches!(
+            CompressedStringTable::read(&huge, offset, &mut scratch),
+            Err(ReadCompressedStringError::InvalidEncoding)
+        ));
+        assert!(matches!(
+            CompressedStringTable::read_with_limit(&huge, offset, &mut scratch, 1024),
+            Err(ReadCompressedStringError::TooLarge { max: 1024, .. })
+        ));
+        assert_eq!(
+            CompressedStringTable::read_with_limit(&buffer, offset_long, &mut scratch, 1024)
+                .unwrap(),
+            long
+        );
+    }
+
+    #[cfg(feature = "writer")]
+    #[test]
+    fn test_compressed_section() {
+        use std::io::Write;
+
+        use watto::{
+            read_compressed_section, write_compressed_section, AlignedVec, CompressedSectionError,
+            Pod, SectionCodec, Writer,
+        };
+
+        let numbers: Vec<u128> = (0..500).map(|i| i % 3).collect();
+
+        let mut writer = Writer::new(vec![]);
+        writer.write_all(b"x").unwrap();
+        write_compressed_section(&mut writer, numbers.as_bytes(), 16, SectionCodec::Zstd(3))
+            .unwrap();
+        write_compressed_section(&mut writer, b"raw", 1, SectionCodec::None).unwrap();
+        write_compressed_section(&mut writer, b"", 64, SectionCodec::Zstd(0)).unwrap();
+        let buffer = writer.into_inner();
+
+        let (section, rest) = read_compressed_section(&buffer[1..]).unwrap();
+        assert_eq!(section.align(), 16);
+        assert_eq!(u128::slice_from_bytes(&section).unwrap(), numbers);
+
+        let (section, rest) = read_compressed_section(rest).unwrap();
+        assert_eq!(&section[..], b"raw");
+
+        let (section, rest) = read_compressed_section(rest).unwrap();
+        assert!(section.is_empty());
+        assert_eq!(section.align(), 64);
+        assert!(rest.is_empty());
+
+        assert!(matches!(
+            read_compressed_section(&buffer[8..40]),
+            Err(CompressedSectionError::Truncated)
+        ));
+
+        let copy = AlignedVec::from_slice(b"abc", 32);
+        assert_eq!(copy.clone(), copy);
+        assert_eq!(copy.as_ptr() as usize % 32, 0);
+    }
+
+    #[cfg(feature = "writer")]
+    #[test]
+    fn test_compressed_section_untrusted_len() {
+        use watto::{
+            read_compressed_section, read_compressed_section_with_limit, write_compressed_section,
+            AlignedVec, AlignedVecError, CompressedSectionError, SectionCodec, Writer,
+        };
+
+        // The header is `codec, reserved, align, uncompressed_len, compressed_len`.
+        let crafted = |codec: u8, uncompressed_len: u64, payload: &[u8]| {
+            let mut buffer = AlignedVec::zeroed(24 + payload.len(), 8);
+            buffer[0] = codec;
+            buffer[4..8].copy_from_slice(&1u32.to_ne_bytes());
+            buffer[8..16].copy_from_slice(&uncompressed_len.to_ne_bytes());
+            buffer[16..24].copy_from_slice(&(payload.len() as u64).to_ne_bytes());
+            buffer[24..].copy_from_slice(payload);
+            buffer
+        };
+
+        assert!(matches!(
+            read_compressed_section(&crafted(0, u64::MAX, b"")),
+            Err(CompressedSectionError::LengthMismatch)
+        ));
+        assert!(matches!(
+            read_compressed_section(&crafted(0, 1 << 40, b"abc")),
+            Err(CompressedSectionError::LengthMismatch)
+        ));
+        assert_eq!(
+            &read_compressed_section(&crafted(0, 3, b"abc")).unwrap().0[..],
+            b"abc"
+        );
+
+        let mut writer = Writer::new(vec![]);
+        write_compressed_section(&mut writer, &[7; 100], 1, SectionCodec::Zstd(0)).unwrap();
+        let zstd_payload = &writer.into_inner()[24..];
+        assert!(matches!(
+            read_compressed_section(&crafted(1, u64::MAX, zstd_payload)),
+            Err(CompressedSectionError::LengthMismatch)
+        ));
+        assert!(matches!(
+            read_compressed_section_with_limit(&crafted(1, 100, zstd_payload), 99),
+            Err(CompressedSectionError::TooLarge { len: 100, max: 99 })
+        ));
+        assert_eq!(
+            &read_compressed_section(&crafted(1, 100, zstd_payload))
+                .unwrap()
+                .0[..],
+            [7; 100]
+        );
+
+        assert_eq!(
+            AlignedVec::try_zeroed(usize::MAX, 8).unwrap_err(),
+            AlignedVecError {
+                len: usize::MAX,
+                align: 8
+            }
+        );
+        assert!(AlignedVec::try_zeroed(16, 3).is_err());
+        assert_eq!(AlignedVec::try_zeroed(16, 8).unwrap().len(), 16);
+    }
+
+    #[test]
+    fn test_probe_encoding() {
+        use watto::{probe, CompressedStringTable, Encoding};
+
+        let mut table = CompressedStringTable::new(16);
+        table.insert("foo");
+        table.insert(&"watto".repeat(10));
+        assert_eq!(
+            probe(table.as_bytes()),
+            Some(Encoding::CompressedStringTableV1)
+        );
+        assert!(Encoding::OffsetSetV1.matches(table.as_bytes()));
+        assert!(!Encoding::StringTableV1.matches(table.as_bytes()));
+
+        for encoding in [
+            Encoding::OffsetSetV1,
+            Encoding::StringTableV1,
+            Encoding::CompressedStringTableV1,
+            Encoding::ContainerV1,
+        ] {
+            assert_eq!(Encoding::from_id(encoding.id()), Some(encoding));
+        }
+        assert_eq!(Encoding::from_id(0), None);
+    }
+}
+
+#[cfg(feature = "container")]
+mod container_tests {
+    use watto::{Container, ContainerError, ContainerWriter, Pod, Writer};
+
+    #[test]
+    fn test_container() {
+        let mut container = ContainerWriter::new();
+        container.add_section("bytes", 1, b"abc");
+        container.add_section("u64s", 8, [1u64, 2].as_bytes());
+        container.add_section("aligned", 64, vec![0xff; 3]);
+        container.add_section("bytes", 1, b"abcd");
+
+        let mut writer = Writer::new(vec![]);
+        std::io::Write::write_all(&mut writer, &[0xaa]).unwrap();
+        container.write_to(&mut writer).unwrap();
+        let buffer = writer.into_inner();
+        // the container itself is aligned to the largest section alignment
+        let buffer = &buffer[64..];
+
+        let container = Container::parse(buffer).unwrap();
+        let sections: Vec<_> = container.sections().map(|(name, _)| name).collect();
+        assert_eq!(sections, ["bytes", "u64s", "aligned"]);
+        assert_eq!(container.section("bytes"), Some(&b"abcd"[..]));
+        let u64s = u64::slice_from_bytes(container.section("u64s").unwrap()).unwrap();
+        assert_eq!(u64s, &[1, 2]);
+        let aligned = container.section("aligned").unwrap();
+        assert_eq!(aligned, &[0xff; 3]);
+        assert_eq!(
+            (aligned.as_ptr() as usize - buffer.as_ptr() as usize) % 64,
+            0
+        );
+
+        assert!(matches!(
+            Container::parse(&buffer[..buffer.len() - 1]),
+            Err(ContainerError::InvalidSection(name)) if name == "aligned"
+        ));
+        assert!(matches!(
+            Container::parse(&buffer[..30]),
+            Err(ContainerError::InvalidToc)
+        ));
+        assert!(matches!(
+            Container::parse(&[0; 32]),
+            Err(ContainerError::Header(_))
+        ));
+    }
+
+    #[test]
+    fn test_container_padding_audit() {
+        let mut container = ContainerWriter::new();
+        container.add_section("bytes", 1, b"abc");
+        container.add_section("u64s", 8, [1u64, 2].as_bytes());
+
+        let mut writer = Writer::new(vec![]);
+        writer.enable_padding_audit();
+        container.write_to(&mut writer).unwrap();
+
+        let report = writer.padding_report().unwrap();
+        let sections: Vec<_> = report.per_section().collect();
+        assert_eq!(sections[1..], [("bytes", 0), ("u64s", 2)]);
+    }
+
+    #[test]
+    fn test_probe_container() {
+        use watto::{probe, ContainerWriter, Encoding};
+
+        let mut container = ContainerWriter::new();
+        container.add_section("name", 1, b"watto");
+        let mut writer = Writer::new(vec![]);
+        container.write_to(&mut writer).unwrap();
+        let buffer = writer.into_inner();
+
+        assert_eq!(probe(&buffer), Some(Encoding::ContainerV1));
+        assert_eq!(Encoding::ContainerV1.id(), watto::CONTAINER_ENCODING_V1);
+    }
+}
+
+#[cfg(all(feature = "offset_set", feature = "writer"))]
+mod multi_map_tests {
+    use watto::{MultiMap, MultiMapView, Writer};
+
+    #[test]
+    fn test_multi_map() {
+        let mut map = MultiMap::new();
+        map.insert_all(10u64, *b"abc");
+        map.insert_all(5, *b"abc");
+        map.insert(7, b'x');
+        map.insert(7, b'y');
+        assert_eq!(map.len(), 3);
+
+        let mut writer = Writer::new(vec![]);
+        map.write_to(&mut writer).unwrap();
+        let buffer = writer.into_inner();
+
+        let view = MultiMapView::<u64, u8>::parse(&buffer).unwrap();
+        assert_eq!(view.keys(), &[5, 7, 10]);
+        assert_eq!(view.get_all(&5), b"abc");
+        assert_eq!(view.get_all(&7), b"xy");
+        assert_eq!(view.get_all(&10), b"abc");
+        assert_eq!(view.get_all(&6), b"");
+        assert_eq!(view.iter().count(), 3);
+
+        // the truncated value set is detected upfront
+        assert!(MultiMapView::<u64, u8>::parse(&buffer[..buffer.len() - 1]).is_none());
+    }
+}
+
+#[cfg(all(feature = "crc32", feature = "xxhash", feature = "sha256"))]
+mod digest_tests {
+    use std::io::Write;
+
+    use watto::{verify_section, DigestAlgorithm, DigestMismatch, HashingWriter};
+
+    #[test]
+    fn test_digest() {
+        let algorithms = [
+            DigestAlgorithm::Crc32,
+            DigestAlgorithm::Xxh3,
+            DigestAlgorithm::Sha256,
+        ];
+        for algorithm in algorithms {
+            assert_eq!(DigestAlgorithm::from_id(algorithm.id()), Some(algorithm));
+
+            let digest = algorithm.digest(b"hello watto");
+            assert_eq!(digest.as_bytes().len(), algorithm.output_len());
+            assert!(verify_section(b"hello watto", algorithm, digest.as_bytes()).is_ok());
+            assert!(matches!(
+                verify_section(b"hello wattO", algorithm, digest.as_bytes()),
+                Err(DigestMismatch { .. })
+            ));
+
+            // hashing while writing, through the padding of the `Writer`
+            let hashing = HashingWriter::new(vec![], algorithm.hasher());
+            let mut writer = watto::Writer::new(hashing);
+            writer.write_all(b"hello").unwrap();
+            writer.align_to(8).unwrap();
+            writer.write_all(b"watto").unwrap();
+            let (buffer, streamed) = writer.into_inner().finish();
+            assert_eq!(buffer, b"hello\0\0\0watto");
+            assert_eq!(streamed, algorithm.digest(&buffer));
+        }
+
+        assert_eq!(
+            DigestAlgorithm::Crc32.digest(b"123456789").as_bytes(),
+            0xcbf43926u32.to_le_bytes()
+        );
+        assert_eq!(
+            DigestAlgorithm::Sha256.digest(b"").to_string(),
+            "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855"
+        );
+        assert_eq!(DigestAlgorithm::from_id(0), None);
+    }
+}
+
+#[cfg(feature = "testing")]
+mod testing_tests {
+    use watto::testing::{self, Corruption};
+
+    #[test]
+    fn test_corruptions() {
+        let all: Vec<_> = Corruption::all(2).collect();
+        assert_eq!(all.len(), 2 + 16);
+        assert_eq!(all[0], Corruption::Truncate(0));
+
+        let flipped = Corruption::BitFlip { offset: 1, bit: 7 }.apply(&[1, 2]);
+        assert_eq!(&flipped[..], &[1, 0x82]);
+        assert_eq!(flipped.as_ptr() as usize % testing::TEST_ALIGN, 0);
+        assert_eq!(&Corruption::Truncate(1).apply(&[1, 2])[..], &[1]);
+
+        testing::assert_roundtrip(&(u32::MAX as u64));
+        testing::assert_slice_roundtrip(&[1u16, 2, 3]);
+    }
+
+    #[test]
+    #[should_panic(expected = "panicked on corrupted buffer (truncated to 0 bytes)")]
+    fn test_assert_robust_panics() {
+        testing::assert_robust(&[1, 2, 3], |bytes| {
+            let _ = bytes[0];
+        });
+    }
+
+    #[cfg(feature = "strings")]
+    #[test]
+    fn test_string_table_robust() {
+        use watto::StringTable;
+
+        testing::assert_string_table_roundtrip(&["foo", "", "bär", "foo"]);
+
+        let mut table = StringTable::new();
+        let offsets: Vec<_> = ["foo", "bar", &"long".repeat(50)]
+            .into_iter()
+            .map(|s| table.insert(s))
+            .collect();
+        testing::assert_robust(table.as_bytes(), |bytes| {
+            for offset in &offsets {
+                let _ = StringTable::read(bytes, *offset);
+            }
+            let _ = StringTable::validate_report(bytes);
+        });
+    }
+}
+
+#[cfg(all(feature = "tracing", feature = "strings"))]
+mod tracing_tests {
+    use std::sync::{Arc, Mutex};
+
+    use tracing::span::{Attributes, Id, Record};
+    use tracing::{Event, Metadata, Subscriber};
+    use watto::StringTable;
+
+    /// Records the names of all spans, and the field names of all events.
+    #[derive(Default, Clone)]
+    struct Recorder(Arc<Mutex<Vec<String>>>);
+
+    impl Subscriber for Recorder {
+        fn enabled(&self, _: &Metadata<'_>) -> bool {
+            true
+        }
+        fn new_span(&self, span: &Attributes<'_>) -> Id {
+            let mut records = self.0.lock().unwrap();
+            records.push(span.metadata().name().to_owned());
+            Id::from_u64(records.len() as u64)
+        }
+        fn record(&self, _: &Id, _: &Record<'_>) {}
+        fn record_follows_from(&self, _: &Id, _: &Id) {}
+        fn event(&self, event: &Event<'_>) {
+            let fields: Vec<_> = event.fields().map(|field| field.name()).collect();
+            self.0.lock().unwrap().push(fields.join(","));
+        }
+        fn enter(&self, _: &Id) {}
+        fn exit(&self, _: &Id) {}
+    }
+
+    #[test]
+    fn test_tracing() {
+        let recorder = Recorder::default();
+        tracing::subscriber::with_default(recorder.clone(), || {
+            let mut table = StringTable::new();
+            for i in 0..100 {
+                table.insert(&i.to_string());
+            }
+            table.insert(&"x".repeat(100_000));
+            StringTable::from_bytes(table.as_bytes()).unwrap();
+        });
+
+        let records = recorder.0.lock().unwrap();
+        assert!(records.contains(&"message,old_capacity,new_capacity".to_owned()));
+        assert!(records.contains(&"message,size,offset".to_owned()));
+        assert!(records.contains(&"OffsetSet::from_bytes".to_owned()));
+        assert!(records.contains(&"message,entries".to_owned()));
+        assert!(records.contains(&"message,elapsed_us".to_owned()));
     }
 }
@@ -0,0 +1,76 @@
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::{parse_quote, DeriveInput, Index, Result};
+
+use crate::utils::{check_repr, struct_fields, Endian, WattoAttrs};
+
+pub fn expand(input: DeriveInput) -> Result<TokenStream> {
+    check_repr(&input)?;
+    let attrs = WattoAttrs::parse(&input.attrs)?;
+    let fields = struct_fields(&input)?;
+
+    let mut generics = input.generics.clone();
+    let where_clause = generics.make_where_clause();
+    for field in fields {
+        let ty = &field.ty;
+        where_clause
+            .predicates
+            .push(parse_quote!(#ty: ::watto::ByteSwap));
+    }
+
+    let swapped = fields.iter().enumerate().map(|(i, field)| {
+        let member = match &field.ident {
+            Some(ident) => quote!(#ident),
+            None => {
+                let index = Index::from(i);
+                quote!(#index)
+            }
+        };
+        quote!(#member: ::watto::ByteSwap::swap_bytes(self.#member))
+    });
+
+    let ident = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+    let mut output = quote! {
+        impl #impl_generics ::watto::ByteSwap for #ident #ty_generics #where_clause {
+            fn swap_bytes(self) -> Self {
+                Self { #(#swapped),* }
+            }
+        }
+    };
+
+    if let Some(endian) = attrs.endian {
+        let (target_endian, name) = match endian {
+            Endian::Little => ("little", "little-endian"),
+            Endian::Big => ("big", "big-endian"),
+        };
+        let to_native_doc = format!(
+            "Converts this value from its {name} disk representation into native byte order."
+        );
+        let to_disk_doc = format!(
+            "Converts this value from native byte order into its {name} disk representation."
+        );
+        let vis = &input.vis;
+        output.extend(quote! {
+            impl #impl_generics #ident #ty_generics #where_clause {
+                #[doc = #to_native_doc]
+                #[inline]
+                #vis fn to_native(self) -> Self {
+                    if cfg!(target_endian = #target_endian) {
+                        self
+                    } else {
+                        ::watto::ByteSwap::swap_bytes(self)
+                    }
+                }
+
+                #[doc = #to_disk_doc]
+                #[inline]
+                #vis fn to_disk(self) -> Self {
+                    self.to_native()
+                }
+            }
+        });
+    }
+
+    Ok(output)
+}
@@ -0,0 +1,52 @@
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::{parse_quote, DeriveInput, Index, Result};
+
+use crate::utils::{check_repr, struct_fields};
+
+pub fn expand(input: DeriveInput) -> Result<TokenStream> {
+    check_repr(&input)?;
+    let fields = struct_fields(&input)?;
+
+    let mut generics = input.generics.clone();
+    let where_clause = generics.make_where_clause();
+    for field in fields {
+        let ty = &field.ty;
+        where_clause
+            .predicates
+            .push(parse_quote!(#ty: ::watto::describe::Describe));
+    }
+
+    let field_layouts = fields.iter().enumerate().map(|(i, field)| {
+        let ty = &field.ty;
+        let member = match &field.ident {
+            Some(ident) => quote!(#ident),
+            None => {
+                let index = Index::from(i);
+                quote!(#index)
+            }
+        };
+        let name = member.to_string();
+        quote! {
+            ::watto::describe::FieldLayout {
+                name: #name,
+                offset: ::core::mem::offset_of!(Self, #member),
+                layout: <#ty as ::watto::describe::Describe>::LAYOUT,
+            }
+        }
+    });
+
+    let ident = &input.ident;
+    let name = ident.to_string();
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+    Ok(quote! {
+        impl #impl_generics ::watto::describe::Describe for #ident #ty_generics #where_clause {
+            const LAYOUT: &'static ::watto::describe::TypeLayout = &::watto::describe::TypeLayout {
+                name: #name,
+                size: ::core::mem::size_of::<Self>(),
+                align: ::core::mem::align_of::<Self>(),
+                kind: ::watto::describe::LayoutKind::Struct(&[#(#field_layouts),*]),
+            };
+        }
+    })
+}
@@ -0,0 +1,77 @@
+//! Derive macros for [watto](https://docs.rs/watto).
+//!
+//! These are re-exported by watto with its `derive` feature, and should not be used directly.
+
+use proc_macro::TokenStream;
+use syn::{parse_macro_input, DeriveInput};
+
+mod byteswap;
+mod describe;
+mod pod;
+mod utils;
+mod write_to;
+mod zeroable;
+
+/// Derives `watto::Pod` for a `#[repr(C)]` or `#[repr(transparent)]` struct.
+///
+/// All field types need to implement `Pod` as well. The struct is also
+/// checked at compile time not to contain any padding. For generic structs,
+/// this happens for each instantiation that is used through `Pod`.
+#[proc_macro_derive(Pod)]
+pub fn derive_pod(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    pod::expand(input)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+/// Derives `watto::describe::Describe` for a `#[repr(C)]` struct.
+///
+/// All field types need to implement `Describe` as well.
+#[proc_macro_derive(Describe)]
+pub fn derive_describe(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    describe::expand(input)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+/// Derives `watto::ByteSwap` for a `#[repr(C)]` struct.
+///
+/// All field types need to implement `ByteSwap` as well. A
+/// `#[watto(endian = "little")]` or `#[watto(endian = "big")]` attribute
+/// additionally generates `to_native` and `to_disk` methods.
+#[proc_macro_derive(ByteSwap, attributes(watto))]
+pub fn derive_byteswap(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    byteswap::expand(input)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+/// Derives `watto::WriteTo` for a struct.
+///
+/// The generated `write_to` writes all fields in declaration order, each of
+/// which can be a `Pod` value, a slice of `Pod` values, a table with a
+/// `write_to` method, or another type implementing `WriteTo`. Every field is
+/// aligned as required by its type.
+#[proc_macro_derive(WriteTo)]
+pub fn derive_write_to(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    write_to::expand(input)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+/// Derives `watto::Zeroable` for a struct or enum.
+///
+/// All field types of a struct need to implement `Zeroable` as well. Enums
+/// need a primitive or `#[repr(C)]` representation, and their first variant
+/// needs the discriminant `0`, with all its fields implementing `Zeroable`.
+#[proc_macro_derive(Zeroable)]
+pub fn derive_zeroable(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    zeroable::expand(input)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
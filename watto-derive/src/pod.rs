@@ -0,0 +1,50 @@
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::{parse_quote, DeriveInput, Result};
+
+use crate::utils::{check_repr, struct_fields};
+
+pub fn expand(input: DeriveInput) -> Result<TokenStream> {
+    check_repr(&input)?;
+    let fields = struct_fields(&input)?;
+
+    let mut generics = input.generics.clone();
+    let where_clause = generics.make_where_clause();
+    for field in fields {
+        let ty = &field.ty;
+        where_clause
+            .predicates
+            .push(parse_quote!(#ty: ::watto::Pod));
+    }
+
+    // Padding bytes are uninitialized, and must not be exposed by `as_bytes`.
+    // The size of the struct thus has to be the sum of the sizes of its fields.
+    let ident = &input.ident;
+    let message = format!("`{ident}` must not contain padding bytes to be `Pod`");
+    let types: Vec<_> = fields.iter().map(|field| &field.ty).collect();
+    let check = quote! {{
+        #(let () = <#types as ::watto::Pod>::LAYOUT_CHECK;)*
+        ::core::assert!(
+            ::core::mem::size_of::<Self>() == 0 #(+ ::core::mem::size_of::<#types>())*,
+            #message,
+        );
+    }};
+
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+    let mut output = quote! {
+        unsafe impl #impl_generics ::watto::Pod for #ident #ty_generics #where_clause {
+            const LAYOUT_CHECK: () = #check;
+        }
+    };
+
+    // The layout of generic structs is only known once they are instantiated,
+    // and is checked whenever they are used through `Pod`. Non-generic
+    // structs are checked right away.
+    if input.generics.params.is_empty() {
+        output.extend(quote! {
+            const _: () = <#ident as ::watto::Pod>::LAYOUT_CHECK;
+        });
+    }
+
+    Ok(output)
+}
@@ -0,0 +1,72 @@
+use syn::punctuated::Punctuated;
+use syn::{Attribute, Data, DeriveInput, Error, Fields, LitStr, Meta, Result, Token};
+
+/// Returns the fields of a struct, or an error for enums and unions.
+pub fn struct_fields(input: &DeriveInput) -> Result<&Fields> {
+    match &input.data {
+        Data::Struct(data) => Ok(&data.fields),
+        _ => Err(Error::new_spanned(
+            &input.ident,
+            "watto derives only support structs",
+        )),
+    }
+}
+
+/// Checks that the struct has a `#[repr(C)]` or `#[repr(transparent)]` layout.
+pub fn check_repr(input: &DeriveInput) -> Result<()> {
+    for attr in &input.attrs {
+        if !attr.path().is_ident("repr") {
+            continue;
+        }
+        let reprs = attr.parse_args_with(Punctuated::<Meta, Token![,]>::parse_terminated)?;
+        if reprs
+            .iter()
+            .any(|repr| repr.path().is_ident("C") || repr.path().is_ident("transparent"))
+        {
+            return Ok(());
+        }
+    }
+    Err(Error::new_spanned(
+        &input.ident,
+        "watto derives require `#[repr(C)]` or `#[repr(transparent)]`",
+    ))
+}
+
+/// The byte order of a disk format.
+#[derive(Clone, Copy)]
+pub enum Endian {
+    Little,
+    Big,
+}
+
+/// The options given in `#[watto(...)]` attributes on a struct.
+#[derive(Default)]
+pub struct WattoAttrs {
+    /// The byte order given by `endian = "little"` or `endian = "big"`.
+    pub endian: Option<Endian>,
+}
+
+impl WattoAttrs {
+    pub fn parse(attrs: &[Attribute]) -> Result<Self> {
+        let mut result = Self::default();
+        for attr in attrs {
+            if !attr.path().is_ident("watto") {
+                continue;
+            }
+            attr.parse_nested_meta(|meta| {
+                if meta.path.is_ident("endian") {
+                    let value: LitStr = meta.value()?.parse()?;
+                    result.endian = Some(match value.value().as_str() {
+                        "little" => Endian::Little,
+                        "big" => Endian::Big,
+                        _ => return Err(meta.error("expected `\"little\"` or `\"big\"`")),
+                    });
+                    Ok(())
+                } else {
+                    Err(meta.error("unknown watto attribute"))
+                }
+            })?;
+        }
+        Ok(result)
+    }
+}
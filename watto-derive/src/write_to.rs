@@ -0,0 +1,40 @@
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::{DeriveInput, Index, Result};
+
+use crate::utils::struct_fields;
+
+pub fn expand(input: DeriveInput) -> Result<TokenStream> {
+    let fields = struct_fields(&input)?;
+
+    let writes = fields.iter().enumerate().map(|(i, field)| {
+        let member = match &field.ident {
+            Some(ident) => quote!(#ident),
+            None => {
+                let index = Index::from(i);
+                quote!(#index)
+            }
+        };
+        // Method call syntax prefers inherent `write_to` methods of tables,
+        // and dereferences references, `Vec`s and `Box`es.
+        quote! {
+            self.#member.write_to(writer)?;
+        }
+    });
+
+    let ident = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    Ok(quote! {
+        impl #impl_generics ::watto::WriteTo for #ident #ty_generics #where_clause {
+            fn write_to<__W: ::std::io::Write>(
+                &self,
+                writer: &mut ::watto::Writer<__W>,
+            ) -> ::std::io::Result<()> {
+                #[allow(unused_imports)]
+                use ::watto::WriteTo as _;
+                #(#writes)*
+                ::core::result::Result::Ok(())
+            }
+        }
+    })
+}
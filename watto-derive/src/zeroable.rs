@@ -0,0 +1,80 @@
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::punctuated::Punctuated;
+use syn::{parse_quote, Data, DeriveInput, Error, Expr, Fields, Lit, Meta, Result, Token};
+
+const PRIMITIVE_REPRS: &[&str] = &[
+    "C", "u8", "u16", "u32", "u64", "u128", "usize", "i8", "i16", "i32", "i64", "i128", "isize",
+];
+
+pub fn expand(input: DeriveInput) -> Result<TokenStream> {
+    let fields: Vec<&Fields> = match &input.data {
+        Data::Struct(data) => vec![&data.fields],
+        Data::Enum(data) => {
+            check_enum_repr(&input)?;
+            let first = data.variants.first().ok_or_else(|| {
+                Error::new_spanned(&input.ident, "Zeroable can not be derived for empty enums")
+            })?;
+            if let Some((_, discriminant)) = &first.discriminant {
+                if !is_zero(discriminant) {
+                    return Err(Error::new_spanned(
+                        discriminant,
+                        "the first variant of a Zeroable enum needs the discriminant `0`",
+                    ));
+                }
+            }
+            vec![&first.fields]
+        }
+        Data::Union(_) => {
+            return Err(Error::new_spanned(
+                &input.ident,
+                "Zeroable can not be derived for unions",
+            ))
+        }
+    };
+
+    let mut generics = input.generics.clone();
+    let where_clause = generics.make_where_clause();
+    for field in fields.into_iter().flatten() {
+        let ty = &field.ty;
+        where_clause
+            .predicates
+            .push(parse_quote!(#ty: ::watto::Zeroable));
+    }
+
+    let ident = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+    Ok(quote! {
+        unsafe impl #impl_generics ::watto::Zeroable for #ident #ty_generics #where_clause {}
+    })
+}
+
+/// Checks that the enum has a primitive or `#[repr(C)]` representation, which
+/// fixes the discriminant of its first variant.
+fn check_enum_repr(input: &DeriveInput) -> Result<()> {
+    for attr in &input.attrs {
+        if !attr.path().is_ident("repr") {
+            continue;
+        }
+        let reprs = attr.parse_args_with(Punctuated::<Meta, Token![,]>::parse_terminated)?;
+        if reprs.iter().any(|repr| {
+            PRIMITIVE_REPRS
+                .iter()
+                .any(|primitive| repr.path().is_ident(primitive))
+        }) {
+            return Ok(());
+        }
+    }
+    Err(Error::new_spanned(
+        &input.ident,
+        "deriving Zeroable for an enum requires a primitive or `#[repr(C)]` representation",
+    ))
+}
+
+/// Returns `true` if the discriminant is the literal `0`.
+fn is_zero(discriminant: &Expr) -> bool {
+    match discriminant {
+        Expr::Lit(expr) => matches!(&expr.lit, Lit::Int(int) if int.base10_digits() == "0"),
+        _ => false,
+    }
+}